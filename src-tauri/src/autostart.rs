@@ -0,0 +1,82 @@
+// Start on login
+//
+// Registered per user under HKCU\...\CurrentVersion\Run so no admin rights are
+// needed. The entry launches the exe with MINIMIZED_FLAG, which keeps the window
+// hidden in the tray so the folder watcher and scheduled posts run unattended.
+
+use crate::errors::{AppError, AppResult};
+use crate::shell_integration::run_reg;
+
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+/// Value name of the entry under the Run key
+const VALUE_NAME: &str = "VRChatPhotoUploader";
+
+/// Launch argument that starts the app hidden in the tray
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+/// `reg add` arguments that create the Run entry for `exe_path`
+fn registration_command(exe_path: &str) -> Vec<String> {
+    vec![
+        "add".into(),
+        RUN_KEY.into(),
+        "/v".into(),
+        VALUE_NAME.into(),
+        "/d".into(),
+        format!("\"{exe_path}\" {MINIMIZED_FLAG}"),
+        "/f".into(),
+    ]
+}
+
+/// Start the current executable minimized on login. Re-running it points the entry
+/// at wherever the exe now lives.
+pub fn enable_auto_launch() -> AppResult<()> {
+    let exe_path = std::env::current_exe()?.to_string_lossy().to_string();
+    if !run_reg(&registration_command(&exe_path))? {
+        return Err(AppError::Config(format!(
+            "Failed to write registry value {RUN_KEY}\\{VALUE_NAME}"
+        )));
+    }
+
+    log::info!("Registered {exe_path} to start on login");
+    Ok(())
+}
+
+/// Remove the Run entry; a missing entry is not an error
+pub fn disable_auto_launch() -> AppResult<()> {
+    run_reg(&[
+        "delete".to_string(),
+        RUN_KEY.to_string(),
+        "/v".to_string(),
+        VALUE_NAME.to_string(),
+        "/f".to_string(),
+    ])?;
+
+    log::info!("Removed start-on-login entry");
+    Ok(())
+}
+
+/// Whether this launch came from the Run entry
+pub fn launched_minimized(args: &[String]) -> bool {
+    args.iter().skip(1).any(|a| a == MINIMIZED_FLAG)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_command_quotes_exe_and_adds_flag() {
+        let command = registration_command(r"C:\Program Files\Uploader\app.exe");
+        assert_eq!(command[1], RUN_KEY);
+        assert_eq!(
+            command[5],
+            r#""C:\Program Files\Uploader\app.exe" --minimized"#
+        );
+
+        assert!(launched_minimized(&[
+            "app.exe".to_string(),
+            MINIMIZED_FLAG.to_string()
+        ]));
+        assert!(!launched_minimized(&["app.exe".to_string()]));
+    }
+}
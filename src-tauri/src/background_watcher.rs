@@ -1,4 +1,5 @@
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::mpsc::channel;
 use std::sync::{Arc, Mutex};
@@ -7,6 +8,7 @@ use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
 use crate::errors::{AppError, AppResult, ProgressState};
+use crate::security::InputValidator;
 use crate::{config, database, uploader};
 
 pub struct BackgroundWatcher {
@@ -47,14 +49,14 @@ impl BackgroundWatcher {
         let mut watcher = RecommendedWatcher::new(tx, Config::default())
             .map_err(|e| format!("Failed to create watcher: {e}"))?;
 
-        let root_path = Path::new(&path_str);
+        let root_path = InputValidator::resolve_user_path(&path_str);
         if !root_path.exists() {
             return Err(format!("Directory does not exist: {path_str}"));
         }
 
         // Watch root directory
         watcher
-            .watch(root_path, RecursiveMode::Recursive)
+            .watch(&root_path, RecursiveMode::Recursive)
             .map_err(|e| format!("Failed to watch root directory: {e}"))?;
 
         // Explicitly watch current month folder if it exists (extra robust for NAS)
@@ -206,7 +208,7 @@ fn start_batch_monitor(
             // Periodic subfolder check (every 60s) to handle NAS issues and month rollovers
             if last_scan_check.elapsed() > Duration::from_secs(60) {
                 if let Some(root_str) = &config.vrchat_path {
-                    let root_path = Path::new(root_str);
+                    let root_path = InputValidator::resolve_user_path(root_str);
                     let now = chrono::Local::now();
                     let month_folder = now.format("%Y-%m").to_string();
                     let month_path = root_path.join(&month_folder);
@@ -307,7 +309,10 @@ fn start_batch_monitor(
                                     match progress {
                                         Ok(p) => p
                                             .get(&session_id)
-                                            .map(|s| s.session_status == "active")
+                                            .map(|s| {
+                                                s.session_status == "active"
+                                                    || s.session_status == "waiting_for_network"
+                                            })
                                             .unwrap_or(false),
                                         Err(_) => false,
                                     }
@@ -330,6 +335,7 @@ fn start_batch_monitor(
                                             {
                                                 session_progress.session_status =
                                                     "cancelled".to_string();
+                                                session_progress.cancel_token.cancel();
                                                 log::info!("Background session {session_id} cancelled due to auto-upload being disabled");
                                             }
                                         }
@@ -475,10 +481,21 @@ async fn process_auto_upload_batch(
         include_player_names: config.auto_upload_include_players,
         grouping_time_window: config.auto_upload_time_window,
         group_by_world: config.auto_upload_group_by_world,
+        group_by_instance: false,
         upload_quality: Some(config.upload_quality),
         compression_format: Some(config.compression_format.clone()),
         single_thread_mode: config.auto_upload_single_thread,
         merge_no_metadata: config.auto_upload_merge_no_metadata,
+        adopt_orphans: config.adopt_orphans,
+        caption_overrides: HashMap::new(),
+        extra_message: None,
+        screenshot_timezone: None,
+        custom_groups: None,
+        group_webhook_overrides: HashMap::new(),
+        post_summary: None,
+        suppress_embeds: None,
+        suppress_notifications: None,
+        forum_tag_ids: None,
     };
 
     // Re-check config right before starting (handles race with settings being saved)
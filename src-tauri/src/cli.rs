@@ -0,0 +1,269 @@
+// Headless command-line uploads
+//
+// `VRChat-Photo-Uploader --upload <files...> --webhook <name|id> [--no-group]` runs a
+// normal upload session without opening the window, prints progress to stdout and
+// exits with a status code, so uploads can be scripted from other tools.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::commands::{ProgressState, Webhook};
+use crate::errors::{AppError, AppResult};
+use crate::security::InputValidator;
+use crate::uploader::{SessionManager, SessionOptions};
+use crate::{config, database};
+
+pub const EXIT_SUCCESS: i32 = 0;
+/// Some or all files failed to upload
+pub const EXIT_UPLOAD_FAILED: i32 = 1;
+/// Bad arguments or the session could not be started
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_CANCELLED: i32 = 3;
+
+pub const USAGE: &str =
+    "Usage: VRChat-Photo-Uploader --upload <files...> --webhook <name|id> [--no-group]";
+
+#[derive(Debug, PartialEq)]
+pub struct CliUpload {
+    pub files: Vec<String>,
+    pub webhook: String,
+    pub group_by_metadata: bool,
+}
+
+/// Parse headless upload arguments. None when `--upload` isn't present and the
+/// regular UI should start.
+pub fn parse_args(args: &[String]) -> Option<Result<CliUpload, String>> {
+    if !args.iter().any(|a| a == "--upload") {
+        return None;
+    }
+
+    let mut files = Vec::new();
+    let mut webhook = None;
+    let mut group_by_metadata = true;
+
+    let mut iter = args.iter().skip(1).peekable();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--upload" => {
+                while let Some(file) = iter.next_if(|a| !a.starts_with("--")) {
+                    files.push(file.clone());
+                }
+            }
+            "--webhook" => match iter.next_if(|a| !a.starts_with("--")) {
+                Some(value) => webhook = Some(value.clone()),
+                None => return Some(Err("--webhook needs a webhook name or ID".to_string())),
+            },
+            "--no-group" => group_by_metadata = false,
+            other => return Some(Err(format!("Unknown argument: {other}"))),
+        }
+    }
+
+    if files.is_empty() {
+        return Some(Err("--upload needs at least one file".to_string()));
+    }
+    let Some(webhook) = webhook else {
+        return Some(Err("--webhook is required".to_string()));
+    };
+
+    Some(Ok(CliUpload {
+        files,
+        webhook,
+        group_by_metadata,
+    }))
+}
+
+/// Match a webhook by ID or (case-insensitive) name
+pub(crate) fn find_webhook<'a>(webhooks: &'a [Webhook], query: &str) -> Option<&'a Webhook> {
+    if let Ok(id) = query.parse::<i64>() {
+        if let Some(webhook) = webhooks.iter().find(|w| w.id == id) {
+            return Some(webhook);
+        }
+    }
+    webhooks.iter().find(|w| w.name.eq_ignore_ascii_case(query))
+}
+
+/// Print to the console a GUI-subsystem build was started from
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // SAFETY: plain Win32 call without pointers; failure just means there's no console
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
+
+/// Run a headless upload and return the process exit code
+pub fn run(upload: CliUpload, context: tauri::Context<tauri::Wry>) -> i32 {
+    attach_parent_console();
+
+    let app = match tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
+        .manage(ProgressState::new(Mutex::new(HashMap::new())))
+        .build(context)
+    {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("Failed to initialize: {e}");
+            return EXIT_USAGE;
+        }
+    };
+    let app_handle = app.handle().clone();
+
+    tauri::async_runtime::block_on(async move {
+        match run_session(&app_handle, upload).await {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                EXIT_USAGE
+            }
+        }
+    })
+}
+
+async fn run_session(app_handle: &tauri::AppHandle, upload: CliUpload) -> AppResult<i32> {
+    use tauri::Manager;
+
+    database::init_database().await?;
+
+    let webhooks = database::get_all_webhooks().await?;
+    let webhook = find_webhook(&webhooks, &upload.webhook).ok_or_else(|| {
+        AppError::validation("webhook", &format!("No webhook named '{}'", upload.webhook))
+    })?;
+
+    let config = config::load_config()?;
+    let files: Vec<String> = upload
+        .files
+        .iter()
+        .map(|f| {
+            InputValidator::resolve_user_path(f)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+
+    let options = SessionOptions::from_config(
+        vec![webhook.id],
+        files.clone(),
+        upload.group_by_metadata,
+        &config,
+    );
+
+    println!("Uploading {} file(s) to '{}'...", files.len(), webhook.name);
+    let session_id = SessionManager::start_session(app_handle, options).await?;
+    let progress_state = app_handle.state::<ProgressState>().inner().clone();
+
+    let mut reported_successes = 0;
+    let mut reported_failures = 0;
+    let mut waiting_reported = false;
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let Some(progress) = progress_state
+            .lock()
+            .ok()
+            .and_then(|p| p.get(&session_id).cloned())
+        else {
+            eprintln!("Upload session disappeared");
+            return Ok(EXIT_UPLOAD_FAILED);
+        };
+
+        for file in progress.successful_uploads.iter().skip(reported_successes) {
+            println!(
+                "[{}/{}] Uploaded {file}",
+                progress.completed, progress.total_images
+            );
+        }
+        reported_successes = progress.successful_uploads.len();
+
+        for failure in progress.failed_uploads.iter().skip(reported_failures) {
+            println!("Failed {}: {}", failure.file_path, failure.error);
+        }
+        reported_failures = progress.failed_uploads.len();
+
+        match progress.session_status.as_str() {
+            "waiting_for_network" if !waiting_reported => {
+                println!("Waiting for network...");
+                waiting_reported = true;
+            }
+            "active" => waiting_reported = false,
+            "completed" | "failed" | "cancelled" => {
+                println!(
+                    "Done: {} uploaded, {} failed ({})",
+                    progress.successful_uploads.len(),
+                    progress.failed_uploads.len(),
+                    progress.session_status
+                );
+                return Ok(match progress.session_status.as_str() {
+                    "cancelled" => EXIT_CANCELLED,
+                    "completed" if progress.failed_uploads.is_empty() => EXIT_SUCCESS,
+                    _ => EXIT_UPLOAD_FAILED,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("uploader")
+            .chain(list.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_args_without_upload_starts_ui() {
+        assert!(parse_args(&args(&["photo.png"])).is_none());
+    }
+
+    #[test]
+    fn test_parse_args_upload() {
+        let parsed = parse_args(&args(&[
+            "--upload",
+            "a.png",
+            "b.png",
+            "--webhook",
+            "Photos",
+            "--no-group",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            parsed,
+            CliUpload {
+                files: vec!["a.png".to_string(), "b.png".to_string()],
+                webhook: "Photos".to_string(),
+                group_by_metadata: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&args(&["--upload", "--webhook", "Photos"]))
+            .unwrap()
+            .is_err());
+        assert!(parse_args(&args(&["--upload", "a.png"])).unwrap().is_err());
+        assert!(parse_args(&args(&["--upload", "a.png", "--webhook"]))
+            .unwrap()
+            .is_err());
+        assert!(
+            parse_args(&args(&["--upload", "a.png", "--webhook", "x", "--bogus"]))
+                .unwrap()
+                .is_err()
+        );
+    }
+}
@@ -1,10 +1,18 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use tauri::{Emitter, State};
+use tokio_util::sync::CancellationToken;
 
 use crate::security::InputValidator;
-use crate::{config, database, image_processor, metadata_editor, uploader};
+use crate::uploader::discord_client::{
+    DiscordClient, DiscordConnectionPool, DISCORD_MESSAGE_LIMIT,
+};
+use crate::uploader::progress_tracker::emit_session_progress;
+use crate::{
+    config, database, folder_scan, image_processor, legacy_import, metadata_editor,
+    metadata_report, photo_index, tags, thumbnail_cache, uploader,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Webhook {
@@ -13,11 +21,20 @@ pub struct Webhook {
     pub url: String,
     pub is_forum: bool,
     pub pinned: bool,
+    /// Default message flags for posts through this webhook
+    pub suppress_embeds: bool,
+    pub suppress_notifications: bool,
+    /// Attachment size limit of the webhook's server in MB; None = Discord's default
+    pub upload_limit_mb: Option<u32>,
+    /// Caption timestamp style ("f", "R", ...); None = the configured default
+    pub timestamp_style: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UploadRequest {
     pub webhook_ids: Vec<i64>,
+    /// May be omitted for `upload_custom_groups`, which takes files from the groups
+    #[serde(default)]
     pub file_paths: Vec<String>,
     pub group_by_metadata: bool,
     pub max_images_per_message: u8,
@@ -26,12 +43,98 @@ pub struct UploadRequest {
     pub grouping_time_window: u32,
     #[serde(default = "default_true")]
     pub group_by_world: bool,
+    /// Keep photos from different instances of the same world in separate groups
+    #[serde(default = "default_false")]
+    pub group_by_instance: bool,
     pub upload_quality: Option<u8>,
     pub compression_format: Option<String>,
     #[serde(default = "default_false")]
     pub single_thread_mode: bool,
     #[serde(default = "default_false")]
     pub merge_no_metadata: bool,
+    /// Put photos without metadata into the world group their timestamp falls in
+    #[serde(default = "default_false")]
+    pub adopt_orphans: bool,
+    #[serde(default = "default_false")]
+    pub dry_run: bool,
+    /// Custom captions keyed by group ID (as returned by the preview commands)
+    #[serde(default)]
+    pub caption_overrides: HashMap<String, String>,
+    /// Comment or hashtags appended to the first message of every group
+    #[serde(default)]
+    pub extra_message: Option<String>,
+    /// Time zone override for filename timestamps; falls back to the configured one
+    #[serde(default)]
+    pub screenshot_timezone: Option<String>,
+    /// Webhook ID per group ID (as returned by the preview commands), overriding the
+    /// session webhook for that group
+    #[serde(default)]
+    pub group_webhook_overrides: HashMap<String, i64>,
+    /// Post a final summary message; falls back to the configured default
+    #[serde(default)]
+    pub post_summary: Option<bool>,
+    /// Don't unfurl link embeds; falls back to each webhook's setting
+    #[serde(default)]
+    pub suppress_embeds: Option<bool>,
+    /// Post without notifications; falls back to each webhook's setting
+    #[serde(default)]
+    pub suppress_notifications: Option<bool>,
+    /// Upload preset whose grouping, caption and compression settings replace the ones above
+    #[serde(default)]
+    pub preset_id: Option<i64>,
+}
+
+/// Response of `upload_images`: a session ID, or a preview when `dry_run` is set
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum UploadResponse {
+    Session(String),
+    Preview(uploader::UploadPreview),
+}
+
+/// Options for `preview_discord_message`; mirrors the grouping/caption fields of `UploadRequest`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MessagePreviewOptions {
+    #[serde(default = "default_true")]
+    pub group_by_metadata: bool,
+    #[serde(default = "default_max_images")]
+    pub max_images_per_message: u8,
+    #[serde(default = "default_true")]
+    pub include_player_names: bool,
+    #[serde(default = "default_time_window")]
+    pub grouping_time_window: u32,
+    #[serde(default = "default_true")]
+    pub group_by_world: bool,
+    #[serde(default = "default_false")]
+    pub group_by_instance: bool,
+    #[serde(default = "default_false")]
+    pub merge_no_metadata: bool,
+    #[serde(default = "default_false")]
+    pub adopt_orphans: bool,
+    #[serde(default = "default_false")]
+    pub is_forum: bool,
+    #[serde(default = "default_false")]
+    pub single_thread_mode: bool,
+    #[serde(default)]
+    pub screenshot_timezone: Option<String>,
+}
+
+/// A group of files as arranged by the user, returned by `prepare_upload_groups`
+/// and accepted back by `upload_custom_groups`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomGroup {
+    pub group_id: String,
+    pub file_paths: Vec<String>,
+    /// Custom caption for this group, same as an entry in `caption_overrides`
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// Webhook for this group, same as an entry in `group_webhook_overrides`
+    #[serde(default)]
+    pub webhook_id: Option<i64>,
+}
+
+fn default_max_images() -> u8 {
+    10
 }
 
 fn default_false() -> bool {
@@ -42,10 +145,34 @@ fn default_time_window() -> u32 {
     10
 }
 
+fn default_forum_thread_age() -> u32 {
+    7
+}
+
 fn default_true() -> bool {
     true
 }
 
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_timestamp_style() -> String {
+    uploader::image_groups::DEFAULT_TIMESTAMP_STYLE.to_string()
+}
+
+fn default_min_upload_quality() -> u8 {
+    crate::uploader::compression_tiers::DEFAULT_MIN_QUALITY
+}
+
+fn default_min_upload_scale() -> u8 {
+    crate::uploader::compression_tiers::DEFAULT_MIN_SCALE_PERCENT
+}
+
+fn default_upload_verification() -> String {
+    uploader::verification::DEFAULT_UPLOAD_VERIFICATION.to_string()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UploadProgress {
     pub total_images: usize,
@@ -54,11 +181,71 @@ pub struct UploadProgress {
     pub current_progress: f32,
     pub failed_uploads: Vec<FailedUpload>,
     pub successful_uploads: Vec<String>,
-    pub session_status: String, // "active", "completed", "failed", "cancelled"
+    pub session_status: String, // "active", "waiting_for_network", "completed", "failed", "cancelled", "interrupted"
     pub estimated_time_remaining: Option<u64>, // seconds
     pub current_webhook_index: usize,
     pub total_webhooks: usize,
     pub current_webhook_name: String,
+    /// Size of every file to upload, counted once per webhook
+    #[serde(default)]
+    pub total_bytes: u64,
+    #[serde(default)]
+    pub uploaded_bytes: u64,
+    /// Measured upload throughput over the recent window, in MB/s
+    #[serde(default)]
+    pub upload_speed_mbps: Option<f64>,
+    /// (time, cumulative uploaded bytes) samples backing the throughput estimate
+    #[serde(skip)]
+    pub throughput_samples: VecDeque<(std::time::Instant, u64)>,
+    /// Cancelled when the session is, aborting in-flight requests and compression
+    #[serde(skip)]
+    pub cancel_token: CancellationToken,
+    /// Per-file state keyed by file path, for the current webhook
+    #[serde(default)]
+    pub files: HashMap<String, FileProgress>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilePhase {
+    Pending,
+    LoadingMetadata,
+    Preparing,
+    CreatingThread,
+    Compressing,
+    Uploading,
+    Completed,
+    Failed,
+}
+
+impl FilePhase {
+    /// Maps the phase labels used in `current_image` ("Compressing - foo.png") to a phase
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "Loading metadata" => Self::LoadingMetadata,
+            "Creating Thread" => Self::CreatingThread,
+            "Compressing" => Self::Compressing,
+            "Uploading" => Self::Uploading,
+            _ => Self::Preparing,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileProgress {
+    pub phase: FilePhase,
+    pub percent: f32,
+    pub error: Option<String>,
+}
+
+impl FileProgress {
+    pub fn pending() -> Self {
+        Self {
+            phase: FilePhase::Pending,
+            percent: 0.0,
+            error: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -69,11 +256,31 @@ pub struct FailedUpload {
     pub is_retryable: bool,
 }
 
+/// Upload options saved with a failed group so it can be retried after a restart
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailedGroupOptions {
+    #[serde(default = "default_max_images")]
+    pub max_images_per_message: u8,
+    #[serde(default = "default_true")]
+    pub include_player_names: bool,
+    #[serde(default)]
+    pub upload_quality: Option<u8>,
+    #[serde(default)]
+    pub compression_format: Option<String>,
+    #[serde(default)]
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub extra_message: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ImageMetadata {
     pub author: Option<AuthorInfo>,
     pub world: Option<WorldInfo>,
     pub players: Vec<PlayerInfo>,
+    /// Avatar the author was wearing, when the metadata records it
+    #[serde(default)]
+    pub avatar: Option<AvatarInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -82,6 +289,13 @@ pub struct AuthorInfo {
     pub id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AvatarInfo {
+    pub name: String,
+    #[serde(default)]
+    pub id: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorldInfo {
     pub name: String,
@@ -113,6 +327,8 @@ pub struct AppConfig {
     pub vrchat_path: Option<String>,
     pub single_thread_mode: bool,
     pub merge_no_metadata: bool,
+    #[serde(default)]
+    pub adopt_orphans: bool,
     pub default_forum_mode: bool,
     #[serde(default)]
     pub enable_multi_webhook: bool,
@@ -127,6 +343,54 @@ pub struct AppConfig {
     pub auto_upload_include_players: bool,
     pub auto_upload_merge_no_metadata: bool,
     pub auto_upload_ignored_folders: Vec<String>,
+    #[serde(default)]
+    pub screenshot_timezone: Option<String>,
+    #[serde(default)]
+    pub temp_directory: Option<String>,
+    #[serde(default)]
+    pub keep_original_filenames: bool,
+    #[serde(default = "default_true")]
+    pub show_upload_notifications: bool,
+    #[serde(default)]
+    pub post_upload_summary: bool,
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    #[serde(default)]
+    pub max_upload_dimension: Option<u32>,
+    #[serde(default = "default_min_upload_quality")]
+    pub min_upload_quality: u8,
+    #[serde(default = "default_min_upload_scale")]
+    pub min_upload_scale_percent: u8,
+    #[serde(default)]
+    pub compress_clips: bool,
+    #[serde(default)]
+    pub ffmpeg_path: Option<String>,
+    #[serde(default)]
+    pub collect_metrics: bool,
+    #[serde(default)]
+    pub include_avatar_in_caption: bool,
+    #[serde(default = "default_true")]
+    pub mention_mapped_players: bool,
+    #[serde(default = "default_true")]
+    pub restrict_caption_mentions: bool,
+    #[serde(default)]
+    pub link_player_profiles: bool,
+    #[serde(default)]
+    pub use_link_buttons: bool,
+    #[serde(default)]
+    pub reuse_forum_threads: bool,
+    #[serde(default = "default_forum_thread_age")]
+    pub forum_thread_max_age_days: u32,
+    #[serde(default)]
+    pub post_thread_closing_message: bool,
+    #[serde(default = "default_timestamp_style")]
+    pub discord_timestamp_style: String,
+    #[serde(default = "default_upload_verification")]
+    pub upload_verification: String,
+    #[serde(default)]
+    pub auto_launch: bool,
+    #[serde(default)]
+    pub roundup: crate::roundup::RoundupConfig,
 }
 
 // Progress state type (defined in main.rs, re-exported here for commands)
@@ -144,7 +408,6 @@ pub async fn retry_failed_group(
     _session_id: String,
     file_paths: Vec<String>,
     webhook_id: i64,
-    progress_state: State<'_, ProgressState>,
     app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
     // Validate inputs
@@ -158,79 +421,289 @@ pub async fn retry_failed_group(
 
     // Validate all file paths
     for file_path in &file_paths {
-        InputValidator::validate_image_file(file_path)?;
+        InputValidator::validate_upload_file(file_path)?;
     }
 
-    let webhook = database::get_webhook_by_id(webhook_id)
+    // Retried as one group, like a persisted group; 10 images is safe for forum channels
+    let options = uploader::SessionOptions {
+        webhook_ids: vec![webhook_id],
+        file_paths: file_paths.clone(),
+        group_by_metadata: true,
+        max_images_per_message: 10,
+        include_player_names: true,
+        grouping_time_window: 10,
+        group_by_world: true,
+        group_by_instance: false,
+        upload_quality: None,
+        compression_format: None,
+        single_thread_mode: false,
+        merge_no_metadata: false,
+        adopt_orphans: false,
+        caption_overrides: HashMap::new(),
+        extra_message: None,
+        screenshot_timezone: None,
+        custom_groups: Some(vec![CustomGroup {
+            group_id: "retry".to_string(),
+            file_paths,
+            caption: None,
+            webhook_id: None,
+        }]),
+        group_webhook_overrides: HashMap::new(),
+        post_summary: None,
+        suppress_embeds: None,
+        suppress_notifications: None,
+        forum_tag_ids: None,
+    };
+
+    let new_session_id = uploader::SessionManager::start_session(&app_handle, options)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Create new upload session for the retry
-    let new_session_id = uuid::Uuid::new_v4().to_string();
+    log::info!("Started group retry with session: {new_session_id}");
+    Ok(new_session_id)
+}
 
-    // Initialize progress for group retry
-    {
-        let mut progress = progress_state.lock().unwrap();
-        progress.insert(
-            new_session_id.clone(),
-            UploadProgress {
-                total_images: file_paths.len(),
-                completed: 0,
-                current_image: None,
-                current_progress: 0.0,
-                failed_uploads: Vec::new(),
-                successful_uploads: Vec::new(),
-                session_status: "active".to_string(),
-                estimated_time_remaining: None,
-                current_webhook_index: 0,
-                total_webhooks: 1,
-                current_webhook_name: String::new(),
-            },
-        );
+/// Discord message and attachment links recorded for a previously uploaded photo
+#[tauri::command]
+pub async fn get_uploaded_message_info(
+    file_path: String,
+) -> Result<Vec<database::UploadedMessageInfo>, String> {
+    database::get_uploaded_message_info(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether each file was uploaded before, and when and where. Files are matched by
+/// path first; the rest are hashed so copies and renamed photos are found too.
+#[tauri::command]
+pub async fn get_upload_status_for_files(
+    file_paths: Vec<String>,
+) -> Result<Vec<database::FileUploadStatus>, String> {
+    use tokio::sync::Semaphore;
+
+    let by_path: Vec<(String, Option<String>)> =
+        file_paths.into_iter().map(|path| (path, None)).collect();
+    let mut statuses = database::get_upload_status_for_files(&by_path)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let semaphore = Arc::new(Semaphore::new(num_cpus().min(8)));
+    let handles: Vec<_> = statuses
+        .iter()
+        .enumerate()
+        .filter(|(_, status)| !status.uploaded)
+        .map(|(index, status)| {
+            let semaphore = semaphore.clone();
+            let file_path = status.file_path.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.ok()?;
+                let hash = image_processor::get_file_hash(&file_path).await.ok()?;
+                Some((index, (file_path, Some(hash.tagged()))))
+            })
+        })
+        .collect();
+
+    let mut indices = Vec::new();
+    let mut by_hash = Vec::new();
+    for handle in handles {
+        if let Ok(Some((index, file))) = handle.await {
+            indices.push(index);
+            by_hash.push(file);
+        }
     }
 
-    // Create upload session in database
-    database::create_upload_session(new_session_id.clone(), webhook_id, file_paths.len() as i32)
+    let hash_matches = database::get_upload_status_for_files(&by_hash)
         .await
         .map_err(|e| e.to_string())?;
+    for (index, status) in indices.into_iter().zip(hash_matches) {
+        statuses[index] = status;
+    }
+
+    Ok(statuses)
+}
 
-    // Update webhook usage
-    database::update_webhook_usage(webhook_id)
+/// Delete the Discord message a photo was posted in. Every photo in that message is
+/// removed with it; their history entries are marked deleted and returned.
+#[tauri::command]
+pub async fn delete_uploaded_message(
+    pool: State<'_, DiscordConnectionPool>,
+    history_id: i64,
+) -> Result<Vec<String>, String> {
+    let entry = database::get_upload_history_message(history_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let message_id = entry
+        .message_id
+        .ok_or_else(|| "No Discord message was recorded for this upload".to_string())?;
+    let webhook = database::get_webhook_by_id(entry.webhook_id)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Start group retry process (with grouping enabled since it was a group failure)
-    let progress_state_clone = progress_state.inner().clone();
-    let new_session_id_clone = new_session_id.clone();
-    let app_handle_clone = app_handle.clone();
+    let client = DiscordClient::with_pool(&pool);
+    let thread_id = message_thread_id(&client, &webhook.url, entry.channel_id).await;
+    client
+        .delete_webhook_message(&webhook.url, &message_id, thread_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
 
-    tokio::spawn(async move {
-        uploader::process_upload_queue(
-            webhook,
-            file_paths,
-            true,  // group_by_metadata = true for group retry
-            10,    // max_images_per_message = 10 (safe for forum channels)
-            true,  // include_player_names = true (default for retries)
-            10,    // grouping_time_window = 10 minutes (default)
-            true,  // group_by_world = true (default)
-            None,  // upload_quality
-            None,  // compression_format
-            false, // single_thread_mode
-            false, // merge_no_metadata
-            progress_state_clone,
-            new_session_id_clone,
-            app_handle_clone,
-            true, // mark completed (single-webhook retry)
+    database::mark_message_deleted(&message_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the text of the Discord message a photo was posted in, e.g. to fix a
+/// caption typo or add a forgotten player tag
+#[tauri::command]
+pub async fn edit_uploaded_message(
+    pool: State<'_, DiscordConnectionPool>,
+    history_id: i64,
+    new_content: String,
+) -> Result<(), String> {
+    if new_content.trim().is_empty() {
+        return Err("Message content cannot be empty".to_string());
+    }
+    if new_content.chars().count() > DISCORD_MESSAGE_LIMIT {
+        return Err(format!(
+            "Message content must be {DISCORD_MESSAGE_LIMIT} characters or fewer"
+        ));
+    }
+
+    let entry = database::get_upload_history_message(history_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let message_id = entry
+        .message_id
+        .ok_or_else(|| "No Discord message was recorded for this upload".to_string())?;
+    let webhook = database::get_webhook_by_id(entry.webhook_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let client = DiscordClient::with_pool(&pool);
+    let thread_id = message_thread_id(&client, &webhook.url, entry.channel_id).await;
+    client
+        .edit_webhook_message(
+            &webhook.url,
+            &message_id,
+            thread_id.as_deref(),
+            &new_content,
         )
-        .await;
-    });
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    log::info!("Started group retry with session: {new_session_id}");
-    Ok(new_session_id)
+/// Messages posted in a forum post or thread have to be addressed with `thread_id`;
+/// that's any channel other than the one the webhook itself points at
+async fn message_thread_id(
+    client: &DiscordClient,
+    webhook_url: &str,
+    message_channel_id: Option<String>,
+) -> Option<String> {
+    let message_channel_id = message_channel_id?;
+    match client.get_webhook_channel_id(webhook_url).await {
+        Some(webhook_channel_id) if webhook_channel_id == message_channel_id => None,
+        _ => Some(message_channel_id),
+    }
+}
+
+/// A past upload session with its files grouped the way they were posted
+#[tauri::command]
+pub async fn get_upload_session_details(
+    session_id: String,
+) -> Result<database::UploadSessionDetails, String> {
+    database::get_upload_session_details(&session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Database file size, journal mode and migrations still to apply
+#[tauri::command]
+pub async fn get_database_status() -> Result<database::DatabaseStatus, String> {
+    database::get_database_status()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Failed groups persisted across restarts, most recent first
+#[tauri::command]
+pub async fn list_failed_groups() -> Result<Vec<database::FailedGroupRecord>, String> {
+    database::get_failed_groups()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Retry a persisted failed group as a new session, keeping its original grouping.
+/// The record is removed once the session starts; a repeat failure is persisted again.
+#[tauri::command]
+pub async fn retry_persisted_group(
+    id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let record = database::get_failed_group(id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut caption_overrides = HashMap::new();
+    if let Some(caption) = &record.options.caption {
+        caption_overrides.insert(record.group_id.clone(), caption.clone());
+    }
+
+    let options = uploader::SessionOptions {
+        webhook_ids: vec![record.webhook_id],
+        file_paths: record.file_paths.clone(),
+        group_by_metadata: true,
+        max_images_per_message: record.options.max_images_per_message,
+        include_player_names: record.options.include_player_names,
+        grouping_time_window: default_time_window(),
+        group_by_world: true,
+        group_by_instance: false,
+        upload_quality: record.options.upload_quality,
+        compression_format: record.options.compression_format.clone(),
+        single_thread_mode: false,
+        merge_no_metadata: false,
+        adopt_orphans: false,
+        caption_overrides,
+        extra_message: record.options.extra_message.clone(),
+        screenshot_timezone: None,
+        custom_groups: Some(vec![CustomGroup {
+            group_id: record.group_id.clone(),
+            file_paths: record.file_paths.clone(),
+            caption: None,
+            webhook_id: None,
+        }]),
+        group_webhook_overrides: HashMap::new(),
+        post_summary: None,
+        suppress_embeds: None,
+        suppress_notifications: None,
+        forum_tag_ids: None,
+    };
+
+    let session_id = uploader::SessionManager::start_session(&app_handle, options)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Err(e) = database::delete_failed_group(id).await {
+        log::warn!("Failed to remove persisted group {id} after retry: {e}");
+    }
+
+    log::info!("Retrying persisted group {id} with session: {session_id}");
+    Ok(session_id)
+}
+
+/// Dismiss a persisted failed group without retrying it
+#[tauri::command]
+pub async fn delete_failed_group(id: i64) -> Result<(), String> {
+    database::delete_failed_group(id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn add_webhook(name: String, url: String, is_forum: bool) -> Result<(), String> {
+pub async fn add_webhook(
+    app_handle: tauri::AppHandle,
+    name: String,
+    url: String,
+    is_forum: bool,
+) -> Result<(), String> {
     // Validate inputs
     InputValidator::validate_webhook_name(&name)?;
     InputValidator::validate_webhook_url(&url)?;
@@ -240,12 +713,15 @@ pub async fn add_webhook(name: String, url: String, is_forum: bool) -> Result<()
 
     database::insert_webhook(sanitized_name, url, is_forum)
         .await
-        .map(|_| ()) // Convert i64 to ()
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    crate::tray::refresh_webhook_menu(&app_handle).await;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn update_webhook(
+    app_handle: tauri::AppHandle,
     id: i64,
     name: String,
     url: String,
@@ -264,18 +740,24 @@ pub async fn update_webhook(
 
     database::update_webhook(id, sanitized_name, url, is_forum)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    crate::tray::refresh_webhook_menu(&app_handle).await;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn delete_webhook(id: i64) -> Result<(), String> {
+pub async fn delete_webhook(app_handle: tauri::AppHandle, id: i64) -> Result<(), String> {
     if id <= 0 {
         return Err("Invalid webhook ID".to_string());
     }
 
     database::delete_webhook(id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    crate::tray::refresh_webhook_menu(&app_handle).await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -289,12 +771,85 @@ pub async fn toggle_webhook_pin(id: i64) -> Result<bool, String> {
         .map_err(|e| e.to_string())
 }
 
+/// Set a webhook's default message flags (see `UploadRequest` for per-upload overrides)
 #[tauri::command]
-pub async fn upload_images(
-    request: UploadRequest,
-    app_handle: tauri::AppHandle,
-) -> Result<String, String> {
-    let options = uploader::SessionOptions {
+pub async fn set_webhook_message_flags(
+    id: i64,
+    suppress_embeds: bool,
+    suppress_notifications: bool,
+) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid webhook ID".to_string());
+    }
+
+    database::set_webhook_message_flags(id, suppress_embeds, suppress_notifications)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set the attachment size limit of a webhook's server (higher on boosted servers),
+/// used to compress payloads that won't fit before sending them
+#[tauri::command]
+pub async fn set_webhook_upload_limit(id: i64, upload_limit_mb: Option<u32>) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid webhook ID".to_string());
+    }
+    if upload_limit_mb.is_some_and(|mb| mb == 0 || mb > 500) {
+        return Err("Upload limit must be between 1 and 500 MB".to_string());
+    }
+
+    database::set_webhook_upload_limit(id, upload_limit_mb)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Override how caption timestamps render for one webhook (e.g. relative for a
+/// channel of recent events); None goes back to the configured default
+#[tauri::command]
+pub async fn set_webhook_timestamp_style(
+    id: i64,
+    timestamp_style: Option<String>,
+) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid webhook ID".to_string());
+    }
+    if timestamp_style
+        .as_deref()
+        .is_some_and(|style| !uploader::image_groups::TIMESTAMP_STYLES.contains(&style))
+    {
+        return Err("Unknown timestamp style".to_string());
+    }
+
+    database::set_webhook_timestamp_style(id, timestamp_style.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the request's settings with those of its preset, if it names one
+async fn apply_upload_preset(request: &mut UploadRequest) -> Result<(), String> {
+    let Some(preset_id) = request.preset_id else {
+        return Ok(());
+    };
+    let preset = database::get_upload_preset(preset_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Upload preset {preset_id} no longer exists"))?;
+
+    request.group_by_metadata = preset.group_by_metadata;
+    request.grouping_time_window = preset.grouping_time_window;
+    request.max_images_per_message = preset.max_images_per_message;
+    request.include_player_names = preset.include_player_names;
+    if preset.compression_format.is_some() {
+        request.compression_format = preset.compression_format;
+    }
+    if preset.upload_quality.is_some() {
+        request.upload_quality = preset.upload_quality;
+    }
+    Ok(())
+}
+
+fn session_options_from_request(request: UploadRequest) -> uploader::SessionOptions {
+    uploader::SessionOptions {
         webhook_ids: request.webhook_ids,
         file_paths: request.file_paths,
         group_by_metadata: request.group_by_metadata,
@@ -302,14 +857,181 @@ pub async fn upload_images(
         include_player_names: request.include_player_names,
         grouping_time_window: request.grouping_time_window,
         group_by_world: request.group_by_world,
+        group_by_instance: request.group_by_instance,
         upload_quality: request.upload_quality,
         compression_format: request.compression_format,
         single_thread_mode: request.single_thread_mode,
         merge_no_metadata: request.merge_no_metadata,
-    };
+        adopt_orphans: request.adopt_orphans,
+        caption_overrides: request.caption_overrides,
+        extra_message: request.extra_message,
+        screenshot_timezone: request.screenshot_timezone,
+        custom_groups: None,
+        group_webhook_overrides: request.group_webhook_overrides,
+        post_summary: request.post_summary,
+        suppress_embeds: request.suppress_embeds,
+        suppress_notifications: request.suppress_notifications,
+        forum_tag_ids: None,
+    }
+}
+
+fn session_options_from_template(
+    template: database::SessionTemplate,
+    file_paths: Vec<String>,
+) -> uploader::SessionOptions {
+    uploader::SessionOptions {
+        webhook_ids: template.webhook_ids,
+        file_paths,
+        group_by_metadata: template.group_by_metadata,
+        max_images_per_message: template.max_images_per_message,
+        include_player_names: template.include_player_names,
+        grouping_time_window: template.grouping_time_window,
+        group_by_world: template.group_by_world,
+        group_by_instance: template.group_by_instance,
+        upload_quality: template.upload_quality,
+        compression_format: template.compression_format,
+        single_thread_mode: template.single_thread_mode,
+        merge_no_metadata: template.merge_no_metadata,
+        adopt_orphans: template.adopt_orphans,
+        caption_overrides: HashMap::new(),
+        extra_message: template.extra_message,
+        screenshot_timezone: None,
+        custom_groups: None,
+        group_webhook_overrides: HashMap::new(),
+        post_summary: None,
+        suppress_embeds: None,
+        suppress_notifications: None,
+        forum_tag_ids: Some(template.forum_tag_ids).filter(|tags| !tags.is_empty()),
+    }
+}
+
+#[tauri::command]
+pub async fn upload_images(
+    mut request: UploadRequest,
+    app_handle: tauri::AppHandle,
+) -> Result<UploadResponse, String> {
+    apply_upload_preset(&mut request).await?;
+    uploader::image_groups::validate_extra_message(request.extra_message.as_deref())?;
+    let dry_run = request.dry_run;
+    let options = session_options_from_request(request);
+
+    if dry_run {
+        return uploader::SessionManager::preview_session(options)
+            .await
+            .map(UploadResponse::Preview)
+            .map_err(|e| e.to_string());
+    }
+
+    uploader::SessionManager::start_session(&app_handle, options)
+        .await
+        .map(UploadResponse::Session)
+        .map_err(|e| e.to_string())
+}
+
+/// Preview the exact Discord messages (captions, overflow messages, thread titles)
+/// that uploading these files would produce, without sending anything
+#[tauri::command]
+pub async fn preview_discord_message(
+    file_paths: Vec<String>,
+    options: MessagePreviewOptions,
+) -> Result<Vec<uploader::dry_run::GroupPreview>, String> {
+    if file_paths.is_empty() {
+        return Err("No files provided".to_string());
+    }
+    for file_path in &file_paths {
+        InputValidator::validate_upload_file(file_path)?;
+    }
+
+    uploader::dry_run::preview_messages(file_paths, &options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// How many groups and messages uploading `file_paths` would create, from cached
+/// metadata only, for a "this will create N posts" hint before uploading
+#[tauri::command]
+pub async fn estimate_groups(
+    file_paths: Vec<String>,
+    options: MessagePreviewOptions,
+) -> Result<uploader::dry_run::GroupEstimate, String> {
+    Ok(uploader::dry_run::estimate_groups(file_paths, &options).await)
+}
+
+/// Warnings for an upload that would split into many single-photo posts, checked
+/// before starting it
+#[tauri::command]
+pub async fn validate_upload_plan(
+    file_paths: Vec<String>,
+    options: MessagePreviewOptions,
+) -> Result<uploader::dry_run::UploadPlanValidation, String> {
+    Ok(uploader::dry_run::validate_upload_plan(file_paths, &options).await)
+}
+
+/// First phase of a manual upload: propose groups for the files so the user can move
+/// files between groups, merge or split them before calling `upload_custom_groups`
+#[tauri::command]
+pub async fn prepare_upload_groups(
+    file_paths: Vec<String>,
+    options: MessagePreviewOptions,
+) -> Result<Vec<CustomGroup>, String> {
+    let previews = preview_discord_message(file_paths, options).await?;
+
+    Ok(previews
+        .into_iter()
+        .map(|preview| CustomGroup {
+            group_id: preview.group_id,
+            file_paths: preview.file_paths,
+            caption: None,
+            webhook_id: None,
+        })
+        .collect())
+}
+
+/// Second phase of a manual upload: upload user-arranged groups as-is. Grouping fields
+/// of `request` are ignored and its `file_paths` are replaced by the groups' files.
+#[tauri::command]
+pub async fn upload_custom_groups(
+    groups: Vec<CustomGroup>,
+    mut request: UploadRequest,
+    app_handle: tauri::AppHandle,
+) -> Result<UploadResponse, String> {
+    if groups.is_empty() {
+        return Err("No groups provided".to_string());
+    }
+
+    request.file_paths = groups
+        .iter()
+        .flat_map(|g| g.file_paths.iter().cloned())
+        .collect();
+    for group in &groups {
+        if let Some(caption) = &group.caption {
+            request
+                .caption_overrides
+                .insert(group.group_id.clone(), caption.clone());
+        }
+        if let Some(webhook_id) = group.webhook_id {
+            request
+                .group_webhook_overrides
+                .insert(group.group_id.clone(), webhook_id);
+        }
+    }
+
+    apply_upload_preset(&mut request).await?;
+    uploader::image_groups::validate_extra_message(request.extra_message.as_deref())?;
+    let dry_run = request.dry_run;
+    let mut options = session_options_from_request(request);
+    options.custom_groups = Some(groups);
+
+    if dry_run {
+        return uploader::SessionManager::preview_session(options)
+            .await
+            .map(UploadResponse::Preview)
+            .map_err(|e| e.to_string());
+    }
 
     uploader::SessionManager::start_session(&app_handle, options)
         .await
+        .map(UploadResponse::Session)
         .map_err(|e| e.to_string())
 }
 
@@ -322,6 +1044,53 @@ pub async fn get_upload_progress(
     Ok(progress.get(&session_id).cloned())
 }
 
+/// Write a session's event log to `path` for bug reports: JSON when the path ends
+/// in `.json`, a plain-text report otherwise
+#[tauri::command]
+pub async fn export_session_log(session_id: String, path: String) -> Result<(), String> {
+    let log = uploader::session_log::get_session_log(&session_id)
+        .ok_or_else(|| format!("No log recorded for session {session_id}"))?;
+
+    let is_json = std::path::Path::new(&path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let contents = if is_json {
+        serde_json::to_string_pretty(&log).map_err(|e| e.to_string())?
+    } else {
+        uploader::session_log::render_text(&log)
+    };
+    // Logs are attached to bug reports, so webhook tokens are masked
+    let contents = crate::security::redact_webhook_tokens(&contents);
+
+    std::fs::write(&path, contents.as_bytes()).map_err(|e| e.to_string())?;
+    log::info!("Exported log for session {session_id} to {path}");
+    Ok(())
+}
+
+/// Write a CSV or JSON report of the photos' metadata (file, world, players,
+/// timestamp, size) to `output_path`
+#[tauri::command]
+pub async fn export_metadata_report(
+    file_paths: Vec<String>,
+    format: String,
+    output_path: String,
+) -> Result<metadata_report::ReportSummary, String> {
+    if file_paths.is_empty() {
+        return Err("No files selected for the report".to_string());
+    }
+    let format = metadata_report::ReportFormat::parse(&format)?;
+    let output_path = InputValidator::resolve_user_path(&output_path);
+
+    Ok(metadata_report::export_metadata_report(&file_paths, format, &output_path).await?)
+}
+
+/// Bring over webhooks and upload history from the old Python uploader, given its
+/// database, JSON history file or data folder
+#[tauri::command]
+pub async fn import_python_history(path: String) -> Result<database::LegacyImportSummary, String> {
+    Ok(legacy_import::import_python_history(&path).await?)
+}
+
 #[tauri::command]
 pub async fn retry_failed_upload(
     session_id: String,
@@ -344,8 +1113,25 @@ pub async fn retry_failed_upload(
     let progress_state_clone = progress_state.inner().clone();
     let session_id_clone = session_id.clone();
     let app_handle_clone = app_handle.clone();
+    // Tracked apart from its session, which may have finished or still be running
+    let running_retry = uploader::shutdown::track_session(
+        &format!("{session_id}:{file_path}"),
+        uploader::shutdown::SessionCheckpoint {
+            webhook_ids: vec![webhook.id],
+            file_paths: vec![file_path.clone()],
+            options: FailedGroupOptions {
+                max_images_per_message: 1,
+                include_player_names: true,
+                upload_quality: None,
+                compression_format: None,
+                caption: None,
+                extra_message: None,
+            },
+        },
+    );
 
     tokio::spawn(async move {
+        let _running_retry = running_retry;
         uploader::retry_single_upload(
             webhook,
             None, // upload_quality
@@ -365,7 +1151,8 @@ pub async fn retry_failed_upload(
 pub async fn get_image_metadata(file_path: String) -> Result<Option<ImageMetadata>, String> {
     InputValidator::validate_image_file(&file_path)?;
 
-    image_processor::extract_metadata(&file_path)
+    let timezone = image_processor::configured_screenshot_timezone();
+    image_processor::extract_metadata(&file_path, timezone)
         .await
         .map_err(|e| e.to_string())
 }
@@ -378,7 +1165,8 @@ pub async fn get_image_metadata_with_source(
 ) -> Result<image_processor::MetadataWithSource, String> {
     InputValidator::validate_image_file(&file_path)?;
 
-    image_processor::extract_metadata_with_source(&file_path)
+    let timezone = image_processor::configured_screenshot_timezone();
+    image_processor::extract_metadata_with_source(&file_path, timezone)
         .await
         .map_err(|e| e.to_string())
 }
@@ -387,10 +1175,42 @@ pub async fn get_image_metadata_with_source(
 pub async fn update_image_metadata(
     file_path: String,
     metadata: ImageMetadata,
-) -> Result<String, String> {
+    as_sidecar: Option<bool>,
+) -> Result<metadata_editor::MetadataEditResult, String> {
+    InputValidator::validate_image_file(&file_path)?;
+
+    let result = if as_sidecar.unwrap_or(false) {
+        metadata_editor::export_sidecar_metadata(&file_path, metadata).await
+    } else {
+        metadata_editor::embed_metadata(&file_path, metadata).await
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Re-read a file after embedding and report where it differs from `expected`
+#[tauri::command]
+pub async fn verify_embedded_metadata(
+    file_path: String,
+    expected: ImageMetadata,
+) -> Result<metadata_editor::MetadataVerification, String> {
     InputValidator::validate_image_file(&file_path)?;
 
-    metadata_editor::embed_metadata(&file_path, metadata)
+    metadata_editor::verify_embedded_metadata(&file_path, &expected)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recent metadata editor outputs, newest first
+#[tauri::command]
+pub async fn get_metadata_edits(limit: Option<i64>) -> Result<Vec<database::MetadataEdit>, String> {
+    database::get_metadata_edits(limit.unwrap_or(20))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn undo_metadata_edit(edit_id: i64) -> Result<String, String> {
+    metadata_editor::undo_metadata_edit(edit_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -415,6 +1235,8 @@ pub async fn get_image_info(file_path: String) -> Result<(u32, u32, u64), String
     image_processor::get_image_info(&file_path).map_err(|e| e.to_string())
 }
 
+/// Dimensions and size of each file, or None for files that can't be uploaded.
+/// Video clips aren't decoded, so they report 0×0.
 #[tauri::command]
 pub async fn get_image_info_batch(
     file_paths: Vec<String>,
@@ -438,8 +1260,18 @@ pub async fn get_image_info_batch(
             tokio::spawn(async move {
                 let _permit = sem.acquire().await.unwrap();
                 let result = tokio::task::spawn_blocking(move || {
-                    let result = InputValidator::validate_image_file(&file_path)
-                        .and_then(|_| image_processor::get_image_info(&file_path));
+                    let result = if crate::security::is_video_file(&file_path) {
+                        InputValidator::validate_upload_file(&file_path).and_then(|_| {
+                            Ok((
+                                0,
+                                0,
+                                crate::security::FileSystemGuard::get_file_size(&file_path)?,
+                            ))
+                        })
+                    } else {
+                        InputValidator::validate_image_file(&file_path)
+                            .and_then(|_| image_processor::get_image_info(&file_path))
+                    };
                     match result {
                         Ok(info) => (file_path, Some(info)),
                         Err(e) => {
@@ -506,6 +1338,28 @@ pub async fn generate_thumbnail(file_path: String) -> Result<String, String> {
     .map_err(|e| e.to_string())?
 }
 
+/// Pre-upload editor: rotate clockwise, returns the edited temp file
+#[tauri::command]
+pub async fn rotate_image(file_path: String, degrees: u32) -> Result<String, String> {
+    image_processor::rotate_image(&file_path, degrees)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Pre-upload editor: crop to a pixel rectangle, returns the edited temp file
+#[tauri::command]
+pub async fn crop_image(
+    file_path: String,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<String, String> {
+    image_processor::crop_image(&file_path, x, y, width, height)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn generate_thumbnails_batch(
     file_paths: Vec<String>,
@@ -608,8 +1462,29 @@ pub async fn save_app_config(
 
     let enable_auto = config.enable_auto_upload;
     let vrchat_path = config.vrchat_path.clone();
+    let log_level = config.log_level.clone();
 
     config::save_config(config).map_err(|e| e.to_string())?;
+    apply_runtime_settings(
+        &log_level,
+        enable_auto,
+        vrchat_path,
+        &watcher_state,
+        app_handle,
+    );
+
+    Ok(())
+}
+
+/// Bring the log level and background watcher in line with newly saved settings
+fn apply_runtime_settings(
+    log_level: &str,
+    enable_auto: bool,
+    vrchat_path: Option<String>,
+    watcher_state: &Mutex<crate::background_watcher::BackgroundWatcher>,
+    app_handle: tauri::AppHandle,
+) {
+    crate::logging::set_level(log_level);
 
     // Manage background watcher
     if let Ok(mut watcher) = watcher_state.lock() {
@@ -625,10 +1500,87 @@ pub async fn save_app_config(
             watcher.stop();
         }
     }
+}
+
+#[tauri::command]
+pub async fn list_profiles() -> Result<config::ProfileList, String> {
+    config::list_profiles().map_err(|e| e.to_string())
+}
+
+/// Save the current settings as a named profile
+#[tauri::command]
+pub async fn save_profile(name: String) -> Result<(), String> {
+    config::save_profile(&name).map_err(|e| e.to_string())
+}
+
+/// Switch to another settings profile, returning its settings for the UI
+#[tauri::command]
+pub async fn switch_profile(
+    name: String,
+    watcher_state: State<'_, Mutex<crate::background_watcher::BackgroundWatcher>>,
+    app_handle: tauri::AppHandle,
+) -> Result<AppConfig, String> {
+    let config = config::switch_profile(&name).map_err(|e| e.to_string())?;
+    apply_runtime_settings(
+        &config.log_level,
+        config.enable_auto_upload,
+        config.vrchat_path.clone(),
+        &watcher_state,
+        app_handle,
+    );
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn delete_profile(name: String) -> Result<(), String> {
+    config::delete_profile(&name).map_err(|e| e.to_string())
+}
+
+/// Files handed to the app on the command line or forwarded by a second launch,
+/// waiting to be added to the upload queue
+#[tauri::command]
+pub async fn take_pending_files() -> Result<Vec<String>, String> {
+    Ok(crate::single_instance::take_pending_files())
+}
+
+/// Whether the Explorer "Send to VRChat Photo Uploader" entry is registered
+#[tauri::command]
+pub async fn get_context_menu_integration() -> Result<bool, String> {
+    Ok(crate::shell_integration::is_context_menu_registered())
+}
+
+/// Opt in to (or remove) the Explorer context-menu entry for image files
+#[tauri::command]
+pub async fn set_context_menu_integration(enabled: bool) -> Result<(), String> {
+    if enabled {
+        crate::shell_integration::register_context_menu()
+    } else {
+        crate::shell_integration::unregister_context_menu()
+    }
+    .map_err(|e| e.to_string())
+}
 
+/// Start (or stop starting) minimized to the tray on login, and remember the choice
+#[tauri::command]
+pub async fn set_auto_launch(enabled: bool) -> Result<(), String> {
+    if enabled {
+        crate::autostart::enable_auto_launch()?;
+    } else {
+        crate::autostart::disable_auto_launch()?;
+    }
+
+    let mut config = config::load_config()?;
+    config.auto_launch = enabled;
+    config::save_config(config)?;
     Ok(())
 }
 
+/// Tail of the application log files for the in-app log viewer
+#[tauri::command]
+pub async fn get_recent_logs(lines: usize) -> Result<Vec<String>, String> {
+    crate::logging::read_recent_lines(lines.min(5000)).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn cleanup_old_data(days: i32) -> Result<(u64, u64), String> {
     if days <= 0 {
@@ -643,18 +1595,115 @@ pub async fn cleanup_old_data(days: i32) -> Result<(u64, u64), String> {
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok((sessions_cleaned, history_cleaned))
+    Ok((sessions_cleaned, history_cleaned))
+}
+
+#[tauri::command]
+pub async fn get_file_hash(file_path: String) -> Result<image_processor::FileHash, String> {
+    InputValidator::validate_image_file(&file_path)?;
+
+    image_processor::get_file_hash(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List images in a folder, emitting them in `folder-scan-batch` events as they're found.
+/// `since` (Unix milliseconds) limits the scan to files modified after it.
+#[tauri::command]
+pub async fn scan_folder(
+    path: String,
+    recursive: bool,
+    since: Option<i64>,
+    scan_id: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<folder_scan::FolderScanSummary, String> {
+    let scan_id = scan_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    tokio::task::spawn_blocking(move || {
+        folder_scan::scan_folder(
+            std::path::Path::new(&path),
+            recursive,
+            since,
+            folder_scan::SCAN_BATCH_SIZE,
+            |files| {
+                let batch = folder_scan::FolderScanBatch {
+                    scan_id: scan_id.clone(),
+                    files,
+                };
+                app_handle.emit("folder-scan-batch", &batch).ok();
+            },
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Delete all cached thumbnails, returning how many were removed
+#[tauri::command]
+pub async fn clear_thumbnail_cache() -> Result<usize, String> {
+    tokio::task::spawn_blocking(thumbnail_cache::clear)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// First-run setup: find the VRChat screenshots folder and VRCX, sample recent
+/// screenshots and suggest settings for them. Nothing is saved.
+#[tauri::command]
+pub async fn detect_environment() -> Result<crate::environment::EnvironmentReport, String> {
+    Ok(crate::environment::detect_environment().await)
 }
 
+/// Database, settings, folders, network and webhooks in one report, so problems can
+/// be shown at startup instead of only being logged
 #[tauri::command]
-pub async fn get_file_hash(file_path: String) -> Result<String, String> {
-    InputValidator::validate_image_file(&file_path)?;
+pub async fn run_health_check(
+    app_handle: tauri::AppHandle,
+) -> Result<crate::health_check::HealthReport, String> {
+    let client = uploader::discord_client::DiscordClient::for_app(&app_handle);
+    Ok(crate::health_check::run_health_check(&client).await)
+}
 
-    image_processor::get_file_hash(&file_path)
+/// Upload phase timings recorded while metrics collection is on, summed per phase
+#[tauri::command]
+pub async fn get_performance_metrics() -> Result<uploader::metrics::PerformanceMetrics, String> {
+    uploader::metrics::performance_metrics()
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Size of the uploader's temp folder and how much of it was left behind by uploads
+/// that are no longer running
+#[tauri::command]
+pub async fn get_temp_usage() -> Result<crate::temp_manifest::TempUsage, String> {
+    tokio::task::spawn_blocking(|| {
+        crate::temp_manifest::temp_usage(&crate::security::FileSystemGuard::get_temp_directory())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Pre-flight check that the temp directory's drive has room for compressing these
+/// files; `compression_format` defaults to the configured one
+#[tauri::command]
+pub async fn check_upload_disk_space(
+    file_paths: Vec<String>,
+    compression_format: Option<String>,
+) -> Result<uploader::disk_space::DiskSpaceCheck, String> {
+    let compression_format = compression_format.unwrap_or_else(|| {
+        crate::config::load_config()
+            .map(|c| c.compression_format)
+            .unwrap_or_else(|_| "webp".to_string())
+    });
+
+    tokio::task::spawn_blocking(move || {
+        uploader::disk_space::check_disk_space(&file_paths, &compression_format)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn cleanup_temp_files(temp_filenames: Vec<String>) -> Result<(), String> {
     let temp_dir = std::env::temp_dir();
@@ -697,7 +1746,8 @@ pub async fn debug_extract_metadata(file_path: String) -> Result<String, String>
 
     log::info!("DEBUG: Starting detailed metadata extraction for {file_path}");
 
-    match image_processor::extract_metadata(&file_path).await {
+    let timezone = image_processor::configured_screenshot_timezone();
+    match image_processor::extract_metadata(&file_path, timezone).await {
         Ok(Some(metadata)) => {
             let debug_info = format!(
                 "SUCCESS: Metadata extracted successfully!\n\
@@ -788,18 +1838,16 @@ pub async fn cancel_upload_session(
 
     let mut progress = progress_state.lock().unwrap();
 
-    if let Some(session_progress) = progress.get_mut(&session_id) {
-        // Only cancel if session is currently active
-        if session_progress.session_status == "active" {
+    let result = if let Some(session_progress) = progress.get_mut(&session_id) {
+        // Only cancel if session is currently active (or parked waiting for network)
+        if session_progress.session_status == "active"
+            || session_progress.session_status == "waiting_for_network"
+        {
             session_progress.session_status = "cancelled".to_string();
             session_progress.estimated_time_remaining = Some(0);
+            session_progress.cancel_token.cancel();
 
             log::info!("Upload session {session_id} marked as cancelled");
-
-            // Emit events to notify frontend
-            app_handle.emit("upload-cancelled", &session_id).ok();
-            app_handle.emit("upload-progress", &session_id).ok();
-
             Ok(())
         } else {
             log::warn!(
@@ -815,7 +1863,15 @@ pub async fn cancel_upload_session(
     } else {
         log::warn!("Attempted to cancel non-existent session: {session_id}");
         Err("Session not found".to_string())
+    };
+    drop(progress);
+
+    if result.is_ok() {
+        // Emit events to notify frontend
+        app_handle.emit("upload-cancelled", &session_id).ok();
+        emit_session_progress(&app_handle, &progress_state, &session_id);
     }
+    result
 }
 
 #[tauri::command]
@@ -899,6 +1955,206 @@ pub async fn add_user_webhook_override(
         .map_err(|e| e.to_string())
 }
 
+/// Upload presets saved for a webhook, default first
+#[tauri::command]
+pub async fn get_presets(webhook_id: i64) -> Result<Vec<database::UploadPreset>, String> {
+    database::get_upload_presets(webhook_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create or update an upload preset, returning its ID
+#[tauri::command]
+pub async fn save_preset(mut preset: database::UploadPreset) -> Result<i64, String> {
+    if preset.webhook_id <= 0 {
+        return Err("Invalid webhook ID".to_string());
+    }
+    preset.name = preset.name.trim().to_string();
+    if preset.name.is_empty() || preset.name.chars().count() > 100 {
+        return Err("Preset name must be between 1 and 100 characters".to_string());
+    }
+    InputValidator::validate_upload_settings(
+        preset.max_images_per_message,
+        preset.group_by_metadata,
+    )?;
+    if preset.upload_quality.is_some_and(|q| q == 0 || q > 100) {
+        return Err("Upload quality must be between 1 and 100".to_string());
+    }
+    if preset
+        .compression_format
+        .as_deref()
+        .is_some_and(|format| !crate::config::COMPRESSION_FORMATS.contains(&format))
+    {
+        return Err("Unknown compression format".to_string());
+    }
+
+    database::save_upload_preset(&preset)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_preset(id: i64) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid preset ID".to_string());
+    }
+
+    database::delete_upload_preset(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Saved session templates, by name
+#[tauri::command]
+pub async fn get_session_templates() -> Result<Vec<database::SessionTemplate>, String> {
+    database::get_session_templates()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Create or update a session template, returning its ID
+#[tauri::command]
+pub async fn save_session_template(mut template: database::SessionTemplate) -> Result<i64, String> {
+    template.name = template.name.trim().to_string();
+    if template.name.is_empty() || template.name.chars().count() > 100 {
+        return Err("Template name must be between 1 and 100 characters".to_string());
+    }
+    if template.webhook_ids.is_empty() || template.webhook_ids.iter().any(|&id| id <= 0) {
+        return Err("Choose at least one webhook for the template".to_string());
+    }
+    InputValidator::validate_upload_settings(
+        template.max_images_per_message,
+        template.group_by_metadata,
+    )?;
+    if template.upload_quality.is_some_and(|q| q == 0 || q > 100) {
+        return Err("Upload quality must be between 1 and 100".to_string());
+    }
+    if template
+        .compression_format
+        .as_deref()
+        .is_some_and(|format| !crate::config::COMPRESSION_FORMATS.contains(&format))
+    {
+        return Err("Unknown compression format".to_string());
+    }
+    template.extra_message = template
+        .extra_message
+        .map(|extra| extra.trim().to_string())
+        .filter(|extra| !extra.is_empty());
+    uploader::image_groups::validate_extra_message(template.extra_message.as_deref())?;
+    template.forum_tag_ids = template
+        .forum_tag_ids
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if template
+        .forum_tag_ids
+        .iter()
+        .any(|tag| !tag.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err("Tag IDs must be Discord IDs (digits only)".to_string());
+    }
+
+    database::save_session_template(&template)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_session_template(id: i64) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid template ID".to_string());
+    }
+
+    database::delete_session_template(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Upload `file_paths` with a saved template's webhooks and settings
+#[tauri::command]
+pub async fn start_session_from_template(
+    template_id: i64,
+    file_paths: Vec<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let template = database::get_session_template(template_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Session template {template_id} no longer exists"))?;
+
+    log::info!(
+        "Starting session from template '{}' with {} file(s)",
+        template.name,
+        file_paths.len()
+    );
+    let options = session_options_from_template(template, file_paths);
+    uploader::SessionManager::start_session(&app_handle, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_webhook_forum_tags(webhook_id: i64) -> Result<Vec<database::ForumTag>, String> {
+    database::get_webhook_forum_tags(webhook_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Apply a forum tag to threads the webhook creates, only for photos from `world_id` if given
+#[tauri::command]
+pub async fn add_webhook_forum_tag(
+    webhook_id: i64,
+    tag_id: String,
+    world_id: Option<String>,
+) -> Result<i64, String> {
+    if webhook_id <= 0 {
+        return Err("Invalid webhook ID".to_string());
+    }
+
+    // Tags are addressed by their snowflake ID (Developer Mode → Copy Tag ID)
+    let tag_id = tag_id.trim();
+    if tag_id.is_empty() || !tag_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Tag ID must be a Discord ID (digits only)".to_string());
+    }
+    let world_id = world_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty());
+
+    database::add_webhook_forum_tag(webhook_id, tag_id, world_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_webhook_forum_tag(id: i64) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid forum tag ID".to_string());
+    }
+
+    database::delete_webhook_forum_tag(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Post a test thread through a forum webhook and check each step of thread_id
+/// handling, deleting the test messages afterwards
+#[tauri::command]
+pub async fn diagnose_forum_webhook(
+    webhook_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<uploader::forum_diagnostics::ForumDiagnosticReport, String> {
+    if webhook_id <= 0 {
+        return Err("Invalid webhook ID".to_string());
+    }
+
+    let client = uploader::discord_client::DiscordClient::for_app(&app_handle);
+    uploader::forum_diagnostics::diagnose_forum_webhook(&client, webhook_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn delete_user_webhook_override(id: i64) -> Result<(), String> {
     if id <= 0 {
@@ -966,3 +2222,226 @@ pub async fn delete_discord_user_mapping(id: i64) -> Result<(), String> {
         .await
         .map_err(|e| e.to_string())
 }
+
+// Collection Commands (user-curated sets of photos)
+
+fn validate_collection_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Collection name cannot be empty".to_string());
+    }
+    if name.chars().count() > 100 {
+        return Err("Collection name must be 100 characters or less".to_string());
+    }
+    Ok(name.to_string())
+}
+
+#[tauri::command]
+pub async fn get_collections() -> Result<Vec<database::Collection>, String> {
+    database::get_collections().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_collection(name: String, description: Option<String>) -> Result<i64, String> {
+    let name = validate_collection_name(&name)?;
+
+    database::create_collection(&name, description.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_collection(
+    id: i64,
+    name: String,
+    description: Option<String>,
+) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid collection ID".to_string());
+    }
+    let name = validate_collection_name(&name)?;
+
+    database::update_collection(id, &name, description.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_collection(id: i64) -> Result<(), String> {
+    if id <= 0 {
+        return Err("Invalid collection ID".to_string());
+    }
+
+    database::delete_collection(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Files in a collection, e.g. to queue the whole collection for upload
+#[tauri::command]
+pub async fn get_collection_items(collection_id: i64) -> Result<Vec<String>, String> {
+    database::get_collection_items(collection_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_to_collection(collection_id: i64, file_paths: Vec<String>) -> Result<u64, String> {
+    for file_path in &file_paths {
+        InputValidator::validate_file_path(file_path)?;
+    }
+
+    database::add_to_collection(collection_id, &file_paths)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_from_collection(
+    collection_id: i64,
+    file_paths: Vec<String>,
+) -> Result<u64, String> {
+    database::remove_from_collection(collection_id, &file_paths)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Tag Commands
+
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    tags.iter()
+        .map(|tag| tags::normalize_tag(tag).map_err(|e| e.to_string()))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn tag_files(file_paths: Vec<String>, tags: Vec<String>) -> Result<u64, String> {
+    for file_path in &file_paths {
+        InputValidator::validate_file_path(file_path)?;
+    }
+    let tags = normalize_tags(&tags)?;
+
+    database::tag_files(&file_paths, &tags, tags::TAG_SOURCE_MANUAL)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn untag_files(file_paths: Vec<String>, tags: Vec<String>) -> Result<u64, String> {
+    let tags = normalize_tags(&tags)?;
+
+    database::untag_files(&file_paths, &tags)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Files tagged `tag`, e.g. to queue "everything tagged X" for upload
+#[tauri::command]
+pub async fn get_files_by_tag(tag: String) -> Result<Vec<String>, String> {
+    let tag = tags::normalize_tag(&tag).map_err(|e| e.to_string())?;
+
+    database::get_files_by_tag(&tag)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_file_tags(file_path: String) -> Result<Vec<String>, String> {
+    database::get_tags_for_file(&file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_all_tags() -> Result<Vec<database::TagCount>, String> {
+    database::get_all_tags().await.map_err(|e| e.to_string())
+}
+
+/// Tag files with `world:` and `player:` tags from their metadata; returns how many tags were added
+#[tauri::command]
+pub async fn auto_tag_files(file_paths: Vec<String>) -> Result<u64, String> {
+    let timezone = image_processor::configured_screenshot_timezone();
+    let mut added = 0;
+    for file_path in &file_paths {
+        InputValidator::validate_file_path(file_path)?;
+
+        let metadata = match image_processor::extract_metadata(file_path, timezone).await {
+            Ok(Some(metadata)) => metadata,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Skipping auto-tagging for {file_path}: {e}");
+                continue;
+            }
+        };
+
+        let derived = tags::metadata_tags(&metadata);
+        added += database::tag_files(
+            std::slice::from_ref(file_path),
+            &derived,
+            tags::TAG_SOURCE_METADATA,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(added)
+}
+
+/// Search photos whose metadata has been extracted by world, player or file name
+#[tauri::command]
+pub async fn search_photos(query: String) -> Result<Vec<database::PhotoSearchResult>, String> {
+    let Some(fts_query) = photo_index::build_search_query(&query) else {
+        return Ok(Vec::new());
+    };
+
+    database::search_photos(&fts_query, photo_index::SEARCH_RESULT_LIMIT)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Who appears most in the user's photos, from metadata indexed so far
+#[tauri::command]
+pub async fn get_player_stats() -> Result<Vec<photo_index::PlayerStats>, String> {
+    let photos = database::get_indexed_photos()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(photo_index::player_stats(&photos))
+}
+
+/// Most photographed worlds, from metadata indexed so far
+#[tauri::command]
+pub async fn get_world_stats() -> Result<Vec<photo_index::WorldStats>, String> {
+    let photos = database::get_indexed_photos()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(photo_index::world_stats(&photos))
+}
+
+/// Discord's limit on attachment descriptions
+const MAX_PHOTO_DESCRIPTION_LENGTH: usize = 1024;
+
+/// Set the alt text sent with a photo when it's uploaded; an empty description clears it
+#[tauri::command]
+pub async fn set_photo_description(file_path: String, description: String) -> Result<(), String> {
+    InputValidator::validate_file_path(&file_path)?;
+
+    let description = description.trim();
+    if description.chars().count() > MAX_PHOTO_DESCRIPTION_LENGTH {
+        return Err(format!(
+            "Descriptions must be {MAX_PHOTO_DESCRIPTION_LENGTH} characters or less"
+        ));
+    }
+
+    database::set_photo_description(&file_path, Some(description).filter(|d| !d.is_empty()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_photo_descriptions(
+    file_paths: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    database::get_photo_descriptions(&file_paths)
+        .await
+        .map_err(|e| e.to_string())
+}
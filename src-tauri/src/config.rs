@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::commands::AppConfig;
 use crate::errors::{AppError, AppResult};
+use crate::roundup::RoundupConfig;
+use crate::security::InputValidator;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -33,6 +35,9 @@ pub struct Config {
     pub single_thread_mode: bool,
     #[serde(default = "default_false_config")]
     pub merge_no_metadata: bool,
+    /// Put photos without metadata into the world group their timestamp falls in
+    #[serde(default = "default_false_config")]
+    pub adopt_orphans: bool,
     #[serde(default = "default_false_config")]
     pub default_forum_mode: bool,
     #[serde(default = "default_false_config")]
@@ -59,6 +64,75 @@ pub struct Config {
     pub auto_upload_merge_no_metadata: bool,
     #[serde(default = "default_empty_vec")]
     pub auto_upload_ignored_folders: Vec<String>,
+    /// IANA time zone (e.g. "Europe/Berlin") screenshots were taken in; None = system local time
+    #[serde(default)]
+    pub screenshot_timezone: Option<String>,
+    /// Folder compressed and resized copies are written under; None = system temp folder
+    #[serde(default)]
+    pub temp_directory: Option<String>,
+    /// Send attachment names with Japanese, emoji or other non-ASCII characters as-is
+    /// instead of transliterating them to ASCII
+    #[serde(default = "default_false_config")]
+    pub keep_original_filenames: bool,
+    /// Post a "Uploaded N photos from M worlds" message after each session
+    #[serde(default = "default_false_config")]
+    pub post_upload_summary: bool,
+    /// Longest side (px) images are downscaled to before upload; None = original size
+    #[serde(default)]
+    pub max_upload_dimension: Option<u32>,
+    /// Lowest lossy quality chunks that are still too large are retried at
+    #[serde(default = "default_min_upload_quality_config")]
+    pub min_upload_quality: u8,
+    /// Smallest resolution (percent of the original) those retries downscale to
+    #[serde(default = "default_min_upload_scale_config")]
+    pub min_upload_scale_percent: u8,
+    /// Re-encode video clips over the webhook's upload limit with FFmpeg
+    #[serde(default = "default_false_config")]
+    pub compress_clips: bool,
+    /// FFmpeg executable for clip compression; None = bundled copy or PATH
+    #[serde(default)]
+    pub ffmpeg_path: Option<String>,
+    /// Record how long each upload phase takes in the local database for tuning
+    #[serde(default = "default_false_config")]
+    pub collect_metrics: bool,
+    /// Add "Wearing **Avatar**" to captions when the metadata records the avatar
+    #[serde(default = "default_false_config")]
+    pub include_avatar_in_caption: bool,
+    /// @mention players that have a Discord user mapping instead of naming them
+    #[serde(default = "default_true_config")]
+    pub mention_mapped_players: bool,
+    /// Send `allowed_mentions` so `@everyone` or role mentions in display names can't ping
+    #[serde(default = "default_true_config")]
+    pub restrict_caption_mentions: bool,
+    /// Link player names to their VRChat profiles in captions
+    #[serde(default = "default_false_config")]
+    pub link_player_profiles: bool,
+    /// Send world links as buttons under the caption instead of text links
+    #[serde(default = "default_false_config")]
+    pub use_link_buttons: bool,
+    /// Post photos from a world into that world's earlier forum thread instead of a new post
+    #[serde(default = "default_false_config")]
+    pub reuse_forum_threads: bool,
+    /// Start a new forum thread once the remembered one is older than this; 0 = never
+    #[serde(default = "default_forum_thread_age_config")]
+    pub forum_thread_max_age_days: u32,
+    /// Close each forum thread a session posted into with a recap of what was added
+    #[serde(default = "default_false_config")]
+    pub post_thread_closing_message: bool,
+    /// How caption timestamps render in Discord ("f", "F", "d", "R", ...)
+    #[serde(default = "default_timestamp_style_config")]
+    pub discord_timestamp_style: String,
+    /// Check attachments after upload: "off", "size" (Discord-reported size) or
+    /// "checksum" (download and compare)
+    #[serde(default = "default_upload_verification_config")]
+    pub upload_verification: String,
+    /// Start minimized to the tray on login (the registry entry itself is managed by
+    /// `autostart`)
+    #[serde(default = "default_false_config")]
+    pub auto_launch: bool,
+    /// Scheduled weekly/monthly roundup posts
+    #[serde(default)]
+    pub roundup: RoundupConfig,
 }
 
 fn default_delay_config() -> u32 {
@@ -81,6 +155,26 @@ fn default_time_window_config() -> u32 {
     60
 }
 
+fn default_forum_thread_age_config() -> u32 {
+    7
+}
+
+fn default_min_upload_quality_config() -> u8 {
+    crate::uploader::compression_tiers::DEFAULT_MIN_QUALITY
+}
+
+fn default_min_upload_scale_config() -> u8 {
+    crate::uploader::compression_tiers::DEFAULT_MIN_SCALE_PERCENT
+}
+
+fn default_timestamp_style_config() -> String {
+    crate::uploader::image_groups::DEFAULT_TIMESTAMP_STYLE.to_string()
+}
+
+fn default_upload_verification_config() -> String {
+    crate::uploader::verification::DEFAULT_UPLOAD_VERIFICATION.to_string()
+}
+
 fn default_empty_vec() -> Vec<String> {
     Vec::new()
 }
@@ -110,6 +204,7 @@ impl Default for Config {
             vrchat_path: None,
             single_thread_mode: false,
             merge_no_metadata: false,
+            adopt_orphans: false,
             default_forum_mode: false,
             enable_multi_webhook: false,
             auto_upload_delay_seconds: 5,
@@ -123,6 +218,28 @@ impl Default for Config {
             auto_upload_include_players: true,
             auto_upload_merge_no_metadata: false,
             auto_upload_ignored_folders: Vec::new(),
+            screenshot_timezone: None,
+            temp_directory: None,
+            keep_original_filenames: false,
+            post_upload_summary: false,
+            max_upload_dimension: None,
+            min_upload_quality: default_min_upload_quality_config(),
+            min_upload_scale_percent: default_min_upload_scale_config(),
+            compress_clips: false,
+            ffmpeg_path: None,
+            collect_metrics: false,
+            include_avatar_in_caption: false,
+            mention_mapped_players: true,
+            restrict_caption_mentions: true,
+            link_player_profiles: false,
+            use_link_buttons: false,
+            reuse_forum_threads: false,
+            forum_thread_max_age_days: 7,
+            post_thread_closing_message: false,
+            discord_timestamp_style: default_timestamp_style_config(),
+            upload_verification: default_upload_verification_config(),
+            auto_launch: false,
+            roundup: RoundupConfig::default(),
         }
     }
 }
@@ -145,6 +262,7 @@ impl From<Config> for AppConfig {
             vrchat_path: config.vrchat_path,
             single_thread_mode: config.single_thread_mode,
             merge_no_metadata: config.merge_no_metadata,
+            adopt_orphans: config.adopt_orphans,
             default_forum_mode: config.default_forum_mode,
             enable_multi_webhook: config.enable_multi_webhook,
             auto_upload_delay_seconds: config.auto_upload_delay_seconds,
@@ -158,6 +276,30 @@ impl From<Config> for AppConfig {
             auto_upload_include_players: config.auto_upload_include_players,
             auto_upload_merge_no_metadata: config.auto_upload_merge_no_metadata,
             auto_upload_ignored_folders: config.auto_upload_ignored_folders,
+            screenshot_timezone: config.screenshot_timezone,
+            temp_directory: config.temp_directory,
+            keep_original_filenames: config.keep_original_filenames,
+            show_upload_notifications: config.show_upload_notifications,
+            post_upload_summary: config.post_upload_summary,
+            log_level: config.log_level,
+            max_upload_dimension: config.max_upload_dimension,
+            min_upload_quality: config.min_upload_quality,
+            min_upload_scale_percent: config.min_upload_scale_percent,
+            compress_clips: config.compress_clips,
+            ffmpeg_path: config.ffmpeg_path,
+            collect_metrics: config.collect_metrics,
+            include_avatar_in_caption: config.include_avatar_in_caption,
+            mention_mapped_players: config.mention_mapped_players,
+            restrict_caption_mentions: config.restrict_caption_mentions,
+            link_player_profiles: config.link_player_profiles,
+            use_link_buttons: config.use_link_buttons,
+            reuse_forum_threads: config.reuse_forum_threads,
+            forum_thread_max_age_days: config.forum_thread_max_age_days,
+            post_thread_closing_message: config.post_thread_closing_message,
+            discord_timestamp_style: config.discord_timestamp_style,
+            upload_verification: config.upload_verification,
+            auto_launch: config.auto_launch,
+            roundup: config.roundup,
         }
     }
 }
@@ -179,6 +321,7 @@ impl From<AppConfig> for Config {
             vrchat_path: app_config.vrchat_path,
             single_thread_mode: app_config.single_thread_mode,
             merge_no_metadata: app_config.merge_no_metadata,
+            adopt_orphans: app_config.adopt_orphans,
             default_forum_mode: app_config.default_forum_mode,
             enable_multi_webhook: app_config.enable_multi_webhook,
             auto_upload_delay_seconds: app_config.auto_upload_delay_seconds,
@@ -192,18 +335,46 @@ impl From<AppConfig> for Config {
             auto_upload_include_players: app_config.auto_upload_include_players,
             auto_upload_merge_no_metadata: app_config.auto_upload_merge_no_metadata,
             auto_upload_ignored_folders: app_config.auto_upload_ignored_folders,
+            screenshot_timezone: app_config.screenshot_timezone,
+            temp_directory: app_config.temp_directory,
+            keep_original_filenames: app_config.keep_original_filenames,
+            show_upload_notifications: app_config.show_upload_notifications,
+            post_upload_summary: app_config.post_upload_summary,
+            log_level: app_config.log_level,
+            max_upload_dimension: app_config.max_upload_dimension,
+            min_upload_quality: app_config.min_upload_quality,
+            min_upload_scale_percent: app_config.min_upload_scale_percent,
+            compress_clips: app_config.compress_clips,
+            ffmpeg_path: app_config.ffmpeg_path,
+            collect_metrics: app_config.collect_metrics,
+            include_avatar_in_caption: app_config.include_avatar_in_caption,
+            mention_mapped_players: app_config.mention_mapped_players,
+            restrict_caption_mentions: app_config.restrict_caption_mentions,
+            link_player_profiles: app_config.link_player_profiles,
+            use_link_buttons: app_config.use_link_buttons,
+            reuse_forum_threads: app_config.reuse_forum_threads,
+            forum_thread_max_age_days: app_config.forum_thread_max_age_days,
+            post_thread_closing_message: app_config.post_thread_closing_message,
+            discord_timestamp_style: app_config.discord_timestamp_style,
+            upload_verification: app_config.upload_verification,
+            auto_launch: app_config.auto_launch,
+            roundup: app_config.roundup,
             ..Default::default()
         }
     }
 }
 
-fn get_config_path() -> AppResult<PathBuf> {
+pub(crate) fn get_config_directory() -> AppResult<PathBuf> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| AppError::Config("Could not find config directory".to_string()))?
         .join("VRChat Photo Uploader");
 
     fs::create_dir_all(&config_dir)?;
-    Ok(config_dir.join("config.json"))
+    Ok(config_dir)
+}
+
+fn get_config_path() -> AppResult<PathBuf> {
+    Ok(get_config_directory()?.join("config.json"))
 }
 
 pub fn load_config() -> AppResult<AppConfig> {
@@ -229,8 +400,19 @@ pub fn load_config() -> AppResult<AppConfig> {
 }
 
 pub fn save_config(app_config: AppConfig) -> AppResult<()> {
-    let config: Config = app_config.into();
+    let mut config: Config = app_config.into();
+    // Stored absolute so `~` and relative folders mean the same thing on every launch
+    for folder in [&mut config.vrchat_path, &mut config.temp_directory] {
+        if let Some(path) = folder.as_mut().filter(|p| !p.trim().is_empty()) {
+            *path = InputValidator::resolve_user_path(path)
+                .to_string_lossy()
+                .to_string();
+        }
+    }
     validate_config(&config)?;
+    if let Some(temp_directory) = &config.temp_directory {
+        validate_temp_directory(Path::new(temp_directory))?;
+    }
     save_config_internal(&config)
 }
 
@@ -261,18 +443,54 @@ pub fn get_data_directory() -> AppResult<PathBuf> {
     Ok(data_dir)
 }
 
+/// Files replaced by the metadata editor are kept here so edits can be undone
+pub fn get_metadata_trash_directory() -> AppResult<PathBuf> {
+    let trash_dir = get_data_directory()?.join("metadata-trash");
+    fs::create_dir_all(&trash_dir)?;
+    Ok(trash_dir)
+}
+
 pub fn get_logs_directory() -> AppResult<PathBuf> {
     let logs_dir = get_data_directory()?.join("logs");
     fs::create_dir_all(&logs_dir)?;
     Ok(logs_dir)
 }
 
+/// The uploader's temp folder (see `FileSystemGuard::get_temp_directory`), created if missing
 pub fn get_temp_directory() -> AppResult<PathBuf> {
-    let temp_dir = std::env::temp_dir().join("vrchat_photo_uploader");
+    let temp_dir = crate::security::FileSystemGuard::get_temp_directory();
     fs::create_dir_all(&temp_dir)?;
     Ok(temp_dir)
 }
 
+/// A configured temp location has to be an existing folder the uploader can write to.
+/// Only checked when saving: a drive that's unplugged later falls back to the system
+/// temp folder instead of breaking the whole config.
+pub fn validate_temp_directory(path: &Path) -> AppResult<()> {
+    if !path.is_absolute() {
+        return Err(AppError::validation(
+            "temp_directory",
+            "Must be an absolute path",
+        ));
+    }
+    if !path.is_dir() {
+        return Err(AppError::validation(
+            "temp_directory",
+            "Folder does not exist",
+        ));
+    }
+
+    let probe = path.join(format!(".vrchat_uploader_probe_{}", uuid::Uuid::new_v4()));
+    fs::write(&probe, b"").map_err(|e| {
+        AppError::validation("temp_directory", &format!("Folder is not writable: {e}"))
+    })?;
+    fs::remove_file(&probe).ok();
+    Ok(())
+}
+
+/// Formats uploads can be compressed to
+pub const COMPRESSION_FORMATS: &[&str] = &["webp", "lossless_webp", "png", "jpg", "avif"];
+
 pub fn validate_config(config: &Config) -> AppResult<()> {
     if config.max_images_per_message == 0 || config.max_images_per_message > 10 {
         return Err(AppError::validation(
@@ -335,14 +553,40 @@ pub fn validate_config(config: &Config) -> AppResult<()> {
     }
 
     // Validate compression format
-    let valid_formats = ["webp", "lossless_webp", "png", "jpg", "avif"];
-    if !valid_formats.contains(&config.compression_format.as_str()) {
+    if !COMPRESSION_FORMATS.contains(&config.compression_format.as_str()) {
         return Err(AppError::validation(
             "compression_format",
             "Must be 'webp', 'lossless_webp', 'png', 'jpg', or 'avif'",
         ));
     }
 
+    if !crate::uploader::image_groups::TIMESTAMP_STYLES
+        .contains(&config.discord_timestamp_style.as_str())
+    {
+        return Err(AppError::validation(
+            "discord_timestamp_style",
+            "Must be one of t, T, d, D, f, F or R",
+        ));
+    }
+
+    if !crate::uploader::verification::UPLOAD_VERIFICATION_MODES
+        .contains(&config.upload_verification.as_str())
+    {
+        return Err(AppError::validation(
+            "upload_verification",
+            "Must be 'off', 'size' or 'checksum'",
+        ));
+    }
+
+    if let Some(timezone) = &config.screenshot_timezone {
+        if crate::image_processor::parse_timezone(timezone).is_err() {
+            return Err(AppError::validation(
+                "screenshot_timezone",
+                "Must be a valid IANA time zone name (e.g. 'Europe/Berlin')",
+            ));
+        }
+    }
+
     Ok(())
 }
 
@@ -377,6 +621,140 @@ pub fn migrate_config() -> AppResult<()> {
     Ok(())
 }
 
+// Profiles
+//
+// A profile is a named copy of the whole config (webhook defaults, compression,
+// grouping, ...) kept as `profiles/<name>.json` next to config.json. config.json
+// always holds the settings in use; switching stores them back into the active
+// profile before the other profile's settings replace them.
+
+const MAX_PROFILE_NAME_LENGTH: usize = 50;
+
+/// Saved profiles and the one currently in use, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileList {
+    pub profiles: Vec<String>,
+    pub active: Option<String>,
+}
+
+fn get_profiles_directory() -> AppResult<PathBuf> {
+    let profiles_dir = get_config_directory()?.join("profiles");
+    fs::create_dir_all(&profiles_dir)?;
+    Ok(profiles_dir)
+}
+
+fn get_active_profile_path() -> AppResult<PathBuf> {
+    Ok(get_config_directory()?.join("active_profile"))
+}
+
+/// Profile names double as file names, so they can't contain path or reserved characters
+pub fn validate_profile_name(name: &str) -> AppResult<&str> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PROFILE_NAME_LENGTH {
+        return Err(AppError::validation(
+            "profile_name",
+            &format!("Must be between 1 and {MAX_PROFILE_NAME_LENGTH} characters"),
+        ));
+    }
+    if name.starts_with('.') || InputValidator::sanitize_filename(name) != name {
+        return Err(AppError::validation(
+            "profile_name",
+            "Can't start with '.' or contain < > : \" / \\ | ? *",
+        ));
+    }
+    Ok(name)
+}
+
+fn get_profile_path(name: &str) -> AppResult<PathBuf> {
+    Ok(get_profiles_directory()?.join(format!("{}.json", validate_profile_name(name)?)))
+}
+
+fn active_profile() -> Option<String> {
+    let name = fs::read_to_string(get_active_profile_path().ok()?).ok()?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn set_active_profile(name: Option<&str>) -> AppResult<()> {
+    let path = get_active_profile_path()?;
+    match name {
+        Some(name) => fs::write(path, name)?,
+        None if path.exists() => fs::remove_file(path)?,
+        None => {}
+    }
+    Ok(())
+}
+
+pub fn list_profiles() -> AppResult<ProfileList> {
+    let mut profiles: Vec<String> = fs::read_dir(get_profiles_directory()?)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .collect();
+    profiles.sort_by_key(|name| name.to_lowercase());
+
+    let active = active_profile().filter(|name| profiles.contains(name));
+    Ok(ProfileList { profiles, active })
+}
+
+/// Save the settings in use as profile `name` (replacing it if it exists) and make it active
+pub fn save_profile(name: &str) -> AppResult<()> {
+    let name = validate_profile_name(name)?;
+    let config: Config = load_config()?.into();
+    fs::write(
+        get_profile_path(name)?,
+        serde_json::to_string_pretty(&config)?,
+    )?;
+    set_active_profile(Some(name))?;
+    log::info!("Saved settings profile '{name}'");
+    Ok(())
+}
+
+/// Keep the current settings in the active profile, then load profile `name`
+pub fn switch_profile(name: &str) -> AppResult<AppConfig> {
+    let name = validate_profile_name(name)?;
+    let profile_path = get_profile_path(name)?;
+    if !profile_path.exists() {
+        return Err(AppError::validation(
+            "profile_name",
+            &format!("No profile named '{name}'"),
+        ));
+    }
+
+    let profile: Config = serde_json::from_str(&fs::read_to_string(&profile_path)?)?;
+    validate_config(&profile)?;
+
+    if let Some(current) = active_profile().filter(|current| current != name) {
+        if let Ok(current_path) = get_profile_path(&current) {
+            let config: Config = load_config()?.into();
+            fs::write(current_path, serde_json::to_string_pretty(&config)?)?;
+        }
+    }
+
+    save_config_internal(&profile)?;
+    set_active_profile(Some(name))?;
+    log::info!("Switched to settings profile '{name}'");
+    Ok(profile.into())
+}
+
+/// Delete profile `name`; the settings in use are left as they are
+pub fn delete_profile(name: &str) -> AppResult<()> {
+    let name = validate_profile_name(name)?;
+    let profile_path = get_profile_path(name)?;
+    if !profile_path.exists() {
+        return Err(AppError::validation(
+            "profile_name",
+            &format!("No profile named '{name}'"),
+        ));
+    }
+    fs::remove_file(profile_path)?;
+    if active_profile().as_deref() == Some(name) {
+        set_active_profile(None)?;
+    }
+    Ok(())
+}
+
 /// Auto-cleanup old data
 pub async fn auto_cleanup() -> AppResult<()> {
     let config = load_config()?;
@@ -532,6 +910,30 @@ mod tests {
         assert!(validate_config(&config).is_ok());
     }
 
+    #[test]
+    fn test_validate_profile_name() {
+        assert_eq!(
+            validate_profile_name("  Event photographer ").unwrap(),
+            "Event photographer"
+        );
+        assert!(validate_profile_name("ホーム 🎉").is_ok());
+        for invalid in ["", "   ", "../config", "a/b", "a\\b", ".hidden", "what?"] {
+            assert!(validate_profile_name(invalid).is_err(), "{invalid:?}");
+        }
+        assert!(validate_profile_name(&"x".repeat(MAX_PROFILE_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_temp_directory() {
+        let dir = std::env::temp_dir();
+        assert!(validate_temp_directory(&dir).is_ok());
+        assert!(validate_temp_directory(Path::new("relative/temp")).is_err());
+        assert!(
+            validate_temp_directory(&dir.join(format!("missing_{}", uuid::Uuid::new_v4())))
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_validate_config_invalid_max_images_zero() {
         let config = Config {
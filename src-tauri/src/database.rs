@@ -1,21 +1,54 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::OnceLock;
+use std::time::Duration;
 
-use crate::commands::Webhook;
+use crate::commands::{FailedGroupOptions, Webhook};
 use crate::errors::{AppError, AppResult};
+use crate::uploader::discord_client::PostedMessage;
 
 pub static DB_POOL: OnceLock<Pool<Sqlite>> = OnceLock::new();
 
-pub async fn init_database() -> AppResult<()> {
+/// Pool size; SQLite serializes writers, so more connections only help concurrent reads
+const MAX_CONNECTIONS: u32 = 5;
+/// How long a connection waits on a locked database before failing with `database is locked`
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn database_path() -> AppResult<PathBuf> {
     let data_dir = dirs::data_dir()
         .ok_or_else(|| AppError::Config("Could not find data directory".to_string()))?
         .join("VRChat Photo Uploader");
+    Ok(data_dir.join("DiscordWebhooks.db"))
+}
+
+/// Open a pool in WAL mode, so readers don't block the uploader's writes and
+/// concurrent writers wait for the lock instead of failing
+async fn connect_pool(url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(url)?
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT);
+
+    SqlitePoolOptions::new()
+        .max_connections(MAX_CONNECTIONS)
+        .acquire_timeout(Duration::from_secs(30))
+        .connect_with(options)
+        .await
+}
+
+pub async fn init_database() -> AppResult<()> {
+    let db_path = database_path()?;
+    let data_dir = db_path
+        .parent()
+        .ok_or_else(|| AppError::Config("Invalid database path".to_string()))?
+        .to_path_buf();
 
     // Ensure directory exists with proper permissions
     std::fs::create_dir_all(&data_dir)?;
     log::info!("Database directory: {}", data_dir.display());
-
-    let db_path = data_dir.join("DiscordWebhooks.db");
     log::info!("Database path: {}", db_path.display());
 
     // Check if we can write to the directory
@@ -70,7 +103,7 @@ pub async fn init_database() -> AppResult<()> {
 
     for (i, url) in connection_attempts.iter().enumerate() {
         log::info!("Connection attempt {}: {}", i + 1, url);
-        match SqlitePool::connect(url).await {
+        match connect_pool(url).await {
             Ok(p) => {
                 log::info!("Successfully connected with URL: {url}");
                 pool = Some(p);
@@ -172,6 +205,40 @@ pub async fn init_database() -> AppResult<()> {
     .execute(&pool)
     .await?;
 
+    // Forum tags applied to threads a webhook creates; an empty world_id applies to every post
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS webhook_forum_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL,
+            tag_id TEXT NOT NULL,
+            world_id TEXT NOT NULL DEFAULT '',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE,
+            UNIQUE(webhook_id, tag_id, world_id)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Forum thread last used for a world's photos, so later sessions can post into it
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS forum_threads (
+            webhook_id INTEGER NOT NULL,
+            world_id TEXT NOT NULL,
+            thread_id TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            last_used_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (webhook_id, world_id),
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
     // Create table for Discord user mappings (VRChat player → Discord @mention)
     sqlx::query(
         r#"
@@ -189,6 +256,124 @@ pub async fn init_database() -> AppResult<()> {
     .execute(&pool)
     .await?;
 
+    // Create table for failed groups so they can be retried after a restart
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS failed_groups (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            webhook_id INTEGER NOT NULL,
+            group_id TEXT NOT NULL,
+            file_paths TEXT NOT NULL,
+            options TEXT NOT NULL,
+            error_message TEXT,
+            failed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Roundup periods that have already been posted, so each is only posted once
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS roundup_posts (
+            period_key TEXT PRIMARY KEY,
+            webhook_id INTEGER NOT NULL,
+            photo_count INTEGER NOT NULL,
+            posted_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // User-curated sets of photos that can span folders
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS collections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS collection_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            collection_id INTEGER NOT NULL,
+            file_path TEXT NOT NULL,
+            added_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(collection_id, file_path),
+            FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Per-file tags, entered by hand or derived from metadata
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS photo_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL,
+            tag TEXT NOT NULL COLLATE NOCASE,
+            source TEXT NOT NULL DEFAULT 'manual',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(file_path, tag)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Metadata of every photo it was extracted from, for search and stats
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS photo_metadata (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL UNIQUE,
+            file_name TEXT NOT NULL,
+            world_name TEXT,
+            world_id TEXT,
+            author_name TEXT,
+            players TEXT NOT NULL DEFAULT '[]',
+            taken_at INTEGER,
+            indexed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Per-file alt text, sent as the Discord attachment description
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS photo_descriptions (
+            file_path TEXT PRIMARY KEY,
+            description TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Full-text index over photo_metadata; rowid matches photo_metadata.id
+    sqlx::query(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS photo_search USING fts5(file_name, world_name, player_names)",
+    )
+    .execute(&pool)
+    .await?;
+
     // Add indexes for better query performance
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_upload_history_hash ON upload_history(file_hash)")
         .execute(&pool)
@@ -216,6 +401,10 @@ pub async fn init_database() -> AppResult<()> {
         .execute(&pool)
         .await?;
 
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_photo_tags_tag ON photo_tags(tag)")
+        .execute(&pool)
+        .await?;
+
     sqlx::query(
         "CREATE INDEX IF NOT EXISTS idx_upload_sessions_webhook ON upload_sessions(webhook_id)",
     )
@@ -241,6 +430,14 @@ pub async fn init_database() -> AppResult<()> {
     .execute(&pool)
     .await?;
 
+    // Earlier versions stored this state, and nothing resumes such a session
+    sqlx::query(
+        "UPDATE upload_sessions SET session_status = 'interrupted'
+         WHERE session_status = 'waiting_for_network'",
+    )
+    .execute(&pool)
+    .await?;
+
     DB_POOL
         .set(pool)
         .map_err(|_| AppError::Internal("Failed to set database pool".to_string()))?;
@@ -252,73 +449,290 @@ pub async fn init_database() -> AppResult<()> {
     Ok(())
 }
 
-pub async fn migrate_database() -> AppResult<()> {
-    let pool = get_pool()?;
+// Schema migrations
+//
+// `init_database` creates each table as it first shipped; every later schema change
+// is a numbered migration below. Applied versions are recorded in `schema_version`,
+// so each migration runs exactly once and in order. Append new migrations with the
+// next version number and never edit one that has shipped.
+
+struct Migration {
+    version: i64,
+    description: &'static str,
+    kind: MigrationKind,
+}
 
-    // Check if upload_status column exists
-    let column_check = sqlx::query(
-        "SELECT name FROM pragma_table_info('upload_history') WHERE name = 'upload_status'",
-    )
-    .fetch_optional(pool)
-    .await?;
+enum MigrationKind {
+    /// Columns added before migrations were versioned. Installs picked them up at
+    /// different times, so each is added only where it is missing.
+    AddMissingColumns(&'static [(&'static str, &'static str, &'static str)]),
+    /// Statements run together in one transaction
+    Sql(&'static [&'static str]),
+}
 
-    if column_check.is_none() {
-        log::info!("Adding missing upload_status column to upload_history table");
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Columns added before schema versioning",
+        kind: MigrationKind::AddMissingColumns(&[
+            (
+                "upload_history",
+                "upload_status",
+                "TEXT NOT NULL DEFAULT 'success'",
+            ),
+            ("upload_history", "error_message", "TEXT"),
+            ("upload_history", "retry_count", "INTEGER DEFAULT 0"),
+            ("webhooks", "pinned", "BOOLEAN NOT NULL DEFAULT FALSE"),
+            // Attachment size limit of the webhook's server (boosted servers allow more)
+            ("webhooks", "upload_limit_mb", "INTEGER"),
+            // Default message flags for posts through the webhook
+            (
+                "webhooks",
+                "suppress_embeds",
+                "BOOLEAN NOT NULL DEFAULT FALSE",
+            ),
+            (
+                "webhooks",
+                "suppress_notifications",
+                "BOOLEAN NOT NULL DEFAULT FALSE",
+            ),
+            // Discord message the file was posted in (for jump links back to the post)
+            ("upload_history", "message_id", "TEXT"),
+            ("upload_history", "channel_id", "TEXT"),
+            ("upload_history", "message_url", "TEXT"),
+            ("upload_history", "attachment_url", "TEXT"),
+        ]),
+    },
+    Migration {
+        version: 2,
+        description: "Index upload history by Discord message",
+        kind: MigrationKind::Sql(&[
+            "CREATE INDEX IF NOT EXISTS idx_upload_history_message ON upload_history(message_id)",
+        ]),
+    },
+    Migration {
+        version: 3,
+        description: "Link upload history to its session and group",
+        kind: MigrationKind::Sql(&[
+            "ALTER TABLE upload_history ADD COLUMN session_id TEXT",
+            "ALTER TABLE upload_history ADD COLUMN group_id TEXT",
+            "CREATE INDEX IF NOT EXISTS idx_upload_history_session ON upload_history(session_id)",
+        ]),
+    },
+    Migration {
+        version: 4,
+        description: "Undo log for metadata editor outputs",
+        kind: MigrationKind::Sql(&[r#"
+            CREATE TABLE IF NOT EXISTS metadata_edits (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_path TEXT NOT NULL,
+                output_path TEXT NOT NULL,
+                backup_path TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                undone_at DATETIME
+            )
+            "#]),
+    },
+    Migration {
+        version: 5,
+        description: "Per-webhook caption timestamp style",
+        kind: MigrationKind::Sql(&["ALTER TABLE webhooks ADD COLUMN timestamp_style TEXT"]),
+    },
+    Migration {
+        version: 6,
+        description: "Named upload presets per webhook",
+        kind: MigrationKind::Sql(&[r#"
+            CREATE TABLE IF NOT EXISTS upload_presets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                webhook_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                group_by_metadata BOOLEAN NOT NULL DEFAULT TRUE,
+                grouping_time_window INTEGER NOT NULL DEFAULT 10,
+                max_images_per_message INTEGER NOT NULL DEFAULT 10,
+                include_player_names BOOLEAN NOT NULL DEFAULT TRUE,
+                compression_format TEXT,
+                upload_quality INTEGER,
+                is_default BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE,
+                UNIQUE(webhook_id, name)
+            )
+            "#]),
+    },
+    Migration {
+        version: 7,
+        description: "Verification status of uploaded attachments",
+        kind: MigrationKind::Sql(&[
+            "ALTER TABLE upload_history ADD COLUMN verification_status TEXT",
+        ]),
+    },
+    Migration {
+        version: 8,
+        description: "Session templates for recurring events",
+        kind: MigrationKind::Sql(&[r#"
+            CREATE TABLE IF NOT EXISTS session_templates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                webhook_ids TEXT NOT NULL,
+                group_by_metadata BOOLEAN NOT NULL DEFAULT TRUE,
+                grouping_time_window INTEGER NOT NULL DEFAULT 10,
+                group_by_world BOOLEAN NOT NULL DEFAULT TRUE,
+                group_by_instance BOOLEAN NOT NULL DEFAULT FALSE,
+                merge_no_metadata BOOLEAN NOT NULL DEFAULT FALSE,
+                adopt_orphans BOOLEAN NOT NULL DEFAULT FALSE,
+                max_images_per_message INTEGER NOT NULL DEFAULT 10,
+                include_player_names BOOLEAN NOT NULL DEFAULT TRUE,
+                single_thread_mode BOOLEAN NOT NULL DEFAULT FALSE,
+                extra_message TEXT,
+                forum_tag_ids TEXT NOT NULL DEFAULT '[]',
+                compression_format TEXT,
+                upload_quality INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )
+            "#]),
+    },
+    Migration {
+        version: 9,
+        description: "Opt-in timings of upload phases",
+        kind: MigrationKind::Sql(&[
+            r#"
+            CREATE TABLE IF NOT EXISTS metrics (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                phase TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                items INTEGER NOT NULL DEFAULT 0,
+                bytes INTEGER NOT NULL DEFAULT 0,
+                recorded_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_metrics_phase ON metrics (phase)",
+        ]),
+    },
+];
 
-        // Add the missing column
-        sqlx::query(
-            "ALTER TABLE upload_history ADD COLUMN upload_status TEXT NOT NULL DEFAULT 'success'",
-        )
-        .execute(pool)
-        .await?;
-    }
+pub async fn migrate_database() -> AppResult<()> {
+    run_migrations(get_pool()?).await
+}
 
-    // Check if error_message column exists
-    let error_column_check = sqlx::query(
-        "SELECT name FROM pragma_table_info('upload_history') WHERE name = 'error_message'",
+/// Apply every migration newer than the database's schema version
+pub async fn run_migrations(pool: &Pool<Sqlite>) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
     )
-    .fetch_optional(pool)
+    .execute(pool)
     .await?;
 
-    if error_column_check.is_none() {
-        log::info!("Adding missing error_message column to upload_history table");
+    let current = schema_version(pool).await?;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        log::info!(
+            "Applying database migration {}: {}",
+            migration.version,
+            migration.description
+        );
 
-        sqlx::query("ALTER TABLE upload_history ADD COLUMN error_message TEXT")
-            .execute(pool)
+        let mut tx = pool.begin().await?;
+        match migration.kind {
+            MigrationKind::AddMissingColumns(columns) => {
+                for (table, column, definition) in columns {
+                    let column_check =
+                        sqlx::query("SELECT name FROM pragma_table_info(?) WHERE name = ?")
+                            .bind(table)
+                            .bind(column)
+                            .fetch_optional(&mut *tx)
+                            .await?;
+                    if column_check.is_some() {
+                        continue;
+                    }
+                    log::info!("Adding {column} column to {table} table");
+
+                    sqlx::query(&format!(
+                        "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+                    ))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+            MigrationKind::Sql(statements) => {
+                for statement in statements {
+                    sqlx::query(statement).execute(&mut *tx).await?;
+                }
+            }
+        }
+        sqlx::query("INSERT INTO schema_version (version, description) VALUES (?, ?)")
+            .bind(migration.version)
+            .bind(migration.description)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
     }
 
-    // Check if retry_count column exists
-    let retry_column_check = sqlx::query(
-        "SELECT name FROM pragma_table_info('upload_history') WHERE name = 'retry_count'",
-    )
-    .fetch_optional(pool)
-    .await?;
+    log::info!("Database migration completed successfully");
+    Ok(())
+}
 
-    if retry_column_check.is_none() {
-        log::info!("Adding missing retry_count column to upload_history table");
+/// Latest applied migration, 0 for a database that predates versioning
+pub async fn schema_version(pool: &Pool<Sqlite>) -> AppResult<i64> {
+    let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+    Ok(version.unwrap_or(0))
+}
 
-        sqlx::query("ALTER TABLE upload_history ADD COLUMN retry_count INTEGER DEFAULT 0")
-            .execute(pool)
-            .await?;
-    }
+/// Migrations not yet applied, as "version: description"
+pub async fn pending_migrations() -> AppResult<Vec<String>> {
+    let current = schema_version(get_pool()?).await?;
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current)
+        .map(|m| format!("{}: {}", m.version, m.description))
+        .collect())
+}
 
-    // Check if pinned column exists on webhooks table
-    let pinned_column_check =
-        sqlx::query("SELECT name FROM pragma_table_info('webhooks') WHERE name = 'pinned'")
-            .fetch_optional(pool)
-            .await?;
+#[derive(Debug, serde::Serialize)]
+pub struct DatabaseStatus {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Size of the write-ahead log not yet checkpointed into the main file
+    pub wal_size_bytes: u64,
+    pub journal_mode: String,
+    pub busy_timeout_ms: i64,
+    pub max_connections: u32,
+    pub open_connections: u32,
+    pub schema_version: i64,
+    pub pending_migrations: Vec<String>,
+}
 
-    if pinned_column_check.is_none() {
-        log::info!("Adding pinned column to webhooks table");
+pub async fn get_database_status() -> AppResult<DatabaseStatus> {
+    let pool = get_pool()?;
+    let db_path = database_path()?;
+    let wal_path = db_path.with_extension("db-wal");
+    let file_size = |path: &std::path::Path| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
 
-        sqlx::query("ALTER TABLE webhooks ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT FALSE")
-            .execute(pool)
-            .await?;
-    }
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(pool)
+        .await?;
+    let busy_timeout_ms: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+        .fetch_one(pool)
+        .await?;
 
-    log::info!("Database migration completed successfully");
-    Ok(())
+    Ok(DatabaseStatus {
+        path: db_path.display().to_string(),
+        size_bytes: file_size(&db_path),
+        wal_size_bytes: file_size(&wal_path),
+        journal_mode,
+        busy_timeout_ms,
+        max_connections: MAX_CONNECTIONS,
+        open_connections: pool.size(),
+        schema_version: schema_version(pool).await?,
+        pending_migrations: pending_migrations().await?,
+    })
 }
 
 fn get_pool() -> AppResult<&'static Pool<Sqlite>> {
@@ -331,7 +745,7 @@ pub async fn get_all_webhooks() -> AppResult<Vec<Webhook>> {
     let pool = get_pool()?;
 
     let rows = sqlx::query(
-        "SELECT id, name, url, is_forum, pinned FROM webhooks ORDER BY pinned DESC, last_used_at DESC, name ASC",
+        "SELECT id, name, url, is_forum, pinned, suppress_embeds, suppress_notifications, upload_limit_mb, timestamp_style FROM webhooks ORDER BY pinned DESC, last_used_at DESC, name ASC",
     )
     .fetch_all(pool)
     .await?;
@@ -344,16 +758,49 @@ pub async fn get_all_webhooks() -> AppResult<Vec<Webhook>> {
             url: row.get("url"),
             is_forum: row.get("is_forum"),
             pinned: row.get("pinned"),
+            suppress_embeds: row.get("suppress_embeds"),
+            suppress_notifications: row.get("suppress_notifications"),
+            upload_limit_mb: row.get("upload_limit_mb"),
+            timestamp_style: row.get("timestamp_style"),
         });
     }
 
     Ok(webhooks)
 }
 
+/// Most recently used webhooks first, never-used ones last
+pub async fn get_recent_webhooks(limit: i64) -> AppResult<Vec<Webhook>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT id, name, url, is_forum, pinned, suppress_embeds, suppress_notifications, upload_limit_mb, timestamp_style FROM webhooks
+         ORDER BY last_used_at IS NULL, last_used_at DESC, name ASC
+         LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| Webhook {
+            id: row.get("id"),
+            name: row.get("name"),
+            url: row.get("url"),
+            is_forum: row.get("is_forum"),
+            pinned: row.get("pinned"),
+            suppress_embeds: row.get("suppress_embeds"),
+            suppress_notifications: row.get("suppress_notifications"),
+            upload_limit_mb: row.get("upload_limit_mb"),
+            timestamp_style: row.get("timestamp_style"),
+        })
+        .collect())
+}
+
 pub async fn get_webhook_by_id(id: i64) -> AppResult<Webhook> {
     let pool = get_pool()?;
 
-    let row = sqlx::query("SELECT id, name, url, is_forum, pinned FROM webhooks WHERE id = ?")
+    let row = sqlx::query("SELECT id, name, url, is_forum, pinned, suppress_embeds, suppress_notifications, upload_limit_mb, timestamp_style FROM webhooks WHERE id = ?")
         .bind(id)
         .fetch_one(pool)
         .await?;
@@ -364,6 +811,10 @@ pub async fn get_webhook_by_id(id: i64) -> AppResult<Webhook> {
         url: row.get("url"),
         is_forum: row.get("is_forum"),
         pinned: row.get("pinned"),
+        suppress_embeds: row.get("suppress_embeds"),
+        suppress_notifications: row.get("suppress_notifications"),
+        upload_limit_mb: row.get("upload_limit_mb"),
+        timestamp_style: row.get("timestamp_style"),
     })
 }
 
@@ -446,50 +897,140 @@ pub async fn toggle_webhook_pin(id: i64) -> AppResult<bool> {
     Ok(new_pinned)
 }
 
-pub async fn update_webhook_usage(webhook_id: i64) -> AppResult<()> {
+/// Default message flags for everything posted through the webhook
+pub async fn set_webhook_message_flags(
+    id: i64,
+    suppress_embeds: bool,
+    suppress_notifications: bool,
+) -> AppResult<()> {
     let pool = get_pool()?;
 
-    sqlx::query(
-        "UPDATE webhooks SET last_used_at = CURRENT_TIMESTAMP, use_count = use_count + 1 WHERE id = ?"
+    let result = sqlx::query(
+        "UPDATE webhooks SET suppress_embeds = ?, suppress_notifications = ? WHERE id = ?",
     )
-    .bind(webhook_id)
+    .bind(suppress_embeds)
+    .bind(suppress_notifications)
+    .bind(id)
     .execute(pool)
     .await?;
 
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
     Ok(())
 }
 
-pub async fn record_upload(
-    file_path: String,
-    file_name: String,
-    file_hash: Option<String>,
-    file_size: Option<u64>,
-    webhook_id: i64,
-    status: &str,
-    error_message: Option<String>,
-) -> AppResult<()> {
+/// Discord timestamp style for the webhook's captions; None uses the configured default
+pub async fn set_webhook_timestamp_style(id: i64, timestamp_style: Option<&str>) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("UPDATE webhooks SET timestamp_style = ? WHERE id = ?")
+        .bind(timestamp_style)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+/// Attachment size limit for the webhook; None uses Discord's default
+pub async fn set_webhook_upload_limit(id: i64, upload_limit_mb: Option<u32>) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("UPDATE webhooks SET upload_limit_mb = ? WHERE id = ?")
+        .bind(upload_limit_mb)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+pub async fn update_webhook_usage(webhook_id: i64) -> AppResult<()> {
     let pool = get_pool()?;
 
     sqlx::query(
-        r#"
-        INSERT INTO upload_history 
-        (file_path, file_name, file_hash, file_size, webhook_id, upload_status, error_message) 
-        VALUES (?, ?, ?, ?, ?, ?, ?)
-        "#,
+        "UPDATE webhooks SET last_used_at = CURRENT_TIMESTAMP, use_count = use_count + 1 WHERE id = ?"
     )
-    .bind(file_path)
-    .bind(file_name)
-    .bind(file_hash)
-    .bind(file_size.map(|s| s as i64))
     .bind(webhook_id)
-    .bind(status)
-    .bind(error_message)
     .execute(pool)
     .await?;
 
     Ok(())
 }
 
+/// One file's outcome, as written to upload_history
+#[derive(Debug, Clone)]
+pub struct UploadRecord {
+    pub file_path: String,
+    pub file_name: String,
+    pub file_hash: Option<String>,
+    pub file_size: Option<u64>,
+    pub webhook_id: i64,
+    pub status: String,
+    pub error_message: Option<String>,
+    /// Discord message the file was posted in
+    pub message: Option<PostedMessage>,
+    pub attachment_url: Option<String>,
+    pub session_id: Option<String>,
+    pub group_id: Option<String>,
+    /// Outcome of checking the posted attachment, when upload verification is on
+    pub verification_status: Option<String>,
+}
+
+/// Record a chunk's results in one transaction, so history never holds half a chunk
+pub async fn record_uploads_batch(records: &[UploadRecord]) -> AppResult<()> {
+    write_upload_records(get_pool()?, records).await
+}
+
+pub async fn write_upload_records(pool: &Pool<Sqlite>, records: &[UploadRecord]) -> AppResult<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    for record in records {
+        let message = record.message.as_ref();
+        sqlx::query(
+            r#"
+            INSERT INTO upload_history
+            (file_path, file_name, file_hash, file_size, webhook_id, upload_status, error_message,
+             message_id, channel_id, message_url, attachment_url, session_id, group_id,
+             verification_status)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&record.file_path)
+        .bind(&record.file_name)
+        .bind(&record.file_hash)
+        .bind(record.file_size.map(|s| s as i64))
+        .bind(record.webhook_id)
+        .bind(&record.status)
+        .bind(&record.error_message)
+        .bind(message.map(|m| &m.message_id))
+        .bind(message.map(|m| &m.channel_id))
+        .bind(message.and_then(|m| m.jump_url.as_ref()))
+        .bind(&record.attachment_url)
+        .bind(&record.session_id)
+        .bind(&record.group_id)
+        .bind(&record.verification_status)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
 /// Upload session management
 pub async fn create_upload_session(
     session_id: String,
@@ -537,6 +1078,18 @@ pub async fn update_upload_session_progress(
     Ok(())
 }
 
+pub async fn update_upload_session_status(session_id: &str, status: &str) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    sqlx::query("UPDATE upload_sessions SET session_status = ? WHERE id = ?")
+        .bind(status)
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn get_upload_session_stats(session_id: &str) -> AppResult<Option<(i32, i32, i32, i32)>> {
     let pool = get_pool()?;
 
@@ -559,7 +1112,107 @@ pub async fn get_upload_session_stats(session_id: &str) -> AppResult<Option<(i32
     }
 }
 
-pub async fn cleanup_old_upload_sessions(days: i32) -> AppResult<u64> {
+// What a session uploaded, reconstructed from its history rows
+#[derive(Debug, serde::Serialize)]
+pub struct SessionUpload {
+    pub history_id: i64,
+    pub file_path: String,
+    pub file_name: String,
+    pub webhook_id: i64,
+    pub webhook_name: Option<String>,
+    pub upload_status: String,
+    pub error_message: Option<String>,
+    pub message_url: Option<String>,
+    pub uploaded_at: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SessionGroupDetails {
+    /// None for uploads recorded outside a group (single-file retries)
+    pub group_id: Option<String>,
+    pub uploads: Vec<SessionUpload>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UploadSessionDetails {
+    pub session_id: String,
+    pub webhook_id: i64,
+    pub session_status: String,
+    pub total_files: i32,
+    pub successful_uploads: i32,
+    pub failed_uploads: i32,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    /// Groups in the order they were uploaded
+    pub groups: Vec<SessionGroupDetails>,
+}
+
+pub async fn get_upload_session_details(session_id: &str) -> AppResult<UploadSessionDetails> {
+    let pool = get_pool()?;
+
+    let session = sqlx::query(
+        r#"
+        SELECT id, webhook_id, session_status, total_files, successful_uploads, failed_uploads,
+               started_at, completed_at
+        FROM upload_sessions
+        WHERE id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT h.id, h.file_path, h.file_name, h.webhook_id, w.name AS webhook_name,
+               h.upload_status, h.error_message, h.message_url, h.uploaded_at, h.group_id
+        FROM upload_history h
+        LEFT JOIN webhooks w ON w.id = h.webhook_id
+        WHERE h.session_id = ?
+        ORDER BY h.id
+        "#,
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut groups: Vec<SessionGroupDetails> = Vec::new();
+    for row in rows {
+        let group_id: Option<String> = row.get("group_id");
+        let upload = SessionUpload {
+            history_id: row.get("id"),
+            file_path: row.get("file_path"),
+            file_name: row.get("file_name"),
+            webhook_id: row.get("webhook_id"),
+            webhook_name: row.get("webhook_name"),
+            upload_status: row.get("upload_status"),
+            error_message: row.get("error_message"),
+            message_url: row.get("message_url"),
+            uploaded_at: row.get("uploaded_at"),
+        };
+        match groups.iter_mut().find(|g| g.group_id == group_id) {
+            Some(group) => group.uploads.push(upload),
+            None => groups.push(SessionGroupDetails {
+                group_id,
+                uploads: vec![upload],
+            }),
+        }
+    }
+
+    Ok(UploadSessionDetails {
+        session_id: session.get("id"),
+        webhook_id: session.get("webhook_id"),
+        session_status: session.get("session_status"),
+        total_files: session.get("total_files"),
+        successful_uploads: session.get("successful_uploads"),
+        failed_uploads: session.get("failed_uploads"),
+        started_at: session.get("started_at"),
+        completed_at: session.get("completed_at"),
+        groups,
+    })
+}
+
+pub async fn cleanup_old_upload_sessions(days: i32) -> AppResult<u64> {
     let pool = get_pool()?;
 
     let result = sqlx::query(
@@ -585,6 +1238,613 @@ pub async fn cleanup_old_upload_history(days: i32) -> AppResult<u64> {
     Ok(result.rows_affected())
 }
 
+// Discord posts recorded for uploaded files
+#[derive(Debug, serde::Serialize)]
+pub struct UploadedMessageInfo {
+    pub history_id: i64,
+    pub webhook_id: i64,
+    pub webhook_name: Option<String>,
+    pub message_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub message_url: Option<String>,
+    pub attachment_url: Option<String>,
+    pub uploaded_at: String,
+}
+
+/// Successful uploads of a file, most recent first. Uploads made before message
+/// tracking existed are included with empty message fields.
+pub async fn get_uploaded_message_info(file_path: &str) -> AppResult<Vec<UploadedMessageInfo>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT h.id, h.webhook_id, w.name AS webhook_name, h.message_id, h.channel_id,
+               h.message_url, h.attachment_url, h.uploaded_at
+        FROM upload_history h
+        LEFT JOIN webhooks w ON w.id = h.webhook_id
+        WHERE h.file_path = ? AND h.upload_status = 'success'
+        ORDER BY h.uploaded_at DESC, h.id DESC
+        "#,
+    )
+    .bind(file_path)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(uploaded_message_from_row).collect())
+}
+
+pub async fn get_upload_history_message(history_id: i64) -> AppResult<UploadedMessageInfo> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT h.id, h.webhook_id, w.name AS webhook_name, h.message_id, h.channel_id,
+               h.message_url, h.attachment_url, h.uploaded_at
+        FROM upload_history h
+        LEFT JOIN webhooks w ON w.id = h.webhook_id
+        WHERE h.id = ?
+        "#,
+    )
+    .bind(history_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(uploaded_message_from_row(&row))
+}
+
+fn uploaded_message_from_row(row: &sqlx::sqlite::SqliteRow) -> UploadedMessageInfo {
+    UploadedMessageInfo {
+        history_id: row.get("id"),
+        webhook_id: row.get("webhook_id"),
+        webhook_name: row.get("webhook_name"),
+        message_id: row.get("message_id"),
+        channel_id: row.get("channel_id"),
+        message_url: row.get("message_url"),
+        attachment_url: row.get("attachment_url"),
+        uploaded_at: row.get("uploaded_at"),
+    }
+}
+
+// Previous uploads of a file, used to badge photos that were already shared
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileUploadStatus {
+    pub file_path: String,
+    pub uploaded: bool,
+    pub upload_count: i64,
+    pub last_uploaded_at: Option<String>,
+    pub webhook_id: Option<i64>,
+    pub webhook_name: Option<String>,
+    /// "path", or "hash" when the same photo was uploaded from somewhere else
+    pub matched_by: Option<String>,
+}
+
+/// Upload status of each file, matched by path or, when a tagged hash is given, by
+/// content. The most recent successful upload is reported.
+pub async fn get_upload_status_for_files(
+    files: &[(String, Option<String>)],
+) -> AppResult<Vec<FileUploadStatus>> {
+    upload_status_for_files(get_pool()?, files).await
+}
+
+pub async fn upload_status_for_files(
+    pool: &Pool<Sqlite>,
+    files: &[(String, Option<String>)],
+) -> AppResult<Vec<FileUploadStatus>> {
+    let mut statuses = Vec::with_capacity(files.len());
+    for (file_path, file_hash) in files {
+        let row = sqlx::query(
+            r#"
+            SELECT h.webhook_id, w.name AS webhook_name, h.uploaded_at,
+                   CASE WHEN h.file_path = ? THEN 'path' ELSE 'hash' END AS matched_by,
+                   COUNT(*) OVER () AS upload_count
+            FROM upload_history h
+            LEFT JOIN webhooks w ON w.id = h.webhook_id
+            WHERE h.upload_status = 'success'
+              AND (h.file_path = ? OR (? IS NOT NULL AND h.file_hash = ?))
+            ORDER BY h.uploaded_at DESC, h.id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(file_path)
+        .bind(file_path)
+        .bind(file_hash)
+        .bind(file_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        statuses.push(match row {
+            Some(row) => FileUploadStatus {
+                file_path: file_path.clone(),
+                uploaded: true,
+                upload_count: row.get("upload_count"),
+                last_uploaded_at: row.get("uploaded_at"),
+                webhook_id: row.get("webhook_id"),
+                webhook_name: row.get("webhook_name"),
+                matched_by: row.get("matched_by"),
+            },
+            None => FileUploadStatus {
+                file_path: file_path.clone(),
+                uploaded: false,
+                upload_count: 0,
+                last_uploaded_at: None,
+                webhook_id: None,
+                webhook_name: None,
+                matched_by: None,
+            },
+        });
+    }
+
+    Ok(statuses)
+}
+
+// Files written by the metadata editor, with the backup of whatever they replaced
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MetadataEdit {
+    pub id: i64,
+    pub source_path: String,
+    pub output_path: String,
+    pub backup_path: Option<String>,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+pub async fn record_metadata_edit(
+    source_path: &str,
+    output_path: &str,
+    backup_path: Option<&str>,
+) -> AppResult<i64> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query(
+        "INSERT INTO metadata_edits (source_path, output_path, backup_path) VALUES (?, ?, ?)",
+    )
+    .bind(source_path)
+    .bind(output_path)
+    .bind(backup_path)
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Most recent metadata edits first
+pub async fn get_metadata_edits(limit: i64) -> AppResult<Vec<MetadataEdit>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT id, source_path, output_path, backup_path, created_at, undone_at FROM metadata_edits ORDER BY id DESC LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(metadata_edit_from_row).collect())
+}
+
+pub async fn get_metadata_edit(id: i64) -> AppResult<MetadataEdit> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query(
+        "SELECT id, source_path, output_path, backup_path, created_at, undone_at FROM metadata_edits WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::validation("edit_id", "Metadata edit not found"))?;
+
+    Ok(metadata_edit_from_row(&row))
+}
+
+/// Whether a later edit that hasn't been undone wrote to the same output file
+pub async fn has_newer_metadata_edit(id: i64, output_path: &str) -> AppResult<bool> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM metadata_edits WHERE id > ? AND output_path = ? AND undone_at IS NULL",
+    )
+    .bind(id)
+    .bind(output_path)
+    .fetch_one(pool)
+    .await?;
+
+    let count: i64 = row.get("count");
+    Ok(count > 0)
+}
+
+pub async fn mark_metadata_edit_undone(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    sqlx::query("UPDATE metadata_edits SET undone_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+fn metadata_edit_from_row(row: &sqlx::sqlite::SqliteRow) -> MetadataEdit {
+    MetadataEdit {
+        id: row.get("id"),
+        source_path: row.get("source_path"),
+        output_path: row.get("output_path"),
+        backup_path: row.get("backup_path"),
+        created_at: row.get("created_at"),
+        undone_at: row.get("undone_at"),
+    }
+}
+
+/// Mark every upload that was part of a deleted Discord message, so those files
+/// count as not uploaded again. Returns the affected file paths.
+pub async fn mark_message_deleted(message_id: &str) -> AppResult<Vec<String>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "UPDATE upload_history SET upload_status = 'deleted' WHERE message_id = ? AND upload_status = 'success' RETURNING file_path",
+    )
+    .bind(message_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(|row| row.get("file_path")).collect())
+}
+
+// Failed groups persisted for retry after restart
+#[derive(Debug, serde::Serialize)]
+pub struct FailedGroupRecord {
+    pub id: i64,
+    pub session_id: String,
+    pub webhook_id: i64,
+    pub group_id: String,
+    pub file_paths: Vec<String>,
+    pub options: FailedGroupOptions,
+    pub error_message: Option<String>,
+    pub failed_at: String,
+}
+
+/// Distinct successfully uploaded files in `[start, end)` (UTC `YYYY-MM-DD HH:MM:SS`),
+/// with the message URL of their most recent upload
+pub async fn get_successful_uploads_between(
+    start: &str,
+    end: &str,
+) -> AppResult<Vec<(String, Option<String>)>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT file_path, MAX(message_url) AS message_url FROM upload_history
+         WHERE upload_status = 'success' AND uploaded_at >= ? AND uploaded_at < ?
+         GROUP BY file_path
+         ORDER BY MIN(uploaded_at)",
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("file_path"), row.get("message_url")))
+        .collect())
+}
+
+/// Files in upload_history with a hash not starting with `prefix` (the current algorithm)
+pub async fn get_files_with_legacy_hash(prefix: &str) -> AppResult<Vec<String>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT DISTINCT file_path FROM upload_history
+         WHERE file_hash IS NOT NULL AND substr(file_hash, 1, length(?1)) != ?1",
+    )
+    .bind(prefix)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(|row| row.get("file_path")).collect())
+}
+
+/// Replace a file's legacy hashes (those not starting with `prefix`) with `file_hash`
+pub async fn replace_legacy_file_hash(
+    file_path: &str,
+    prefix: &str,
+    file_hash: Option<&str>,
+) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    sqlx::query(
+        "UPDATE upload_history SET file_hash = ?1
+         WHERE file_path = ?2 AND file_hash IS NOT NULL AND substr(file_hash, 1, length(?3)) != ?3",
+    )
+    .bind(file_hash)
+    .bind(file_path)
+    .bind(prefix)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// A webhook read from the old Python uploader's store
+#[derive(Debug, Clone)]
+pub struct LegacyWebhook {
+    /// Id in the old store, which its history rows refer to
+    pub legacy_id: Option<i64>,
+    pub name: String,
+    pub url: String,
+}
+
+/// An upload history row read from the old Python uploader's store
+#[derive(Debug, Clone)]
+pub struct LegacyUpload {
+    pub file_path: String,
+    /// Whatever hash the old uploader stored; rehashed by `migrate_legacy_file_hashes`
+    pub file_hash: Option<String>,
+    pub file_size: Option<u64>,
+    pub legacy_webhook_id: Option<i64>,
+    pub webhook_url: Option<String>,
+    /// UTC `YYYY-MM-DD HH:MM:SS`
+    pub uploaded_at: Option<String>,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct LegacyImportSummary {
+    pub webhooks_added: usize,
+    /// Old webhooks whose URL was already in the database
+    pub webhooks_matched: usize,
+    pub uploads_imported: usize,
+    /// Rows already imported or whose webhook couldn't be found
+    pub uploads_skipped: usize,
+}
+
+pub async fn import_legacy_history(
+    webhooks: &[LegacyWebhook],
+    uploads: &[LegacyUpload],
+) -> AppResult<LegacyImportSummary> {
+    write_legacy_history(get_pool()?, webhooks, uploads).await
+}
+
+/// Add the old uploader's webhooks (matched by URL) and history in one transaction.
+/// Rows already present are skipped, so importing the same store twice is harmless.
+pub async fn write_legacy_history(
+    pool: &Pool<Sqlite>,
+    webhooks: &[LegacyWebhook],
+    uploads: &[LegacyUpload],
+) -> AppResult<LegacyImportSummary> {
+    let mut summary = LegacyImportSummary::default();
+    let mut tx = pool.begin().await?;
+
+    let mut by_legacy_id = HashMap::new();
+    let mut by_url = HashMap::new();
+    for webhook in webhooks {
+        let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM webhooks WHERE url = ?")
+            .bind(&webhook.url)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let webhook_id = match existing {
+            Some(id) => {
+                summary.webhooks_matched += 1;
+                id
+            }
+            None => {
+                // Names are unique too, so a clashing name gets a suffix
+                let mut name = webhook.name.clone();
+                let mut attempt = 0;
+                loop {
+                    let taken: Option<i64> =
+                        sqlx::query_scalar("SELECT id FROM webhooks WHERE name = ?")
+                            .bind(&name)
+                            .fetch_optional(&mut *tx)
+                            .await?;
+                    if taken.is_none() {
+                        break;
+                    }
+                    attempt += 1;
+                    name = match attempt {
+                        1 => format!("{} (imported)", webhook.name),
+                        n => format!("{} (imported {n})", webhook.name),
+                    };
+                }
+                summary.webhooks_added += 1;
+                sqlx::query("INSERT INTO webhooks (name, url) VALUES (?, ?)")
+                    .bind(&name)
+                    .bind(&webhook.url)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid()
+            }
+        };
+
+        if let Some(legacy_id) = webhook.legacy_id {
+            by_legacy_id.insert(legacy_id, webhook_id);
+        }
+        by_url.insert(webhook.url.as_str(), webhook_id);
+    }
+
+    // Stores with a single webhook don't always say which webhook a row belongs to
+    let only_webhook = match by_url.values().collect::<Vec<_>>().as_slice() {
+        [id] => Some(**id),
+        _ => None,
+    };
+
+    for upload in uploads {
+        let mut webhook_id = upload
+            .legacy_webhook_id
+            .and_then(|id| by_legacy_id.get(&id).copied())
+            .or_else(|| {
+                upload
+                    .webhook_url
+                    .as_deref()
+                    .and_then(|url| by_url.get(url).copied())
+            });
+        if webhook_id.is_none() {
+            if let Some(url) = &upload.webhook_url {
+                webhook_id = sqlx::query_scalar("SELECT id FROM webhooks WHERE url = ?")
+                    .bind(url)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            }
+        }
+        let Some(webhook_id) = webhook_id.or(only_webhook) else {
+            summary.uploads_skipped += 1;
+            continue;
+        };
+
+        let already_imported: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM upload_history
+             WHERE file_path = ?1 AND webhook_id = ?2 AND (?3 IS NULL OR uploaded_at = ?3)",
+        )
+        .bind(&upload.file_path)
+        .bind(webhook_id)
+        .bind(&upload.uploaded_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if already_imported.is_some() {
+            summary.uploads_skipped += 1;
+            continue;
+        }
+
+        let file_name = std::path::Path::new(&upload.file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| upload.file_path.clone());
+        sqlx::query(
+            r#"
+            INSERT INTO upload_history
+            (file_path, file_name, file_hash, file_size, webhook_id, upload_status, uploaded_at)
+            VALUES (?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))
+            "#,
+        )
+        .bind(&upload.file_path)
+        .bind(&file_name)
+        .bind(&upload.file_hash)
+        .bind(upload.file_size.map(|s| s as i64))
+        .bind(webhook_id)
+        .bind(&upload.status)
+        .bind(&upload.uploaded_at)
+        .execute(&mut *tx)
+        .await?;
+        summary.uploads_imported += 1;
+    }
+
+    tx.commit().await?;
+    Ok(summary)
+}
+
+pub async fn is_roundup_posted(period_key: &str) -> AppResult<bool> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query("SELECT 1 FROM roundup_posts WHERE period_key = ?")
+        .bind(period_key)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.is_some())
+}
+
+pub async fn record_roundup_posted(
+    period_key: &str,
+    webhook_id: i64,
+    photo_count: usize,
+) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    sqlx::query(
+        "INSERT OR REPLACE INTO roundup_posts (period_key, webhook_id, photo_count) VALUES (?, ?, ?)",
+    )
+    .bind(period_key)
+    .bind(webhook_id)
+    .bind(photo_count as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn record_failed_group(
+    session_id: &str,
+    webhook_id: i64,
+    group_id: &str,
+    file_paths: &[String],
+    options: &FailedGroupOptions,
+    error_message: Option<String>,
+) -> AppResult<i64> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO failed_groups
+        (session_id, webhook_id, group_id, file_paths, options, error_message)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(session_id)
+    .bind(webhook_id)
+    .bind(group_id)
+    .bind(serde_json::to_string(file_paths)?)
+    .bind(serde_json::to_string(options)?)
+    .bind(error_message)
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+fn failed_group_from_row(row: &sqlx::sqlite::SqliteRow) -> AppResult<FailedGroupRecord> {
+    let file_paths: String = row.get("file_paths");
+    let options: String = row.get("options");
+
+    Ok(FailedGroupRecord {
+        id: row.get("id"),
+        session_id: row.get("session_id"),
+        webhook_id: row.get("webhook_id"),
+        group_id: row.get("group_id"),
+        file_paths: serde_json::from_str(&file_paths)?,
+        options: serde_json::from_str(&options)?,
+        error_message: row.get("error_message"),
+        failed_at: row.get("failed_at"),
+    })
+}
+
+pub async fn get_failed_groups() -> AppResult<Vec<FailedGroupRecord>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT id, session_id, webhook_id, group_id, file_paths, options, error_message, failed_at FROM failed_groups ORDER BY failed_at DESC, id DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter().map(failed_group_from_row).collect()
+}
+
+pub async fn get_failed_group(id: i64) -> AppResult<FailedGroupRecord> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query(
+        "SELECT id, session_id, webhook_id, group_id, file_paths, options, error_message, failed_at FROM failed_groups WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    failed_group_from_row(&row)
+}
+
+pub async fn delete_failed_group(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("DELETE FROM failed_groups WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
 // User Webhook Overrides
 #[derive(Debug, serde::Serialize)]
 pub struct UserWebhookOverride {
@@ -613,50 +1873,503 @@ pub async fn get_user_webhook_overrides() -> AppResult<Vec<UserWebhookOverride>>
         });
     }
 
-    Ok(overrides)
+    Ok(overrides)
+}
+
+pub async fn add_user_webhook_override(
+    user_id: Option<String>,
+    user_display_name: Option<String>,
+    webhook_id: i64,
+) -> AppResult<i64> {
+    let pool = get_pool()?;
+
+    if user_id.is_none() && user_display_name.is_none() {
+        return Err(AppError::validation(
+            "user",
+            "Must provide either User ID or User Display Name",
+        ));
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO user_webhook_overrides (user_id, user_display_name, webhook_id) VALUES (?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(user_display_name)
+    .bind(webhook_id)
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(result) => Ok(result.last_insert_rowid()),
+        Err(e) => Err(AppError::Database(e)),
+    }
+}
+
+pub async fn delete_user_webhook_override(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("DELETE FROM user_webhook_overrides WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+// Forum tags
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ForumTag {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub tag_id: String,
+    /// Only tag posts from this world; None tags every post
+    pub world_id: Option<String>,
+}
+
+pub async fn get_webhook_forum_tags(webhook_id: i64) -> AppResult<Vec<ForumTag>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT id, webhook_id, tag_id, world_id FROM webhook_forum_tags
+         WHERE webhook_id = ? ORDER BY world_id ASC, id ASC",
+    )
+    .bind(webhook_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let world_id: String = row.get("world_id");
+            ForumTag {
+                id: row.get("id"),
+                webhook_id: row.get("webhook_id"),
+                tag_id: row.get("tag_id"),
+                world_id: (!world_id.is_empty()).then_some(world_id),
+            }
+        })
+        .collect())
+}
+
+pub async fn add_webhook_forum_tag(
+    webhook_id: i64,
+    tag_id: &str,
+    world_id: Option<&str>,
+) -> AppResult<i64> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query(
+        "INSERT INTO webhook_forum_tags (webhook_id, tag_id, world_id) VALUES (?, ?, ?)",
+    )
+    .bind(webhook_id)
+    .bind(tag_id)
+    .bind(world_id.unwrap_or_default())
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(result) => Ok(result.last_insert_rowid()),
+        Err(sqlx::Error::Database(db_err))
+            if db_err.code() == Some(std::borrow::Cow::Borrowed("2067")) =>
+        {
+            Err(AppError::validation(
+                "tag_id",
+                "This tag is already applied to these posts",
+            ))
+        }
+        Err(e) => Err(AppError::Database(e)),
+    }
+}
+
+pub async fn delete_webhook_forum_tag(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("DELETE FROM webhook_forum_tags WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+// Upload presets
+/// Named upload settings saved for a webhook; the default one is applied when the
+/// webhook is picked. `id` is None for a preset that hasn't been saved yet.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UploadPreset {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub webhook_id: i64,
+    pub name: String,
+    pub group_by_metadata: bool,
+    pub grouping_time_window: u32,
+    pub max_images_per_message: u8,
+    pub include_player_names: bool,
+    /// None keeps the configured compression settings
+    #[serde(default)]
+    pub compression_format: Option<String>,
+    #[serde(default)]
+    pub upload_quality: Option<u8>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+fn upload_preset_from_row(row: &sqlx::sqlite::SqliteRow) -> UploadPreset {
+    UploadPreset {
+        id: Some(row.get("id")),
+        webhook_id: row.get("webhook_id"),
+        name: row.get("name"),
+        group_by_metadata: row.get("group_by_metadata"),
+        grouping_time_window: row.get::<i64, _>("grouping_time_window") as u32,
+        max_images_per_message: row.get::<i64, _>("max_images_per_message") as u8,
+        include_player_names: row.get("include_player_names"),
+        compression_format: row.get("compression_format"),
+        upload_quality: row.get::<Option<i64>, _>("upload_quality").map(|q| q as u8),
+        is_default: row.get("is_default"),
+    }
+}
+
+pub async fn get_upload_presets(webhook_id: i64) -> AppResult<Vec<UploadPreset>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT * FROM upload_presets WHERE webhook_id = ? ORDER BY is_default DESC, name ASC",
+    )
+    .bind(webhook_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(upload_preset_from_row).collect())
+}
+
+pub async fn get_upload_preset(id: i64) -> AppResult<Option<UploadPreset>> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query("SELECT * FROM upload_presets WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(upload_preset_from_row))
+}
+
+/// Insert a new preset or update the one with `preset.id`, returning its ID. Making a
+/// preset the default clears the flag on the webhook's other presets.
+pub async fn save_upload_preset(preset: &UploadPreset) -> AppResult<i64> {
+    let pool = get_pool()?;
+    let mut tx = pool.begin().await?;
+
+    if preset.is_default {
+        sqlx::query(
+            "UPDATE upload_presets SET is_default = FALSE WHERE webhook_id = ? AND id IS NOT ?",
+        )
+        .bind(preset.webhook_id)
+        .bind(preset.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let result = match preset.id {
+        Some(id) => sqlx::query(
+            "UPDATE upload_presets SET webhook_id = ?, name = ?, group_by_metadata = ?,
+                 grouping_time_window = ?, max_images_per_message = ?, include_player_names = ?,
+                 compression_format = ?, upload_quality = ?, is_default = ?
+             WHERE id = ?",
+        )
+        .bind(preset.webhook_id)
+        .bind(&preset.name)
+        .bind(preset.group_by_metadata)
+        .bind(preset.grouping_time_window)
+        .bind(preset.max_images_per_message)
+        .bind(preset.include_player_names)
+        .bind(&preset.compression_format)
+        .bind(preset.upload_quality)
+        .bind(preset.is_default)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map(|result| (result.rows_affected() > 0).then_some(id)),
+        None => sqlx::query(
+            "INSERT INTO upload_presets (webhook_id, name, group_by_metadata,
+                 grouping_time_window, max_images_per_message, include_player_names,
+                 compression_format, upload_quality, is_default)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(preset.webhook_id)
+        .bind(&preset.name)
+        .bind(preset.group_by_metadata)
+        .bind(preset.grouping_time_window)
+        .bind(preset.max_images_per_message)
+        .bind(preset.include_player_names)
+        .bind(&preset.compression_format)
+        .bind(preset.upload_quality)
+        .bind(preset.is_default)
+        .execute(&mut *tx)
+        .await
+        .map(|result| Some(result.last_insert_rowid())),
+    };
+
+    let id = match result {
+        Ok(Some(id)) => id,
+        Ok(None) => return Err(AppError::Database(sqlx::Error::RowNotFound)),
+        Err(sqlx::Error::Database(db_err))
+            if db_err.code() == Some(std::borrow::Cow::Borrowed("2067")) =>
+        {
+            return Err(AppError::validation(
+                "name",
+                "This webhook already has a preset with that name",
+            ));
+        }
+        Err(e) => return Err(AppError::Database(e)),
+    };
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+pub async fn delete_upload_preset(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("DELETE FROM upload_presets WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+// Session templates
+/// A full upload setup saved under a name (e.g. a weekly club night), so a session
+/// can be started from it with just the photos. `id` is None until it's saved.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SessionTemplate {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    pub webhook_ids: Vec<i64>,
+    pub group_by_metadata: bool,
+    pub grouping_time_window: u32,
+    pub group_by_world: bool,
+    #[serde(default)]
+    pub group_by_instance: bool,
+    #[serde(default)]
+    pub merge_no_metadata: bool,
+    #[serde(default)]
+    pub adopt_orphans: bool,
+    pub max_images_per_message: u8,
+    pub include_player_names: bool,
+    #[serde(default)]
+    pub single_thread_mode: bool,
+    #[serde(default)]
+    pub extra_message: Option<String>,
+    /// Tags applied to forum posts instead of the webhook's own; empty uses the webhook's
+    #[serde(default)]
+    pub forum_tag_ids: Vec<String>,
+    /// None keeps the configured compression settings
+    #[serde(default)]
+    pub compression_format: Option<String>,
+    #[serde(default)]
+    pub upload_quality: Option<u8>,
+}
+
+fn session_template_from_row(row: &sqlx::sqlite::SqliteRow) -> SessionTemplate {
+    SessionTemplate {
+        id: Some(row.get("id")),
+        name: row.get("name"),
+        webhook_ids: serde_json::from_str(row.get("webhook_ids")).unwrap_or_default(),
+        group_by_metadata: row.get("group_by_metadata"),
+        grouping_time_window: row.get::<i64, _>("grouping_time_window") as u32,
+        group_by_world: row.get("group_by_world"),
+        group_by_instance: row.get("group_by_instance"),
+        merge_no_metadata: row.get("merge_no_metadata"),
+        adopt_orphans: row.get("adopt_orphans"),
+        max_images_per_message: row.get::<i64, _>("max_images_per_message") as u8,
+        include_player_names: row.get("include_player_names"),
+        single_thread_mode: row.get("single_thread_mode"),
+        extra_message: row.get("extra_message"),
+        forum_tag_ids: serde_json::from_str(row.get("forum_tag_ids")).unwrap_or_default(),
+        compression_format: row.get("compression_format"),
+        upload_quality: row.get::<Option<i64>, _>("upload_quality").map(|q| q as u8),
+    }
+}
+
+pub async fn get_session_templates() -> AppResult<Vec<SessionTemplate>> {
+    read_session_templates(get_pool()?).await
+}
+
+pub async fn read_session_templates(pool: &Pool<Sqlite>) -> AppResult<Vec<SessionTemplate>> {
+    let rows = sqlx::query("SELECT * FROM session_templates ORDER BY name ASC")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(session_template_from_row).collect())
+}
+
+pub async fn get_session_template(id: i64) -> AppResult<Option<SessionTemplate>> {
+    let pool = get_pool()?;
+
+    let row = sqlx::query("SELECT * FROM session_templates WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(session_template_from_row))
+}
+
+/// Insert a new template or update the one with `template.id`, returning its ID
+pub async fn save_session_template(template: &SessionTemplate) -> AppResult<i64> {
+    write_session_template(get_pool()?, template).await
+}
+
+pub async fn write_session_template(
+    pool: &Pool<Sqlite>,
+    template: &SessionTemplate,
+) -> AppResult<i64> {
+    let webhook_ids = serde_json::to_string(&template.webhook_ids)?;
+    let forum_tag_ids = serde_json::to_string(&template.forum_tag_ids)?;
+
+    let query = match template.id {
+        Some(_) => sqlx::query(
+            "UPDATE session_templates SET name = ?, webhook_ids = ?, group_by_metadata = ?,
+                 grouping_time_window = ?, group_by_world = ?, group_by_instance = ?,
+                 merge_no_metadata = ?, adopt_orphans = ?, max_images_per_message = ?,
+                 include_player_names = ?, single_thread_mode = ?, extra_message = ?,
+                 forum_tag_ids = ?, compression_format = ?, upload_quality = ?
+             WHERE id = ?",
+        ),
+        None => sqlx::query(
+            "INSERT INTO session_templates (name, webhook_ids, group_by_metadata,
+                 grouping_time_window, group_by_world, group_by_instance, merge_no_metadata,
+                 adopt_orphans, max_images_per_message, include_player_names,
+                 single_thread_mode, extra_message, forum_tag_ids, compression_format,
+                 upload_quality)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        ),
+    };
+    let mut query = query
+        .bind(&template.name)
+        .bind(webhook_ids)
+        .bind(template.group_by_metadata)
+        .bind(template.grouping_time_window)
+        .bind(template.group_by_world)
+        .bind(template.group_by_instance)
+        .bind(template.merge_no_metadata)
+        .bind(template.adopt_orphans)
+        .bind(template.max_images_per_message)
+        .bind(template.include_player_names)
+        .bind(template.single_thread_mode)
+        .bind(&template.extra_message)
+        .bind(forum_tag_ids)
+        .bind(&template.compression_format)
+        .bind(template.upload_quality);
+    if let Some(id) = template.id {
+        query = query.bind(id);
+    }
+    let result = query.execute(pool).await;
+
+    match (result, template.id) {
+        (Ok(result), Some(id)) if result.rows_affected() > 0 => Ok(id),
+        (Ok(_), Some(_)) => Err(AppError::Database(sqlx::Error::RowNotFound)),
+        (Ok(result), None) => Ok(result.last_insert_rowid()),
+        (Err(sqlx::Error::Database(db_err)), _)
+            if db_err.code() == Some(std::borrow::Cow::Borrowed("2067")) =>
+        {
+            Err(AppError::validation(
+                "name",
+                "A template with that name already exists",
+            ))
+        }
+        (Err(e), _) => Err(AppError::Database(e)),
+    }
+}
+
+pub async fn delete_session_template(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("DELETE FROM session_templates WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
 }
 
-pub async fn add_user_webhook_override(
-    user_id: Option<String>,
-    user_display_name: Option<String>,
+// Forum threads per world
+/// Thread remembered for a world's photos, unless it was created more than
+/// `max_age_days` ago (0 = any age)
+pub async fn get_forum_thread(
     webhook_id: i64,
-) -> AppResult<i64> {
+    world_id: &str,
+    max_age_days: u32,
+) -> AppResult<Option<String>> {
     let pool = get_pool()?;
 
-    if user_id.is_none() && user_display_name.is_none() {
-        return Err(AppError::validation(
-            "user",
-            "Must provide either User ID or User Display Name",
-        ));
-    }
+    let row = sqlx::query(
+        "SELECT thread_id FROM forum_threads
+         WHERE webhook_id = ? AND world_id = ?
+           AND (? = 0 OR created_at >= datetime('now', '-' || ? || ' days'))",
+    )
+    .bind(webhook_id)
+    .bind(world_id)
+    .bind(max_age_days)
+    .bind(max_age_days)
+    .fetch_optional(pool)
+    .await?;
 
-    let result = sqlx::query(
-        "INSERT INTO user_webhook_overrides (user_id, user_display_name, webhook_id) VALUES (?, ?, ?)",
+    Ok(row.map(|row| row.get("thread_id")))
+}
+
+/// Remember the thread a world's photos went to. A different thread replaces the
+/// old one and restarts its age.
+pub async fn remember_forum_thread(
+    webhook_id: i64,
+    world_id: &str,
+    thread_id: &str,
+) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    sqlx::query(
+        "INSERT INTO forum_threads (webhook_id, world_id, thread_id) VALUES (?, ?, ?)
+         ON CONFLICT(webhook_id, world_id) DO UPDATE SET
+             created_at = CASE WHEN thread_id = excluded.thread_id
+                               THEN created_at ELSE CURRENT_TIMESTAMP END,
+             thread_id = excluded.thread_id,
+             last_used_at = CURRENT_TIMESTAMP",
     )
-    .bind(user_id)
-    .bind(user_display_name)
     .bind(webhook_id)
+    .bind(world_id)
+    .bind(thread_id)
     .execute(pool)
-    .await;
+    .await?;
 
-    match result {
-        Ok(result) => Ok(result.last_insert_rowid()),
-        Err(e) => Err(AppError::Database(e)),
-    }
+    Ok(())
 }
 
-pub async fn delete_user_webhook_override(id: i64) -> AppResult<()> {
+pub async fn forget_forum_thread(webhook_id: i64, world_id: &str) -> AppResult<()> {
     let pool = get_pool()?;
 
-    let result = sqlx::query("DELETE FROM user_webhook_overrides WHERE id = ?")
-        .bind(id)
+    sqlx::query("DELETE FROM forum_threads WHERE webhook_id = ? AND world_id = ?")
+        .bind(webhook_id)
+        .bind(world_id)
         .execute(pool)
         .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::Database(sqlx::Error::RowNotFound));
-    }
-
     Ok(())
 }
 
@@ -781,6 +2494,483 @@ pub async fn delete_discord_user_mapping(id: i64) -> AppResult<()> {
     Ok(())
 }
 
+// Collections (user-curated sets of photos)
+#[derive(Debug, serde::Serialize)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub item_count: i64,
+    pub created_at: String,
+}
+
+fn collection_name_error(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db_err)
+            if db_err.code() == Some(std::borrow::Cow::Borrowed("2067")) =>
+        {
+            AppError::validation("name", "A collection with this name already exists")
+        }
+        e => AppError::Database(e),
+    }
+}
+
+pub async fn get_collections() -> AppResult<Vec<Collection>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT c.id, c.name, c.description, c.created_at, COUNT(i.id) AS item_count
+         FROM collections c
+         LEFT JOIN collection_items i ON i.collection_id = c.id
+         GROUP BY c.id
+         ORDER BY c.name COLLATE NOCASE",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Collection {
+            id: row.get("id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            item_count: row.get("item_count"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+pub async fn create_collection(name: &str, description: Option<&str>) -> AppResult<i64> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("INSERT INTO collections (name, description) VALUES (?, ?)")
+        .bind(name)
+        .bind(description)
+        .execute(pool)
+        .await
+        .map_err(collection_name_error)?;
+
+    log::info!("Created collection: {name}");
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_collection(id: i64, name: &str, description: Option<&str>) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query(
+        "UPDATE collections SET name = ?, description = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    )
+    .bind(name)
+    .bind(description)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(collection_name_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+pub async fn delete_collection(id: i64) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    let result = sqlx::query("DELETE FROM collections WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::Database(sqlx::Error::RowNotFound));
+    }
+
+    Ok(())
+}
+
+/// File paths in a collection, in the order they were added
+pub async fn get_collection_items(collection_id: i64) -> AppResult<Vec<String>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT file_path FROM collection_items WHERE collection_id = ? ORDER BY added_at, id",
+    )
+    .bind(collection_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(|row| row.get("file_path")).collect())
+}
+
+/// Add files to a collection, skipping ones already in it; returns how many were added
+pub async fn add_to_collection(collection_id: i64, file_paths: &[String]) -> AppResult<u64> {
+    let pool = get_pool()?;
+    let mut tx = pool.begin().await?;
+
+    let mut added = 0;
+    for file_path in file_paths {
+        added += sqlx::query(
+            "INSERT OR IGNORE INTO collection_items (collection_id, file_path) VALUES (?, ?)",
+        )
+        .bind(collection_id)
+        .bind(file_path)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    sqlx::query("UPDATE collections SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(collection_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(added)
+}
+
+/// Remove files from a collection; returns how many were removed
+pub async fn remove_from_collection(collection_id: i64, file_paths: &[String]) -> AppResult<u64> {
+    let pool = get_pool()?;
+    let mut tx = pool.begin().await?;
+
+    let mut removed = 0;
+    for file_path in file_paths {
+        removed +=
+            sqlx::query("DELETE FROM collection_items WHERE collection_id = ? AND file_path = ?")
+                .bind(collection_id)
+                .bind(file_path)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+    }
+
+    sqlx::query("UPDATE collections SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(collection_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+// Photo tags
+#[derive(Debug, serde::Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub file_count: i64,
+}
+
+/// Tag every file with every tag, skipping pairs that already exist; returns how many were added
+pub async fn tag_files(file_paths: &[String], tags: &[String], source: &str) -> AppResult<u64> {
+    let pool = get_pool()?;
+    let mut tx = pool.begin().await?;
+
+    let mut added = 0;
+    for file_path in file_paths {
+        for tag in tags {
+            added += sqlx::query(
+                "INSERT OR IGNORE INTO photo_tags (file_path, tag, source) VALUES (?, ?, ?)",
+            )
+            .bind(file_path)
+            .bind(tag)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+    }
+    tx.commit().await?;
+
+    Ok(added)
+}
+
+/// Remove tags from files; returns how many tags were removed
+pub async fn untag_files(file_paths: &[String], tags: &[String]) -> AppResult<u64> {
+    let pool = get_pool()?;
+    let mut tx = pool.begin().await?;
+
+    let mut removed = 0;
+    for file_path in file_paths {
+        for tag in tags {
+            removed += sqlx::query("DELETE FROM photo_tags WHERE file_path = ? AND tag = ?")
+                .bind(file_path)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+    }
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+/// Files carrying a tag (case-insensitive), oldest tagged first
+pub async fn get_files_by_tag(tag: &str) -> AppResult<Vec<String>> {
+    let pool = get_pool()?;
+
+    let rows =
+        sqlx::query("SELECT file_path FROM photo_tags WHERE tag = ? ORDER BY created_at, id")
+            .bind(tag)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows.iter().map(|row| row.get("file_path")).collect())
+}
+
+pub async fn get_tags_for_file(file_path: &str) -> AppResult<Vec<String>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query("SELECT tag FROM photo_tags WHERE file_path = ? ORDER BY tag")
+        .bind(file_path)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get("tag")).collect())
+}
+
+/// Every tag in use with how many files carry it
+pub async fn get_all_tags() -> AppResult<Vec<TagCount>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT tag, COUNT(*) AS file_count FROM photo_tags GROUP BY tag ORDER BY file_count DESC, tag",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TagCount {
+            tag: row.get("tag"),
+            file_count: row.get("file_count"),
+        })
+        .collect())
+}
+
+// Photo metadata index
+pub struct PhotoMetadataRecord<'a> {
+    pub file_path: &'a str,
+    pub file_name: &'a str,
+    pub world_name: Option<&'a str>,
+    pub world_id: Option<&'a str>,
+    pub author_name: Option<&'a str>,
+    pub players: Vec<String>,
+    /// Unix seconds
+    pub taken_at: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PhotoSearchResult {
+    pub file_path: String,
+    pub world_name: Option<String>,
+    pub players: Vec<String>,
+    pub taken_at: Option<i64>,
+}
+
+/// Insert or refresh a photo's metadata and its full-text entry
+pub async fn upsert_photo_metadata(record: &PhotoMetadataRecord<'_>) -> AppResult<()> {
+    write_photo_metadata(get_pool()?, record).await
+}
+
+pub async fn write_photo_metadata(
+    pool: &Pool<Sqlite>,
+    record: &PhotoMetadataRecord<'_>,
+) -> AppResult<()> {
+    let players_json = serde_json::to_string(&record.players)?;
+    let mut tx = pool.begin().await?;
+
+    let id: i64 = sqlx::query(
+        "INSERT INTO photo_metadata
+            (file_path, file_name, world_name, world_id, author_name, players, taken_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(file_path) DO UPDATE SET
+            file_name = excluded.file_name,
+            world_name = excluded.world_name,
+            world_id = excluded.world_id,
+            author_name = excluded.author_name,
+            players = excluded.players,
+            taken_at = excluded.taken_at,
+            indexed_at = CURRENT_TIMESTAMP
+         RETURNING id",
+    )
+    .bind(record.file_path)
+    .bind(record.file_name)
+    .bind(record.world_name)
+    .bind(record.world_id)
+    .bind(record.author_name)
+    .bind(&players_json)
+    .bind(record.taken_at)
+    .fetch_one(&mut *tx)
+    .await?
+    .get("id");
+
+    sqlx::query("DELETE FROM photo_search WHERE rowid = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO photo_search (rowid, file_name, world_name, player_names) VALUES (?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(record.file_name)
+    .bind(record.world_name.unwrap_or_default())
+    .bind(record.players.join(" "))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Indexed metadata of a photo, as used for stats
+#[derive(Debug, Clone, Default)]
+pub struct IndexedPhoto {
+    pub world_name: Option<String>,
+    pub world_id: Option<String>,
+    pub author_name: Option<String>,
+    pub players: Vec<String>,
+    pub taken_at: Option<i64>,
+}
+
+pub async fn get_indexed_photos() -> AppResult<Vec<IndexedPhoto>> {
+    read_indexed_photos(get_pool()?).await
+}
+
+pub async fn read_indexed_photos(pool: &Pool<Sqlite>) -> AppResult<Vec<IndexedPhoto>> {
+    let rows = sqlx::query(
+        "SELECT world_name, world_id, author_name, players, taken_at FROM photo_metadata",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| IndexedPhoto {
+            world_name: row.get("world_name"),
+            world_id: row.get("world_id"),
+            author_name: row.get("author_name"),
+            players: serde_json::from_str(row.get::<&str, _>("players")).unwrap_or_default(),
+            taken_at: row.get("taken_at"),
+        })
+        .collect())
+}
+
+/// Indexed metadata of whichever of `file_paths` have been read before, keyed by path
+pub async fn get_indexed_photos_for(
+    file_paths: &[String],
+) -> AppResult<HashMap<String, IndexedPhoto>> {
+    read_indexed_photos_for(get_pool()?, file_paths).await
+}
+
+pub async fn read_indexed_photos_for(
+    pool: &SqlitePool,
+    file_paths: &[String],
+) -> AppResult<HashMap<String, IndexedPhoto>> {
+    let rows = sqlx::query(
+        "SELECT file_path, world_name, world_id, author_name, players, taken_at
+         FROM photo_metadata
+         WHERE file_path IN (SELECT value FROM json_each(?))",
+    )
+    .bind(serde_json::to_string(file_paths)?)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                row.get("file_path"),
+                IndexedPhoto {
+                    world_name: row.get("world_name"),
+                    world_id: row.get("world_id"),
+                    author_name: row.get("author_name"),
+                    players: serde_json::from_str(row.get::<&str, _>("players"))
+                        .unwrap_or_default(),
+                    taken_at: row.get("taken_at"),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Photos matching an FTS5 query, best matches first
+pub async fn search_photos(fts_query: &str, limit: i64) -> AppResult<Vec<PhotoSearchResult>> {
+    let pool = get_pool()?;
+
+    let rows = sqlx::query(
+        "SELECT m.file_path, m.world_name, m.players, m.taken_at
+         FROM photo_search s
+         JOIN photo_metadata m ON m.id = s.rowid
+         WHERE photo_search MATCH ?
+         ORDER BY s.rank
+         LIMIT ?",
+    )
+    .bind(fts_query)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PhotoSearchResult {
+            file_path: row.get("file_path"),
+            world_name: row.get("world_name"),
+            players: serde_json::from_str(row.get::<&str, _>("players")).unwrap_or_default(),
+            taken_at: row.get("taken_at"),
+        })
+        .collect())
+}
+
+// Photo descriptions
+
+/// Set a file's description, or clear it with `None`
+pub async fn set_photo_description(file_path: &str, description: Option<&str>) -> AppResult<()> {
+    let pool = get_pool()?;
+
+    match description {
+        Some(description) => {
+            sqlx::query(
+                "INSERT INTO photo_descriptions (file_path, description) VALUES (?, ?)
+                 ON CONFLICT(file_path) DO UPDATE SET
+                     description = excluded.description,
+                     updated_at = CURRENT_TIMESTAMP",
+            )
+            .bind(file_path)
+            .bind(description)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM photo_descriptions WHERE file_path = ?")
+                .bind(file_path)
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Descriptions of the given files, keyed by path; files without one are left out
+pub async fn get_photo_descriptions(file_paths: &[String]) -> AppResult<HashMap<String, String>> {
+    let pool = get_pool()?;
+
+    let mut descriptions = HashMap::new();
+    for file_path in file_paths {
+        let row = sqlx::query("SELECT description FROM photo_descriptions WHERE file_path = ?")
+            .bind(file_path)
+            .fetch_optional(pool)
+            .await?;
+        if let Some(row) = row {
+            descriptions.insert(file_path.clone(), row.get("description"));
+        }
+    }
+
+    Ok(descriptions)
+}
+
 pub async fn is_file_processed(file_path: &str) -> AppResult<bool> {
     let pool = get_pool()?;
     let row = sqlx::query("SELECT COUNT(*) as count FROM upload_history WHERE file_path = ? AND upload_status = 'success'")
@@ -791,3 +2981,77 @@ pub async fn is_file_processed(file_path: &str) -> AppResult<bool> {
     let count: i32 = row.get("count");
     Ok(count > 0)
 }
+
+// Performance metrics
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PhaseMetrics {
+    pub phase: String,
+    /// How many times the phase was recorded (one per session or chunk)
+    pub samples: i64,
+    pub total_ms: i64,
+    pub max_ms: i64,
+    /// Files handled across all samples
+    pub items: i64,
+    pub bytes: i64,
+}
+
+pub async fn record_metric(
+    session_id: &str,
+    phase: &str,
+    duration_ms: u64,
+    items: u64,
+    bytes: u64,
+) -> AppResult<()> {
+    write_metric(get_pool()?, session_id, phase, duration_ms, items, bytes).await
+}
+
+pub async fn write_metric(
+    pool: &Pool<Sqlite>,
+    session_id: &str,
+    phase: &str,
+    duration_ms: u64,
+    items: u64,
+    bytes: u64,
+) -> AppResult<()> {
+    sqlx::query(
+        "INSERT INTO metrics (session_id, phase, duration_ms, items, bytes)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(session_id)
+    .bind(phase)
+    .bind(duration_ms as i64)
+    .bind(items as i64)
+    .bind(bytes as i64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Recorded timings summed up per phase
+pub async fn get_phase_metrics() -> AppResult<Vec<PhaseMetrics>> {
+    read_phase_metrics(get_pool()?).await
+}
+
+pub async fn read_phase_metrics(pool: &Pool<Sqlite>) -> AppResult<Vec<PhaseMetrics>> {
+    let rows = sqlx::query(
+        "SELECT phase, COUNT(*) AS samples, SUM(duration_ms) AS total_ms,
+                MAX(duration_ms) AS max_ms, SUM(items) AS items, SUM(bytes) AS bytes
+         FROM metrics
+         GROUP BY phase
+         ORDER BY phase ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PhaseMetrics {
+            phase: row.get("phase"),
+            samples: row.get("samples"),
+            total_ms: row.get("total_ms"),
+            max_ms: row.get("max_ms"),
+            items: row.get("items"),
+            bytes: row.get("bytes"),
+        })
+        .collect())
+}
@@ -0,0 +1,236 @@
+// vrcphoto:// URI scheme so other apps (e.g. VRCX) can hand photos to the uploader
+//
+// `vrcphoto://upload?path=<file>[&path=<file>...][&webhook=<name|id>]`
+// The files are added to the window's queue and a webhook, if given, is selected;
+// the upload itself only starts once the user confirms it in the window, since any
+// web page can open a link. On Windows and Linux the OS launches the exe with the URL
+// as an argument, which reaches the running instance through the single-instance
+// signal. Links can only select webhooks that are already configured in the app.
+
+use reqwest::Url;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::Webhook;
+use crate::security::InputValidator;
+use crate::{database, single_instance};
+
+pub const SCHEME: &str = "vrcphoto";
+
+#[derive(Debug, PartialEq)]
+pub enum DeepLinkAction {
+    Upload {
+        paths: Vec<String>,
+        webhook: Option<String>,
+    },
+}
+
+/// Whether a command-line argument is one of our deep links
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.get(..SCHEME.len() + 1)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{SCHEME}:")))
+}
+
+/// Parse a `vrcphoto://` URL, keeping only paths that point at existing image files
+pub fn parse_deep_link(url: &str) -> Result<DeepLinkAction, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid link: {e}"))?;
+    if !parsed.scheme().eq_ignore_ascii_case(SCHEME) {
+        return Err(format!("Not a {SCHEME}:// link"));
+    }
+
+    let action = parsed.host_str().unwrap_or_default();
+    if !action.eq_ignore_ascii_case("upload") {
+        return Err(format!("Unknown link action '{action}'"));
+    }
+
+    let mut paths = Vec::new();
+    let mut webhook = None;
+    for (key, value) in parsed.query_pairs() {
+        match key.as_ref() {
+            "path" => {
+                if let Ok(path) = InputValidator::resolve_file_path(&value) {
+                    paths.push(path.to_string_lossy().to_string());
+                } else {
+                    log::warn!("Deep link path is not a readable image: {value}");
+                }
+            }
+            "webhook" if !value.is_empty() => webhook = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    if paths.is_empty() {
+        return Err("Link contains no valid image paths".to_string());
+    }
+    Ok(DeepLinkAction::Upload { paths, webhook })
+}
+
+/// What the window does with a link: queue its files, optionally selecting a webhook
+/// for the user to confirm the upload to
+#[derive(Debug, PartialEq)]
+pub enum DeepLinkRequest {
+    Queue {
+        paths: Vec<String>,
+    },
+    QueueForWebhook {
+        paths: Vec<String>,
+        webhook_id: i64,
+        webhook_name: String,
+    },
+}
+
+/// Resolve a link's webhook against the configured ones
+fn plan_request(action: DeepLinkAction, webhooks: &[Webhook]) -> Result<DeepLinkRequest, String> {
+    let DeepLinkAction::Upload { paths, webhook } = action;
+    let Some(webhook) = webhook else {
+        return Ok(DeepLinkRequest::Queue { paths });
+    };
+    let found = crate::cli::find_webhook(webhooks, &webhook)
+        .ok_or_else(|| format!("No webhook named '{webhook}'"))?;
+    Ok(DeepLinkRequest::QueueForWebhook {
+        paths,
+        webhook_id: found.id,
+        webhook_name: found.name.clone(),
+    })
+}
+
+/// Queue a deep link's files in the window; never starts an upload by itself
+pub async fn handle_deep_link(app_handle: &AppHandle, url: &str) {
+    log::info!("Handling deep link {url}");
+
+    let request = match parse_deep_link(url) {
+        Ok(DeepLinkAction::Upload {
+            paths,
+            webhook: None,
+        }) => Ok(DeepLinkRequest::Queue { paths }),
+        Ok(action) => match database::get_all_webhooks().await {
+            Ok(webhooks) => plan_request(action, &webhooks),
+            Err(e) => Err(e.to_string()),
+        },
+        Err(e) => Err(e),
+    };
+
+    match request {
+        Ok(DeepLinkRequest::Queue { paths }) => single_instance::queue_files(app_handle, paths),
+        Ok(DeepLinkRequest::QueueForWebhook {
+            paths,
+            webhook_id,
+            webhook_name,
+        }) => {
+            let file_count = single_instance::add_pending_files(paths);
+            let _ = app_handle.emit(
+                "deep-link-upload-requested",
+                serde_json::json!({
+                    "webhook_id": webhook_id,
+                    "webhook_name": webhook_name,
+                    "file_count": file_count,
+                }),
+            );
+        }
+        Err(e) => {
+            log::warn!("Ignoring deep link: {e}");
+            let _ = app_handle.emit("deep-link-error", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_link_upload() {
+        let dir = std::env::temp_dir().join(format!("vrcpu_deeplink_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = dir.join("VRChat 2024.png");
+        std::fs::write(&image, b"png").unwrap();
+        let image_path = image.to_string_lossy().to_string();
+
+        let mut url = Url::parse("vrcphoto://upload").unwrap();
+        url.query_pairs_mut()
+            .append_pair("path", &image_path)
+            .append_pair("path", "/missing/photo.png")
+            .append_pair("webhook", "Photos");
+
+        assert_eq!(
+            parse_deep_link(url.as_str()).unwrap(),
+            DeepLinkAction::Upload {
+                paths: vec![image_path],
+                webhook: Some("Photos".to_string()),
+            }
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn webhook(id: i64, name: &str) -> Webhook {
+        Webhook {
+            id,
+            name: name.to_string(),
+            url: format!("https://discord.com/api/webhooks/{id}/token"),
+            is_forum: false,
+            pinned: false,
+            suppress_embeds: false,
+            suppress_notifications: false,
+            upload_limit_mb: None,
+            timestamp_style: None,
+        }
+    }
+
+    #[test]
+    fn test_link_with_webhook_only_queues_and_selects_it() {
+        let webhooks = [webhook(1, "Photos"), webhook(2, "Friends")];
+        let paths = vec!["/photos/a.png".to_string()];
+
+        // Naming a webhook selects it for the user to confirm; there's no request that
+        // starts an upload
+        assert_eq!(
+            plan_request(
+                DeepLinkAction::Upload {
+                    paths: paths.clone(),
+                    webhook: Some("2".to_string()),
+                },
+                &webhooks,
+            ),
+            Ok(DeepLinkRequest::QueueForWebhook {
+                paths: paths.clone(),
+                webhook_id: 2,
+                webhook_name: "Friends".to_string(),
+            })
+        );
+        assert_eq!(
+            plan_request(
+                DeepLinkAction::Upload {
+                    paths: paths.clone(),
+                    webhook: None,
+                },
+                &webhooks,
+            ),
+            Ok(DeepLinkRequest::Queue {
+                paths: paths.clone()
+            })
+        );
+        assert!(plan_request(
+            DeepLinkAction::Upload {
+                paths,
+                webhook: Some("7".to_string()),
+            },
+            &webhooks,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_deep_link_rejects_bad_links() {
+        assert!(parse_deep_link("https://upload?path=/a.png").is_err());
+        assert!(parse_deep_link("vrcphoto://delete?path=/a.png").is_err());
+        assert!(parse_deep_link("vrcphoto://upload?path=/missing.png").is_err());
+    }
+
+    #[test]
+    fn test_is_deep_link() {
+        assert!(is_deep_link("vrcphoto://upload?path=a"));
+        assert!(is_deep_link("VRCPHOTO://upload"));
+        assert!(!is_deep_link("C:\\photos\\a.png"));
+        assert!(!is_deep_link("vrc"));
+    }
+}
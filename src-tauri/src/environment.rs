@@ -0,0 +1,215 @@
+// Environment detection for first-run setup
+//
+// Looks for the VRChat screenshots folder and a VRCX install, samples recent
+// screenshots for their size and metadata, and turns that into suggested settings
+// a setup wizard can show before anything is saved.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::folder_scan::{self, ScannedFile};
+use crate::uploader::chunk_planner::predicted_upload_size;
+use crate::uploader::dry_run::DISCORD_UPLOAD_LIMIT;
+
+/// Only screenshots from this far back are sampled, which keeps the scan quick
+/// on folders with years of photos
+const SAMPLE_WINDOW: Duration = Duration::from_secs(180 * 24 * 60 * 60);
+/// Recent screenshots the typical size is taken from
+const SIZE_SAMPLE: usize = 100;
+/// Recent screenshots checked for embedded metadata
+const METADATA_SAMPLE: usize = 10;
+
+/// What was found on this machine, plus the settings suggested for it
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentReport {
+    /// VRChat screenshots folder, if one of the usual locations exists
+    pub vrchat_folder: Option<String>,
+    pub vrcx_installed: bool,
+    /// Screenshots from the last six months in the VRChat folder
+    pub recent_screenshots: usize,
+    /// Median size of recent screenshots
+    pub typical_screenshot_bytes: Option<u64>,
+    /// Sampled screenshots carrying world/player metadata, out of `metadata_sampled`
+    pub screenshots_with_metadata: usize,
+    pub metadata_sampled: usize,
+    pub recommended: RecommendedSettings,
+}
+
+/// Suggested values for the matching config fields
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecommendedSettings {
+    pub vrchat_path: Option<String>,
+    pub group_by_metadata: bool,
+    pub include_player_names: bool,
+    pub compression_format: String,
+    pub max_images_per_message: u8,
+}
+
+/// Usual VRChat screenshot locations, most likely first
+fn vrchat_folder_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(pictures) = dirs::picture_dir() {
+        candidates.push(pictures.join("VRChat"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join("Pictures").join("VRChat"));
+        // Pictures redirected into OneDrive
+        candidates.push(home.join("OneDrive").join("Pictures").join("VRChat"));
+        // VRChat under Steam Proton on Linux
+        candidates.push(home.join(
+            ".local/share/Steam/steamapps/compatdata/438100/pfx/drive_c/users/steamuser/Pictures/VRChat",
+        ));
+    }
+    candidates
+}
+
+pub(crate) fn find_vrchat_folder() -> Option<PathBuf> {
+    vrchat_folder_candidates()
+        .into_iter()
+        .find(|path| path.is_dir())
+}
+
+/// VRCX keeps its database and settings in %AppData%\VRCX
+fn vrcx_installed() -> bool {
+    dirs::config_dir().is_some_and(|dir| dir.join("VRCX").is_dir())
+}
+
+/// Recent screenshots in `folder`, newest first
+fn recent_screenshots(folder: &Path) -> Vec<ScannedFile> {
+    let since = SystemTime::now()
+        .checked_sub(SAMPLE_WINDOW)
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64);
+
+    let mut files = Vec::new();
+    if let Err(e) =
+        folder_scan::scan_folder(folder, true, since, folder_scan::SCAN_BATCH_SIZE, |batch| {
+            files.extend(batch)
+        })
+    {
+        log::warn!("Failed to scan {}: {e}", folder.display());
+    }
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified_ms));
+    files
+}
+
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    Some(values[values.len() / 2])
+}
+
+/// Settings suggested for what was found. Grouping and player names need metadata,
+/// and as many images per message are suggested as fit Discord's default upload
+/// limit at the typical screenshot size.
+pub fn recommend_settings(
+    vrchat_folder: Option<&Path>,
+    typical_file: Option<(&str, u64)>,
+    expects_metadata: bool,
+) -> RecommendedSettings {
+    let compression_format = "webp".to_string();
+    let max_images_per_message = typical_file
+        .map(|(path, size)| predicted_upload_size(path, size, &compression_format).max(1))
+        .map(|predicted| (DISCORD_UPLOAD_LIMIT / predicted).clamp(1, 10) as u8)
+        .unwrap_or(10);
+
+    RecommendedSettings {
+        vrchat_path: vrchat_folder.map(|p| p.to_string_lossy().to_string()),
+        group_by_metadata: expects_metadata,
+        include_player_names: expects_metadata,
+        compression_format,
+        max_images_per_message,
+    }
+}
+
+pub async fn detect_environment() -> EnvironmentReport {
+    let vrchat_folder = find_vrchat_folder();
+    let vrcx_installed = vrcx_installed();
+
+    let screenshots = match &vrchat_folder {
+        Some(folder) => {
+            let folder = folder.clone();
+            tokio::task::spawn_blocking(move || recent_screenshots(&folder))
+                .await
+                .unwrap_or_default()
+        }
+        None => Vec::new(),
+    };
+
+    let typical_screenshot_bytes = median(
+        screenshots
+            .iter()
+            .take(SIZE_SAMPLE)
+            .map(|file| file.size)
+            .collect(),
+    );
+
+    let timezone = crate::image_processor::configured_screenshot_timezone();
+    let mut screenshots_with_metadata = 0;
+    let metadata_sample: Vec<&ScannedFile> = screenshots.iter().take(METADATA_SAMPLE).collect();
+    for file in &metadata_sample {
+        if matches!(
+            crate::image_processor::extract_metadata(&file.path, timezone).await,
+            Ok(Some(_))
+        ) {
+            screenshots_with_metadata += 1;
+        }
+    }
+
+    // Without samples to go by, a VRCX install is the best hint that metadata is written
+    let expects_metadata = if metadata_sample.is_empty() {
+        vrcx_installed
+    } else {
+        screenshots_with_metadata * 2 >= metadata_sample.len()
+    };
+    let typical_file = typical_screenshot_bytes.and_then(|size| {
+        screenshots
+            .iter()
+            .find(|file| file.size == size)
+            .map(|file| (file.path.as_str(), size))
+    });
+
+    EnvironmentReport {
+        recommended: recommend_settings(vrchat_folder.as_deref(), typical_file, expects_metadata),
+        vrchat_folder: vrchat_folder.map(|p| p.to_string_lossy().to_string()),
+        vrcx_installed,
+        recent_screenshots: screenshots.len(),
+        typical_screenshot_bytes,
+        screenshots_with_metadata,
+        metadata_sampled: metadata_sample.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_recommend_settings_fits_typical_screenshots() {
+        // 4K PNGs shrink to about a third as WebP, so three fit in 10 MB
+        let settings = recommend_settings(None, Some(("shot.png", 10 * MB)), true);
+        assert_eq!(settings.max_images_per_message, 3);
+        assert!(settings.group_by_metadata);
+
+        let small = recommend_settings(None, Some(("shot.png", MB)), false);
+        assert_eq!(small.max_images_per_message, 10);
+        assert!(!small.include_player_names);
+
+        assert_eq!(
+            recommend_settings(None, None, false).max_images_per_message,
+            10
+        );
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![5, 1, 3]), Some(3));
+    }
+}
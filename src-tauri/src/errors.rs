@@ -1,7 +1,7 @@
 use crate::commands::UploadProgress;
+use crate::security::redact_webhook_tokens;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::Emitter;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -19,7 +19,7 @@ pub enum AppError {
     ImageProcessing(String),
 
     #[error("Network error: {0}")]
-    Network(#[from] reqwest::Error),
+    Network(reqwest::Error),
 
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
@@ -68,7 +68,18 @@ pub enum AppError {
 /// Convert to string for Tauri
 impl From<AppError> for String {
     fn from(error: AppError) -> Self {
-        error.to_string()
+        redact_webhook_tokens(&error.to_string()).into_owned()
+    }
+}
+
+/// reqwest puts the request URL in its errors, which for webhooks carries the token
+impl From<reqwest::Error> for AppError {
+    fn from(mut error: reqwest::Error) -> Self {
+        if let Some(url) = error.url_mut() {
+            let redacted = redact_webhook_tokens(url.path()).into_owned();
+            url.set_path(&redacted);
+        }
+        Self::Network(error)
     }
 }
 
@@ -104,7 +115,7 @@ impl AppError {
 
     pub fn invalid_webhook(url: &str) -> Self {
         Self::InvalidWebhook {
-            url: url.to_string(),
+            url: redact_webhook_tokens(url).into_owned(),
         }
     }
 
@@ -212,20 +223,6 @@ where
     }
 }
 
-/// Emit UI event with error handling
-pub fn safe_emit_event(app_handle: &tauri::AppHandle, event_name: &str, payload: &str) -> bool {
-    match app_handle.emit(event_name, payload) {
-        Ok(_) => {
-            log::debug!("Successfully emitted event '{event_name}' with payload: {payload}");
-            true
-        }
-        Err(e) => {
-            log::warn!("Failed to emit event '{event_name}' (non-critical): {e}");
-            false
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,177 @@
+// Folder scanning for the whole-folder view
+//
+// Walks a folder (optionally recursively) for supported images and video clips and
+// hands them to the caller in batches, so large VRChat folders can be shown while
+// the scan is still running instead of after it finishes.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::errors::{AppError, AppResult};
+
+/// Files per `folder-scan-batch` event
+pub const SCAN_BATCH_SIZE: usize = 200;
+
+const SCANNED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "webp", "gif", "bmp", "tif", "tiff", "mp4", "webm",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScannedFile {
+    pub path: String,
+    pub size: u64,
+    /// Last modified time in Unix milliseconds
+    pub modified_ms: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FolderScanSummary {
+    pub files_found: usize,
+    pub directories_scanned: usize,
+    /// Images skipped for being older than `since`
+    pub skipped_older: usize,
+    /// Entries or subfolders that couldn't be read
+    pub errors: usize,
+}
+
+/// Event payload for one batch of a scan
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderScanBatch {
+    pub scan_id: String,
+    pub files: Vec<ScannedFile>,
+}
+
+fn is_scanned_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| SCANNED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as i64
+}
+
+/// Scan `root` for images modified after `since_ms`, calling `on_batch` with up
+/// to `batch_size` files at a time. Symlinked folders are not followed, so
+/// links back up the tree can't loop.
+pub fn scan_folder(
+    root: &Path,
+    recursive: bool,
+    since_ms: Option<i64>,
+    batch_size: usize,
+    mut on_batch: impl FnMut(Vec<ScannedFile>),
+) -> AppResult<FolderScanSummary> {
+    if !root.is_dir() {
+        return Err(AppError::validation(
+            "path",
+            &format!("'{}' is not a folder", root.display()),
+        ));
+    }
+
+    let mut summary = FolderScanSummary::default();
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut pending_dirs: Vec<PathBuf> = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending_dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to read folder {}: {e}", dir.display());
+                summary.errors += 1;
+                continue;
+            }
+        };
+        summary.directories_scanned += 1;
+
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut subdirs = Vec::new();
+        for entry in entries {
+            let Ok(file_type) = entry.file_type() else {
+                summary.errors += 1;
+                continue;
+            };
+            let path = entry.path();
+
+            if file_type.is_dir() {
+                if recursive {
+                    subdirs.push(path);
+                }
+                continue;
+            }
+            if !file_type.is_file() || !is_scanned_image(&path) {
+                continue;
+            }
+
+            let Ok(metadata) = entry.metadata() else {
+                summary.errors += 1;
+                continue;
+            };
+            let modified_ms = metadata.modified().map(unix_millis).unwrap_or(0);
+            if since_ms.is_some_and(|since| modified_ms <= since) {
+                summary.skipped_older += 1;
+                continue;
+            }
+
+            summary.files_found += 1;
+            batch.push(ScannedFile {
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified_ms,
+            });
+            if batch.len() >= batch_size {
+                on_batch(std::mem::replace(
+                    &mut batch,
+                    Vec::with_capacity(batch_size),
+                ));
+            }
+        }
+
+        // Reversed so subfolders are visited in name order off the stack
+        pending_dirs.extend(subdirs.into_iter().rev());
+    }
+
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_folder_batches_and_filters() {
+        let root = std::env::temp_dir().join(format!("scan_test_{}", uuid::Uuid::new_v4()));
+        let nested = root.join("2024-01");
+        std::fs::create_dir_all(&nested).unwrap();
+        for name in ["a.png", "b.JPG", "notes.txt"] {
+            std::fs::write(root.join(name), b"x").unwrap();
+        }
+        std::fs::write(nested.join("c.webp"), b"x").unwrap();
+
+        let mut batches = Vec::new();
+        let summary = scan_folder(&root, true, None, 2, |files| batches.push(files)).unwrap();
+        assert_eq!(summary.files_found, 3);
+        assert_eq!(summary.directories_scanned, 2);
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(batches[1][0].path.ends_with("c.webp"));
+
+        let summary = scan_folder(&root, false, None, 10, |_| {}).unwrap();
+        assert_eq!(summary.files_found, 2);
+
+        let summary = scan_folder(&root, true, Some(i64::MAX), 10, |_| {}).unwrap();
+        assert_eq!((summary.files_found, summary.skipped_older), (0, 3));
+
+        assert!(scan_folder(&root.join("a.png"), false, None, 10, |_| {}).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
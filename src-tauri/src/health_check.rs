@@ -0,0 +1,267 @@
+// Startup health check
+//
+// Runs the checks that otherwise only show up as log lines when something is
+// misconfigured (database, config, VRChat folder, temp folder, Discord reachability,
+// webhooks) and returns them as one report, each failing check with a hint the UI
+// can show so the user knows what to fix.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::security::FileSystemGuard;
+use crate::uploader::discord_client::DiscordClient;
+use crate::{config, database, environment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    /// "database", "config", "vrchat_folder", "temp_directory", "network" or "webhooks"
+    pub name: &'static str,
+    pub status: HealthStatus,
+    pub message: String,
+    /// What the user can do about a warning or error
+    pub action: Option<String>,
+}
+
+impl HealthCheck {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: HealthStatus::Ok,
+            message: message.into(),
+            action: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: HealthStatus,
+        message: impl Into<String>,
+        action: &str,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            message: message.into(),
+            action: Some(action.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// Worst status among the checks
+    pub status: HealthStatus,
+    pub webhook_count: usize,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    fn new(checks: Vec<HealthCheck>, webhook_count: usize) -> Self {
+        let status = if checks.iter().any(|c| c.status == HealthStatus::Error) {
+            HealthStatus::Error
+        } else if checks.iter().any(|c| c.status == HealthStatus::Warning) {
+            HealthStatus::Warning
+        } else {
+            HealthStatus::Ok
+        };
+        Self {
+            status,
+            webhook_count,
+            checks,
+        }
+    }
+}
+
+async fn check_database() -> HealthCheck {
+    match database::get_database_status().await {
+        Ok(status) if !status.pending_migrations.is_empty() => HealthCheck::problem(
+            "database",
+            HealthStatus::Warning,
+            format!(
+                "{} database migration(s) haven't been applied",
+                status.pending_migrations.len()
+            ),
+            "Restart the app to finish updating the database",
+        ),
+        Ok(status) => HealthCheck::ok("database", format!("Database at {}", status.path)),
+        Err(e) => HealthCheck::problem(
+            "database",
+            HealthStatus::Error,
+            format!("Database unavailable: {e}"),
+            "Check that the app data folder isn't read-only or full, then restart",
+        ),
+    }
+}
+
+fn check_config() -> HealthCheck {
+    match config::load_config() {
+        Ok(_) => HealthCheck::ok("config", "Settings loaded"),
+        Err(e) => HealthCheck::problem(
+            "config",
+            HealthStatus::Error,
+            format!("Settings are invalid: {e}"),
+            "Open Settings, fix the highlighted value and save",
+        ),
+    }
+}
+
+fn check_vrchat_folder(configured: Option<&str>) -> HealthCheck {
+    match configured.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) if Path::new(path).is_dir() => {
+            HealthCheck::ok("vrchat_folder", format!("VRChat folder: {path}"))
+        }
+        Some(path) => HealthCheck::problem(
+            "vrchat_folder",
+            HealthStatus::Warning,
+            format!("The VRChat folder {path} doesn't exist"),
+            "Choose the VRChat screenshots folder again in Settings",
+        ),
+        None => match environment::find_vrchat_folder() {
+            Some(found) => HealthCheck::ok(
+                "vrchat_folder",
+                format!("VRChat folder found at {}", found.display()),
+            ),
+            None => HealthCheck::problem(
+                "vrchat_folder",
+                HealthStatus::Warning,
+                "No VRChat screenshots folder found",
+                "Choose it in Settings to enable the folder watcher and auto-upload",
+            ),
+        },
+    }
+}
+
+/// Whether a file can be created in `dir`, which is what uploads need from it
+fn check_temp_directory(dir: &Path) -> HealthCheck {
+    let probe = dir.join(format!(".health_check_{}", uuid::Uuid::new_v4()));
+    let result = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&probe, b"ok"));
+    std::fs::remove_file(&probe).ok();
+    match result {
+        Ok(()) => HealthCheck::ok(
+            "temp_directory",
+            format!("Temp folder {} is writable", dir.display()),
+        ),
+        Err(e) => HealthCheck::problem(
+            "temp_directory",
+            HealthStatus::Error,
+            format!("Can't write to the temp folder {}: {e}", dir.display()),
+            "Pick a different temp folder in Settings",
+        ),
+    }
+}
+
+async fn check_network(client: &DiscordClient) -> HealthCheck {
+    if client.is_discord_reachable().await {
+        HealthCheck::ok("network", "Discord is reachable")
+    } else {
+        HealthCheck::problem(
+            "network",
+            HealthStatus::Warning,
+            "Discord can't be reached",
+            "Check your internet connection; uploads wait for the connection to return",
+        )
+    }
+}
+
+async fn check_webhooks() -> (HealthCheck, usize) {
+    match database::get_all_webhooks().await {
+        Ok(webhooks) if webhooks.is_empty() => (
+            HealthCheck::problem(
+                "webhooks",
+                HealthStatus::Warning,
+                "No webhooks have been added",
+                "Add a Discord webhook to start uploading",
+            ),
+            0,
+        ),
+        Ok(webhooks) => (
+            HealthCheck::ok("webhooks", format!("{} webhook(s) set up", webhooks.len())),
+            webhooks.len(),
+        ),
+        Err(e) => (
+            HealthCheck::problem(
+                "webhooks",
+                HealthStatus::Error,
+                format!("Couldn't load webhooks: {e}"),
+                "See the database check",
+            ),
+            0,
+        ),
+    }
+}
+
+/// Run every startup check
+pub async fn run_health_check(client: &DiscordClient) -> HealthReport {
+    let vrchat_path = config::load_config().ok().and_then(|c| c.vrchat_path);
+    let (webhooks, webhook_count) = check_webhooks().await;
+    let checks = vec![
+        check_database().await,
+        check_config(),
+        check_vrchat_folder(vrchat_path.as_deref()),
+        check_temp_directory(&FileSystemGuard::get_temp_directory()),
+        check_network(client).await,
+        webhooks,
+    ];
+
+    let report = HealthReport::new(checks, webhook_count);
+    for check in report
+        .checks
+        .iter()
+        .filter(|c| c.status != HealthStatus::Ok)
+    {
+        log::warn!("Health check {}: {}", check.name, check.message);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folder_checks_flag_missing_and_unwritable_paths() {
+        let dir = std::env::temp_dir();
+        assert_eq!(check_temp_directory(&dir).status, HealthStatus::Ok);
+        assert_eq!(check_vrchat_folder(dir.to_str()).status, HealthStatus::Ok);
+
+        let missing = dir.join("health_check_missing_folder");
+        let vrchat = check_vrchat_folder(missing.to_str());
+        assert_eq!(vrchat.status, HealthStatus::Warning);
+        assert!(vrchat.action.is_some());
+
+        // A file where the temp folder should be
+        let file = dir.join(format!("health_check_file_{}", uuid::Uuid::new_v4()));
+        std::fs::write(&file, b"x").unwrap();
+        assert_eq!(check_temp_directory(&file).status, HealthStatus::Error);
+        std::fs::remove_file(&file).ok();
+    }
+
+    #[test]
+    fn test_report_status_is_worst_check() {
+        let ok = HealthCheck::ok("config", "fine");
+        let warning = HealthCheck::problem("network", HealthStatus::Warning, "offline", "wait");
+        let error = HealthCheck::problem("database", HealthStatus::Error, "gone", "restart");
+
+        assert_eq!(
+            HealthReport::new(vec![ok.clone()], 1).status,
+            HealthStatus::Ok
+        );
+        assert_eq!(
+            HealthReport::new(vec![ok.clone(), warning.clone()], 1).status,
+            HealthStatus::Warning
+        );
+        assert_eq!(
+            HealthReport::new(vec![warning, error, ok], 1).status,
+            HealthStatus::Error
+        );
+    }
+}
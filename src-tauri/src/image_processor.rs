@@ -1,11 +1,14 @@
 use chrono::Offset;
+use fast_image_resize::images::Image;
+use fast_image_resize::{FilterType, IntoImageView, PixelType, ResizeAlg, ResizeOptions, Resizer};
 use flate2::read::DeflateDecoder;
 use image::codecs::jpeg::JpegEncoder;
 use std::fs;
 use std::io::{BufReader, Read, Seek, SeekFrom};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-use crate::commands::{AuthorInfo, ImageMetadata, PlayerInfo, WorldInfo};
+use crate::commands::{AuthorInfo, AvatarInfo, ImageMetadata, PlayerInfo, WorldInfo};
 use crate::errors::{AppError, AppResult};
 use crate::security::{FileSystemGuard, InputValidator};
 
@@ -16,6 +19,8 @@ pub enum MetadataSource {
     Vrcx,
     /// VRChat native XMP metadata
     VrchatXmp,
+    /// `photo.png.json` sidecar file next to the image
+    Sidecar,
     /// No metadata found
     None,
 }
@@ -28,7 +33,10 @@ pub struct MetadataWithSource {
 }
 
 /// Extract metadata with information about its source
-pub async fn extract_metadata_with_source(file_path: &str) -> AppResult<MetadataWithSource> {
+pub async fn extract_metadata_with_source(
+    file_path: &str,
+    timezone: Option<chrono_tz::Tz>,
+) -> AppResult<MetadataWithSource> {
     log::info!("Extracting metadata with source info for: {file_path}");
 
     // Validate input first
@@ -39,12 +47,29 @@ pub async fn extract_metadata_with_source(file_path: &str) -> AppResult<Metadata
         return Err(AppError::file_not_found(file_path));
     }
 
+    // Both embedded formats live in text chunks, so the file is only walked once
+    let text_chunks = match read_png_text_chunks(file_path) {
+        Ok(chunks) => chunks,
+        Err(e) => {
+            // Not a readable PNG, but a sidecar can still describe it
+            let Some(sidecar_metadata) = read_sidecar_metadata(file_path) else {
+                return Err(e);
+            };
+            crate::photo_index::index_metadata(file_path, &sidecar_metadata, timezone).await;
+            return Ok(MetadataWithSource {
+                metadata: Some(sidecar_metadata),
+                source: MetadataSource::Sidecar,
+            });
+        }
+    };
+
     // Priority 1: Try VRCX-style metadata from PNG Description chunk
-    if let Some(metadata_json) = get_png_description(file_path)? {
+    if let Some(metadata_json) = find_png_description(&text_chunks) {
         let cleaned_json = metadata_json.trim();
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(cleaned_json) {
             if let Ok(metadata) = parse_vrchat_metadata(json) {
                 log::info!("Found VRCX metadata in {file_path}");
+                crate::photo_index::index_metadata(file_path, &metadata, timezone).await;
                 return Ok(MetadataWithSource {
                     metadata: Some(metadata),
                     source: MetadataSource::Vrcx,
@@ -54,15 +79,26 @@ pub async fn extract_metadata_with_source(file_path: &str) -> AppResult<Metadata
     }
 
     // Priority 2: Try VRChat native XMP metadata
-    if let Some(xmp_metadata) = extract_vrchat_xmp_metadata(file_path)? {
+    if let Some(xmp_metadata) = find_vrchat_xmp_metadata(&text_chunks) {
         log::info!("Found VRChat XMP metadata in {file_path}");
+        crate::photo_index::index_metadata(file_path, &xmp_metadata, timezone).await;
         return Ok(MetadataWithSource {
             metadata: Some(xmp_metadata),
             source: MetadataSource::VrchatXmp,
         });
     }
 
-    // Priority 3: Filename pattern (only provides timestamp, no actual metadata)
+    // Priority 3: Sidecar JSON written by tools that don't embed
+    if let Some(sidecar_metadata) = read_sidecar_metadata(file_path) {
+        log::info!("Found sidecar metadata for {file_path}");
+        crate::photo_index::index_metadata(file_path, &sidecar_metadata, timezone).await;
+        return Ok(MetadataWithSource {
+            metadata: Some(sidecar_metadata),
+            source: MetadataSource::Sidecar,
+        });
+    }
+
+    // Priority 4: Filename pattern (only provides timestamp, no actual metadata)
     log::info!("No embedded metadata found in {file_path}");
     Ok(MetadataWithSource {
         metadata: None,
@@ -70,7 +106,20 @@ pub async fn extract_metadata_with_source(file_path: &str) -> AppResult<Metadata
     })
 }
 
-pub async fn extract_metadata(file_path: &str) -> AppResult<Option<ImageMetadata>> {
+/// Extract metadata and record it in the photo index for search and stats, dating the
+/// photo in `timezone`
+pub async fn extract_metadata(
+    file_path: &str,
+    timezone: Option<chrono_tz::Tz>,
+) -> AppResult<Option<ImageMetadata>> {
+    let metadata = read_metadata(file_path).await?;
+    if let Some(metadata) = &metadata {
+        crate::photo_index::index_metadata(file_path, metadata, timezone).await;
+    }
+    Ok(metadata)
+}
+
+async fn read_metadata(file_path: &str) -> AppResult<Option<ImageMetadata>> {
     log::info!("Starting metadata extraction for: {file_path}");
 
     // Validate input first
@@ -81,8 +130,20 @@ pub async fn extract_metadata(file_path: &str) -> AppResult<Option<ImageMetadata
         return Err(AppError::file_not_found(file_path));
     }
 
+    // Both embedded formats live in text chunks, so the file is only walked once
+    let text_chunks = match read_png_text_chunks(file_path) {
+        Ok(chunks) => chunks,
+        Err(e) => {
+            // Not a readable PNG, but a sidecar can still describe it
+            return match read_sidecar_metadata(file_path) {
+                Some(sidecar_metadata) => Ok(Some(sidecar_metadata)),
+                None => Err(e),
+            };
+        }
+    };
+
     // Priority 1: Try to get VRCX-style metadata from PNG text chunks (Description)
-    if let Some(metadata_json) = get_png_description(file_path)? {
+    if let Some(metadata_json) = find_png_description(&text_chunks) {
         log::info!("Found PNG Description metadata in {file_path}");
         log::debug!(
             "Raw metadata JSON (first 500 chars): {}",
@@ -130,19 +191,67 @@ pub async fn extract_metadata(file_path: &str) -> AppResult<Option<ImageMetadata
 
     // Priority 2: Try to get VRChat native XMP metadata
     log::info!("Trying VRChat XMP metadata extraction for {file_path}");
-    if let Some(xmp_metadata) = extract_vrchat_xmp_metadata(file_path)? {
+    if let Some(xmp_metadata) = find_vrchat_xmp_metadata(&text_chunks) {
         log::info!("Successfully extracted VRChat XMP metadata from {file_path}");
         return Ok(Some(xmp_metadata));
     } else {
         log::info!("No VRChat XMP metadata found in {file_path}");
     }
 
-    // Priority 3: If no metadata found, try extracting from filename patterns
+    // Priority 3: Sidecar JSON written by tools that don't embed
+    if let Some(sidecar_metadata) = read_sidecar_metadata(file_path) {
+        log::info!("Found sidecar metadata for {file_path}");
+        return Ok(Some(sidecar_metadata));
+    }
+
+    // Priority 4: If no metadata found, try extracting from filename patterns
     log::info!("Trying filename pattern extraction for {file_path}");
     extract_metadata_from_filename(file_path)
 }
 
-fn get_png_description(file_path: &str) -> AppResult<Option<String>> {
+/// Sidecar metadata file for a photo: `photo.png` -> `photo.png.json`
+pub fn sidecar_path(file_path: &str) -> PathBuf {
+    PathBuf::from(format!("{file_path}.json"))
+}
+
+/// Metadata from a sidecar file, which uses the same JSON schema as the VRCX
+/// Description chunk
+fn read_sidecar_metadata(file_path: &str) -> Option<ImageMetadata> {
+    let sidecar = sidecar_path(file_path);
+    let size = fs::metadata(&sidecar).ok()?.len();
+    if size > MAX_TEXT_CHUNK_SIZE as u64 {
+        log::warn!(
+            "Skipping sidecar {} ({size} bytes is too large for metadata)",
+            sidecar.display()
+        );
+        return None;
+    }
+
+    let contents = fs::read_to_string(&sidecar).ok()?;
+    match serde_json::from_str::<serde_json::Value>(contents.trim()) {
+        Ok(json) => parse_vrchat_metadata(json).ok(),
+        Err(e) => {
+            log::warn!("Failed to parse sidecar {}: {e}", sidecar.display());
+            None
+        }
+    }
+}
+
+/// Text chunks larger than this are skipped; VRCX and VRChat metadata is only a few KB
+const MAX_TEXT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// Total text chunk bytes kept per file
+const MAX_TEXT_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+
+/// A tEXt, iTXt or zTXt chunk read from a PNG
+struct PngTextChunk {
+    chunk_type: String,
+    data: Vec<u8>,
+}
+
+/// Walk a PNG's chunks once and keep only its text chunks. Image data and other
+/// chunks are seeked past rather than read, so memory stays bounded by the text
+/// chunk limits no matter how large the screenshot is.
+fn read_png_text_chunks(file_path: &str) -> AppResult<Vec<PngTextChunk>> {
     log::debug!("Opening PNG file for chunk analysis: {file_path}");
 
     let file = fs::File::open(file_path)?;
@@ -152,26 +261,20 @@ fn get_png_description(file_path: &str) -> AppResult<Option<String>> {
     let mut signature = [0u8; 8];
     reader.read_exact(&mut signature)?;
 
-    const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
     if signature != PNG_SIGNATURE {
         log::warn!("File {file_path} is not a valid PNG (invalid signature)");
         return Err(AppError::invalid_file_type(file_path));
     }
 
-    log::debug!("Valid PNG signature confirmed");
-
     let mut chunks_found = Vec::new();
-    let mut text_chunks_found = 0;
+    let mut text_chunks = Vec::new();
+    let mut text_bytes = 0;
 
-    // Read chunks to find tEXt, iTXt, or zTXt chunks with "Description"
     loop {
         let mut chunk_header = [0u8; 8];
-        match reader.read_exact(&mut chunk_header) {
-            Ok(_) => {}
-            Err(_) => {
-                log::debug!("End of PNG file reached");
-                break;
-            }
+        if reader.read_exact(&mut chunk_header).is_err() {
+            log::debug!("End of PNG file reached");
+            break;
         }
 
         let length = u32::from_be_bytes([
@@ -186,48 +289,28 @@ fn get_png_description(file_path: &str) -> AppResult<Option<String>> {
 
         chunks_found.push(format!("{chunk_type_str}({length})"));
 
-        // Limit chunk size to prevent memory issues but be more generous for metadata
-        const MAX_CHUNK_SIZE: usize = 50 * 1024 * 1024; // 50MB - much larger for big metadata
-        if length > MAX_CHUNK_SIZE {
+        let is_text = matches!(chunk_type_str, "tEXt" | "iTXt" | "zTXt");
+        if is_text && length > MAX_TEXT_CHUNK_SIZE {
             log::warn!(
                 "Skipping oversized chunk {} with size {} MB",
                 chunk_type_str,
                 length / 1024 / 1024
             );
-            reader.seek(SeekFrom::Current(length as i64 + 4))?; // +4 for CRC
-            continue;
         }
 
-        // Check for any text chunk type
-        if matches!(chunk_type_str, "tEXt" | "iTXt" | "zTXt") {
-            text_chunks_found += 1;
-            log::info!(
-                "Found text chunk #{text_chunks_found}: {chunk_type_str} with {length} bytes"
-            );
-
-            let mut chunk_data = vec![0u8; length];
-            reader.read_exact(&mut chunk_data)?;
-
-            // Try to extract Description from this chunk
-            if let Some(description) = extract_description_from_chunk(chunk_type_str, &chunk_data) {
-                log::info!("Successfully extracted Description from {chunk_type_str} chunk!");
-                log::debug!("Description length: {} bytes", description.len());
-                return Ok(Some(description));
-            } else {
-                log::debug!("No Description found in {chunk_type_str} chunk");
-
-                // Log what keywords we did find for debugging
-                if let Some(keyword) = get_chunk_keyword(chunk_type_str, &chunk_data) {
-                    log::debug!("Chunk keyword found: '{keyword}'");
-                } else {
-                    log::debug!("No keyword found in chunk");
-                }
-            }
-
+        if is_text && length <= MAX_TEXT_CHUNK_SIZE && text_bytes + length <= MAX_TEXT_CHUNK_BYTES {
+            let mut data = vec![0u8; length];
+            reader.read_exact(&mut data)?;
             // Skip CRC
             reader.seek(SeekFrom::Current(4))?;
+
+            text_bytes += length;
+            text_chunks.push(PngTextChunk {
+                chunk_type: chunk_type_str.to_string(),
+                data,
+            });
         } else {
-            // Skip non-text chunk data and CRC
+            // Skip chunk data and CRC
             reader.seek(SeekFrom::Current(length as i64 + 4))?;
         }
 
@@ -238,12 +321,36 @@ fn get_png_description(file_path: &str) -> AppResult<Option<String>> {
         }
     }
 
-    log::info!("PNG Analysis Summary for {file_path}:");
-    log::info!("   Total chunks found: [{}]", chunks_found.join(", "));
-    log::info!("   Text chunks found: {text_chunks_found}");
-    log::info!("   No Description metadata found");
+    log::debug!(
+        "PNG chunks in {file_path}: [{}], {} text chunks kept",
+        chunks_found.join(", "),
+        text_chunks.len()
+    );
 
-    Ok(None)
+    Ok(text_chunks)
+}
+
+/// VRCX Description text from already read chunks
+fn find_png_description(chunks: &[PngTextChunk]) -> Option<String> {
+    for chunk in chunks {
+        if let Some(description) = extract_description_from_chunk(&chunk.chunk_type, &chunk.data) {
+            log::info!(
+                "Successfully extracted Description from {} chunk!",
+                chunk.chunk_type
+            );
+            log::debug!("Description length: {} bytes", description.len());
+            return Some(description);
+        }
+
+        // Log what keywords we did find for debugging
+        if let Some(keyword) = get_chunk_keyword(&chunk.chunk_type, &chunk.data) {
+            log::debug!("Chunk keyword found: '{keyword}'");
+        } else {
+            log::debug!("No keyword found in chunk");
+        }
+    }
+
+    None
 }
 
 fn extract_description_from_chunk(chunk_type: &str, data: &[u8]) -> Option<String> {
@@ -440,114 +547,46 @@ fn decompress_deflate_data(compressed_data: &[u8]) -> Option<String> {
     None
 }
 
-/// Extract VRChat native XMP metadata from a PNG file
+/// Extract VRChat native XMP metadata from a PNG's text chunks
 /// VRChat stores metadata in XMP format with fields like:
 /// - XMP:Author
 /// - XMP:AuthorID
 /// - XMP:WorldID
 /// - XMP:WorldDisplayName
-fn extract_vrchat_xmp_metadata(file_path: &str) -> AppResult<Option<ImageMetadata>> {
-    log::debug!("Attempting to extract VRChat XMP metadata from: {file_path}");
-
-    let file = fs::File::open(file_path)?;
-    let mut reader = BufReader::new(file);
-
-    // Verify PNG signature
-    let mut signature = [0u8; 8];
-    reader.read_exact(&mut signature)?;
-
-    const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
-    if signature != PNG_SIGNATURE {
-        log::debug!("Not a valid PNG file for XMP extraction");
-        return Ok(None);
-    }
-
-    // Read chunks looking for iTXt with XMP data
-    loop {
-        let mut chunk_header = [0u8; 8];
-        match reader.read_exact(&mut chunk_header) {
-            Ok(_) => {}
-            Err(_) => {
-                log::debug!("End of PNG file reached while searching for XMP");
-                break;
-            }
-        }
-
-        let length = u32::from_be_bytes([
-            chunk_header[0],
-            chunk_header[1],
-            chunk_header[2],
-            chunk_header[3],
-        ]) as usize;
-
-        let chunk_type = &chunk_header[4..8];
-        let chunk_type_str = std::str::from_utf8(chunk_type).unwrap_or("INVALID");
-
+fn find_vrchat_xmp_metadata(chunks: &[PngTextChunk]) -> Option<ImageMetadata> {
+    for chunk in chunks {
         // XMP data is typically stored in iTXt chunks with "XML:com.adobe.xmp" keyword
-        // or in a raw XMP chunk
-        if chunk_type_str == "iTXt" {
-            const MAX_CHUNK_SIZE: usize = 50 * 1024 * 1024;
-            if length > MAX_CHUNK_SIZE {
-                reader.seek(SeekFrom::Current(length as i64 + 4))?;
-                continue;
-            }
-
-            let mut chunk_data = vec![0u8; length];
-            reader.read_exact(&mut chunk_data)?;
-
-            // Check if this is an XMP chunk
-            if let Some(xmp_content) = extract_xmp_from_itxt(&chunk_data) {
-                log::debug!("Found XMP data in iTXt chunk");
-                if let Some(metadata) = parse_vrchat_xmp(&xmp_content) {
-                    return Ok(Some(metadata));
+        let text_content = match chunk.chunk_type.as_str() {
+            "iTXt" => {
+                if let Some(xmp_content) = extract_xmp_from_itxt(&chunk.data) {
+                    log::debug!("Found XMP data in iTXt chunk");
+                    if let Some(metadata) = parse_vrchat_xmp(&xmp_content) {
+                        return Some(metadata);
+                    }
                 }
-            }
-
-            // Skip CRC
-            reader.seek(SeekFrom::Current(4))?;
-        } else if chunk_type_str == "tEXt" || chunk_type_str == "zTXt" {
-            // Check text chunks for XMP-related content
-            const MAX_CHUNK_SIZE: usize = 50 * 1024 * 1024;
-            if length > MAX_CHUNK_SIZE {
-                reader.seek(SeekFrom::Current(length as i64 + 4))?;
                 continue;
             }
-
-            let mut chunk_data = vec![0u8; length];
-            reader.read_exact(&mut chunk_data)?;
-
-            // Try to extract XMP from text chunks
-            if let Some(text_content) = if chunk_type_str == "tEXt" {
-                extract_text_content(&chunk_data)
-            } else {
-                extract_compressed_text_content(&chunk_data)
-            } {
-                if text_content.contains("x]mm[")
-                    || text_content.contains("XMP")
-                    || text_content.contains("WorldID")
-                    || text_content.contains("AuthorID")
-                {
-                    log::debug!("Found potential XMP data in {chunk_type_str} chunk");
-                    if let Some(metadata) = parse_vrchat_xmp(&text_content) {
-                        return Ok(Some(metadata));
-                    }
+            "tEXt" => extract_text_content(&chunk.data),
+            "zTXt" => extract_compressed_text_content(&chunk.data),
+            _ => None,
+        };
+
+        // Check text chunks for XMP-related content
+        if let Some(text_content) = text_content {
+            if text_content.contains("x]mm[")
+                || text_content.contains("XMP")
+                || text_content.contains("WorldID")
+                || text_content.contains("AuthorID")
+            {
+                log::debug!("Found potential XMP data in {} chunk", chunk.chunk_type);
+                if let Some(metadata) = parse_vrchat_xmp(&text_content) {
+                    return Some(metadata);
                 }
             }
-
-            // Skip CRC
-            reader.seek(SeekFrom::Current(4))?;
-        } else {
-            // Skip non-text chunk data and CRC
-            reader.seek(SeekFrom::Current(length as i64 + 4))?;
-        }
-
-        // Stop at IEND chunk
-        if chunk_type_str == "IEND" {
-            break;
         }
     }
 
-    Ok(None)
+    None
 }
 
 /// Extract XMP content from an iTXt chunk
@@ -641,6 +680,7 @@ fn parse_vrchat_xmp(xmp_content: &str) -> Option<ImageMetadata> {
         author: None,
         world: None,
         players: Vec::new(),
+        avatar: None,
     };
 
     let mut found_any = false;
@@ -778,6 +818,40 @@ fn extract_xmp_value(content: &str, property: &str) -> Option<String> {
     None
 }
 
+/// Top-level fields written by VRCX and by this app's metadata editor
+const KNOWN_METADATA_FIELDS: &[&str] = &[
+    "application",
+    "version",
+    "created_at",
+    "author",
+    "world",
+    "players",
+    "avatar",
+];
+
+/// First string value among `keys`, so casing variants across VRCX versions all work
+fn string_field<'a>(value: &'a serde_json::Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
+}
+
+/// A user entry, written either as an object or (by some VRCX versions) as a plain
+/// display name. Missing ids are kept as empty strings rather than dropping the user.
+fn parse_user_entry(value: &serde_json::Value) -> Option<(String, String)> {
+    let (name, id) = match value.as_str() {
+        Some(name) => (name, ""),
+        None => (
+            string_field(
+                value,
+                &["displayName", "display_name", "displayname", "name"],
+            )?,
+            string_field(value, &["id", "userId", "user_id"]).unwrap_or(""),
+        ),
+    };
+    let name = name.trim();
+    (!name.is_empty()).then(|| (name.to_string(), id.trim().to_string()))
+}
+
 fn parse_vrchat_metadata(json: serde_json::Value) -> AppResult<ImageMetadata> {
     log::debug!("Parsing VRChat metadata JSON structure");
 
@@ -785,18 +859,27 @@ fn parse_vrchat_metadata(json: serde_json::Value) -> AppResult<ImageMetadata> {
         author: None,
         world: None,
         players: Vec::new(),
+        avatar: None,
     };
 
+    if let Some(fields) = json.as_object() {
+        let unknown: Vec<&str> = fields
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !KNOWN_METADATA_FIELDS.contains(key))
+            .collect();
+        if !unknown.is_empty() {
+            log::debug!("Ignoring unrecognized metadata fields: {unknown:?}");
+        }
+    }
+
     // Extract author info
     if let Some(author_obj) = json.get("author") {
-        if let (Some(name), Some(id)) = (
-            author_obj.get("displayName").and_then(|v| v.as_str()),
-            author_obj.get("id").and_then(|v| v.as_str()),
-        ) {
+        if let Some((name, id)) = parse_user_entry(author_obj) {
             log::debug!("Found author: {name} ({id})");
             metadata.author = Some(AuthorInfo {
-                display_name: name.to_string(),
-                id: id.to_string(),
+                display_name: name,
+                id,
             });
         }
     }
@@ -812,10 +895,7 @@ fn parse_vrchat_metadata(json: serde_json::Value) -> AppResult<ImageMetadata> {
             .and_then(|v| v.as_str())
             .unwrap_or("Unknown ID");
         // Note: instance_id is still extracted but not displayed in Discord messages
-        let instance_id = world_obj
-            .get("instanceId")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
+        let instance_id = string_field(world_obj, &["instanceId", "instance_id"]).unwrap_or("");
 
         log::debug!("Found world: {world_name} ({world_id}) - Instance: {instance_id}");
 
@@ -831,19 +911,37 @@ fn parse_vrchat_metadata(json: serde_json::Value) -> AppResult<ImageMetadata> {
         log::debug!("Found {} players", players_array.len());
 
         for (i, player) in players_array.iter().enumerate() {
-            if let (Some(name), Some(id)) = (
-                player.get("displayName").and_then(|v| v.as_str()),
-                player.get("id").and_then(|v| v.as_str()),
-            ) {
-                log::debug!("Player {}: {} ({})", i + 1, name, id);
-                metadata.players.push(PlayerInfo {
-                    display_name: name.to_string(),
-                    id: id.to_string(),
-                });
+            match parse_user_entry(player) {
+                Some((name, id)) => {
+                    log::debug!("Player {}: {} ({})", i + 1, name, id);
+                    metadata.players.push(PlayerInfo {
+                        display_name: name,
+                        id,
+                    });
+                }
+                None => log::debug!("Skipping unreadable player entry {}: {player}", i + 1),
             }
         }
     }
 
+    // Extract avatar info (VRCX records the avatar the author was wearing)
+    if let Some(avatar_obj) = json.get("avatar") {
+        let avatar_name = avatar_obj
+            .get("name")
+            .or_else(|| avatar_obj.get("displayName"))
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        if let Some(name) = avatar_name {
+            let id = avatar_obj.get("id").and_then(|v| v.as_str()).unwrap_or("");
+            log::debug!("Found avatar: {name} ({id})");
+            metadata.avatar = Some(AvatarInfo {
+                name: name.to_string(),
+                id: id.to_string(),
+            });
+        }
+    }
+
     log::info!(
         "Successfully parsed metadata - Author: {}, World: {}, Players: {}",
         metadata.author.is_some(),
@@ -913,9 +1011,10 @@ pub async fn resize_image_simple(file_path: &str, scale: f32) -> AppResult<Strin
     let temp_path = FileSystemGuard::create_secure_temp_file(file_path)?;
     let output_path = temp_path.with_extension("png"); // Always save resized intermediate as PNG for quality
 
-    resized
-        .save_with_format(&output_path, image::ImageFormat::Png)
-        .map_err(|e| AppError::ImageProcessing(e.to_string()))?;
+    fs::write(
+        &output_path,
+        encode_png(&resized, read_icc_profile(file_path))?,
+    )?;
 
     Ok(output_path.to_string_lossy().to_string())
 }
@@ -986,8 +1085,8 @@ async fn compress_image_with_format_internal(
                 let output_path_clone = output_path.clone();
                 tokio::task::spawn_blocking(move || {
                     let img = load_image_efficiently(&file_path_owned)?;
-                    img.save_with_format(&output_path_clone, image::ImageFormat::Png)
-                        .map_err(|e| AppError::ImageProcessing(e.to_string()))?;
+                    let icc = read_icc_profile(&file_path_owned);
+                    fs::write(&output_path_clone, encode_png(&img, icc)?)?;
                     Ok::<_, AppError>(())
                 })
                 .await
@@ -1010,8 +1109,8 @@ async fn compress_image_with_format_internal(
             let output_path_clone = output_path.clone();
             tokio::task::spawn_blocking(move || {
                 let img = load_image_efficiently(&file_path_owned)?;
-                img.save_with_format(&output_path_clone, image::ImageFormat::Png)
-                    .map_err(|e| AppError::ImageProcessing(e.to_string()))?;
+                let icc = read_icc_profile(&file_path_owned);
+                fs::write(&output_path_clone, encode_png(&img, icc)?)?;
                 log::info!("Converted {file_path_owned} to PNG");
                 Ok::<_, AppError>(())
             })
@@ -1031,8 +1130,12 @@ async fn compress_image_with_format_internal(
 
             let encoder = webp::Encoder::from_rgba(&rgba_img, width, height);
             let webp_data = encoder.encode_lossless();
+            let webp_data = match read_icc_profile(&file_path_owned) {
+                Some(icc) => embed_webp_icc(&webp_data, &icc, width, height),
+                None => webp_data.to_vec(),
+            };
 
-            fs::write(&output_path_clone, &*webp_data)?;
+            fs::write(&output_path_clone, webp_data)?;
             log::info!("Compressed {file_path_owned} to Lossless WebP");
             Ok::<_, AppError>(())
         })
@@ -1050,6 +1153,9 @@ async fn compress_image_with_format_internal(
             let mut output = Vec::new();
             {
                 let mut encoder = JpegEncoder::new_with_quality(&mut output, quality);
+                if let Some(icc) = read_icc_profile(&file_path_owned) {
+                    set_encoder_icc(&mut encoder, icc);
+                }
                 encoder
                     .encode_image(&img)
                     .map_err(|e| AppError::ImageProcessing(e.to_string()))?;
@@ -1074,6 +1180,11 @@ async fn compress_image_with_format_internal(
         // Load and convert image in blocking thread, then encode AVIF
         let (rgba_img, width, height) = tokio::task::spawn_blocking(move || {
             let img = load_image_efficiently(&file_path_owned)?;
+            // ravif can't embed a profile, so bake it into sRGB pixels instead
+            let img = match read_icc_profile(&file_path_owned) {
+                Some(icc) => convert_to_srgb(img, &icc),
+                None => img,
+            };
             let rgba_img = img.to_rgba8();
             let (width, height) = rgba_img.dimensions();
             Ok::<_, AppError>((rgba_img, width, height))
@@ -1107,8 +1218,12 @@ async fn compress_image_with_format_internal(
 
             let encoder = webp::Encoder::from_rgba(&rgba_img, width, height);
             let webp_data = encoder.encode(quality as f32);
+            let webp_data = match read_icc_profile(&file_path_owned) {
+                Some(icc) => embed_webp_icc(&webp_data, &icc, width, height),
+                None => webp_data.to_vec(),
+            };
 
-            fs::write(&output_path_clone, &*webp_data)?;
+            fs::write(&output_path_clone, webp_data)?;
             log::info!(
                 "Compressed {} to WebP at {} (quality: {})",
                 file_path_owned,
@@ -1141,10 +1256,129 @@ pub async fn resize_image_box(file_path: &str, scale: f32) -> AppResult<String>
         // Using Lanczos3 for high quality downscaling (better than Box for photos)
         let resized = img.resize(width, height, image::imageops::FilterType::Lanczos3);
 
-        resized
-            .save_with_format(&output_path_clone, image::ImageFormat::Png)
-            .map_err(|e| AppError::ImageProcessing(e.to_string()))?;
+        let icc = read_icc_profile(&file_path_owned);
+        fs::write(&output_path_clone, encode_png(&resized, icc)?)?;
+
+        Ok::<_, AppError>(())
+    })
+    .await
+    .map_err(|e| AppError::ImageProcessing(format!("Task failed: {e}")))??;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Rotate clockwise by 90, 180 or 270 degrees into a secure temp PNG
+pub async fn rotate_image(file_path: &str, degrees: u32) -> AppResult<String> {
+    let rotate: fn(&image::DynamicImage) -> image::DynamicImage = match degrees % 360 {
+        90 => image::DynamicImage::rotate90,
+        180 => image::DynamicImage::rotate180,
+        270 => image::DynamicImage::rotate270,
+        _ => {
+            return Err(AppError::validation(
+                "degrees",
+                "Rotation must be 90, 180 or 270 degrees",
+            ))
+        }
+    };
+
+    log::info!("Rotating {file_path} by {degrees}°");
+    save_edited_image(file_path, move |img| Ok(rotate(&img))).await
+}
+
+/// Crop to the `width`×`height` area at (`x`, `y`) into a secure temp PNG
+pub async fn crop_image(
+    file_path: &str,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> AppResult<String> {
+    if width == 0 || height == 0 {
+        return Err(AppError::validation("crop", "Crop area must not be empty"));
+    }
+
+    log::info!("Cropping {file_path} to {width}x{height} at ({x}, {y})");
+    save_edited_image(file_path, move |img| {
+        if u64::from(x) + u64::from(width) > u64::from(img.width())
+            || u64::from(y) + u64::from(height) > u64::from(img.height())
+        {
+            return Err(AppError::validation(
+                "crop",
+                "Crop area is outside the image",
+            ));
+        }
+        Ok(img.crop_imm(x, y, width, height))
+    })
+    .await
+}
+
+/// Downscale so neither side exceeds `max_dimension`, keeping aspect ratio and metadata.
+/// None when the image already fits.
+pub async fn resize_to_max_dimension(
+    file_path: &str,
+    max_dimension: u32,
+) -> AppResult<Option<String>> {
+    let (width, height) = image::image_dimensions(file_path)?;
+    if width.max(height) <= max_dimension {
+        return Ok(None);
+    }
+
+    log::info!("Downscaling {file_path} from {width}x{height} to fit {max_dimension}px");
+    save_edited_image(file_path, move |img| {
+        Ok(img.resize(
+            max_dimension,
+            max_dimension,
+            image::imageops::FilterType::Lanczos3,
+        ))
+    })
+    .await
+    .map(Some)
+}
+
+/// Re-encode an image Discord doesn't show inline (BMP, TIFF) as PNG, keeping metadata.
+/// PNG rather than WebP so the XMP and ICC profile can come along; a result that's too
+/// big still goes through the usual compression fallback.
+pub async fn convert_to_png(file_path: &str) -> AppResult<String> {
+    log::info!("Converting {file_path} to PNG for upload");
+    save_edited_image(file_path, Ok).await
+}
 
+/// Apply an edit and save the result as PNG, carrying over the original's text chunks
+/// (VRCX Description and VRChat XMP) so the edited photo keeps its metadata
+async fn save_edited_image<F>(file_path: &str, edit: F) -> AppResult<String>
+where
+    F: FnOnce(image::DynamicImage) -> AppResult<image::DynamicImage> + Send + 'static,
+{
+    InputValidator::validate_image_file(file_path)?;
+    let temp_path = FileSystemGuard::create_secure_temp_file(file_path)?;
+    let output_path = temp_path.with_extension("png");
+    let file_path_owned = file_path.to_string();
+    let output_path_clone = output_path.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let original = fs::read(&file_path_owned)?;
+        let edited = edit(image::load_from_memory(&original)?)?;
+        let encoded = encode_png(&edited, read_icc_profile(&file_path_owned))?;
+
+        let mut text_chunks = png_text_chunks(&original);
+        // Other formats (TIFF, JPEG) carry XMP outside PNG chunks; move it into one
+        let xmp_chunk = text_chunks
+            .is_empty()
+            .then(|| read_xmp_metadata(&file_path_owned))
+            .flatten()
+            .map(|xmp| xmp_itxt_chunk(&xmp));
+        if let Some(chunk) = &xmp_chunk {
+            text_chunks.push(chunk);
+        }
+        fs::write(
+            &output_path_clone,
+            insert_png_chunks(&encoded, &text_chunks),
+        )?;
+        log::info!(
+            "Saved edited image to {} ({} metadata chunk(s) kept)",
+            output_path_clone.display(),
+            text_chunks.len()
+        );
         Ok::<_, AppError>(())
     })
     .await
@@ -1153,6 +1387,207 @@ pub async fn resize_image_box(file_path: &str, scale: f32) -> AppResult<String>
     Ok(output_path.to_string_lossy().to_string())
 }
 
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Raw tEXt/iTXt/zTXt chunks (length, type, data and CRC) of a PNG; empty for other formats
+fn png_text_chunks(png_data: &[u8]) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    if !png_data.starts_with(&PNG_SIGNATURE) {
+        return chunks;
+    }
+
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 8 <= png_data.len() {
+        let length = u32::from_be_bytes([
+            png_data[pos],
+            png_data[pos + 1],
+            png_data[pos + 2],
+            png_data[pos + 3],
+        ]) as usize;
+        let chunk_end = pos + 12 + length;
+        if chunk_end > png_data.len() {
+            break;
+        }
+
+        let chunk_type = &png_data[pos + 4..pos + 8];
+        if matches!(chunk_type, b"tEXt" | b"iTXt" | b"zTXt") {
+            chunks.push(&png_data[pos..chunk_end]);
+        }
+        pos = chunk_end;
+    }
+    chunks
+}
+
+/// Insert raw chunks into an encoded PNG right before its first IDAT chunk
+fn insert_png_chunks(png_data: &[u8], chunks: &[&[u8]]) -> Vec<u8> {
+    let mut result =
+        Vec::with_capacity(png_data.len() + chunks.iter().map(|c| c.len()).sum::<usize>());
+    let mut pos = PNG_SIGNATURE.len().min(png_data.len());
+    result.extend_from_slice(&png_data[..pos]);
+
+    let mut inserted = chunks.is_empty();
+    while pos + 8 <= png_data.len() {
+        let length = u32::from_be_bytes([
+            png_data[pos],
+            png_data[pos + 1],
+            png_data[pos + 2],
+            png_data[pos + 3],
+        ]) as usize;
+        if !inserted && &png_data[pos + 4..pos + 8] == b"IDAT" {
+            for chunk in chunks {
+                result.extend_from_slice(chunk);
+            }
+            inserted = true;
+        }
+
+        let chunk_end = (pos + 12 + length).min(png_data.len());
+        result.extend_from_slice(&png_data[pos..chunk_end]);
+        pos = chunk_end;
+    }
+    result
+}
+
+/// Embedded ICC color profile of an image, if it has one
+fn read_icc_profile(file_path: &str) -> Option<Vec<u8>> {
+    use image::ImageDecoder;
+
+    let mut decoder = image::ImageReader::open(file_path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    decoder
+        .icc_profile()
+        .ok()
+        .flatten()
+        .filter(|icc| !icc.is_empty())
+}
+
+/// XMP packet of a non-PNG image, as the decoder exposes it
+fn read_xmp_metadata(file_path: &str) -> Option<Vec<u8>> {
+    use image::ImageDecoder;
+
+    let mut decoder = image::ImageReader::open(file_path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    decoder
+        .xmp_metadata()
+        .ok()
+        .flatten()
+        .filter(|xmp| !xmp.is_empty())
+}
+
+/// Uncompressed iTXt chunk holding an XMP packet, the way VRChat writes it
+fn xmp_itxt_chunk(xmp: &[u8]) -> Vec<u8> {
+    // Keyword, compression flag and method, then empty language and translated keyword
+    let data = [b"XML:com.adobe.xmp\0\0\0\0\0".as_slice(), xmp].concat();
+    let mut crc = flate2::Crc::new();
+    crc.update(b"iTXt");
+    crc.update(&data);
+
+    let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(b"iTXt");
+    chunk.extend_from_slice(&data);
+    chunk.extend_from_slice(&crc.sum().to_be_bytes());
+    chunk
+}
+
+fn set_encoder_icc(encoder: &mut impl image::ImageEncoder, icc: Vec<u8>) {
+    if let Err(e) = encoder.set_icc_profile(icc) {
+        log::warn!("Encoder can't embed ICC profile: {e}");
+    }
+}
+
+/// Encode as PNG, embedding the ICC profile when there is one
+fn encode_png(img: &image::DynamicImage, icc: Option<Vec<u8>>) -> AppResult<Vec<u8>> {
+    let mut encoded = Vec::new();
+    let mut encoder = image::codecs::png::PngEncoder::new(&mut encoded);
+    if let Some(icc) = icc {
+        set_encoder_icc(&mut encoder, icc);
+    }
+    img.write_with_encoder(encoder)?;
+    Ok(encoded)
+}
+
+/// Add an ICCP chunk to a WebP from libwebp's simple encoder. Plain VP8/VP8L files are
+/// upgraded to the extended (VP8X) format, which is required to carry a profile.
+fn embed_webp_icc(webp: &[u8], icc: &[u8], width: u32, height: u32) -> Vec<u8> {
+    const ICC_FLAG: u8 = 0x20;
+    const ALPHA_FLAG: u8 = 0x10;
+    const VP8X_END: usize = 12 + 8 + 10;
+
+    if webp.len() < 20 || &webp[0..4] != b"RIFF" || &webp[8..12] != b"WEBP" {
+        return webp.to_vec();
+    }
+
+    let mut out = Vec::with_capacity(webp.len() + icc.len() + 32);
+    out.extend_from_slice(b"RIFF\0\0\0\0WEBP");
+
+    let rest = if &webp[12..16] == b"VP8X" && webp.len() >= VP8X_END {
+        out.extend_from_slice(&webp[12..VP8X_END]);
+        out[20] |= ICC_FLAG;
+        &webp[VP8X_END..]
+    } else {
+        // VP8L keeps its alpha bit in the bitstream header; lossy VP8 never has alpha
+        let has_alpha = &webp[12..16] == b"VP8L"
+            && webp.len() >= 25
+            && (u32::from_le_bytes([webp[21], webp[22], webp[23], webp[24]]) >> 28) & 1 == 1;
+
+        out.extend_from_slice(b"VP8X");
+        out.extend_from_slice(&10u32.to_le_bytes());
+        out.push(ICC_FLAG | if has_alpha { ALPHA_FLAG } else { 0 });
+        out.extend_from_slice(&[0; 3]);
+        out.extend_from_slice(&width.saturating_sub(1).to_le_bytes()[..3]);
+        out.extend_from_slice(&height.saturating_sub(1).to_le_bytes()[..3]);
+        &webp[12..]
+    };
+
+    out.extend_from_slice(b"ICCP");
+    out.extend_from_slice(&(icc.len() as u32).to_le_bytes());
+    out.extend_from_slice(icc);
+    if icc.len() % 2 == 1 {
+        out.push(0);
+    }
+    out.extend_from_slice(rest);
+
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    out
+}
+
+/// Convert pixels from their ICC profile to sRGB, for formats that can't carry a profile
+fn convert_to_srgb(img: image::DynamicImage, icc: &[u8]) -> image::DynamicImage {
+    use moxcms::{ColorProfile, Layout, TransformOptions};
+
+    let transform = ColorProfile::new_from_slice(icc).and_then(|profile| {
+        profile.create_transform_8bit(
+            Layout::Rgba,
+            &ColorProfile::new_srgb(),
+            Layout::Rgba,
+            TransformOptions::default(),
+        )
+    });
+    let transform = match transform {
+        Ok(transform) => transform,
+        Err(e) => {
+            log::warn!("Ignoring unusable ICC profile: {e}");
+            return img;
+        }
+    };
+
+    let source = img.to_rgba8();
+    let mut converted = image::RgbaImage::new(source.width(), source.height());
+    if let Err(e) = transform.transform(&source, &mut converted) {
+        log::warn!("ICC to sRGB conversion failed: {e}");
+        return img;
+    }
+    image::DynamicImage::ImageRgba8(converted)
+}
+
 fn load_image_efficiently(file_path: &str) -> AppResult<image::DynamicImage> {
     // Check file size first
     let file_size = FileSystemGuard::get_file_size(file_path)?;
@@ -1167,7 +1602,7 @@ fn load_image_efficiently(file_path: &str) -> AppResult<image::DynamicImage> {
 
         // For very large files, we might want to use a streaming approach
         // or limit the maximum dimensions
-        let img = image::open(file_path)?;
+        let img = to_8bit_srgb(image::open(file_path)?);
 
         // Resize if too large
         const MAX_DIMENSION: u32 = 4096;
@@ -1184,10 +1619,69 @@ fn load_image_efficiently(file_path: &str) -> AppResult<image::DynamicImage> {
         }
     } else {
         // Normal loading for smaller files
-        Ok(image::open(file_path)?)
+        Ok(to_8bit_srgb(image::open(file_path)?))
     }
 }
 
+/// Reduce 16-bit and floating-point images to 8-bit sRGB before encoding.
+/// 16-bit channels are rescaled; float (HDR) data is linear light, so it's tone mapped
+/// with extended Reinhard (white point = brightest pixel) and gamma encoded.
+fn to_8bit_srgb(img: image::DynamicImage) -> image::DynamicImage {
+    use image::DynamicImage;
+
+    match img {
+        DynamicImage::ImageLuma16(_) | DynamicImage::ImageRgb16(_) => {
+            log::info!("Reducing 16-bit image to 8-bit");
+            DynamicImage::ImageRgb8(img.to_rgb8())
+        }
+        DynamicImage::ImageLumaA16(_) | DynamicImage::ImageRgba16(_) => {
+            log::info!("Reducing 16-bit image to 8-bit");
+            DynamicImage::ImageRgba8(img.to_rgba8())
+        }
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => {
+            log::info!("Tone mapping floating-point image to 8-bit sRGB");
+            DynamicImage::ImageRgba8(tone_map_hdr(&img.to_rgba32f()))
+        }
+        other => other,
+    }
+}
+
+fn tone_map_hdr(img: &image::Rgba32FImage) -> image::RgbaImage {
+    let luminance = |p: &image::Rgba<f32>| 0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2];
+    let white = img.pixels().map(luminance).fold(0.0f32, f32::max);
+
+    let to_srgb_u8 = |linear: f32| {
+        let c = linear.clamp(0.0, 1.0);
+        let encoded = if c <= 0.003_130_8 {
+            12.92 * c
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (encoded * 255.0).round() as u8
+    };
+
+    image::RgbaImage::from_fn(img.width(), img.height(), |x, y| {
+        let p = img.get_pixel(x, y);
+        // Already in display range: only gamma encode
+        let scale = if white > 1.0 {
+            let l = luminance(p);
+            if l > 0.0 {
+                (1.0 + l / (white * white)) / (1.0 + l)
+            } else {
+                1.0
+            }
+        } else {
+            1.0
+        };
+        image::Rgba([
+            to_srgb_u8(p[0] * scale),
+            to_srgb_u8(p[1] * scale),
+            to_srgb_u8(p[2] * scale),
+            (p[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+        ])
+    })
+}
+
 /// Encode an RGBA image to AVIF format using ravif
 /// This is CPU-intensive, so we run it in a blocking thread pool
 async fn encode_avif(
@@ -1228,41 +1722,213 @@ async fn encode_avif(
     .map_err(|e| AppError::ImageProcessing(format!("AVIF encoding task failed: {e}")))?
 }
 
-pub async fn get_file_hash(file_path: &str) -> AppResult<String> {
+/// Algorithm used for file content hashes
+pub const FILE_HASH_ALGORITHM: &str = "sha256";
+
+/// Content hash of a file
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FileHash {
+    pub algorithm: String,
+    pub hash: String,
+}
+
+impl FileHash {
+    /// `algorithm:hash` form stored in upload_history, so hashes from older
+    /// versions (bare DefaultHasher hex) can be told apart and migrated
+    pub fn tagged(&self) -> String {
+        format!("{}:{}", self.algorithm, self.hash)
+    }
+}
+
+/// Hex SHA-256 of a file's contents, streamed so large files aren't loaded into memory
+pub fn sha256_file(file_path: &str) -> AppResult<String> {
+    use sha2::{Digest, Sha256};
+
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    let mut file = fs::File::open(file_path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+pub async fn get_file_hash(file_path: &str) -> AppResult<FileHash> {
     InputValidator::validate_file_path(file_path)?;
 
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
+    let path = file_path.to_string();
+    let hash = tokio::task::spawn_blocking(move || sha256_file(&path))
+        .await
+        .map_err(|e| AppError::Internal(format!("Hashing task failed: {e}")))??;
 
-    // For large files, read in chunks to avoid memory issues
-    let file_size = FileSystemGuard::get_file_size(file_path)?;
-    const CHUNK_SIZE: usize = 8192; // 8KB chunks
+    Ok(FileHash {
+        algorithm: FILE_HASH_ALGORITHM.to_string(),
+        hash,
+    })
+}
 
-    let mut hasher = DefaultHasher::new();
+/// Re-hash files whose upload_history rows still carry a pre-SHA-256 hash.
+/// Files that no longer exist get their hash cleared, since it can't be recomputed.
+pub async fn migrate_legacy_file_hashes() -> AppResult<usize> {
+    let prefix = format!("{FILE_HASH_ALGORITHM}:");
+    let paths = crate::database::get_files_with_legacy_hash(&prefix).await?;
+    if paths.is_empty() {
+        return Ok(0);
+    }
 
-    if file_size > 100 * 1024 * 1024 {
-        // Files larger than 100MB
-        // Stream-based hashing for large files
-        let mut file = fs::File::open(file_path)?;
-        let mut buffer = vec![0u8; CHUNK_SIZE];
+    log::info!(
+        "Migrating {} upload history file hashes to {FILE_HASH_ALGORITHM}",
+        paths.len()
+    );
+    for path in &paths {
+        let new_hash = match get_file_hash(path).await {
+            Ok(hash) => Some(hash.tagged()),
+            Err(e) => {
+                log::debug!("Clearing legacy hash for {path}: {e}");
+                None
+            }
+        };
+        crate::database::replace_legacy_file_hash(path, &prefix, new_hash.as_deref()).await?;
+    }
+
+    Ok(paths.len())
+}
+
+/// Parse an IANA time zone name such as "Europe/Berlin" or "UTC"
+pub fn parse_timezone(name: &str) -> AppResult<chrono_tz::Tz> {
+    name.trim().parse::<chrono_tz::Tz>().map_err(|_| {
+        AppError::validation(
+            "screenshot_timezone",
+            &format!("Unknown time zone '{name}'"),
+        )
+    })
+}
 
-        loop {
-            let bytes_read = file.read(&mut buffer)?;
-            if bytes_read == 0 {
-                break;
+/// Resolve an optional time zone name, logging and ignoring invalid names (system local time is used instead)
+pub fn resolve_screenshot_timezone(name: Option<&str>) -> Option<chrono_tz::Tz> {
+    let name = name.filter(|n| !n.trim().is_empty())?;
+    match parse_timezone(name) {
+        Ok(tz) => Some(tz),
+        Err(e) => {
+            log::warn!("Ignoring screenshot time zone: {e}");
+            None
+        }
+    }
+}
+
+/// The configured screenshot time zone (system local time if none is configured).
+/// Load it once per operation and pass it along rather than per file.
+pub fn configured_screenshot_timezone() -> Option<chrono_tz::Tz> {
+    let config = crate::config::load_config().ok();
+    resolve_screenshot_timezone(
+        config
+            .as_ref()
+            .and_then(|c| c.screenshot_timezone.as_deref()),
+    )
+}
+
+/// Convert a wall-clock datetime to a UTC timestamp, interpreting it in `timezone`
+/// (system local time when `None`)
+fn naive_datetime_to_timestamp(dt: chrono::NaiveDateTime, timezone: Option<chrono_tz::Tz>) -> i64 {
+    // Explicit screenshot time zone (user travelled or changed system time zone)
+    if let Some(tz) = timezone {
+        if let Some(zoned_dt) = dt.and_local_timezone(tz).earliest() {
+            let utc_timestamp = zoned_dt.timestamp();
+            log::debug!("Datetime in {tz}: {zoned_dt} (Discord: <t:{utc_timestamp}:f>)");
+            return utc_timestamp;
+        }
+        log::warn!("Time {dt} does not exist in {tz} (DST gap), using local time");
+    }
+
+    // VRChat screenshots are saved in local time
+    // Get current system timezone offset
+    let local_offset = chrono::Local::now().offset().fix();
+    log::debug!("Local timezone offset: {local_offset}");
+
+    // Convert to local datetime with timezone
+    match dt.and_local_timezone(local_offset).single() {
+        Some(local_dt) => {
+            let utc_timestamp = local_dt.timestamp();
+            log::debug!("Local datetime: {local_dt}");
+            log::debug!("UTC timestamp: {utc_timestamp} (Discord: <t:{utc_timestamp}:f>)");
+            return utc_timestamp;
+        }
+        None => {
+            log::warn!("Ambiguous local timezone conversion (likely DST transition)");
+            // During DST transitions, pick the earliest interpretation
+            if let Some(local_dt) = dt.and_local_timezone(local_offset).earliest() {
+                let utc_timestamp = local_dt.timestamp();
+                log::debug!("Using earliest DST interpretation: {local_dt}");
+                return utc_timestamp;
+            } else {
+                log::warn!("Could not resolve DST ambiguity, using UTC fallback");
             }
-            buffer[..bytes_read].hash(&mut hasher);
         }
-    } else {
-        // Read entire file for smaller files
-        let contents = fs::read(file_path)?;
-        contents.hash(&mut hasher);
     }
 
-    Ok(format!("{:x}", hasher.finish()))
+    // Fallback: treat as UTC (this is safe but may be wrong by timezone offset)
+    let utc_timestamp = dt.and_utc().timestamp();
+    log::warn!(
+        "FALLBACK: Treating timestamp as UTC. This may be incorrect by your timezone offset."
+    );
+    log::debug!("Fallback UTC timestamp: {utc_timestamp} (Discord: <t:{utc_timestamp}:f>)");
+    utc_timestamp
+}
+
+/// Read EXIF DateTimeOriginal from a photo (JPEG, PNG eXIf, WebP, TIFF).
+/// Uses OffsetTimeOriginal when present, otherwise interprets the time in `timezone`.
+fn get_exif_timestamp(file_path: &str, timezone: Option<chrono_tz::Tz>) -> Option<i64> {
+    let file = fs::File::open(file_path).ok()?;
+    let mut reader = BufReader::new(file);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+
+    let field = exif.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)?;
+    let exif::Value::Ascii(ref values) = field.value else {
+        return None;
+    };
+    let raw = std::str::from_utf8(values.first()?).ok()?.trim();
+    let dt = chrono::NaiveDateTime::parse_from_str(raw, "%Y:%m:%d %H:%M:%S").ok()?;
+    log::debug!("Found EXIF DateTimeOriginal in {file_path}: {dt}");
+
+    // OffsetTimeOriginal ("+09:00") pins the time zone the photo was taken in
+    let offset = exif
+        .get_field(exif::Tag::OffsetTimeOriginal, exif::In::PRIMARY)
+        .and_then(|f| match f.value {
+            exif::Value::Ascii(ref v) => v
+                .first()
+                .and_then(|b| std::str::from_utf8(b).ok())
+                .and_then(|s| chrono::FixedOffset::from_str(s.trim()).ok()),
+            _ => None,
+        });
+
+    if let Some(offset) = offset {
+        if let Some(zoned_dt) = dt.and_local_timezone(offset).single() {
+            return Some(zoned_dt.timestamp());
+        }
+    }
+
+    Some(naive_datetime_to_timestamp(dt, timezone))
 }
 
-pub fn get_timestamp_from_filename(file_path: &str) -> Option<i64> {
+/// Get a photo's capture timestamp: the VRChat-style filename interpreted in `timezone`
+/// (system local time when `None`), otherwise EXIF DateTimeOriginal, falling back to the
+/// file creation time
+pub fn get_timestamp_from_filename_in_timezone(
+    file_path: &str,
+    timezone: Option<chrono_tz::Tz>,
+) -> Option<i64> {
     let filename = Path::new(file_path).file_name().and_then(|n| n.to_str())?;
 
     let date_regex =
@@ -1281,46 +1947,18 @@ pub fn get_timestamp_from_filename(file_path: &str) -> Option<i64> {
         for format in &formats {
             if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&datetime_str, format) {
                 log::debug!("Parsed NaiveDateTime: {dt}");
-
-                // VRChat screenshots are saved in local time
-                // Get current system timezone offset
-                let local_offset = chrono::Local::now().offset().fix();
-                log::debug!("Local timezone offset: {local_offset}");
-
-                // Convert to local datetime with timezone
-                match dt.and_local_timezone(local_offset).single() {
-                    Some(local_dt) => {
-                        let utc_timestamp = local_dt.timestamp();
-                        log::debug!("Local datetime: {local_dt}");
-                        log::debug!(
-                            "UTC timestamp: {utc_timestamp} (Discord: <t:{utc_timestamp}:f>)"
-                        );
-                        return Some(utc_timestamp);
-                    }
-                    None => {
-                        log::warn!("Ambiguous local timezone conversion (likely DST transition)");
-                        // During DST transitions, pick the earliest interpretation
-                        if let Some(local_dt) = dt.and_local_timezone(local_offset).earliest() {
-                            let utc_timestamp = local_dt.timestamp();
-                            log::debug!("Using earliest DST interpretation: {local_dt}");
-                            return Some(utc_timestamp);
-                        } else {
-                            log::warn!("Could not resolve DST ambiguity, using UTC fallback");
-                        }
-                    }
-                }
-
-                // Fallback: treat as UTC (this is safe but may be wrong by timezone offset)
-                let utc_timestamp = dt.and_utc().timestamp();
-                log::warn!("FALLBACK: Treating timestamp as UTC. This may be incorrect by your timezone offset.");
-                log::debug!(
-                    "Fallback UTC timestamp: {utc_timestamp} (Discord: <t:{utc_timestamp}:f>)"
-                );
-                return Some(utc_timestamp);
+                return Some(naive_datetime_to_timestamp(dt, timezone));
             }
         }
     }
 
+    // Renamed or edited photos may still carry EXIF DateTimeOriginal; only read the
+    // file when the name doesn't date it
+    if let Some(timestamp) = get_exif_timestamp(file_path, timezone) {
+        log::debug!("Using EXIF timestamp: {timestamp} (Discord: <t:{timestamp}:f>)");
+        return Some(timestamp);
+    }
+
     // Fallback to file creation time (this is always in correct timezone)
     if let Ok(metadata) = fs::metadata(file_path) {
         if let Ok(created) = metadata.created() {
@@ -1356,43 +1994,88 @@ pub fn generate_thumbnail(file_path: &str, max_dimension: u32) -> AppResult<Stri
 
     log::debug!("Generating thumbnail for {file_path} with max dimension {max_dimension}");
 
+    // Same content at the same size was thumbnailed before
+    let cache_key = crate::thumbnail_cache::cache_key(file_path, max_dimension);
+    if let Some(cached) = cache_key
+        .as_deref()
+        .and_then(crate::thumbnail_cache::lookup)
+    {
+        log::debug!("Using cached thumbnail for {file_path}");
+        return Ok(cached.to_string_lossy().to_string());
+    }
+
     // Load the image
     let img = image::open(file_path)?;
+    let (source_width, source_height) = (img.width(), img.height());
 
-    // Resize to thumbnail using thumbnail method
-    let thumbnail = img.thumbnail(max_dimension, max_dimension);
-
-    log::debug!(
-        "Resized from {}x{} to {}x{}",
-        img.width(),
-        img.height(),
-        thumbnail.width(),
-        thumbnail.height()
-    );
+    let thumbnail = resize_for_thumbnail(img, max_dimension)?;
+    let (width, height) = (thumbnail.width(), thumbnail.height());
 
-    // Create output path in secure temp directory
-    let temp_path = FileSystemGuard::create_secure_temp_file(file_path)?;
-    let output_path = temp_path.with_extension("thumb.webp");
+    log::debug!("Resized from {source_width}x{source_height} to {width}x{height}");
 
     // Convert to WebP using webp crate for better compression
-    let rgba_img = thumbnail.to_rgba8();
-    let (width, height) = rgba_img.dimensions();
-    let encoder = webp::Encoder::from_rgba(&rgba_img, width, height);
+    let encoder = if thumbnail.pixel_type() == PixelType::U8x3 {
+        webp::Encoder::from_rgb(thumbnail.buffer(), width, height)
+    } else {
+        webp::Encoder::from_rgba(thumbnail.buffer(), width, height)
+    };
     let webp_data = encoder.encode(60.0); // quality 60 for thumbnails
 
-    fs::write(&output_path, &*webp_data)?;
+    // Keep it in the cache when possible, otherwise fall back to a one-off temp file
+    let output_path = match cache_key
+        .as_deref()
+        .and_then(|key| crate::thumbnail_cache::store(key, &webp_data))
+    {
+        Some(path) => path,
+        None => {
+            let temp_path = FileSystemGuard::create_secure_temp_file(file_path)?;
+            let output_path = temp_path.with_extension("thumb.webp");
+            fs::write(&output_path, &*webp_data)?;
+            output_path
+        }
+    };
 
     log::info!(
         "Generated thumbnail for {} at {} ({}x{})",
         file_path,
         output_path.display(),
-        thumbnail.width(),
-        thumbnail.height()
+        width,
+        height
     );
 
     Ok(output_path.to_string_lossy().to_string())
 }
 
+/// Size that fits within `max_dimension` on the longer side, never larger than the source
+fn thumbnail_dimensions(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    let scale = (f64::from(max_dimension) / f64::from(width.max(height).max(1))).min(1.0);
+    (
+        ((f64::from(width) * scale).round() as u32).max(1),
+        ((f64::from(height) * scale).round() as u32).max(1),
+    )
+}
+
+/// Downscale with fast_image_resize's SIMD convolution. 8-bit RGB and RGBA images,
+/// which covers VRChat's PNGs and JPEGs, are resized straight from the decoded buffer;
+/// anything else is converted to RGBA first.
+fn resize_for_thumbnail(img: image::DynamicImage, max_dimension: u32) -> AppResult<Image<'static>> {
+    let img = match img {
+        image::DynamicImage::ImageRgb8(_) | image::DynamicImage::ImageRgba8(_) => img,
+        other => image::DynamicImage::ImageRgba8(other.to_rgba8()),
+    };
+    let pixel_type = img
+        .pixel_type()
+        .ok_or_else(|| AppError::ImageProcessing("Unsupported thumbnail pixel format".into()))?;
+    let (width, height) = thumbnail_dimensions(img.width(), img.height(), max_dimension);
+
+    let mut thumbnail = Image::new(width, height, pixel_type);
+    let options = ResizeOptions::new().resize_alg(ResizeAlg::Convolution(FilterType::Bilinear));
+    Resizer::new()
+        .resize(&img, &mut thumbnail, &options)
+        .map_err(|e| AppError::ImageProcessing(format!("Failed to resize thumbnail: {e}")))?;
+    Ok(thumbnail)
+}
+
 /// Check if image needs compression for Discord
 pub fn should_compress_image(file_path: &str) -> AppResult<bool> {
     let file_size = FileSystemGuard::get_file_size(file_path)?;
@@ -1520,10 +2203,37 @@ mod tests {
 
     #[tokio::test]
     async fn test_extract_metadata_nonexistent_file() {
-        let result = extract_metadata("nonexistent_file.png").await;
+        let result = extract_metadata("nonexistent_file.png", None).await;
         assert!(result.is_err(), "Should fail for nonexistent file");
     }
 
+    #[tokio::test]
+    async fn test_extract_metadata_falls_back_to_sidecar() {
+        let test_file_path =
+            std::env::temp_dir().join(format!("sidecar_test_{}.png", uuid::Uuid::new_v4()));
+        image::RgbImage::from_pixel(4, 4, image::Rgb([10, 20, 30]))
+            .save(&test_file_path)
+            .unwrap();
+        let path_str = test_file_path.to_string_lossy().to_string();
+        let sidecar = sidecar_path(&path_str);
+        std::fs::write(
+            &sidecar,
+            r#"{"author": {"displayName": "SidecarUser", "id": "usr_sidecar"}, "players": ["Alice"]}"#,
+        )
+        .unwrap();
+
+        let result = extract_metadata_with_source(&path_str, None).await;
+
+        let _ = std::fs::remove_file(&test_file_path);
+        let _ = std::fs::remove_file(&sidecar);
+
+        let result = result.unwrap();
+        assert_eq!(result.source, MetadataSource::Sidecar);
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata.author.unwrap().display_name, "SidecarUser");
+        assert_eq!(metadata.players.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_extract_metadata_no_metadata() {
         let (test_file_path, png_data) = create_test_image();
@@ -1532,7 +2242,7 @@ mod tests {
             let _ = file.write_all(&png_data);
 
             let path_str = test_file_path.to_string_lossy();
-            let result = extract_metadata(&path_str).await;
+            let result = extract_metadata(&path_str, None).await;
 
             // Cleanup
             let _ = std::fs::remove_file(&test_file_path);
@@ -1604,4 +2314,365 @@ mod tests {
             assert!(metadata.players.is_empty() || !metadata.players.is_empty());
         }
     }
+
+    #[test]
+    fn test_parse_vrchat_metadata_avatar() {
+        let json = serde_json::json!({
+            "author": { "displayName": "TestUser", "id": "usr_test123" },
+            "avatar": { "name": " Robo Cat ", "id": "avtr_test123" }
+        });
+        let avatar = parse_vrchat_metadata(json).unwrap().avatar.unwrap();
+        assert_eq!(avatar.name, "Robo Cat");
+        assert_eq!(avatar.id, "avtr_test123");
+
+        let json = serde_json::json!({ "avatar": { "name": "", "id": "avtr_test123" } });
+        assert!(parse_vrchat_metadata(json).unwrap().avatar.is_none());
+    }
+
+    #[test]
+    fn test_parse_vrchat_metadata_variants() {
+        let json = serde_json::json!({
+            "author": { "display_name": "TestUser" },
+            "world": { "name": "Test World", "id": "wrld_test", "instance_id": "1~public" },
+            "players": [
+                "Alice",
+                { "displayName": "Bob" },
+                { "display_name": "Carol", "id": "usr_carol" },
+                { "id": "usr_nameless" },
+                42
+            ],
+            "screenshotSource": "camera"
+        });
+        let metadata = parse_vrchat_metadata(json).unwrap();
+
+        let author = metadata.author.unwrap();
+        assert_eq!(author.display_name, "TestUser");
+        assert_eq!(author.id, "");
+        assert_eq!(metadata.world.unwrap().instance_id, "1~public");
+
+        let players: Vec<(&str, &str)> = metadata
+            .players
+            .iter()
+            .map(|p| (p.display_name.as_str(), p.id.as_str()))
+            .collect();
+        assert_eq!(
+            players,
+            vec![("Alice", ""), ("Bob", ""), ("Carol", "usr_carol")]
+        );
+    }
+
+    fn write_jpeg_with_exif_datetime(path: &std::path::Path, datetime: &str, offset: Option<&str>) {
+        use exif::experimental::Writer;
+        use exif::{Field, In, Tag, Value};
+
+        let datetime_field = Field {
+            tag: Tag::DateTimeOriginal,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![datetime.as_bytes().to_vec()]),
+        };
+        let offset_field = offset.map(|o| Field {
+            tag: Tag::OffsetTimeOriginal,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![o.as_bytes().to_vec()]),
+        });
+        let mut writer = Writer::new();
+        writer.push_field(&datetime_field);
+        if let Some(ref field) = offset_field {
+            writer.push_field(field);
+        }
+        let mut tiff = std::io::Cursor::new(Vec::new());
+        writer.write(&mut tiff, false).unwrap();
+        let tiff = tiff.into_inner();
+
+        let mut jpeg = Vec::new();
+        image::DynamicImage::new_rgb8(1, 1)
+            .write_to(
+                &mut std::io::Cursor::new(&mut jpeg),
+                image::ImageFormat::Jpeg,
+            )
+            .unwrap();
+
+        // Insert an APP1 Exif segment right after SOI
+        let mut app1 = vec![0xFF, 0xE1];
+        app1.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        app1.extend_from_slice(b"Exif\0\0");
+        app1.extend_from_slice(&tiff);
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&app1);
+        out.extend_from_slice(&jpeg[2..]);
+        std::fs::write(path, out).unwrap();
+    }
+
+    #[test]
+    fn test_filename_preferred_over_exif_datetime() {
+        let path = std::env::temp_dir().join("2020-01-01_00-00-00.000_exif_test.jpg");
+        write_jpeg_with_exif_datetime(&path, "2024:01:15 14:30:00", Some("+09:00"));
+
+        let tz = parse_timezone("UTC").unwrap();
+        let ts = get_timestamp_from_filename_in_timezone(&path.to_string_lossy(), Some(tz));
+        let _ = std::fs::remove_file(&path);
+
+        // 2020-01-01 00:00:00 UTC from the name, not the EXIF time
+        assert_eq!(ts, Some(1577836800));
+    }
+
+    #[test]
+    fn test_exif_datetime_used_when_filename_has_no_date() {
+        let path = std::env::temp_dir().join("renamed_exif_test.jpg");
+        write_jpeg_with_exif_datetime(&path, "2024:01:15 14:30:00", Some("+09:00"));
+
+        let ts = get_timestamp_from_filename_in_timezone(&path.to_string_lossy(), None);
+        let _ = std::fs::remove_file(&path);
+
+        // 2024-01-15 14:30:00 +09:00 == 05:30:00 UTC
+        assert_eq!(ts, Some(1705296600));
+    }
+
+    #[test]
+    fn test_exif_datetime_without_offset_uses_timezone() {
+        let path = std::env::temp_dir().join("exif_no_offset_test.jpg");
+        write_jpeg_with_exif_datetime(&path, "2024:01:15 14:30:00", None);
+
+        let tz = parse_timezone("UTC").unwrap();
+        let ts = get_timestamp_from_filename_in_timezone(&path.to_string_lossy(), Some(tz));
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(ts, Some(1705329000));
+    }
+
+    fn text_chunk(keyword: &str, text: &[u8]) -> Vec<u8> {
+        let data = [keyword.as_bytes(), b"\0", text].concat();
+        let mut crc = flate2::Crc::new();
+        crc.update(b"tEXt");
+        crc.update(&data);
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(b"tEXt");
+        chunk.extend_from_slice(&data);
+        chunk.extend_from_slice(&crc.sum().to_be_bytes());
+        chunk
+    }
+
+    fn png_with_chunks(chunks: &[&[u8]]) -> Vec<u8> {
+        let img = image::DynamicImage::ImageRgb8(image::RgbImage::new(4, 2));
+        let mut encoded = Vec::new();
+        img.write_to(
+            &mut std::io::Cursor::new(&mut encoded),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+        insert_png_chunks(&encoded, chunks)
+    }
+
+    fn get_png_description(file_path: &str) -> AppResult<Option<String>> {
+        Ok(find_png_description(&read_png_text_chunks(file_path)?))
+    }
+
+    fn png_with_description(description: &str) -> Vec<u8> {
+        png_with_chunks(&[&text_chunk("Description", description.as_bytes())])
+    }
+
+    #[tokio::test]
+    async fn test_get_file_hash_is_sha256() {
+        let path = std::env::temp_dir().join(format!("hash_test_{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"abc").unwrap();
+
+        let hash = get_file_hash(&path.to_string_lossy()).await.unwrap();
+        assert_eq!(hash.algorithm, "sha256");
+        assert_eq!(
+            hash.tagged(),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_read_png_text_chunks_skips_oversized_chunks() {
+        let path = std::env::temp_dir().join(format!("chunks_test_{}.png", uuid::Uuid::new_v4()));
+        let oversized = text_chunk("Comment", &vec![b'x'; MAX_TEXT_CHUNK_SIZE + 1]);
+        let description = r#"{"application":"VRCX"}"#;
+        std::fs::write(
+            &path,
+            png_with_chunks(&[
+                &oversized,
+                &text_chunk("Description", description.as_bytes()),
+            ]),
+        )
+        .unwrap();
+
+        let chunks = read_png_text_chunks(&path.to_string_lossy()).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(find_png_description(&chunks).as_deref(), Some(description));
+        assert!(find_vrchat_xmp_metadata(&chunks).is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_to_8bit_srgb_reduces_high_bit_depth() {
+        let img16 = image::DynamicImage::ImageRgba16(image::ImageBuffer::from_pixel(
+            2,
+            2,
+            image::Rgba([65535u16, 32896, 0, 65535]),
+        ));
+        let reduced = to_8bit_srgb(img16);
+        assert_eq!(reduced.color(), image::ColorType::Rgba8);
+        assert_eq!(reduced.to_rgba8().get_pixel(0, 0).0, [255, 128, 0, 255]);
+
+        // Display-range float data is only gamma encoded (linear 0.5 -> sRGB 188)
+        let sdr = image::DynamicImage::ImageRgb32F(image::ImageBuffer::from_pixel(
+            1,
+            1,
+            image::Rgb([0.5f32, 0.5, 0.5]),
+        ));
+        assert_eq!(
+            to_8bit_srgb(sdr).to_rgba8().get_pixel(0, 0).0,
+            [188, 188, 188, 255]
+        );
+
+        // HDR data: the brightest pixel maps to white, darker ones stay below it
+        let mut hdr = image::Rgb32FImage::from_pixel(2, 1, image::Rgb([4.0f32, 4.0, 4.0]));
+        hdr.put_pixel(1, 0, image::Rgb([1.0, 1.0, 1.0]));
+        let mapped = to_8bit_srgb(image::DynamicImage::ImageRgb32F(hdr)).to_rgba8();
+        assert_eq!(mapped.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert!(mapped.get_pixel(1, 0)[0] < 255);
+    }
+
+    #[test]
+    fn test_icc_profile_carried_into_png_and_webp() {
+        use image::ImageDecoder;
+
+        let icc = moxcms::ColorProfile::new_display_p3().encode().unwrap();
+        let img = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            3,
+            2,
+            image::Rgba([10, 20, 30, 255]),
+        ));
+
+        let png = encode_png(&img, Some(icc.clone())).unwrap();
+        let mut decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(&png)).unwrap();
+        assert_eq!(decoder.icc_profile().unwrap(), Some(icc.clone()));
+
+        let rgba = img.to_rgba8();
+        for webp_data in [
+            webp::Encoder::from_rgba(&rgba, 3, 2).encode(80.0).to_vec(),
+            webp::Encoder::from_rgba(&rgba, 3, 2)
+                .encode_lossless()
+                .to_vec(),
+        ] {
+            let with_icc = embed_webp_icc(&webp_data, &icc, 3, 2);
+            let mut decoder =
+                image::codecs::webp::WebPDecoder::new(std::io::Cursor::new(&with_icc)).unwrap();
+            assert_eq!(decoder.icc_profile().unwrap(), Some(icc.clone()));
+            assert_eq!(decoder.dimensions(), (3, 2));
+            assert!(image::load_from_memory(&with_icc).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_convert_to_srgb() {
+        let icc = moxcms::ColorProfile::new_display_p3().encode().unwrap();
+        let mut pixels = image::RgbaImage::from_pixel(2, 1, image::Rgba([128, 128, 128, 255]));
+        pixels.put_pixel(1, 0, image::Rgba([0, 200, 0, 255]));
+
+        let converted =
+            convert_to_srgb(image::DynamicImage::ImageRgba8(pixels.clone()), &icc).to_rgba8();
+        // Neutral gray is the same in both spaces, a saturated P3 green is not
+        let gray = converted.get_pixel(0, 0);
+        assert!(gray.0.iter().take(3).all(|&c| c.abs_diff(128) <= 1));
+        assert_ne!(converted.get_pixel(1, 0), pixels.get_pixel(1, 0));
+
+        // Unusable profiles leave the pixels alone
+        let unchanged =
+            convert_to_srgb(image::DynamicImage::ImageRgba8(pixels.clone()), b"not icc");
+        assert_eq!(unchanged.to_rgba8(), pixels);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_and_crop_keep_metadata_chunks() {
+        let path = std::env::temp_dir().join(format!("edit_test_{}.png", uuid::Uuid::new_v4()));
+        let description = r#"{"application":"VRCX","world":{"name":"Test"}}"#;
+        std::fs::write(&path, png_with_description(description)).unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        let rotated = rotate_image(&path_str, 90).await.unwrap();
+        assert_eq!(image::image_dimensions(&rotated).unwrap(), (2, 4));
+        assert_eq!(
+            get_png_description(&rotated).unwrap().as_deref(),
+            Some(description)
+        );
+
+        let cropped = crop_image(&path_str, 1, 0, 2, 2).await.unwrap();
+        assert_eq!(image::image_dimensions(&cropped).unwrap(), (2, 2));
+        assert_eq!(
+            get_png_description(&cropped).unwrap().as_deref(),
+            Some(description)
+        );
+
+        assert!(crop_image(&path_str, 3, 0, 2, 2).await.is_err());
+
+        let downscaled = resize_to_max_dimension(&path_str, 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(image::image_dimensions(&downscaled).unwrap(), (2, 1));
+        assert_eq!(
+            get_png_description(&downscaled).unwrap().as_deref(),
+            Some(description)
+        );
+        assert!(resize_to_max_dimension(&path_str, 4)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(rotate_image(&path_str, 45).await.is_err());
+
+        for file in [&path_str, &rotated, &cropped, &downscaled] {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_convert_tiff_to_png() {
+        let path = std::env::temp_dir().join(format!("convert_test_{}.tiff", uuid::Uuid::new_v4()));
+        image::RgbImage::new(4, 2).save(&path).unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        let converted = convert_to_png(&path_str).await.unwrap();
+        assert_eq!(
+            image::ImageFormat::from_path(&converted).unwrap(),
+            image::ImageFormat::Png
+        );
+        assert_eq!(image::image_dimensions(&converted).unwrap(), (4, 2));
+
+        // XMP moved out of a TIFF lands where the metadata reader looks for it
+        let xmp = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"></x:xmpmeta>"#;
+        let chunk = xmp_itxt_chunk(xmp.as_bytes());
+        assert_eq!(&chunk[4..8], b"iTXt");
+        assert_eq!(
+            extract_xmp_from_itxt(&chunk[8..chunk.len() - 4]).as_deref(),
+            Some(xmp)
+        );
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&converted);
+    }
+
+    #[test]
+    fn test_thumbnail_resize_keeps_aspect_and_pixel_layout() {
+        assert_eq!(thumbnail_dimensions(3840, 2160, 200), (200, 113));
+        assert_eq!(thumbnail_dimensions(1080, 1920, 200), (113, 200));
+        // Small images aren't enlarged
+        assert_eq!(thumbnail_dimensions(120, 80, 200), (120, 80));
+
+        let rgb = image::DynamicImage::ImageRgb8(image::RgbImage::new(400, 300));
+        let thumbnail = resize_for_thumbnail(rgb, 200).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (200, 150));
+        assert_eq!(thumbnail.pixel_type(), PixelType::U8x3);
+
+        // 16-bit and grayscale images go through RGBA
+        let gray = image::DynamicImage::ImageLuma16(image::ImageBuffer::new(100, 400));
+        let thumbnail = resize_for_thumbnail(gray, 200).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (50, 200));
+        assert_eq!(thumbnail.pixel_type(), PixelType::U8x4);
+    }
 }
@@ -0,0 +1,269 @@
+// Import from the old Python uploader
+//
+// The Python version of this tool kept its webhooks and upload history either in a
+// SQLite database or a JSON file. Both are read into the same shape here, with
+// column/key names matched loosely since they changed between its releases, and
+// handed to `database::import_legacy_history` so dedupe and stats cover photos
+// uploaded before the migration.
+
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteRow};
+use sqlx::{Column, ConnectOptions, Row};
+
+use crate::database::{self, LegacyImportSummary, LegacyUpload, LegacyWebhook};
+use crate::errors::{AppError, AppResult};
+use crate::image_processor;
+use crate::security::InputValidator;
+
+/// Database file name the Python version used, looked for when a folder is given
+const LEGACY_DATABASE_NAME: &str = "DiscordWebhooks.db";
+
+/// Tables the Python version kept its history in, across releases
+const HISTORY_TABLES: &[&str] = &["upload_history", "uploads", "uploaded_files", "history"];
+
+#[derive(Debug, Default, Deserialize)]
+struct LegacyStore {
+    #[serde(default)]
+    webhooks: Vec<StoredWebhook>,
+    #[serde(
+        default,
+        alias = "history",
+        alias = "uploads",
+        alias = "uploaded_files"
+    )]
+    upload_history: Vec<StoredUpload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredWebhook {
+    #[serde(default)]
+    id: Option<i64>,
+    #[serde(alias = "webhook_name")]
+    name: String,
+    #[serde(alias = "webhook_url")]
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredUpload {
+    #[serde(alias = "path", alias = "file", alias = "filepath")]
+    file_path: String,
+    #[serde(default, alias = "hash")]
+    file_hash: Option<String>,
+    #[serde(default, alias = "size")]
+    file_size: Option<u64>,
+    #[serde(default)]
+    webhook_id: Option<i64>,
+    #[serde(default, alias = "webhook")]
+    webhook_url: Option<String>,
+    #[serde(default, alias = "timestamp", alias = "upload_time", alias = "date")]
+    uploaded_at: Option<Value>,
+    #[serde(default, alias = "status")]
+    upload_status: Option<String>,
+}
+
+/// Normalise an old timestamp to the UTC `YYYY-MM-DD HH:MM:SS` upload_history uses.
+/// Numbers are Unix seconds; timestamps without an offset are local time, which
+/// is what Python's `datetime.now()` wrote.
+fn normalize_timestamp(value: &Value) -> Option<String> {
+    let utc: DateTime<Utc> = match value {
+        Value::Number(n) => Utc.timestamp_opt(n.as_f64()? as i64, 0).single()?,
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+                t.with_timezone(&Utc)
+            } else {
+                let naive = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                    .iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())?;
+                Local
+                    .from_local_datetime(&naive)
+                    .earliest()?
+                    .with_timezone(&Utc)
+            }
+        }
+        _ => return None,
+    };
+    Some(utc.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+impl LegacyStore {
+    fn into_records(self) -> (Vec<LegacyWebhook>, Vec<LegacyUpload>) {
+        let webhooks = self
+            .webhooks
+            .into_iter()
+            .filter(|w| !w.url.trim().is_empty())
+            .map(|w| LegacyWebhook {
+                legacy_id: w.id,
+                name: w.name,
+                url: w.url,
+            })
+            .collect();
+        let uploads = self
+            .upload_history
+            .into_iter()
+            .filter(|u| !u.file_path.trim().is_empty())
+            .map(|u| LegacyUpload {
+                file_path: u.file_path,
+                file_hash: u.file_hash.filter(|h| !h.is_empty()),
+                file_size: u.file_size,
+                legacy_webhook_id: u.webhook_id,
+                webhook_url: u.webhook_url,
+                uploaded_at: u.uploaded_at.as_ref().and_then(normalize_timestamp),
+                status: u.upload_status.unwrap_or_else(|| "success".to_string()),
+            })
+            .collect();
+        (webhooks, uploads)
+    }
+}
+
+fn parse_json_store(json: &str) -> AppResult<LegacyStore> {
+    serde_json::from_str(json).map_err(|e| {
+        AppError::validation("path", &format!("Not a Python uploader history file: {e}"))
+    })
+}
+
+/// A row as a JSON object, so SQLite rows go through the same lenient parsing as JSON files
+fn row_to_json(row: &SqliteRow) -> Value {
+    let mut object = Map::new();
+    for column in row.columns() {
+        let i = column.ordinal();
+        let value = if let Ok(Some(n)) = row.try_get::<Option<i64>, _>(i) {
+            Value::from(n)
+        } else if let Ok(Some(n)) = row.try_get::<Option<f64>, _>(i) {
+            Value::from(n)
+        } else if let Ok(Some(s)) = row.try_get::<Option<String>, _>(i) {
+            Value::from(s)
+        } else {
+            Value::Null
+        };
+        object.insert(column.name().to_ascii_lowercase(), value);
+    }
+    Value::Object(object)
+}
+
+async fn read_sqlite_store(path: &Path) -> AppResult<LegacyStore> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await?;
+
+    let tables: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .fetch_all(&mut conn)
+            .await?;
+
+    let mut store = Map::new();
+    if tables.iter().any(|t| t == "webhooks") {
+        let rows = sqlx::query("SELECT * FROM webhooks")
+            .fetch_all(&mut conn)
+            .await?;
+        store.insert(
+            "webhooks".to_string(),
+            Value::Array(rows.iter().map(row_to_json).collect()),
+        );
+    }
+    if let Some(table) = HISTORY_TABLES
+        .iter()
+        .find(|name| tables.iter().any(|t| t == *name))
+    {
+        let rows = sqlx::query(&format!("SELECT * FROM {table}"))
+            .fetch_all(&mut conn)
+            .await?;
+        store.insert(
+            "upload_history".to_string(),
+            Value::Array(rows.iter().map(row_to_json).collect()),
+        );
+    }
+
+    serde_json::from_value(Value::Object(store)).map_err(|e| {
+        AppError::validation(
+            "path",
+            &format!("Unrecognised Python uploader database: {e}"),
+        )
+    })
+}
+
+/// Import webhooks and history from the Python uploader's database or JSON file.
+/// A folder is searched for its database file.
+pub async fn import_python_history(path: &str) -> AppResult<LegacyImportSummary> {
+    let mut path = InputValidator::resolve_user_path(path);
+    if path.is_dir() {
+        path = path.join(LEGACY_DATABASE_NAME);
+    }
+    if !path.is_file() {
+        return Err(AppError::validation(
+            "path",
+            &format!("{} does not exist", path.display()),
+        ));
+    }
+
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let store = if is_json {
+        parse_json_store(&tokio::fs::read_to_string(&path).await?)?
+    } else {
+        read_sqlite_store(&path).await?
+    };
+
+    let (webhooks, uploads) = store.into_records();
+    let summary = database::import_legacy_history(&webhooks, &uploads).await?;
+    log::info!(
+        "Imported Python uploader history from {}: {summary:?}",
+        path.display()
+    );
+
+    // The old hashes don't match ours; rehash so dedupe by content works
+    if let Err(e) = image_processor::migrate_legacy_file_hashes().await {
+        log::warn!("Failed to rehash imported history: {e}");
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_store_accepts_old_key_names() {
+        let store = parse_json_store(
+            r#"{
+                "webhooks": [{"id": 3, "webhook_name": "Photos", "webhook_url": "https://discord.com/api/webhooks/1/a"}],
+                "history": [
+                    {"path": "C:/VRChat/a.png", "hash": "d41d8cd9", "webhook_id": 3, "timestamp": 1700000000},
+                    {"file_path": "", "webhook_id": 3}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let (webhooks, uploads) = store.into_records();
+        assert_eq!(webhooks[0].legacy_id, Some(3));
+        assert_eq!(webhooks[0].name, "Photos");
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].file_hash.as_deref(), Some("d41d8cd9"));
+        assert_eq!(
+            uploads[0].uploaded_at.as_deref(),
+            Some("2023-11-14 22:13:20")
+        );
+        assert_eq!(uploads[0].status, "success");
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        assert_eq!(
+            normalize_timestamp(&Value::from("2024-05-01T10:00:00+02:00")).as_deref(),
+            Some("2024-05-01 08:00:00")
+        );
+        assert!(normalize_timestamp(&Value::from("2024-05-01 10:00:00.123456")).is_some());
+        assert_eq!(normalize_timestamp(&Value::from("yesterday")), None);
+        assert_eq!(normalize_timestamp(&Value::Null), None);
+    }
+}
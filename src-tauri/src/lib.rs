@@ -1,13 +1,30 @@
 #![allow(non_snake_case)]
 
+pub mod autostart;
 pub mod background_watcher;
+pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod database;
+pub mod deep_link;
+pub mod environment;
 pub mod errors;
+pub mod folder_scan;
+pub mod health_check;
 pub mod image_processor;
+pub mod legacy_import;
+pub mod logging;
 pub mod metadata_editor;
+pub mod metadata_report;
+pub mod photo_index;
+pub mod roundup;
 pub mod security;
+pub mod shell_integration;
 pub mod single_instance;
+pub mod tags;
+pub mod temp_manifest;
 pub mod test_helpers;
+pub mod thumbnail_cache;
+pub mod tray;
 pub mod uploader;
+pub mod window_state;
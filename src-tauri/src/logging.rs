@@ -0,0 +1,238 @@
+// File-based logging
+//
+// Release builds on Windows have no console, so everything logged also goes to
+// rotating files in the logs directory. The level comes from `log_level` in the
+// config and can be changed at runtime when the config is saved.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::errors::AppResult;
+use crate::security::redact_webhook_tokens;
+
+const LOG_FILE_NAME: &str = "uploader.log";
+/// Size at which the current file is rotated out
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one (uploader.1.log … uploader.4.log)
+const MAX_ROTATED_FILES: usize = 4;
+
+struct RotatingFile {
+    directory: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(directory: PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(directory.join(LOG_FILE_NAME))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            directory,
+            file,
+            size,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size + line.len() as u64 > MAX_LOG_FILE_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated_path(&self.directory, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.directory, index + 1))?;
+            }
+        }
+        fs::rename(
+            self.directory.join(LOG_FILE_NAME),
+            rotated_path(&self.directory, 1),
+        )?;
+
+        *self = Self::open(self.directory.clone())?;
+        Ok(())
+    }
+}
+
+/// `uploader.{index}.log`; index 0 is the current file
+fn rotated_path(directory: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        directory.join(LOG_FILE_NAME)
+    } else {
+        directory.join(format!("uploader.{index}.log"))
+    }
+}
+
+struct FileLogger {
+    console: env_logger::Logger,
+    file: Mutex<Option<RotatingFile>>,
+    /// Crate name prefix; other crates' debug/trace output is left out of the files
+    own_target: &'static str,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.level() <= log::Level::Info
+                || metadata.target().starts_with(self.own_target))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Webhook URLs end up in messages and errors; neither output gets the token
+        let message = record.args().to_string();
+        let message = redact_webhook_tokens(&message);
+        self.console.log(
+            &Record::builder()
+                .args(format_args!("{message}"))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+
+        let line = format!(
+            "{} [{:<5}] {}: {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            message
+        );
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(writer) = file.as_mut() {
+                // Nowhere left to report a failing log write
+                let _ = writer.write_line(&line);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(writer) = file.as_mut() {
+                let _ = writer.file.flush();
+            }
+        }
+    }
+}
+
+/// Parse a config `log_level`, falling back to info
+pub fn parse_level(level: &str) -> LevelFilter {
+    level.parse().unwrap_or(LevelFilter::Info)
+}
+
+/// Install the console + rotating file logger. Must run once, before anything logs.
+pub fn init() {
+    let level = crate::config::load_config()
+        .map(|c| parse_level(&c.log_level))
+        .unwrap_or(LevelFilter::Info);
+
+    let console = env_logger::Builder::from_default_env()
+        .filter_level(LevelFilter::Trace)
+        .build();
+    let file = crate::config::get_logs_directory()
+        .ok()
+        .and_then(|dir| RotatingFile::open(dir).ok());
+    let has_file = file.is_some();
+
+    let logger = FileLogger {
+        console,
+        file: Mutex::new(file),
+        own_target: module_path!().split("::").next().unwrap_or_default(),
+    };
+
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(level);
+    }
+    if !has_file {
+        log::warn!("Could not open log file, logging to console only");
+    }
+}
+
+/// Apply a changed `log_level` without restarting
+pub fn set_level(level: &str) {
+    log::set_max_level(parse_level(level));
+}
+
+/// Last `lines` lines across the current and rotated log files, oldest first
+pub fn read_recent_lines(lines: usize) -> AppResult<Vec<String>> {
+    read_recent_lines_in(&crate::config::get_logs_directory()?, lines)
+}
+
+fn read_recent_lines_in(directory: &Path, lines: usize) -> AppResult<Vec<String>> {
+    let mut collected: Vec<String> = Vec::new();
+
+    for index in 0..=MAX_ROTATED_FILES {
+        if collected.len() >= lines {
+            break;
+        }
+        let path = rotated_path(directory, index);
+        if !path.exists() {
+            continue;
+        }
+
+        let file_lines: Vec<String> = BufReader::new(File::open(&path)?)
+            .lines()
+            .map_while(Result::ok)
+            .collect();
+        let needed = lines - collected.len();
+        let start = file_lines.len().saturating_sub(needed);
+
+        // Older files go in front of what has been collected so far
+        let mut older = file_lines[start..].to_vec();
+        older.append(&mut collected);
+        collected = older;
+    }
+
+    Ok(collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_and_recent_lines() {
+        let dir = std::env::temp_dir().join(format!("vrcpu_logging_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut writer = RotatingFile::open(dir.clone()).unwrap();
+        writer.write_line("first\n").unwrap();
+        writer.write_line("second\n").unwrap();
+        writer.rotate().unwrap();
+        writer.write_line("third\n").unwrap();
+        writer.file.flush().unwrap();
+
+        assert!(rotated_path(&dir, 1).exists());
+        assert_eq!(
+            read_recent_lines_in(&dir, 2).unwrap(),
+            vec!["second".to_string(), "third".to_string()]
+        );
+        assert_eq!(read_recent_lines_in(&dir, 10).unwrap().len(), 3);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_parse_level_falls_back_to_info() {
+        assert_eq!(parse_level("debug"), LevelFilter::Debug);
+        assert_eq!(parse_level("verbose"), LevelFilter::Info);
+    }
+}
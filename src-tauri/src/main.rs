@@ -4,22 +4,39 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Emitter, Manager,
 };
 
+mod autostart;
 pub mod background_watcher;
+mod cli;
 mod commands;
 mod config;
 mod database;
+mod deep_link;
+mod environment;
 mod errors;
+mod folder_scan;
+mod health_check;
 mod image_processor;
+mod legacy_import;
+mod logging;
 mod metadata_editor;
+mod metadata_report;
+mod photo_index;
+mod roundup;
 mod security;
+mod shell_integration;
 mod single_instance;
+mod tags;
+mod temp_manifest;
+mod thumbnail_cache;
+mod tray;
 
 mod uploader;
+mod window_state;
 
 #[cfg(test)]
 pub mod test_helpers;
@@ -30,13 +47,50 @@ use commands::*;
 type ProgressState = Arc<Mutex<HashMap<String, UploadProgress>>>;
 
 fn main() {
-    // Initialize logging
-    env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info)
-        .init();
+    // Initialize logging (console + rotating files in the logs directory)
+    logging::init();
 
     log::info!("Starting VRChat Photo Uploader");
 
+    // Installer hooks toggle the Explorer context-menu entry without starting the UI
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == shell_integration::REGISTER_FLAG) {
+        let code = match shell_integration::register_context_menu() {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("Failed to register context menu: {e}");
+                1
+            }
+        };
+        std::process::exit(code);
+    }
+    if args.iter().any(|a| a == shell_integration::UNREGISTER_FLAG) {
+        let code = match shell_integration::unregister_context_menu() {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("Failed to unregister context menu: {e}");
+                1
+            }
+        };
+        std::process::exit(code);
+    }
+
+    // Headless upload mode: no window, progress on stdout, exit code for scripts
+    if let Some(parsed) = cli::parse_args(&args) {
+        let code = match parsed {
+            Ok(upload) => {
+                let mut context = tauri::generate_context!();
+                context.config_mut().app.windows.clear();
+                cli::run(upload, context)
+            }
+            Err(message) => {
+                eprintln!("{message}\n{}", cli::USAGE);
+                cli::EXIT_USAGE
+            }
+        };
+        std::process::exit(code);
+    }
+
     // Check for single instance BEFORE starting Tauri
     if single_instance::check_single_instance().is_err() {
         log::info!("Application is already running. Exiting this instance.");
@@ -46,6 +100,8 @@ fn main() {
     // Register cleanup handlers
     single_instance::register_cleanup_handler();
 
+    let start_minimized = autostart::launched_minimized(&args);
+
     // Migrate configuration if needed
     if let Err(e) = config::migrate_config() {
         log::error!("Failed to migrate configuration: {e}");
@@ -53,12 +109,14 @@ fn main() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_os::init())
         .manage(ProgressState::new(Mutex::new(HashMap::new())))
         .manage(Mutex::new(background_watcher::BackgroundWatcher::new()))
+        .manage(uploader::discord_client::DiscordConnectionPool::new())
         .invoke_handler(tauri::generate_handler![
             get_webhooks,
             add_webhook,
@@ -66,23 +124,62 @@ fn main() {
             delete_webhook,
             toggle_webhook_pin,
             upload_images,
+            preview_discord_message,
+            prepare_upload_groups,
+            estimate_groups,
+            validate_upload_plan,
+            upload_custom_groups,
             get_upload_progress,
             retry_failed_upload,
             retry_failed_group,
+            export_session_log,
+            export_metadata_report,
+            import_python_history,
+            get_uploaded_message_info,
+            get_upload_status_for_files,
+            delete_uploaded_message,
+            edit_uploaded_message,
+            list_failed_groups,
+            retry_persisted_group,
+            delete_failed_group,
             get_image_metadata,
             get_image_metadata_with_source,
             update_image_metadata,
+            get_metadata_edits,
+            undo_metadata_edit,
+            verify_embedded_metadata,
             get_app_config,
             save_app_config,
             compress_image,
             cleanup_old_data,
+            get_recent_logs,
+            take_pending_files,
+            get_context_menu_integration,
+            set_context_menu_integration,
+            set_auto_launch,
             get_file_hash,
             cancel_upload_session,
             get_image_info,
             get_image_info_batch,
             generate_thumbnail,
+            clear_thumbnail_cache,
+            scan_folder,
+            rotate_image,
+            crop_image,
             generate_thumbnails_batch,
             should_compress_image,
+            check_upload_disk_space,
+            get_temp_usage,
+            get_presets,
+            save_preset,
+            delete_preset,
+            list_profiles,
+            save_profile,
+            switch_profile,
+            delete_profile,
+            detect_environment,
+            run_health_check,
+            get_performance_metrics,
             cleanup_temp_files,
             shell_open,
             debug_extract_metadata,
@@ -93,9 +190,40 @@ fn main() {
             get_discord_user_mappings,
             add_discord_user_mapping,
             update_discord_user_mapping,
-            delete_discord_user_mapping
+            delete_discord_user_mapping,
+            get_collections,
+            create_collection,
+            update_collection,
+            delete_collection,
+            get_collection_items,
+            add_to_collection,
+            remove_from_collection,
+            tag_files,
+            untag_files,
+            get_files_by_tag,
+            get_file_tags,
+            get_all_tags,
+            auto_tag_files,
+            search_photos,
+            get_player_stats,
+            get_world_stats,
+            set_photo_description,
+            get_photo_descriptions,
+            set_webhook_message_flags,
+            get_session_templates,
+            save_session_template,
+            delete_session_template,
+            start_session_from_template,
+            get_webhook_forum_tags,
+            add_webhook_forum_tag,
+            delete_webhook_forum_tag,
+            diagnose_forum_webhook,
+            set_webhook_upload_limit,
+            set_webhook_timestamp_style,
+            get_database_status,
+            get_upload_session_details
         ])
-        .setup(|app| {
+        .setup(move |app| {
             log::info!("Setting up application...");
 
             // Register updater plugin
@@ -153,6 +281,8 @@ fn main() {
             // Build system tray menu
             let upload_files =
                 MenuItem::with_id(app, "upload_files", "📁 Upload Files", true, None::<&str>)?;
+            // Filled from the database once it's initialized
+            let upload_to = Submenu::with_id(app, "upload_to", "📤 Upload to", true)?;
             let open_vrchat = MenuItem::with_id(
                 app,
                 "open_vrchat_folder",
@@ -186,6 +316,7 @@ fn main() {
                 app,
                 &[
                     &upload_files,
+                    &upload_to,
                     &open_vrchat,
                     &sep1,
                     &show,
@@ -198,6 +329,7 @@ fn main() {
                     &quit,
                 ],
             )?;
+            app.manage(tray::TrayWebhookMenu(upload_to));
 
             // Build tray icon
             let _tray = TrayIconBuilder::new()
@@ -289,7 +421,22 @@ fn main() {
                         single_instance::cleanup_lock_file();
                         app.exit(0);
                     }
-                    _ => {}
+                    id => {
+                        let Some(webhook_id) = tray::parse_webhook_menu_id(id) else {
+                            return;
+                        };
+                        if let Some(window) = app.get_webview_window("main") {
+                            if let Err(e) = window.emit("upload-to-webhook-request", webhook_id) {
+                                log::error!("Failed to emit upload to webhook event: {e}");
+                            }
+                            if let Err(e) = window.show() {
+                                log::error!("Failed to show window: {e}");
+                            }
+                            if let Err(e) = window.set_focus() {
+                                log::error!("Failed to focus window: {e}");
+                            }
+                        }
+                    }
                 })
                 .on_tray_icon_event(|tray, event| match event {
                     TrayIconEvent::Click {
@@ -331,6 +478,23 @@ fn main() {
                 })
                 .build(app)?;
 
+            // vrcphoto:// links: macOS delivers them as events, Windows/Linux launch the exe
+            // with the URL, which the signal checker below picks up
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                #[cfg(any(target_os = "windows", target_os = "linux"))]
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("Failed to register vrcphoto:// scheme: {e}");
+                }
+                let deep_link_handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    single_instance::handle_deep_links(
+                        &deep_link_handle,
+                        event.urls().iter().map(|u| u.to_string()).collect(),
+                    );
+                });
+            }
+
             // Start the signal checker for single instance
             single_instance::start_signal_checker(app.handle().clone());
 
@@ -339,6 +503,14 @@ fn main() {
                 match database::init_database().await {
                     Ok(()) => {
                         log::info!("Database initialized successfully");
+                        tray::refresh_webhook_menu(app.handle()).await;
+                        roundup::start_scheduler();
+                        tauri::async_runtime::spawn_blocking(thumbnail_cache::evict);
+                        tauri::async_runtime::spawn(async {
+                            if let Err(e) = image_processor::migrate_legacy_file_hashes().await {
+                                log::warn!("File hash migration failed: {e}");
+                            }
+                        });
                     }
                     Err(e) => {
                         log::error!("Failed to initialize database: {e}");
@@ -353,6 +525,24 @@ fn main() {
                 if let Err(e) = window.set_title(&title) {
                     log::warn!("Failed to set window title: {e}");
                 }
+
+                window_state::restore(&window);
+
+                // Started on login: stay in the tray
+                if start_minimized {
+                    log::info!("Started minimized to tray");
+                    if let Err(e) = window.hide() {
+                        log::warn!("Failed to hide window: {e}");
+                    }
+                }
+            }
+
+            // Keep the start-on-login entry pointing at this exe after updates or moves
+            #[cfg(target_os = "windows")]
+            if config::load_config().is_ok_and(|c| c.auto_launch) {
+                if let Err(e) = autostart::enable_auto_launch() {
+                    log::warn!("Failed to refresh start-on-login entry: {e}");
+                }
             }
 
             // Schedule auto-cleanup task - but wait for database to be ready
@@ -418,6 +608,9 @@ fn main() {
         })
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if let Some(webview_window) = window.get_webview_window(window.label()) {
+                    window_state::save(&webview_window);
+                }
                 // Hide to tray instead of closing
                 let _ = window.hide();
                 api.prevent_close();
@@ -425,8 +618,16 @@ fn main() {
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app_handle, event| {
-            if let tauri::RunEvent::ExitRequested { .. } = event {
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::ExitRequested { api, .. } = event {
+                if let Some(window) = app_handle.get_webview_window("main") {
+                    window_state::save(&window);
+                }
+                // Let running uploads finish their chunk or checkpoint first
+                if uploader::shutdown::begin(app_handle) {
+                    api.prevent_exit();
+                    return;
+                }
                 single_instance::cleanup_lock_file();
             }
         });
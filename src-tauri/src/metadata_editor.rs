@@ -1,12 +1,24 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::commands::ImageMetadata;
 use crate::errors::{AppError, AppResult};
+use crate::image_processor::{self, MetadataSource};
 use crate::security::InputValidator;
+use crate::{config, database};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MetadataEditResult {
+    /// Undo log entry; missing if the edit couldn't be recorded
+    pub edit_id: Option<i64>,
+    pub output_path: String,
+}
 
 /// Embed metadata into a PNG file using VRCX-style JSON format
-pub async fn embed_metadata(file_path: &str, metadata: ImageMetadata) -> AppResult<String> {
+pub async fn embed_metadata(
+    file_path: &str,
+    metadata: ImageMetadata,
+) -> AppResult<MetadataEditResult> {
     // Validate input
     InputValidator::validate_image_file(file_path)?;
 
@@ -27,20 +39,7 @@ pub async fn embed_metadata(file_path: &str, metadata: ImageMetadata) -> AppResu
     let extension = path.extension().unwrap_or_default().to_string_lossy();
     let output_path = parent.join(format!("{stem}_Modified.{extension}"));
 
-    // Check if output file already exists and try to remove it
-    if output_path.exists() {
-        log::info!(
-            "Output file already exists, attempting to remove: {}",
-            output_path.display()
-        );
-        match std::fs::remove_file(&output_path) {
-            Ok(_) => log::info!("Successfully removed existing file"),
-            Err(e) => {
-                log::error!("Failed to remove existing file: {e}");
-                return Err(AppError::Io(e));
-            }
-        }
-    }
+    let backup_path = back_up_existing_output(&output_path)?;
 
     // Check if parent directory is writable
     if let Err(e) = std::fs::metadata(parent) {
@@ -63,7 +62,259 @@ pub async fn embed_metadata(file_path: &str, metadata: ImageMetadata) -> AppResu
         output_path.display()
     );
 
-    Ok(output_path.to_string_lossy().to_string())
+    Ok(record_edit(file_path, &output_path, backup_path).await)
+}
+
+/// Write metadata to a `photo.png.json` sidecar next to the image instead of
+/// creating a modified copy. The image itself is left untouched.
+pub async fn export_sidecar_metadata(
+    file_path: &str,
+    metadata: ImageMetadata,
+) -> AppResult<MetadataEditResult> {
+    InputValidator::validate_image_file(file_path)?;
+    if !Path::new(file_path).exists() {
+        return Err(AppError::file_not_found(file_path));
+    }
+
+    let vrchat_metadata = create_vrchat_metadata_json(&metadata)?;
+    let output_path = image_processor::sidecar_path(file_path);
+    let backup_path = back_up_existing_output(&output_path)?;
+    fs::write(&output_path, vrchat_metadata)?;
+
+    log::info!(
+        "Wrote sidecar metadata for {} -> {}",
+        file_path,
+        output_path.display()
+    );
+
+    Ok(record_edit(file_path, &output_path, backup_path).await)
+}
+
+/// Move an existing output out of the way so the edit can be undone
+fn back_up_existing_output(output_path: &Path) -> AppResult<Option<PathBuf>> {
+    if !output_path.exists() {
+        return Ok(None);
+    }
+    log::info!(
+        "Output file already exists, moving it to the metadata trash: {}",
+        output_path.display()
+    );
+    let backup = move_to_trash(output_path, &config::get_metadata_trash_directory()?)
+        .inspect_err(|e| log::error!("Failed to move existing file to trash: {e}"))?;
+    Ok(Some(backup))
+}
+
+async fn record_edit(
+    source_path: &str,
+    output_path: &Path,
+    backup_path: Option<PathBuf>,
+) -> MetadataEditResult {
+    let output_path = output_path.to_string_lossy().to_string();
+    let backup_path = backup_path.map(|p| p.to_string_lossy().to_string());
+    let edit_id =
+        match database::record_metadata_edit(source_path, &output_path, backup_path.as_deref())
+            .await
+        {
+            Ok(id) => Some(id),
+            Err(e) => {
+                log::warn!("Failed to record metadata edit for undo: {e}");
+                None
+            }
+        };
+
+    MetadataEditResult {
+        edit_id,
+        output_path,
+    }
+}
+
+/// Roll back a metadata edit: the file it wrote is removed and whatever it replaced
+/// is restored. Returns the output path.
+pub async fn undo_metadata_edit(edit_id: i64) -> AppResult<String> {
+    let edit = database::get_metadata_edit(edit_id).await?;
+    if edit.undone_at.is_some() {
+        return Err(AppError::validation(
+            "edit_id",
+            "This edit has already been undone",
+        ));
+    }
+    if database::has_newer_metadata_edit(edit.id, &edit.output_path).await? {
+        return Err(AppError::validation(
+            "edit_id",
+            "A newer edit wrote to the same file; undo that one first",
+        ));
+    }
+
+    restore_output(
+        Path::new(&edit.output_path),
+        edit.backup_path.as_deref().map(Path::new),
+    )?;
+    database::mark_metadata_edit_undone(edit.id).await?;
+
+    log::info!("Undid metadata edit {} ({})", edit.id, edit.output_path);
+    Ok(edit.output_path)
+}
+
+/// A field that didn't read back the way it was embedded
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct MetadataDifference {
+    pub field: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MetadataVerification {
+    pub verified: bool,
+    /// Anything other than `Vrcx` means the embedded Description chunk didn't survive
+    pub source: MetadataSource,
+    pub differences: Vec<MetadataDifference>,
+}
+
+/// Re-read a file's metadata after an embed and compare it with what was requested,
+/// catching writes that silently lost the chunk or some of its fields
+pub async fn verify_embedded_metadata(
+    file_path: &str,
+    expected: &ImageMetadata,
+) -> AppResult<MetadataVerification> {
+    let extracted = image_processor::extract_metadata_with_source(
+        file_path,
+        image_processor::configured_screenshot_timezone(),
+    )
+    .await?;
+    let actual = match extracted.source {
+        MetadataSource::Vrcx => extracted.metadata.as_ref(),
+        _ => None,
+    };
+    let differences = diff_metadata(expected, actual);
+
+    if !differences.is_empty() {
+        log::warn!(
+            "Embedded metadata in {file_path} differs from the request in {} field(s)",
+            differences.len()
+        );
+    }
+
+    Ok(MetadataVerification {
+        verified: differences.is_empty(),
+        source: extracted.source,
+        differences,
+    })
+}
+
+/// Field-by-field comparison. Values are compared the way the extractor reads them
+/// back: trimmed, with empty strings counting as missing.
+fn diff_metadata(
+    expected: &ImageMetadata,
+    actual: Option<&ImageMetadata>,
+) -> Vec<MetadataDifference> {
+    let expected_fields = metadata_fields(Some(expected));
+    let actual_fields = metadata_fields(actual);
+
+    let mut differences: Vec<MetadataDifference> = expected_fields
+        .into_iter()
+        .zip(actual_fields)
+        .filter(|((_, expected), (_, actual))| expected != actual)
+        .map(|((field, expected), (_, actual))| MetadataDifference {
+            field: field.to_string(),
+            expected,
+            actual,
+        })
+        .collect();
+
+    let expected_players = player_labels(Some(expected));
+    let actual_players = player_labels(actual);
+    for player in expected_players
+        .iter()
+        .filter(|p| !actual_players.contains(p))
+    {
+        differences.push(MetadataDifference {
+            field: "players".to_string(),
+            expected: Some(player.clone()),
+            actual: None,
+        });
+    }
+    for player in actual_players
+        .iter()
+        .filter(|p| !expected_players.contains(p))
+    {
+        differences.push(MetadataDifference {
+            field: "players".to_string(),
+            expected: None,
+            actual: Some(player.clone()),
+        });
+    }
+
+    differences
+}
+
+fn metadata_fields(metadata: Option<&ImageMetadata>) -> [(&'static str, Option<String>); 7] {
+    let author = metadata.and_then(|m| m.author.as_ref());
+    let world = metadata.and_then(|m| m.world.as_ref());
+    let avatar = metadata.and_then(|m| m.avatar.as_ref());
+    [
+        (
+            "author.display_name",
+            author.and_then(|a| normalized(&a.display_name)),
+        ),
+        ("author.id", author.and_then(|a| normalized(&a.id))),
+        ("world.name", world.and_then(|w| normalized(&w.name))),
+        ("world.id", world.and_then(|w| normalized(&w.id))),
+        (
+            "world.instance_id",
+            world.and_then(|w| normalized(&w.instance_id)),
+        ),
+        ("avatar.name", avatar.and_then(|a| normalized(&a.name))),
+        ("avatar.id", avatar.and_then(|a| normalized(&a.id))),
+    ]
+}
+
+fn player_labels(metadata: Option<&ImageMetadata>) -> Vec<String> {
+    metadata
+        .map(|m| {
+            m.players
+                .iter()
+                .map(|p| format!("{} ({})", p.display_name.trim(), p.id.trim()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn normalized(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Move a file into the trash directory under a unique name
+fn move_to_trash(file_path: &Path, trash_dir: &Path) -> AppResult<PathBuf> {
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    let backup_path = trash_dir.join(format!("{}_{file_name}", uuid::Uuid::new_v4()));
+    move_file(file_path, &backup_path)?;
+    Ok(backup_path)
+}
+
+fn restore_output(output_path: &Path, backup_path: Option<&Path>) -> AppResult<()> {
+    if let Some(backup) = backup_path {
+        if !backup.exists() {
+            return Err(AppError::file_not_found(&backup.to_string_lossy()));
+        }
+    }
+    if output_path.exists() {
+        fs::remove_file(output_path)?;
+    }
+    if let Some(backup) = backup_path {
+        move_file(backup, output_path)?;
+    }
+    Ok(())
+}
+
+/// Rename, falling back to copy + delete when the trash is on another drive
+fn move_file(from: &Path, to: &Path) -> AppResult<()> {
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
 }
 
 fn create_vrchat_metadata_json(metadata: &ImageMetadata) -> AppResult<String> {
@@ -112,6 +363,20 @@ fn create_vrchat_metadata_json(metadata: &ImageMetadata) -> AppResult<String> {
         json_obj.insert("world".to_string(), serde_json::Value::Object(world_obj));
     }
 
+    // Add avatar info
+    if let Some(ref avatar) = metadata.avatar {
+        let mut avatar_obj = serde_json::Map::new();
+        avatar_obj.insert(
+            "name".to_string(),
+            serde_json::Value::String(avatar.name.clone()),
+        );
+        avatar_obj.insert(
+            "id".to_string(),
+            serde_json::Value::String(avatar.id.clone()),
+        );
+        json_obj.insert("avatar".to_string(), serde_json::Value::Object(avatar_obj));
+    }
+
     // Add players array
     let players_array: Vec<serde_json::Value> = metadata
         .players
@@ -344,7 +609,7 @@ fn calculate_crc(data: &[u8]) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::{AuthorInfo, PlayerInfo, WorldInfo};
+    use crate::commands::{AuthorInfo, AvatarInfo, PlayerInfo, WorldInfo};
     use crate::test_helpers::{create_minimal_png, create_png_with_metadata};
 
     // -----------------------------------------------------------------------
@@ -373,6 +638,10 @@ mod tests {
                     id: "usr_bob".to_string(),
                 },
             ],
+            avatar: Some(AvatarInfo {
+                name: "Robo Cat".to_string(),
+                id: "avtr_test789".to_string(),
+            }),
         };
 
         let json_str = create_vrchat_metadata_json(&metadata).expect("Should produce valid JSON");
@@ -390,6 +659,9 @@ mod tests {
         assert_eq!(parsed["world"]["id"], "wrld_test456");
         assert_eq!(parsed["world"]["instanceId"], "12345~private(usr_test123)");
 
+        assert_eq!(parsed["avatar"]["name"], "Robo Cat");
+        assert_eq!(parsed["avatar"]["id"], "avtr_test789");
+
         let players = parsed["players"]
             .as_array()
             .expect("players should be array");
@@ -404,6 +676,7 @@ mod tests {
             author: None,
             world: None,
             players: vec![],
+            avatar: None,
         };
 
         let json_str = create_vrchat_metadata_json(&metadata).expect("Should produce valid JSON");
@@ -414,6 +687,7 @@ mod tests {
         assert_eq!(parsed["version"], 2);
         assert!(parsed.get("author").is_none());
         assert!(parsed.get("world").is_none());
+        assert!(parsed.get("avatar").is_none());
         assert_eq!(
             parsed["players"].as_array().unwrap().len(),
             0,
@@ -434,6 +708,7 @@ mod tests {
                 display_name: "Solo".to_string(),
                 id: "usr_solo".to_string(),
             }],
+            avatar: None,
         };
 
         let json_str = create_vrchat_metadata_json(&metadata).unwrap();
@@ -453,6 +728,7 @@ mod tests {
             }),
             world: None,
             players: vec![],
+            avatar: None,
         };
 
         let json_str = create_vrchat_metadata_json(&metadata).unwrap();
@@ -488,6 +764,7 @@ mod tests {
                     id: "usr_quotes".to_string(),
                 },
             ],
+            avatar: None,
         };
 
         let json_str =
@@ -630,6 +907,102 @@ mod tests {
         );
     }
 
+    // -----------------------------------------------------------------------
+    // diff_metadata tests
+    // -----------------------------------------------------------------------
+
+    fn sample_metadata() -> ImageMetadata {
+        ImageMetadata {
+            author: Some(AuthorInfo {
+                display_name: "TestUser".to_string(),
+                id: "usr_test".to_string(),
+            }),
+            world: Some(WorldInfo {
+                name: "Test World".to_string(),
+                id: "wrld_test".to_string(),
+                instance_id: "1~public".to_string(),
+            }),
+            players: vec![PlayerInfo {
+                display_name: "Alice".to_string(),
+                id: "usr_alice".to_string(),
+            }],
+            avatar: Some(AvatarInfo {
+                name: " Robo Cat ".to_string(),
+                id: "avtr_test".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_diff_metadata_identical() {
+        let expected = sample_metadata();
+        let mut actual = sample_metadata();
+        // The extractor trims names, which isn't a difference
+        actual.avatar.as_mut().unwrap().name = "Robo Cat".to_string();
+        assert!(diff_metadata(&expected, Some(&actual)).is_empty());
+    }
+
+    #[test]
+    fn test_diff_metadata_reports_changed_and_missing_fields() {
+        let expected = sample_metadata();
+        let mut actual = sample_metadata();
+        actual.world.as_mut().unwrap().name = "Other World".to_string();
+        actual.players.clear();
+
+        let differences = diff_metadata(&expected, Some(&actual));
+        assert_eq!(differences.len(), 2);
+        assert_eq!(differences[0].field, "world.name");
+        assert_eq!(differences[0].actual.as_deref(), Some("Other World"));
+        assert_eq!(differences[1].field, "players");
+        assert_eq!(
+            differences[1].expected.as_deref(),
+            Some("Alice (usr_alice)")
+        );
+
+        // Nothing read back at all: every requested field is missing
+        assert_eq!(diff_metadata(&expected, None).len(), 8);
+    }
+
+    // -----------------------------------------------------------------------
+    // undo helper tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_restore_output_brings_back_replaced_file() {
+        let dir = std::env::temp_dir().join(format!("metadata_undo_{}", uuid::Uuid::new_v4()));
+        let trash = dir.join("trash");
+        fs::create_dir_all(&trash).unwrap();
+        let output = dir.join("photo_Modified.png");
+
+        fs::write(&output, b"first edit").unwrap();
+        let backup = move_to_trash(&output, &trash).unwrap();
+        assert!(!output.exists());
+        fs::write(&output, b"second edit").unwrap();
+
+        restore_output(&output, Some(&backup)).unwrap();
+        assert_eq!(fs::read(&output).unwrap(), b"first edit");
+        assert!(!backup.exists());
+
+        // An edit that created its output just removes it
+        restore_output(&output, None).unwrap();
+        assert!(!output.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restore_output_keeps_file_when_backup_is_missing() {
+        let dir = std::env::temp_dir().join(format!("metadata_undo_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("photo_Modified.png");
+        fs::write(&output, b"edit").unwrap();
+
+        assert!(restore_output(&output, Some(&dir.join("gone.png"))).is_err());
+        assert!(output.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     // -----------------------------------------------------------------------
     // calculate_crc tests
     // -----------------------------------------------------------------------
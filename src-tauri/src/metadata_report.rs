@@ -0,0 +1,212 @@
+// Metadata report export
+//
+// Extracts metadata for a set of photos and writes one row per photo (file, world,
+// players, timestamp, size) as CSV or JSON, for cataloguing event photos in a
+// spreadsheet or another tool.
+
+use std::path::Path;
+
+use chrono::{Local, TimeZone};
+use serde::Serialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::image_processor;
+use crate::security::InputValidator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn parse(format: &str) -> AppResult<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(AppError::validation(
+                "format",
+                &format!("Unsupported report format '{other}' (expected csv or json)"),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportRow {
+    pub file: String,
+    pub world_name: Option<String>,
+    pub world_id: Option<String>,
+    pub author: Option<String>,
+    pub players: Vec<String>,
+    /// Local time the photo was taken, from the VRChat file name or the file's
+    /// modified time
+    pub timestamp: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// What ended up in the report
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSummary {
+    pub rows: usize,
+    /// Files that couldn't be read and were left out
+    pub skipped: Vec<String>,
+}
+
+const CSV_HEADER: &str = "file,world_name,world_id,author,players,timestamp,size_bytes";
+
+async fn build_row(file_path: &str, timezone: Option<chrono_tz::Tz>) -> AppResult<ReportRow> {
+    let path = InputValidator::resolve_file_path(file_path)?;
+    let file_path = path.to_string_lossy().to_string();
+    let file_metadata = std::fs::metadata(&path)?;
+
+    let taken_at = image_processor::get_timestamp_from_filename_in_timezone(&file_path, timezone)
+        .or_else(|| {
+            file_metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+        });
+
+    let mut row = ReportRow {
+        timestamp: taken_at
+            .and_then(|ts| Local.timestamp_opt(ts, 0).single())
+            .map(|t| t.to_rfc3339()),
+        size_bytes: file_metadata.len(),
+        file: file_path.clone(),
+        ..ReportRow::default()
+    };
+
+    // Photos without metadata still get a row with just the file details
+    if let Some(metadata) = image_processor::extract_metadata(&file_path, timezone).await? {
+        row.world_name = metadata.world.as_ref().map(|w| w.name.clone());
+        row.world_id = metadata.world.as_ref().map(|w| w.id.clone());
+        row.author = metadata.author.map(|a| a.display_name);
+        row.players = metadata
+            .players
+            .into_iter()
+            .map(|p| p.display_name)
+            .collect();
+    }
+    Ok(row)
+}
+
+/// Quote a CSV field when needed. Fields starting with a formula character get a
+/// leading apostrophe so spreadsheets show player names like "=Bob" as text.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+pub fn render_csv(rows: &[ReportRow]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push_str("\r\n");
+    for row in rows {
+        let fields = [
+            row.file.as_str(),
+            row.world_name.as_deref().unwrap_or(""),
+            row.world_id.as_deref().unwrap_or(""),
+            row.author.as_deref().unwrap_or(""),
+            &row.players.join("; "),
+            row.timestamp.as_deref().unwrap_or(""),
+            &row.size_bytes.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+pub fn render_report(rows: &[ReportRow], format: ReportFormat) -> AppResult<String> {
+    Ok(match format {
+        ReportFormat::Csv => render_csv(rows),
+        ReportFormat::Json => serde_json::to_string_pretty(rows)?,
+    })
+}
+
+/// Build the report for `file_paths` and write it to `output_path`
+pub async fn export_metadata_report(
+    file_paths: &[String],
+    format: ReportFormat,
+    output_path: &Path,
+) -> AppResult<ReportSummary> {
+    let mut rows = Vec::with_capacity(file_paths.len());
+    let mut skipped = Vec::new();
+    let timezone = image_processor::configured_screenshot_timezone();
+    for file_path in file_paths {
+        match build_row(file_path, timezone).await {
+            Ok(row) => rows.push(row),
+            Err(e) => {
+                log::warn!("Leaving {file_path} out of the metadata report: {e}");
+                skipped.push(file_path.clone());
+            }
+        }
+    }
+
+    if let Some(parent) = output_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    std::fs::write(output_path, render_report(&rows, format)?)?;
+    log::info!(
+        "Exported metadata report for {} photo(s) to {}",
+        rows.len(),
+        output_path.display()
+    );
+
+    Ok(ReportSummary {
+        rows: rows.len(),
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_csv_escapes_fields() {
+        let rows = vec![ReportRow {
+            file: "C:\\Photos\\shot.png".to_string(),
+            world_name: Some("The \"Great\" Pug, Remastered".to_string()),
+            world_id: Some("wrld_123".to_string()),
+            author: None,
+            players: vec!["Alice".to_string(), "=Bob".to_string()],
+            timestamp: None,
+            size_bytes: 1024,
+        }];
+
+        let csv = render_csv(&rows);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "C:\\Photos\\shot.png,\"The \"\"Great\"\" Pug, Remastered\",wrld_123,,Alice; =Bob,,1024"
+        );
+    }
+
+    #[test]
+    fn test_csv_field_neutralises_formulas() {
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(csv_field("@Bob"), "'@Bob");
+        assert_eq!(csv_field("plain"), "plain");
+    }
+
+    #[test]
+    fn test_parse_report_format() {
+        assert_eq!(ReportFormat::parse("CSV").unwrap(), ReportFormat::Csv);
+        assert_eq!(ReportFormat::parse("json").unwrap(), ReportFormat::Json);
+        assert!(ReportFormat::parse("xml").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+// Photo metadata index
+//
+// Every time metadata is extracted from a photo it's recorded in `photo_metadata`
+// and the `photo_search` FTS5 table, so photos can be searched by world, player or
+// file name without re-reading the files. Indexing is best-effort: failures (or no
+// database, as in tests) only cost the index entry.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::commands::ImageMetadata;
+use crate::database::{self, IndexedPhoto, PhotoMetadataRecord};
+use crate::image_processor;
+
+/// Results returned by a search
+pub const SEARCH_RESULT_LIMIT: i64 = 500;
+/// Worlds listed per player in player stats
+const PLAYER_TOP_WORLDS: usize = 5;
+
+pub async fn index_metadata(
+    file_path: &str,
+    metadata: &ImageMetadata,
+    timezone: Option<chrono_tz::Tz>,
+) {
+    let file_name = Path::new(file_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let taken_at = image_processor::get_timestamp_from_filename_in_timezone(file_path, timezone)
+        .or_else(|| {
+            std::fs::metadata(file_path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+        });
+
+    let record = PhotoMetadataRecord {
+        file_path,
+        file_name: &file_name,
+        world_name: metadata.world.as_ref().map(|w| w.name.as_str()),
+        world_id: metadata.world.as_ref().map(|w| w.id.as_str()),
+        author_name: metadata.author.as_ref().map(|a| a.display_name.as_str()),
+        players: metadata
+            .players
+            .iter()
+            .map(|p| p.display_name.clone())
+            .collect(),
+        taken_at,
+    };
+
+    if let Err(e) = database::upsert_photo_metadata(&record).await {
+        log::debug!("Not indexing metadata for {file_path}: {e}");
+    }
+}
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix, in any
+/// column. Words are quoted so FTS5 syntax characters in names can't break the query.
+pub fn build_search_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// A world and how many photos were taken there
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorldCount {
+    pub world_name: String,
+    pub photo_count: usize,
+}
+
+/// How often a player shows up in the user's photos
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerStats {
+    pub display_name: String,
+    pub photo_count: usize,
+    /// Worlds most photographed with this player, most photos first
+    pub top_worlds: Vec<WorldCount>,
+    /// Unix seconds of the first and last photo with them
+    pub first_seen: Option<i64>,
+    pub last_seen: Option<i64>,
+}
+
+fn sorted_world_counts(counts: HashMap<String, usize>, limit: usize) -> Vec<WorldCount> {
+    let mut worlds: Vec<WorldCount> = counts
+        .into_iter()
+        .map(|(world_name, photo_count)| WorldCount {
+            world_name,
+            photo_count,
+        })
+        .collect();
+    worlds.sort_by(|a, b| {
+        b.photo_count
+            .cmp(&a.photo_count)
+            .then_with(|| a.world_name.cmp(&b.world_name))
+    });
+    worlds.truncate(limit);
+    worlds
+}
+
+/// Aggregate co-appearances per player, most photographed first. The photo's
+/// author is skipped, since they're in (or behind) every one of their photos.
+pub fn player_stats(photos: &[IndexedPhoto]) -> Vec<PlayerStats> {
+    struct Acc {
+        photo_count: usize,
+        worlds: HashMap<String, usize>,
+        first_seen: Option<i64>,
+        last_seen: Option<i64>,
+    }
+
+    let mut players: HashMap<&str, Acc> = HashMap::new();
+    for photo in photos {
+        let mut seen_in_photo = Vec::new();
+        for name in &photo.players {
+            if photo.author_name.as_deref() == Some(name.as_str()) || seen_in_photo.contains(&name)
+            {
+                continue;
+            }
+            seen_in_photo.push(name);
+
+            let acc = players.entry(name).or_insert_with(|| Acc {
+                photo_count: 0,
+                worlds: HashMap::new(),
+                first_seen: None,
+                last_seen: None,
+            });
+            acc.photo_count += 1;
+            if let Some(world) = &photo.world_name {
+                *acc.worlds.entry(world.clone()).or_default() += 1;
+            }
+            if let Some(taken_at) = photo.taken_at {
+                acc.first_seen = Some(acc.first_seen.map_or(taken_at, |t| t.min(taken_at)));
+                acc.last_seen = Some(acc.last_seen.map_or(taken_at, |t| t.max(taken_at)));
+            }
+        }
+    }
+
+    let mut stats: Vec<PlayerStats> = players
+        .into_iter()
+        .map(|(name, acc)| PlayerStats {
+            display_name: name.to_string(),
+            photo_count: acc.photo_count,
+            top_worlds: sorted_world_counts(acc.worlds, PLAYER_TOP_WORLDS),
+            first_seen: acc.first_seen,
+            last_seen: acc.last_seen,
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.photo_count
+            .cmp(&a.photo_count)
+            .then_with(|| a.display_name.cmp(&b.display_name))
+    });
+    stats
+}
+
+/// Photos taken in a world
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorldStats {
+    pub world_name: String,
+    pub world_id: Option<String>,
+    /// VRChat website page for the world
+    pub world_url: Option<String>,
+    pub photo_count: usize,
+    /// Unix seconds of the first and last photo there
+    pub first_photo: Option<i64>,
+    pub last_photo: Option<i64>,
+}
+
+pub fn world_page_url(world_id: &str) -> String {
+    format!("https://vrchat.com/home/world/{world_id}")
+}
+
+/// Aggregate photos per world, most photographed first. Worlds are grouped by ID
+/// when known so renamed worlds stay together, showing their most recent name.
+pub fn world_stats(photos: &[IndexedPhoto]) -> Vec<WorldStats> {
+    struct Acc {
+        stats: WorldStats,
+        name_taken_at: Option<i64>,
+    }
+
+    let mut worlds: HashMap<String, Acc> = HashMap::new();
+    for photo in photos {
+        let Some(name) = photo.world_name.as_deref().filter(|n| !n.trim().is_empty()) else {
+            continue;
+        };
+        let world_id = photo.world_id.as_deref().filter(|id| !id.is_empty());
+        let key = world_id.map_or_else(|| format!("name:{name}"), |id| format!("id:{id}"));
+
+        let acc = worlds.entry(key).or_insert_with(|| Acc {
+            stats: WorldStats {
+                world_name: name.to_string(),
+                world_id: world_id.map(str::to_string),
+                world_url: world_id.map(world_page_url),
+                photo_count: 0,
+                first_photo: None,
+                last_photo: None,
+            },
+            name_taken_at: photo.taken_at,
+        });
+
+        let stats = &mut acc.stats;
+        stats.photo_count += 1;
+        if let Some(taken_at) = photo.taken_at {
+            stats.first_photo = Some(stats.first_photo.map_or(taken_at, |t| t.min(taken_at)));
+            stats.last_photo = Some(stats.last_photo.map_or(taken_at, |t| t.max(taken_at)));
+            if acc.name_taken_at.is_none_or(|t| taken_at > t) {
+                acc.name_taken_at = Some(taken_at);
+                stats.world_name = name.to_string();
+            }
+        }
+    }
+
+    let mut stats: Vec<WorldStats> = worlds.into_values().map(|acc| acc.stats).collect();
+    stats.sort_by(|a, b| {
+        b.photo_count
+            .cmp(&a.photo_count)
+            .then_with(|| a.world_name.cmp(&b.world_name))
+    });
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_search_query() {
+        assert_eq!(
+            build_search_query("PlayerX  black cat").as_deref(),
+            Some(r#""PlayerX"* "black"* "cat"*"#)
+        );
+        assert_eq!(
+            build_search_query(r#"say "hi" OR"#).as_deref(),
+            Some(r#""say"* """hi"""* "OR"*"#)
+        );
+        assert_eq!(build_search_query("   "), None);
+    }
+
+    #[test]
+    fn test_player_stats() {
+        let photo = |world: &str, players: &[&str], taken_at: i64| IndexedPhoto {
+            world_name: Some(world.to_string()),
+            author_name: Some("Me".to_string()),
+            players: players.iter().map(|p| p.to_string()).collect(),
+            taken_at: Some(taken_at),
+            ..Default::default()
+        };
+        let photos = vec![
+            photo("The Black Cat", &["Me", "Alice", "Bob"], 300),
+            photo("The Black Cat", &["Alice", "Alice"], 100),
+            photo("Void Club", &["Alice"], 200),
+        ];
+
+        let stats = player_stats(&photos);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].display_name, "Alice");
+        assert_eq!(stats[0].photo_count, 3);
+        assert_eq!(
+            (stats[0].first_seen, stats[0].last_seen),
+            (Some(100), Some(300))
+        );
+        assert_eq!(
+            stats[0].top_worlds,
+            vec![
+                WorldCount {
+                    world_name: "The Black Cat".to_string(),
+                    photo_count: 2
+                },
+                WorldCount {
+                    world_name: "Void Club".to_string(),
+                    photo_count: 1
+                },
+            ]
+        );
+        assert_eq!(stats[1].display_name, "Bob");
+    }
+
+    #[test]
+    fn test_world_stats() {
+        let photo = |name: &str, id: Option<&str>, taken_at: i64| IndexedPhoto {
+            world_name: Some(name.to_string()),
+            world_id: id.map(str::to_string),
+            taken_at: Some(taken_at),
+            ..Default::default()
+        };
+        let photos = vec![
+            photo("Black Cat", Some("wrld_cat"), 100),
+            photo("The Black Cat", Some("wrld_cat"), 300),
+            photo("The Black Cat", Some("wrld_cat"), 200),
+            photo("Home", None, 50),
+            IndexedPhoto::default(),
+        ];
+
+        let stats = world_stats(&photos);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[0],
+            WorldStats {
+                world_name: "The Black Cat".to_string(),
+                world_id: Some("wrld_cat".to_string()),
+                world_url: Some("https://vrchat.com/home/world/wrld_cat".to_string()),
+                photo_count: 3,
+                first_photo: Some(100),
+                last_photo: Some(300),
+            }
+        );
+        assert_eq!(stats[1].world_name, "Home");
+        assert_eq!(stats[1].world_url, None);
+    }
+}
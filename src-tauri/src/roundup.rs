@@ -0,0 +1,342 @@
+// Weekly/monthly roundup posts
+//
+// Once a period (Monday–Sunday week or calendar month, local time) has ended, the
+// scheduler summarises what was uploaded during it and posts one message to the
+// configured webhook. World and player highlights are read back from the uploaded
+// files' metadata, so files that were moved or deleted since only count as photos.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppResult;
+use crate::uploader::discord_client::DiscordClient;
+use crate::{config, database, image_processor};
+
+/// How often the scheduler checks whether a roundup is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Delay before the first check so startup isn't slowed down
+const STARTUP_DELAY: Duration = Duration::from_secs(2 * 60);
+/// Worlds listed by name in the message
+const TOP_WORLDS: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundupCadence {
+    #[default]
+    Weekly,
+    Monthly,
+}
+
+/// `roundup` section of the config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoundupConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub cadence: RoundupCadence,
+    /// Webhook the roundup is posted to
+    #[serde(default)]
+    pub webhook_id: Option<i64>,
+}
+
+/// A finished period a roundup covers
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundupPeriod {
+    pub cadence: RoundupCadence,
+    pub start: NaiveDate,
+    /// First day after the period
+    pub end: NaiveDate,
+}
+
+impl RoundupPeriod {
+    /// Unique key used to remember that the period was posted
+    pub fn key(&self) -> String {
+        match self.cadence {
+            RoundupCadence::Weekly => format!("weekly:{}", self.start),
+            RoundupCadence::Monthly => format!("monthly:{}", self.start.format("%Y-%m")),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self.cadence {
+            RoundupCadence::Weekly => format!(
+                "Weekly roundup ({} – {})",
+                self.start.format("%b %-d"),
+                (self.end - ChronoDuration::days(1)).format("%b %-d")
+            ),
+            RoundupCadence::Monthly => {
+                format!("Monthly roundup ({})", self.start.format("%B %Y"))
+            }
+        }
+    }
+
+    fn noun(&self) -> &'static str {
+        match self.cadence {
+            RoundupCadence::Weekly => "week",
+            RoundupCadence::Monthly => "month",
+        }
+    }
+}
+
+/// The most recent period that has fully ended on `today`
+pub fn previous_period(cadence: RoundupCadence, today: NaiveDate) -> RoundupPeriod {
+    let (start, end) = match cadence {
+        RoundupCadence::Weekly => {
+            let this_monday =
+                today - ChronoDuration::days(today.weekday().num_days_from_monday() as i64);
+            (this_monday - ChronoDuration::days(7), this_monday)
+        }
+        RoundupCadence::Monthly => {
+            let this_month = today.with_day(1).unwrap_or(today);
+            let last_month = (this_month - ChronoDuration::days(1))
+                .with_day(1)
+                .unwrap_or(this_month);
+            (last_month, this_month)
+        }
+    };
+    RoundupPeriod {
+        cadence,
+        start,
+        end,
+    }
+}
+
+/// Photo with the most players in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusiestPhoto {
+    pub player_count: usize,
+    pub world: Option<String>,
+    pub message_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoundupStats {
+    pub photo_count: usize,
+    /// World names with their photo counts, most photos first
+    pub worlds: Vec<(String, usize)>,
+    pub busiest_photo: Option<BusiestPhoto>,
+}
+
+/// Local midnight of `date` as the UTC timestamp format upload_history uses
+fn utc_bound(date: NaiveDate) -> String {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let local = Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight));
+    local.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Gather uploads in the period and read their metadata for highlights
+pub async fn collect_stats(period: &RoundupPeriod) -> AppResult<RoundupStats> {
+    let uploads =
+        database::get_successful_uploads_between(&utc_bound(period.start), &utc_bound(period.end))
+            .await?;
+
+    let mut world_counts: HashMap<String, usize> = HashMap::new();
+    let mut busiest_photo: Option<BusiestPhoto> = None;
+
+    let timezone = image_processor::configured_screenshot_timezone();
+    for (file_path, message_url) in &uploads {
+        let Ok(Some(metadata)) = image_processor::extract_metadata(file_path, timezone).await
+        else {
+            continue;
+        };
+        let world = metadata.world.map(|w| w.name);
+        if let Some(name) = &world {
+            *world_counts.entry(name.clone()).or_default() += 1;
+        }
+
+        let player_count = metadata.players.len();
+        if player_count > busiest_photo.as_ref().map_or(0, |b| b.player_count) {
+            busiest_photo = Some(BusiestPhoto {
+                player_count,
+                world,
+                message_url: message_url.clone(),
+            });
+        }
+    }
+
+    let mut worlds: Vec<(String, usize)> = world_counts.into_iter().collect();
+    worlds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Ok(RoundupStats {
+        photo_count: uploads.len(),
+        worlds,
+        busiest_photo,
+    })
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{count} {word}")
+    } else {
+        format!("{count} {word}s")
+    }
+}
+
+/// Discord message for a period's stats
+pub fn build_roundup_message(period: &RoundupPeriod, stats: &RoundupStats) -> String {
+    let mut lines = vec![format!("📸 **{}**", period.title())];
+
+    let mut headline = format!(
+        "This {}: {}",
+        period.noun(),
+        plural(stats.photo_count, "photo")
+    );
+    if !stats.worlds.is_empty() {
+        headline.push_str(&format!(" across {}", plural(stats.worlds.len(), "world")));
+    }
+    lines.push(headline);
+
+    if !stats.worlds.is_empty() {
+        let top: Vec<String> = stats
+            .worlds
+            .iter()
+            .take(TOP_WORLDS)
+            .map(|(name, count)| format!("{name} ({count})"))
+            .collect();
+        lines.push(format!("🌍 Most visited: {}", top.join(", ")));
+    }
+
+    if let Some(busiest) = &stats.busiest_photo {
+        let mut line = format!(
+            "👥 Busiest photo: {}",
+            plural(busiest.player_count, "player")
+        );
+        if let Some(world) = &busiest.world {
+            line.push_str(&format!(" in {world}"));
+        }
+        if let Some(url) = &busiest.message_url {
+            line.push_str(&format!(" — {url}"));
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+/// Post the previous period's roundup if it's enabled, due and not posted yet
+pub async fn run_due_roundup() -> AppResult<()> {
+    let roundup = config::load_config()?.roundup;
+    let (true, Some(webhook_id)) = (roundup.enabled, roundup.webhook_id) else {
+        return Ok(());
+    };
+
+    let period = previous_period(roundup.cadence, Local::now().date_naive());
+    let key = period.key();
+    if database::is_roundup_posted(&key).await? {
+        return Ok(());
+    }
+
+    let stats = collect_stats(&period).await?;
+    if stats.photo_count == 0 {
+        log::info!("No uploads for roundup {key}, skipping");
+        database::record_roundup_posted(&key, webhook_id, 0).await?;
+        return Ok(());
+    }
+
+    let webhook = database::get_webhook_by_id(webhook_id).await?;
+    let message = build_roundup_message(&period, &stats);
+    let client = DiscordClient::new();
+    if webhook.is_forum {
+        client
+            .send_forum_text_message(&webhook.url, &message, Some(&period.title()))
+            .await?;
+    } else {
+        client
+            .send_text_message(&webhook.url, &message, None)
+            .await?;
+    }
+
+    database::record_roundup_posted(&key, webhook_id, stats.photo_count).await?;
+    log::info!(
+        "Posted roundup {key} ({} photos) to '{}'",
+        stats.photo_count,
+        webhook.name
+    );
+    Ok(())
+}
+
+/// Check for due roundups in the background for the lifetime of the app
+pub fn start_scheduler() {
+    tauri::async_runtime::spawn(async {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            if let Err(e) = run_due_roundup().await {
+                log::warn!("Roundup check failed: {e}");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_previous_period() {
+        // Wednesday 2024-01-10 -> week of Mon 2024-01-01
+        let week = previous_period(RoundupCadence::Weekly, date(2024, 1, 10));
+        assert_eq!((week.start, week.end), (date(2024, 1, 1), date(2024, 1, 8)));
+        assert_eq!(week.key(), "weekly:2024-01-01");
+
+        // A Monday reports the week that just ended
+        let week = previous_period(RoundupCadence::Weekly, date(2024, 1, 8));
+        assert_eq!(week.start, date(2024, 1, 1));
+
+        let month = previous_period(RoundupCadence::Monthly, date(2024, 1, 15));
+        assert_eq!(
+            (month.start, month.end),
+            (date(2023, 12, 1), date(2024, 1, 1))
+        );
+        assert_eq!(month.key(), "monthly:2023-12");
+        assert_eq!(month.title(), "Monthly roundup (December 2023)");
+    }
+
+    #[test]
+    fn test_build_roundup_message() {
+        let period = previous_period(RoundupCadence::Weekly, date(2024, 1, 10));
+        let stats = RoundupStats {
+            photo_count: 143,
+            worlds: vec![
+                ("The Black Cat".to_string(), 40),
+                ("Midnight Rooftop".to_string(), 20),
+                ("Void Club".to_string(), 5),
+                ("Home".to_string(), 1),
+            ],
+            busiest_photo: Some(BusiestPhoto {
+                player_count: 12,
+                world: Some("Void Club".to_string()),
+                message_url: None,
+            }),
+        };
+
+        let message = build_roundup_message(&period, &stats);
+        assert_eq!(
+            message,
+            "📸 **Weekly roundup (Jan 1 – Jan 7)**\n\
+             This week: 143 photos across 4 worlds\n\
+             🌍 Most visited: The Black Cat (40), Midnight Rooftop (20), Void Club (5)\n\
+             👥 Busiest photo: 12 players in Void Club"
+        );
+
+        let single = RoundupStats {
+            photo_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            build_roundup_message(&period, &single),
+            "📸 **Weekly roundup (Jan 1 – Jan 7)**\nThis week: 1 photo"
+        );
+    }
+}
@@ -1,6 +1,45 @@
 use crate::errors::{AppError, AppResult};
 use regex::Regex;
-use std::path::{Component, Path};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Video clips that can be posted alongside photos. They're uploaded as recorded:
+/// never decoded, resized or compressed.
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm"];
+
+/// Largest file accepted for upload (Discord's limit for boosted servers)
+const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Whether a file is a video clip, going by its extension
+pub fn is_video_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// MIME type Discord should get for an attachment, going by its extension
+pub fn attachment_mime_type(path: &str) -> &'static str {
+    let ext = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "image/png", // Default fallback
+    }
+}
+
+/// Whether the file starts like an MP4 (an ftyp box) or WebM (an EBML header)
+fn has_video_signature(header: &[u8]) -> bool {
+    header.get(4..8) == Some(b"ftyp") || header.starts_with(&[0x1A, 0x45, 0xDF, 0xA3])
+}
 
 pub struct InputValidator;
 
@@ -57,7 +96,27 @@ impl InputValidator {
         Ok(())
     }
 
+    /// Expand a leading `~` to the home folder and make the path absolute against the
+    /// working directory. Nothing is resolved on disk, so the path may not exist
+    pub fn resolve_user_path(path: &str) -> PathBuf {
+        let path = path.trim();
+        let expanded = match path.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => dirs::home_dir()
+                .map(|home| home.join(rest.trim_start_matches(['/', '\\'])))
+                .unwrap_or_else(|| PathBuf::from(path)),
+            _ => PathBuf::from(path),
+        };
+        std::path::absolute(&expanded).unwrap_or(expanded)
+    }
+
     pub fn validate_file_path(path: &str) -> AppResult<()> {
+        Self::resolve_file_path(path).map(|_| ())
+    }
+
+    /// Resolve a user-supplied image path (`~` and relative paths included) to an
+    /// absolute one. The checks run on the canonical target, so `..` segments and
+    /// symlinks can't point validation at anything but a real image file
+    pub fn resolve_file_path(path: &str) -> AppResult<PathBuf> {
         if path.trim().is_empty() {
             return Err(AppError::validation(
                 "file_path",
@@ -65,47 +124,38 @@ impl InputValidator {
             ));
         }
 
-        let path_obj = Path::new(path);
+        let resolved = Self::resolve_user_path(path);
+        Self::validate_image_extension(&resolved, path)?;
 
-        let has_parent_dir = path_obj
-            .components()
-            .any(|c| matches!(c, Component::ParentDir));
-        let starts_with_tilde =
-            path.starts_with('~') || path.starts_with("~/") || path.starts_with("~\\");
+        // Check file exists and is readable, then that what it really points at is an image
+        let canonical = resolved
+            .canonicalize()
+            .map_err(|_| AppError::file_not_found(path))?;
+        Self::validate_image_extension(&canonical, path)?;
 
-        if has_parent_dir || starts_with_tilde {
-            return Err(AppError::validation(
-                "file_path",
-                "Invalid file path detected",
-            ));
+        if !canonical.is_file() {
+            return Err(AppError::validation("file_path", "Path is not a file"));
         }
 
-        // Ensure it's an image file
+        Ok(resolved)
+    }
+
+    fn validate_image_extension(path_obj: &Path, path: &str) -> AppResult<()> {
         if let Some(extension) = path_obj.extension() {
             let ext = extension.to_string_lossy().to_lowercase();
             if !matches!(
                 ext.as_str(),
-                "png" | "jpg" | "jpeg" | "webp" | "gif" | "bmp"
+                "png" | "jpg" | "jpeg" | "webp" | "gif" | "bmp" | "tif" | "tiff" | "mp4" | "webm"
             ) {
                 return Err(AppError::invalid_file_type(path));
             }
+            Ok(())
         } else {
-            return Err(AppError::validation(
+            Err(AppError::validation(
                 "file_path",
                 "File must have an extension",
-            ));
-        }
-
-        // Check file exists and is readable
-        if !path_obj.exists() {
-            return Err(AppError::file_not_found(path));
+            ))
         }
-
-        if !path_obj.is_file() {
-            return Err(AppError::validation("file_path", "Path is not a file"));
-        }
-
-        Ok(())
     }
 
     pub fn sanitize_filename(filename: &str) -> String {
@@ -128,7 +178,6 @@ impl InputValidator {
         let metadata = std::fs::metadata(file_path)?;
 
         // Check file size (max 50MB for Discord)
-        const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
         if metadata.len() > MAX_FILE_SIZE {
             return Err(AppError::file_too_large(file_path));
         }
@@ -139,6 +188,27 @@ impl InputValidator {
         Ok(())
     }
 
+    /// Validate a file to upload: a photo, or a video clip. Clips aren't decoded;
+    /// only their size and container header are checked.
+    pub fn validate_upload_file(file_path: &str) -> AppResult<()> {
+        if !is_video_file(file_path) {
+            return Self::validate_image_file(file_path);
+        }
+        Self::validate_file_path(file_path)?;
+
+        let metadata = std::fs::metadata(file_path)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(AppError::file_too_large(file_path));
+        }
+
+        let mut header = [0u8; 12];
+        let read = std::io::Read::read(&mut std::fs::File::open(file_path)?, &mut header)?;
+        if !has_video_signature(&header[..read]) {
+            return Err(AppError::invalid_file_type(file_path));
+        }
+        Ok(())
+    }
+
     pub fn validate_upload_settings(max_images: u8, _group_metadata: bool) -> AppResult<()> {
         if max_images == 0 || max_images > 10 {
             return Err(AppError::validation(
@@ -151,12 +221,51 @@ impl InputValidator {
     }
 }
 
+/// Stands in for a webhook token in logs and error messages
+const REDACTED_TOKEN: &str = "[redacted]";
+
+static WEBHOOK_TOKEN_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+/// Mask the token of every webhook URL in `text`, keeping the webhook ID so logs
+/// still show which webhook was involved
+pub fn redact_webhook_tokens(text: &str) -> Cow<'_, str> {
+    let pattern = WEBHOOK_TOKEN_PATTERN
+        .get_or_init(|| Regex::new(r"(?i)(/api/(?:v\d+/)?webhooks/\d+/)[\w\-]+").unwrap());
+    pattern.replace_all(text, format!("${{1}}{REDACTED_TOKEN}"))
+}
+
+/// Name of the uploader's own folder inside the temp location
+const TEMP_FOLDER_NAME: &str = "vrchat_uploader_secure";
+
 /// File system security utilities
 pub struct FileSystemGuard;
 
 impl FileSystemGuard {
+    /// Folder the uploader's compressed and resized copies are written to: its own
+    /// subfolder of the configured temp location, or of the system temp folder. Cleanup
+    /// only ever removes this subfolder, never anything else in a user-chosen folder.
+    pub fn get_temp_directory() -> std::path::PathBuf {
+        let configured = crate::config::load_config()
+            .ok()
+            .and_then(|c| c.temp_directory)
+            .filter(|dir| !dir.trim().is_empty())
+            .map(std::path::PathBuf::from);
+        let base = match configured {
+            Some(dir) if dir.is_dir() => dir,
+            Some(dir) => {
+                log::warn!(
+                    "Temp folder {} is unavailable, using the system temp folder",
+                    dir.display()
+                );
+                std::env::temp_dir()
+            }
+            None => std::env::temp_dir(),
+        };
+        base.join(TEMP_FOLDER_NAME)
+    }
+
     pub fn create_secure_temp_file(original_path: &str) -> AppResult<std::path::PathBuf> {
-        let temp_dir = std::env::temp_dir().join("vrchat_uploader_secure");
+        let temp_dir = Self::get_temp_directory();
         std::fs::create_dir_all(&temp_dir)?;
 
         // Preserve original filename stem, add unique suffix to avoid collisions
@@ -172,14 +281,20 @@ impl FileSystemGuard {
         // Add short UUID suffix for uniqueness while keeping original name
         let short_id = &uuid::Uuid::new_v4().to_string()[..8];
         let temp_path = temp_dir.join(format!("{original_stem}_{short_id}.{extension}"));
+        crate::temp_manifest::record(&temp_path);
 
         Ok(temp_path)
     }
 
+    /// Remove temp files orphaned by uploads that were killed mid-way, including in
+    /// the system temp folder left over from before a temp location was configured.
+    /// Files of uploads that are still running are left alone.
     pub fn cleanup_temp_files() -> AppResult<()> {
-        let temp_dir = std::env::temp_dir().join("vrchat_uploader_secure");
-        if temp_dir.exists() {
-            std::fs::remove_dir_all(&temp_dir)?;
+        let default_dir = std::env::temp_dir().join(TEMP_FOLDER_NAME);
+        let configured_dir = Self::get_temp_directory();
+        crate::temp_manifest::cleanup_orphans(&configured_dir)?;
+        if default_dir != configured_dir {
+            crate::temp_manifest::cleanup_orphans(&default_dir)?;
         }
         Ok(())
     }
@@ -333,8 +448,11 @@ mod tests {
             ("../etc/passwd", "path traversal"),
             ("..\\windows\\system32\\file.png", "windows path traversal"),
             ("foo/../../etc/passwd", "embedded path traversal"),
-            ("~/secret/file.png", "home directory traversal"),
-            ("~\\secret\\file.png", "windows home directory traversal"),
+            ("~/secret/file.png", "missing file under home"),
+            (
+                "~\\secret\\file.png",
+                "missing file under home (windows separators)",
+            ),
             ("file.txt", "not an image extension"),
             ("file.exe", "executable file"),
             ("image", "no extension"),
@@ -369,6 +487,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_user_path_expands_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            InputValidator::resolve_user_path("~/Pictures/VRChat"),
+            home.join("Pictures/VRChat")
+        );
+        assert_eq!(InputValidator::resolve_user_path("~"), home);
+        // Only a leading `~` means the home folder
+        assert!(InputValidator::resolve_user_path("PROGRA~1")
+            .to_string_lossy()
+            .ends_with("PROGRA~1"));
+        assert!(InputValidator::resolve_user_path("relative/folder").is_absolute());
+    }
+
+    #[test]
+    fn test_resolve_file_path_allows_parent_segments_to_real_images() {
+        let dir = std::env::temp_dir().join(format!("resolve_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        File::create(dir.join("photo.png")).unwrap();
+        File::create(dir.join("notes.txt")).unwrap();
+
+        let via_parent = dir.join("sub").join("..").join("photo.png");
+        assert!(InputValidator::resolve_file_path(&via_parent.to_string_lossy()).is_ok());
+
+        // A link with an image name still has to point at an image
+        #[cfg(unix)]
+        {
+            let link = dir.join("link.png");
+            std::os::unix::fs::symlink(dir.join("notes.txt"), &link).unwrap();
+            assert!(InputValidator::resolve_file_path(&link.to_string_lossy()).is_err());
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_filesystem_guard_temp_file_creation() {
         let result = FileSystemGuard::create_secure_temp_file("test.png");
@@ -435,4 +589,52 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_validate_upload_file_accepts_clips_without_decoding() {
+        let temp_dir = std::env::temp_dir();
+        let mp4_path = temp_dir.join("upload_clip_test.mp4");
+        std::fs::write(&mp4_path, b"\0\0\0\x18ftypisom\0\0\x02\0").unwrap();
+        let webm_path = temp_dir.join("upload_clip_test.webm");
+        std::fs::write(&webm_path, [0x1A, 0x45, 0xDF, 0xA3, 0x9F, 0x42, 0x86, 0x81]).unwrap();
+        let fake_path = temp_dir.join("upload_clip_fake.mp4");
+        std::fs::write(&fake_path, b"not a video at all").unwrap();
+
+        let mp4 = mp4_path.to_string_lossy();
+        assert!(is_video_file(&mp4));
+        assert_eq!(attachment_mime_type(&mp4), "video/mp4");
+        assert!(InputValidator::validate_upload_file(&mp4).is_ok());
+        // Clips are still rejected wherever a decodable image is required
+        assert!(InputValidator::validate_image_file(&mp4).is_err());
+
+        let webm = webm_path.to_string_lossy();
+        assert_eq!(attachment_mime_type(&webm), "video/webm");
+        assert!(InputValidator::validate_upload_file(&webm).is_ok());
+        assert!(InputValidator::validate_upload_file(&fake_path.to_string_lossy()).is_err());
+
+        assert!(!is_video_file("photo.PNG"));
+        assert_eq!(attachment_mime_type("photo.JPG"), "image/jpeg");
+
+        for path in [mp4_path, webm_path, fake_path] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_redact_webhook_tokens_keeps_webhook_id() {
+        let url = "https://discord.com/api/webhooks/123456789012345678/abcDEF_123-xyz?wait=true";
+        assert_eq!(
+            redact_webhook_tokens(&format!("POST {url} failed")),
+            "POST https://discord.com/api/webhooks/123456789012345678/[redacted]?wait=true failed"
+        );
+        assert_eq!(
+            redact_webhook_tokens("https://discord.com/api/v10/webhooks/1/token/messages/2"),
+            "https://discord.com/api/v10/webhooks/1/[redacted]/messages/2"
+        );
+        assert_eq!(redact_webhook_tokens("nothing to hide"), "nothing to hide");
+
+        let error: String = AppError::invalid_webhook(url).into();
+        assert!(!error.contains("abcDEF"));
+        assert!(error.contains("123456789012345678"));
+    }
 }
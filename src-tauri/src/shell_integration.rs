@@ -0,0 +1,126 @@
+// Windows Explorer "Send to VRChat Photo Uploader" context-menu entry
+//
+// Registered per user under HKCU\Software\Classes\SystemFileAssociations so no admin
+// rights are needed. Explorer launches the exe with the selected file as an argument;
+// a second launch forwards it to the running instance (see single_instance).
+
+use crate::errors::{AppError, AppResult};
+
+/// Shell verb key name under each file type
+const VERB_KEY: &str = "VRChatPhotoUploader";
+const MENU_LABEL: &str = "Send to VRChat Photo Uploader";
+/// Image types the entry is shown for
+const EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "webp"];
+
+/// Command-line flags an installer can run to toggle the entry without starting the UI
+pub const REGISTER_FLAG: &str = "--register-context-menu";
+pub const UNREGISTER_FLAG: &str = "--unregister-context-menu";
+
+/// Registry key of the verb for one extension
+fn verb_key(extension: &str) -> String {
+    format!(r"HKCU\Software\Classes\SystemFileAssociations\.{extension}\shell\{VERB_KEY}")
+}
+
+/// `reg add` argument lists that create the entry for every supported extension
+fn registration_commands(exe_path: &str) -> Vec<Vec<String>> {
+    EXTENSIONS
+        .iter()
+        .flat_map(|ext| {
+            let key = verb_key(ext);
+            [
+                vec![key.clone(), "/ve".into(), "/d".into(), MENU_LABEL.into()],
+                vec![
+                    key.clone(),
+                    "/v".into(),
+                    "Icon".into(),
+                    "/d".into(),
+                    format!("\"{exe_path}\",0"),
+                ],
+                vec![
+                    format!(r"{key}\command"),
+                    "/ve".into(),
+                    "/d".into(),
+                    format!("\"{exe_path}\" \"%1\""),
+                ],
+            ]
+        })
+        .collect()
+}
+
+/// Run `reg` with `args`, returning whether it succeeded
+#[cfg(target_os = "windows")]
+pub(crate) fn run_reg(args: &[String]) -> AppResult<bool> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let status = std::process::Command::new("reg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()?;
+    Ok(status.success())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn run_reg(_args: &[String]) -> AppResult<bool> {
+    Err(AppError::Config(
+        "This setting is only available on Windows".to_string(),
+    ))
+}
+
+/// Add the context-menu entry for the current executable
+pub fn register_context_menu() -> AppResult<()> {
+    let exe_path = std::env::current_exe()?.to_string_lossy().to_string();
+
+    for args in registration_commands(&exe_path) {
+        let mut full_args = vec!["add".to_string()];
+        full_args.extend(args);
+        full_args.push("/f".to_string());
+        if !run_reg(&full_args)? {
+            return Err(AppError::Config(format!(
+                "Failed to write registry key {}",
+                full_args[1]
+            )));
+        }
+    }
+
+    log::info!("Registered Explorer context-menu entry for {exe_path}");
+    Ok(())
+}
+
+/// Remove the context-menu entry; missing keys are not an error
+pub fn unregister_context_menu() -> AppResult<()> {
+    for ext in EXTENSIONS {
+        run_reg(&["delete".to_string(), verb_key(ext), "/f".to_string()])?;
+    }
+
+    log::info!("Removed Explorer context-menu entry");
+    Ok(())
+}
+
+/// Whether the entry is currently registered
+pub fn is_context_menu_registered() -> bool {
+    run_reg(&["query".to_string(), verb_key(EXTENSIONS[0])]).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_commands_quote_exe_and_argument() {
+        let commands = registration_commands(r"C:\Program Files\Uploader\app.exe");
+        assert_eq!(commands.len(), EXTENSIONS.len() * 3);
+
+        let command_entry = &commands[2];
+        assert_eq!(
+            command_entry[0],
+            r"HKCU\Software\Classes\SystemFileAssociations\.png\shell\VRChatPhotoUploader\command"
+        );
+        assert_eq!(
+            command_entry.last().unwrap(),
+            r#""C:\Program Files\Uploader\app.exe" "%1""#
+        );
+    }
+}
@@ -1,11 +1,33 @@
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 use sysinfo::{Pid, System};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::security::InputValidator;
 
 #[derive(Debug)]
 pub struct SingleInstanceError;
 
+/// Signals older than this are leftovers from a crashed or closed instance and are ignored
+const STALE_SIGNAL_AGE: Duration = Duration::from_secs(60);
+
+/// Files passed on the command line ("Open with", dropped onto the exe) that the
+/// frontend has not picked up yet
+static PENDING_FILES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Message a second launch leaves for the running instance
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstanceSignal {
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// vrcphoto:// links the OS launched the second instance with
+    #[serde(default)]
+    pub urls: Vec<String>,
+}
+
 /// Check if another instance of the application is already running
 pub fn check_single_instance() -> Result<(), SingleInstanceError> {
     let lock_file = get_lock_file_path();
@@ -26,7 +48,10 @@ pub fn check_single_instance() -> Result<(), SingleInstanceError> {
                         || process_name.contains("uploader")
                     {
                         log::info!("Found existing instance (PID: {pid}), signaling it to show");
-                        signal_existing_instance();
+                        signal_existing_instance(&InstanceSignal {
+                            files: launch_file_args(),
+                            urls: launch_deep_link_args(),
+                        });
                         return Err(SingleInstanceError); // Exit this instance
                     }
                 }
@@ -54,17 +79,140 @@ fn get_lock_file_path() -> PathBuf {
     temp_dir.join("vrchat_photo_uploader.lock")
 }
 
-/// Signal an existing instance to show its window
-fn signal_existing_instance() {
-    // Create a signal file that the existing instance can detect
-    let signal_file = std::env::temp_dir().join("vrchat_photo_uploader_show.signal");
-    if let Err(e) = fs::write(&signal_file, "show") {
-        log::warn!("Failed to create signal file: {e}");
-    } else {
-        log::info!("Created signal file to show existing instance");
+/// Directory second launches drop their signal files into
+fn get_signal_directory() -> PathBuf {
+    std::env::temp_dir().join("vrchat_photo_uploader_signals")
+}
+
+/// Signal an existing instance to show its window and queue any forwarded files.
+/// Each launch writes its own file, renamed into place so a half-written signal is never read.
+fn signal_existing_instance(signal: &InstanceSignal) {
+    let signal_dir = get_signal_directory();
+    if let Err(e) = fs::create_dir_all(&signal_dir) {
+        log::warn!("Failed to create signal directory: {e}");
+        return;
+    }
+
+    let name = format!("{}_{}", std::process::id(), uuid::Uuid::new_v4());
+    let temp_path = signal_dir.join(format!("{name}.tmp"));
+    let signal_path = signal_dir.join(format!("{name}.json"));
+
+    let result = serde_json::to_string(signal)
+        .map_err(std::io::Error::other)
+        .and_then(|json| fs::write(&temp_path, json))
+        .and_then(|_| fs::rename(&temp_path, &signal_path));
+
+    match result {
+        Ok(()) => log::info!(
+            "Signaled existing instance to show ({} file(s) forwarded)",
+            signal.files.len()
+        ),
+        Err(e) => {
+            log::warn!("Failed to create signal file: {e}");
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+}
+
+/// Image files among this process's command-line arguments, as absolute paths
+pub fn launch_file_args() -> Vec<String> {
+    image_file_args(std::env::args().skip(1))
+}
+
+/// vrcphoto:// links among this process's command-line arguments
+pub fn launch_deep_link_args() -> Vec<String> {
+    std::env::args()
+        .skip(1)
+        .filter(|arg| crate::deep_link::is_deep_link(arg))
+        .collect()
+}
+
+fn image_file_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    args.filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| InputValidator::resolve_file_path(&arg).ok())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Add files to the pending queue and tell the frontend to pick them up
+pub fn queue_files(app_handle: &AppHandle, files: Vec<String>) {
+    if add_pending_files(files) > 0 {
+        let _ = app_handle.emit("external-files-queued", ());
     }
 }
 
+/// Add files to the pending queue for the frontend's next `take_pending_files`,
+/// returning how many were passed
+pub(crate) fn add_pending_files(files: Vec<String>) -> usize {
+    let count = files.len();
+    if count == 0 {
+        return 0;
+    }
+
+    log::info!("Queued {count} file(s) passed to the application");
+    if let Ok(mut pending) = PENDING_FILES.lock() {
+        for file in files {
+            if !pending.contains(&file) {
+                pending.push(file);
+            }
+        }
+    }
+    count
+}
+
+/// Pass deep links on to the upload pipeline
+pub fn handle_deep_links(app_handle: &AppHandle, urls: Vec<String>) {
+    for url in urls {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            crate::deep_link::handle_deep_link(&app_handle, &url).await;
+        });
+    }
+}
+
+/// Take the files waiting to be added to the upload queue
+pub fn take_pending_files() -> Vec<String> {
+    PENDING_FILES
+        .lock()
+        .map(|mut pending| std::mem::take(&mut *pending))
+        .unwrap_or_default()
+}
+
+/// Read and remove every complete signal in `signal_dir`, skipping stale ones
+fn drain_signals(signal_dir: &Path) -> Vec<InstanceSignal> {
+    let Ok(entries) = fs::read_dir(signal_dir) else {
+        return Vec::new();
+    };
+
+    let mut signals = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+
+        let is_stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_SIGNAL_AGE);
+        let contents = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+
+        if is_stale {
+            log::info!("Ignoring stale instance signal {}", path.display());
+            continue;
+        }
+        match contents.map(|c| serde_json::from_str::<InstanceSignal>(&c)) {
+            Ok(Ok(signal)) => signals.push(signal),
+            Ok(Err(e)) => log::warn!("Ignoring malformed instance signal: {e}"),
+            Err(e) => log::warn!("Failed to read instance signal: {e}"),
+        }
+    }
+    signals
+}
+
 /// Clean up the lock file when the application exits
 pub fn cleanup_lock_file() {
     let lock_file = get_lock_file_path();
@@ -87,44 +235,99 @@ pub fn register_cleanup_handler() {
     .expect("Error setting Ctrl-C handler");
 }
 
-/// Start the signal checker that watches for show requests from other instances
+/// Start the signal checker that watches for show requests (and forwarded files) from
+/// other instances. Files passed to this instance itself are queued right away.
 pub fn start_signal_checker(app_handle: AppHandle) {
+    queue_files(&app_handle, launch_file_args());
+    handle_deep_links(&app_handle, launch_deep_link_args());
+
     tauri::async_runtime::spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_millis(500));
-        let signal_file = std::env::temp_dir().join("vrchat_photo_uploader_show.signal");
+        let signal_dir = get_signal_directory();
 
         loop {
             interval.tick().await;
 
-            if signal_file.exists() {
-                log::info!("Received show signal from another instance");
-
-                // Remove signal file
-                let _ = fs::remove_file(&signal_file);
-
-                // Show and focus window
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    if let Err(e) = window.show() {
-                        log::error!("Failed to show window: {e}");
-                    }
-                    if let Err(e) = window.unminimize() {
-                        log::error!("Failed to unminimize window: {e}");
-                    }
-                    if let Err(e) = window.set_focus() {
-                        log::error!("Failed to focus window: {e}");
-                    }
+            let signals = drain_signals(&signal_dir);
+            if signals.is_empty() {
+                continue;
+            }
+            log::info!("Received show signal from another instance");
 
-                    // On Windows, use a trick to bring window to front
-                    #[cfg(target_os = "windows")]
-                    {
-                        let _ = window.set_always_on_top(true);
-                        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                        let _ = window.set_always_on_top(false);
-                    }
+            // Show and focus window
+            if let Some(window) = app_handle.get_webview_window("main") {
+                if let Err(e) = window.show() {
+                    log::error!("Failed to show window: {e}");
+                }
+                if let Err(e) = window.unminimize() {
+                    log::error!("Failed to unminimize window: {e}");
+                }
+                if let Err(e) = window.set_focus() {
+                    log::error!("Failed to focus window: {e}");
+                }
 
-                    log::info!("Window shown and focused");
+                // On Windows, use a trick to bring window to front
+                #[cfg(target_os = "windows")]
+                {
+                    let _ = window.set_always_on_top(true);
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    let _ = window.set_always_on_top(false);
                 }
+
+                log::info!("Window shown and focused");
             }
+
+            let (files, urls): (Vec<_>, Vec<_>) =
+                signals.into_iter().map(|s| (s.files, s.urls)).unzip();
+            queue_files(&app_handle, files.into_iter().flatten().collect());
+            handle_deep_links(&app_handle, urls.into_iter().flatten().collect());
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_file_args_keeps_existing_images_only() {
+        let dir = std::env::temp_dir().join(format!("vrcpu_args_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let image = dir.join("photo.png");
+        let text = dir.join("notes.txt");
+        fs::write(&image, b"png").unwrap();
+        fs::write(&text, b"txt").unwrap();
+
+        let args = vec![
+            "--minimized".to_string(),
+            image.to_string_lossy().to_string(),
+            text.to_string_lossy().to_string(),
+            dir.join("missing.png").to_string_lossy().to_string(),
+        ];
+        assert_eq!(
+            image_file_args(args.into_iter()),
+            vec![image.to_string_lossy().to_string()]
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_drain_signals_reads_and_removes() {
+        let dir = std::env::temp_dir().join(format!("vrcpu_signals_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.json"), r#"{"files": ["C:/photos/a.png"]}"#).unwrap();
+        fs::write(dir.join("b.json"), "{}").unwrap();
+        fs::write(dir.join("c.tmp"), "partial").unwrap();
+
+        let mut signals = drain_signals(&dir);
+        signals.sort_by_key(|s| s.files.len());
+        assert_eq!(signals.len(), 2);
+        assert!(signals[0].files.is_empty());
+        assert_eq!(signals[1].files, vec!["C:/photos/a.png".to_string()]);
+        assert!(!dir.join("a.json").exists());
+        assert!(dir.join("c.tmp").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
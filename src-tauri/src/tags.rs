@@ -0,0 +1,89 @@
+// Photo tags
+//
+// Tags are free-form labels stored per file path in `photo_tags`. Besides the ones
+// users add by hand, `world:<name>` and `player:<name>` tags can be derived from a
+// photo's metadata so uploads can be selected by world or by who's in them.
+
+use crate::commands::ImageMetadata;
+use crate::errors::{AppError, AppResult};
+
+const MAX_TAG_LENGTH: usize = 100;
+
+/// Where a tag came from
+pub const TAG_SOURCE_MANUAL: &str = "manual";
+pub const TAG_SOURCE_METADATA: &str = "metadata";
+
+/// Trim and validate a tag; lookups are case-insensitive so case is kept as typed
+pub fn normalize_tag(tag: &str) -> AppResult<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    if tag.is_empty() {
+        return Err(AppError::validation("tag", "Tag cannot be empty"));
+    }
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(AppError::validation(
+            "tag",
+            &format!("Tags must be {MAX_TAG_LENGTH} characters or less"),
+        ));
+    }
+    Ok(tag)
+}
+
+/// Tags derived from a photo's world and players
+pub fn metadata_tags(metadata: &ImageMetadata) -> Vec<String> {
+    let world = metadata
+        .world
+        .iter()
+        .filter(|world| !world.name.trim().is_empty())
+        .map(|world| format!("world:{}", world.name));
+    let players = metadata
+        .players
+        .iter()
+        .filter(|player| !player.display_name.trim().is_empty())
+        .map(|player| format!("player:{}", player.display_name));
+
+    let mut tags = Vec::new();
+    for tag in world.chain(players) {
+        if let Ok(tag) = normalize_tag(&tag) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{PlayerInfo, WorldInfo};
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("  best   of 2024 ").unwrap(), "best of 2024");
+        assert!(normalize_tag("   ").is_err());
+        assert!(normalize_tag(&"x".repeat(MAX_TAG_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_metadata_tags() {
+        let player = |name: &str| PlayerInfo {
+            display_name: name.to_string(),
+            id: String::new(),
+        };
+        let metadata = ImageMetadata {
+            author: None,
+            world: Some(WorldInfo {
+                name: "The Black Cat".to_string(),
+                id: "wrld_1".to_string(),
+                instance_id: String::new(),
+            }),
+            players: vec![player("Alice"), player("Bob"), player("Alice"), player(" ")],
+            avatar: None,
+        };
+
+        assert_eq!(
+            metadata_tags(&metadata),
+            vec!["world:The Black Cat", "player:Alice", "player:Bob"]
+        );
+    }
+}
@@ -0,0 +1,310 @@
+// Temp manifest - records which uploader temp files belong to which session
+//
+// Every temp file the uploader creates is listed in a small manifest next to it,
+// one manifest per upload session (plus one for work done outside a session). A
+// manifest whose process is no longer running belongs to a run that was killed or
+// crashed, so its files are orphans and can be removed without touching anything
+// a running upload still needs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use sysinfo::{Pid, System};
+
+use crate::errors::AppResult;
+use crate::security::{FileSystemGuard, InputValidator};
+
+/// Folder inside the uploader's temp folder the manifests are kept in
+const MANIFEST_FOLDER_NAME: &str = "manifests";
+
+/// Owner used for temp files created outside an upload session (editor, thumbnails)
+const APP_OWNER: &str = "app";
+
+tokio::task_local! {
+    static CURRENT_SESSION: String;
+}
+
+static MANIFESTS: OnceLock<Mutex<HashMap<String, TrackedManifest>>> = OnceLock::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    pid: u32,
+    #[serde(default)]
+    session_id: Option<String>,
+    /// Temp file paths without their extension; the pipeline picks the final
+    /// extension after the name is reserved
+    #[serde(default)]
+    files: Vec<PathBuf>,
+}
+
+struct TrackedManifest {
+    path: PathBuf,
+    manifest: Manifest,
+}
+
+/// Space taken by the uploader's temp files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TempUsage {
+    pub temp_directory: String,
+    pub total_bytes: u64,
+    pub file_count: usize,
+    /// Files left behind by uploads that are no longer running
+    pub reclaimable_bytes: u64,
+    pub reclaimable_files: usize,
+}
+
+fn manifests() -> &'static Mutex<HashMap<String, TrackedManifest>> {
+    MANIFESTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Run `future` with temp files it creates recorded against `session_id`
+pub async fn scope<F: Future>(session_id: String, future: F) -> F::Output {
+    let output = CURRENT_SESSION.scope(session_id.clone(), future).await;
+    prune(&session_id);
+    output
+}
+
+/// Wrap `future` (usually about to be spawned) so it runs under the caller's session, if any
+pub fn inherit<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let session_id = current_session();
+    async move {
+        match session_id {
+            Some(session_id) => CURRENT_SESSION.scope(session_id, future).await,
+            None => future.await,
+        }
+    }
+}
+
+fn current_session() -> Option<String> {
+    CURRENT_SESSION.try_with(|s| s.clone()).ok()
+}
+
+/// Record a temp file reserved by `FileSystemGuard::create_secure_temp_file`
+pub fn record(temp_path: &Path) {
+    let session_id = current_session();
+    let owner = session_id.clone().unwrap_or_else(|| APP_OWNER.to_string());
+
+    let mut manifests = manifests().lock().unwrap();
+    let tracked = manifests.entry(owner.clone()).or_insert_with(|| {
+        let folder = temp_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(FileSystemGuard::get_temp_directory);
+        let name = InputValidator::sanitize_filename(&format!("{}_{owner}", std::process::id()));
+        TrackedManifest {
+            path: folder
+                .join(MANIFEST_FOLDER_NAME)
+                .join(format!("{name}.json")),
+            manifest: Manifest {
+                pid: std::process::id(),
+                session_id,
+                files: Vec::new(),
+            },
+        }
+    });
+    tracked.manifest.files.push(temp_path.with_extension(""));
+    if let Err(e) = write_manifest(&tracked.path, &tracked.manifest) {
+        log::warn!("Failed to update temp manifest: {e}");
+    }
+}
+
+/// Forget the session's files that are already gone, dropping the manifest once
+/// none are left. Leftovers stay listed and are removed on the next startup.
+fn prune(session_id: &str) {
+    let mut manifests = manifests().lock().unwrap();
+    let Some(tracked) = manifests.get_mut(session_id) else {
+        return;
+    };
+    tracked
+        .manifest
+        .files
+        .retain(|stem| !existing_files(stem).is_empty());
+
+    if tracked.manifest.files.is_empty() {
+        fs::remove_file(&tracked.path).ok();
+        manifests.remove(session_id);
+    } else if let Err(e) = write_manifest(&tracked.path, &tracked.manifest) {
+        log::warn!("Failed to update temp manifest: {e}");
+    }
+}
+
+fn write_manifest(path: &Path, manifest: &Manifest) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(manifest)?)?;
+    Ok(())
+}
+
+/// Files on disk belonging to a recorded stem: `stem.ext`, `stem.thumb.webp`, ...
+fn existing_files(stem: &Path) -> Vec<PathBuf> {
+    let (Some(folder), Some(name)) = (stem.parent(), stem.file_name().and_then(|n| n.to_str()))
+    else {
+        return Vec::new();
+    };
+    let prefix = format!("{name}.");
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|n| n == name || n.starts_with(&prefix))
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Manifests in `temp_folder` whose process has exited
+fn orphaned_manifests(temp_folder: &Path) -> Vec<(PathBuf, Manifest)> {
+    let Ok(entries) = fs::read_dir(temp_folder.join(MANIFEST_FOLDER_NAME)) else {
+        return Vec::new();
+    };
+    let mut system = System::new();
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let manifest: Manifest = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())?;
+            let running = manifest.pid == std::process::id()
+                || system.refresh_process(Pid::from(manifest.pid as usize));
+            (!running).then_some((path, manifest))
+        })
+        .collect()
+}
+
+/// Orphaned files in `temp_folder`. Entries pointing outside the folder are ignored
+fn orphaned_files(temp_folder: &Path) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    orphaned_manifests(temp_folder)
+        .into_iter()
+        .map(|(path, manifest)| {
+            let files = manifest
+                .files
+                .iter()
+                .filter(|stem| stem.parent() == Some(temp_folder))
+                .flat_map(|stem| existing_files(stem))
+                .collect();
+            (path, files)
+        })
+        .collect()
+}
+
+/// Remove files left behind by uploads whose process is gone. Returns the number
+/// of files removed
+pub fn cleanup_orphans(temp_folder: &Path) -> AppResult<usize> {
+    let mut removed = 0;
+    for (manifest_path, files) in orphaned_files(temp_folder) {
+        for file in files {
+            match fs::remove_file(&file) {
+                Ok(()) => removed += 1,
+                Err(e) => log::warn!(
+                    "Failed to remove orphaned temp file {}: {e}",
+                    file.display()
+                ),
+            }
+        }
+        fs::remove_file(&manifest_path)?;
+    }
+    if removed > 0 {
+        log::info!(
+            "Removed {removed} orphaned temp file(s) from {}",
+            temp_folder.display()
+        );
+    }
+    Ok(removed)
+}
+
+/// Size of the temp folder and how much of it belongs to orphaned uploads
+pub fn temp_usage(temp_folder: &Path) -> TempUsage {
+    let mut usage = TempUsage {
+        temp_directory: temp_folder.to_string_lossy().to_string(),
+        ..TempUsage::default()
+    };
+    if let Ok(entries) = fs::read_dir(temp_folder) {
+        for entry in entries.flatten() {
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    usage.total_bytes += metadata.len();
+                    usage.file_count += 1;
+                }
+            }
+        }
+    }
+    for (_, files) in orphaned_files(temp_folder) {
+        for file in files {
+            usage.reclaimable_bytes += fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+            usage.reclaimable_files += 1;
+        }
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_folder() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("temp_manifest_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join(MANIFEST_FOLDER_NAME)).unwrap();
+        dir
+    }
+
+    fn write_orphan(folder: &Path, stems: &[&str]) {
+        let manifest = Manifest {
+            // PIDs are never this large, so the owner can't be running
+            pid: u32::MAX,
+            session_id: Some("crashed".to_string()),
+            files: stems.iter().map(|s| folder.join(s)).collect(),
+        };
+        write_manifest(
+            &folder.join(MANIFEST_FOLDER_NAME).join("crashed.json"),
+            &manifest,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_removes_only_orphaned_files() {
+        let folder = test_folder();
+        fs::write(folder.join("shot_ab12cd34.webp"), b"orphan").unwrap();
+        fs::write(folder.join("shot_ab12cd34.thumb.webp"), b"orphan").unwrap();
+        fs::write(folder.join("other_ef56gh78.webp"), b"keep").unwrap();
+        write_orphan(&folder, &["shot_ab12cd34"]);
+
+        let usage = temp_usage(&folder);
+        assert_eq!(usage.file_count, 3);
+        assert_eq!(usage.reclaimable_files, 2);
+        assert_eq!(usage.reclaimable_bytes, 12);
+
+        assert_eq!(cleanup_orphans(&folder).unwrap(), 2);
+        assert!(folder.join("other_ef56gh78.webp").exists());
+        assert!(!folder.join("shot_ab12cd34.webp").exists());
+        assert_eq!(temp_usage(&folder).reclaimable_files, 0);
+
+        fs::remove_dir_all(&folder).ok();
+    }
+
+    #[test]
+    fn test_cleanup_ignores_entries_outside_folder() {
+        let folder = test_folder();
+        let outside = test_folder();
+        fs::write(outside.join("photo.png"), b"keep").unwrap();
+        write_orphan(&folder, &[outside.join("photo").to_str().unwrap()]);
+
+        assert_eq!(cleanup_orphans(&folder).unwrap(), 0);
+        assert!(outside.join("photo.png").exists());
+
+        fs::remove_dir_all(&folder).ok();
+        fs::remove_dir_all(&outside).ok();
+    }
+}
@@ -0,0 +1,172 @@
+// Persistent thumbnail cache
+//
+// Thumbnails are stored as `<sha256>_<size>.webp` in the user cache directory, so
+// re-opening a folder reuses them instead of decoding every screenshot again, and
+// renamed or moved files still hit. When the cache grows past MAX_CACHE_BYTES the
+// least recently used entries are evicted.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use crate::errors::AppResult;
+use crate::image_processor;
+
+/// Size the cache is trimmed back to
+const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+/// New thumbnails written between eviction passes
+const EVICTION_INTERVAL: usize = 100;
+
+static WRITES_SINCE_EVICTION: AtomicUsize = AtomicUsize::new(0);
+
+pub fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("VRChat Photo Uploader")
+        .join("thumbnails")
+}
+
+/// Cache key for a file's thumbnail at a size, None if the file can't be read
+pub fn cache_key(file_path: &str, max_dimension: u32) -> Option<String> {
+    match image_processor::sha256_file(file_path) {
+        Ok(hash) => Some(format!("{hash}_{max_dimension}")),
+        Err(e) => {
+            log::debug!("Not caching thumbnail for {file_path}: {e}");
+            None
+        }
+    }
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.webp"))
+}
+
+/// Cached thumbnail for a key; a hit counts as a use for eviction
+pub fn lookup(key: &str) -> Option<PathBuf> {
+    let path = entry_path(&cache_dir(), key);
+    if !path.is_file() {
+        return None;
+    }
+    if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(path)
+}
+
+/// Store an encoded thumbnail, None if the cache directory isn't writable
+pub fn store(key: &str, webp_data: &[u8]) -> Option<PathBuf> {
+    let dir = cache_dir();
+    let path = entry_path(&dir, key);
+
+    // Write to a unique temp name first so concurrent thumbnails of the same
+    // file never expose a half-written entry
+    let partial = dir.join(format!("{key}.{}.partial", uuid::Uuid::new_v4()));
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&partial, webp_data))
+        .and_then(|_| std::fs::rename(&partial, &path));
+    if let Err(e) = result {
+        log::warn!("Failed to cache thumbnail in {}: {e}", dir.display());
+        let _ = std::fs::remove_file(&partial);
+        return None;
+    }
+
+    if WRITES_SINCE_EVICTION.fetch_add(1, Ordering::Relaxed) + 1 >= EVICTION_INTERVAL {
+        WRITES_SINCE_EVICTION.store(0, Ordering::Relaxed);
+        evict();
+    }
+
+    Some(path)
+}
+
+/// Trim the cache to MAX_CACHE_BYTES, logging rather than failing
+pub fn evict() {
+    match evict_dir(&cache_dir(), MAX_CACHE_BYTES) {
+        Ok(0) => {}
+        Ok(removed) => log::info!("Evicted {removed} cached thumbnails"),
+        Err(e) => log::warn!("Thumbnail cache eviction failed: {e}"),
+    }
+}
+
+/// Remove least recently used entries until the directory is at most `max_bytes`
+fn evict_dir(dir: &Path, max_bytes: u64) -> AppResult<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut entries = Vec::new();
+    let mut total = 0;
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        total += metadata.len();
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        entries.push((modified, metadata.len(), entry.path()));
+    }
+
+    entries.sort_by_key(|(modified, _, _)| *modified);
+
+    let mut removed = 0;
+    for (_, size, path) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Delete every cached thumbnail, returning how many were removed
+pub fn clear() -> AppResult<usize> {
+    clear_dir(&cache_dir())
+}
+
+fn clear_dir(dir: &Path) -> AppResult<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        if entry.path().is_file() && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_evict_removes_least_recently_used() {
+        let dir = std::env::temp_dir().join(format!("thumb_cache_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let now = SystemTime::now();
+        for (i, name) in ["old", "middle", "new"].iter().enumerate() {
+            let path = dir.join(format!("{name}.webp"));
+            std::fs::write(&path, [0u8; 100]).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - Duration::from_secs(300 - i as u64 * 100))
+                .unwrap();
+        }
+
+        assert_eq!(evict_dir(&dir, 250).unwrap(), 1);
+        assert!(!dir.join("old.webp").exists());
+        assert!(dir.join("middle.webp").exists());
+        assert_eq!(evict_dir(&dir, 250).unwrap(), 0);
+
+        assert_eq!(clear_dir(&dir).unwrap(), 2);
+        let _ = std::fs::remove_dir(&dir);
+    }
+}
@@ -0,0 +1,85 @@
+// Tray "Upload to" submenu listing recently used webhooks
+//
+// The submenu is created with the rest of the tray menu and refilled from the
+// database whenever webhooks are added, edited, removed or used.
+
+use tauri::menu::{MenuItem, Submenu};
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::database;
+
+/// Webhooks shown in the submenu, most recently used first
+const MAX_TRAY_WEBHOOKS: i64 = 10;
+const WEBHOOK_ITEM_PREFIX: &str = "upload_to_";
+
+/// Managed handle to the "Upload to" submenu
+pub struct TrayWebhookMenu(pub Submenu<Wry>);
+
+/// Menu item ID for a webhook entry
+pub fn webhook_menu_id(webhook_id: i64) -> String {
+    format!("{WEBHOOK_ITEM_PREFIX}{webhook_id}")
+}
+
+/// Webhook ID of a submenu entry, None for any other menu item
+pub fn parse_webhook_menu_id(menu_id: &str) -> Option<i64> {
+    menu_id.strip_prefix(WEBHOOK_ITEM_PREFIX)?.parse().ok()
+}
+
+/// Refill the submenu from the database; failures only cost the tray entries
+pub async fn refresh_webhook_menu(app_handle: &AppHandle) {
+    let Some(menu) = app_handle.try_state::<TrayWebhookMenu>() else {
+        return;
+    };
+
+    let webhooks = match database::get_recent_webhooks(MAX_TRAY_WEBHOOKS).await {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            log::warn!("Failed to load webhooks for tray menu: {e}");
+            return;
+        }
+    };
+
+    let result = (|| -> tauri::Result<()> {
+        for item in menu.0.items()? {
+            menu.0.remove(&item)?;
+        }
+
+        if webhooks.is_empty() {
+            let empty = MenuItem::with_id(
+                app_handle,
+                "upload_to_none",
+                "No webhooks yet",
+                false,
+                None::<&str>,
+            )?;
+            menu.0.append(&empty)?;
+        }
+        for webhook in &webhooks {
+            let item = MenuItem::with_id(
+                app_handle,
+                webhook_menu_id(webhook.id),
+                &webhook.name,
+                true,
+                None::<&str>,
+            )?;
+            menu.0.append(&item)?;
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        log::warn!("Failed to rebuild tray webhook menu: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_menu_id_round_trip() {
+        assert_eq!(parse_webhook_menu_id(&webhook_menu_id(42)), Some(42));
+        assert_eq!(parse_webhook_menu_id("upload_to_none"), None);
+        assert_eq!(parse_webhook_menu_id("upload_files"), None);
+    }
+}
@@ -0,0 +1,191 @@
+// Chunk planner - splits a group's images into messages up-front
+//
+// Chunks respect both the max-images-per-message setting and the webhook's upload
+// size budget, so oversized messages are avoided before anything is sent. Discord's
+// own rules are checked here too: at most 10 attachments per message, and only image
+// types it shows inline; other formats are converted before upload.
+
+use std::path::Path;
+
+use super::dry_run::DISCORD_UPLOAD_LIMIT;
+use crate::commands::Webhook;
+
+/// Most attachments Discord accepts on one message
+pub const DISCORD_MAX_ATTACHMENTS: usize = 10;
+
+/// Image types Discord shows inline in a message
+const INLINE_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Share of the upload budget chunks are planned against, leaving room for the
+/// multipart framing and for compressed sizes coming out above the prediction
+const PLANNING_HEADROOM: f64 = 0.9;
+
+/// Upload size budget for a webhook, falling back to Discord's default limit
+pub fn webhook_upload_limit(webhook: &Webhook) -> u64 {
+    webhook
+        .upload_limit_mb
+        .map(|mb| u64::from(mb) * 1024 * 1024)
+        .unwrap_or(DISCORD_UPLOAD_LIMIT)
+}
+
+/// Predicted size of a file once it has gone through the upload pipeline.
+/// PNG sources shrink when compressed; already-compressed formats and video clips
+/// are counted as-is
+pub fn predicted_upload_size(file_path: &str, file_size: u64, compression_format: &str) -> u64 {
+    let is_png = Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+    if !is_png {
+        return file_size;
+    }
+
+    let ratio = match compression_format {
+        "png" => 1.0,
+        "lossless_webp" => 0.75,
+        // Lossy formats (webp, jpg, avif) typically land well under a third of the PNG
+        _ => 0.3,
+    };
+    (file_size as f64 * ratio) as u64
+}
+
+/// MIME type of an image from its contents, since the extension can be wrong
+pub fn sniff_image_mime(file_path: &str) -> Option<&'static str> {
+    let format = image::ImageReader::open(file_path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .format()?;
+    Some(format.to_mime_type())
+}
+
+/// Whether a file is an image Discord wouldn't show inline (BMP, TIFF) and has to be
+/// converted before upload. Files that can't be identified are left alone.
+pub fn needs_conversion(file_path: &str) -> bool {
+    sniff_image_mime(file_path).is_some_and(|mime| !INLINE_IMAGE_TYPES.contains(&mime))
+}
+
+/// Plan chunks from known file sizes, keeping the original order. A file that is
+/// predicted to exceed the budget on its own still gets a chunk to itself. Chunks
+/// never exceed Discord's attachment cap, whatever `max_images` says.
+pub fn plan_chunks_with_sizes(
+    files: &[(String, u64)],
+    max_images: usize,
+    size_limit: u64,
+    compression_format: &str,
+) -> Vec<Vec<String>> {
+    let max_images = max_images.clamp(1, DISCORD_MAX_ATTACHMENTS);
+    let budget = (size_limit as f64 * PLANNING_HEADROOM) as u64;
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_size = 0u64;
+
+    for (path, size) in files {
+        let predicted = predicted_upload_size(path, *size, compression_format);
+        if !current.is_empty() && (current.len() >= max_images || current_size + predicted > budget)
+        {
+            chunks.push(std::mem::take(&mut current));
+            current_size = 0;
+        }
+        current.push(path.clone());
+        current_size += predicted;
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Plan chunks for files on disk; unreadable files count as empty so they only
+/// take up an image slot
+pub fn plan_chunks(
+    file_paths: &[String],
+    max_images: usize,
+    size_limit: u64,
+    compression_format: &str,
+) -> Vec<Vec<String>> {
+    let files: Vec<(String, u64)> = file_paths
+        .iter()
+        .map(|path| {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            (path.clone(), size)
+        })
+        .collect();
+    plan_chunks_with_sizes(&files, max_images, size_limit, compression_format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn files(sizes: &[(&str, u64)]) -> Vec<(String, u64)> {
+        sizes
+            .iter()
+            .map(|(name, size)| (name.to_string(), *size))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_respects_image_count() {
+        let input = files(&[("a.jpg", 1), ("b.jpg", 1), ("c.jpg", 1), ("d.jpg", 1)]);
+        let chunks = plan_chunks_with_sizes(&input, 3, 10 * MB, "webp");
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), 3);
+        assert_eq!(chunks[1], vec!["d.jpg".to_string()]);
+    }
+
+    #[test]
+    fn test_plan_respects_size_budget() {
+        let input = files(&[("a.jpg", 4 * MB), ("b.jpg", 4 * MB), ("c.jpg", 4 * MB)]);
+        let chunks = plan_chunks_with_sizes(&input, 10, 10 * MB, "webp");
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], vec!["a.jpg".to_string(), "b.jpg".to_string()]);
+        assert_eq!(chunks[1], vec!["c.jpg".to_string()]);
+    }
+
+    #[test]
+    fn test_plan_uses_predicted_png_sizes() {
+        let input = files(&[("a.png", 8 * MB), ("b.png", 8 * MB), ("c.png", 8 * MB)]);
+        // Lossy WebP is predicted to shrink the PNGs enough to share a message
+        assert_eq!(plan_chunks_with_sizes(&input, 10, 10 * MB, "webp").len(), 1);
+        // Kept as PNG, each one needs a message of its own
+        assert_eq!(plan_chunks_with_sizes(&input, 10, 10 * MB, "png").len(), 3);
+    }
+
+    #[test]
+    fn test_plan_caps_chunks_at_discord_attachment_limit() {
+        let input: Vec<(String, u64)> = (0..25).map(|i| (format!("{i}.jpg"), 1)).collect();
+        let chunks = plan_chunks_with_sizes(&input, 25, 100 * MB, "webp");
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![10, 10, 5]);
+    }
+
+    #[test]
+    fn test_needs_conversion_sniffs_contents() {
+        use crate::test_helpers::{create_minimal_png, create_temp_png};
+
+        let mut bmp = Vec::new();
+        image::RgbImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut bmp), image::ImageFormat::Bmp)
+            .unwrap();
+        let bmp_file = create_temp_png(&bmp, "preflight_photo.bmp");
+        assert_eq!(sniff_image_mime(&bmp_file.path_str()), Some("image/bmp"));
+        assert!(needs_conversion(&bmp_file.path_str()));
+
+        // A PNG with the wrong extension is still fine as it is
+        let png_file = create_temp_png(&create_minimal_png(), "preflight_misnamed.bmp");
+        assert!(!needs_conversion(&png_file.path_str()));
+    }
+
+    #[test]
+    fn test_plan_isolates_oversized_file() {
+        let input = files(&[("a.jpg", MB), ("huge.jpg", 30 * MB), ("b.jpg", MB)]);
+        let chunks = plan_chunks_with_sizes(&input, 10, 10 * MB, "webp");
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1], vec!["huge.jpg".to_string()]);
+    }
+}
@@ -0,0 +1,263 @@
+// Clip compression - re-encodes oversized video clips with FFmpeg
+//
+// Clips are normally uploaded as recorded, so one over the webhook's limit can't be
+// sent. When clip compression is turned on, FFmpeg is looked up (the configured path,
+// next to the executable, then PATH), the clip's duration is probed, and it's
+// re-encoded at the bitrate that fits the limit. Like image compression the original
+// is left alone and the result is a temp copy; progress comes from FFmpeg's
+// `-progress` output.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::{AppError, AppResult};
+use crate::security::FileSystemGuard;
+
+/// Share of the upload limit the re-encoded clip aims for. Bitrates are averages, so
+/// the result can come out a little above the target.
+const TARGET_HEADROOM: f64 = 0.85;
+const AUDIO_BITRATE: u64 = 96_000;
+/// Below this the clip wouldn't be worth watching, so it's reported as too large
+const MIN_VIDEO_BITRATE: u64 = 150_000;
+
+fn ffmpeg_file_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "ffmpeg.exe"
+    } else {
+        "ffmpeg"
+    }
+}
+
+/// Find FFmpeg: the configured path, then one bundled next to the executable, then PATH
+pub fn locate_ffmpeg(configured: Option<&str>) -> Option<PathBuf> {
+    if let Some(path) = configured.map(str::trim).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        return path.is_file().then_some(path);
+    }
+
+    let bundled = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(ffmpeg_file_name())))
+        .filter(|path| path.is_file());
+    bundled.or_else(|| {
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(ffmpeg_file_name()))
+            .find(|path| path.is_file())
+    })
+}
+
+fn ffmpeg_command(ffmpeg: &Path) -> Command {
+    let mut command = Command::new(ffmpeg);
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command.stdin(Stdio::null()).kill_on_drop(true);
+    command
+}
+
+/// Duration in seconds from the "Duration: 00:01:23.45" line FFmpeg prints for an input
+fn parse_duration(ffmpeg_output: &str) -> Option<f64> {
+    let value = ffmpeg_output
+        .split("Duration: ")
+        .nth(1)?
+        .split(',')
+        .next()?;
+    let mut parts = value.trim().split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let duration = hours * 3600.0 + minutes * 60.0 + seconds;
+    (duration > 0.0).then_some(duration)
+}
+
+/// Seconds encoded so far, from an `out_time_us=` line of FFmpeg's progress output
+fn parse_progress_line(line: &str) -> Option<f64> {
+    let micros: f64 = line.strip_prefix("out_time_us=")?.trim().parse().ok()?;
+    Some(micros / 1_000_000.0)
+}
+
+/// Video bitrate (bits/s) that fits a clip of `duration` seconds into `size_limit`
+/// bytes next to the audio track, or None when that would be unwatchably low
+fn target_video_bitrate(size_limit: u64, duration: f64) -> Option<u64> {
+    let total = (size_limit as f64 * TARGET_HEADROOM * 8.0 / duration) as u64;
+    total
+        .checked_sub(AUDIO_BITRATE)
+        .filter(|&bitrate| bitrate >= MIN_VIDEO_BITRATE)
+}
+
+/// FFmpeg arguments to re-encode `input` at `video_bitrate`, keeping its container:
+/// H.264/AAC for MP4 and VP9/Opus for WebM, both of which Discord plays inline
+fn encode_args(input: &str, output: &str, video_bitrate: u64) -> Vec<String> {
+    let is_webm = Path::new(input)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("webm"));
+    let mut args: Vec<String> = ["-hide_banner", "-loglevel", "error", "-y", "-i", input]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    let codecs: &[&str] = if is_webm {
+        &[
+            "-c:v",
+            "libvpx-vp9",
+            "-deadline",
+            "realtime",
+            "-cpu-used",
+            "8",
+            "-c:a",
+            "libopus",
+        ]
+    } else {
+        &[
+            "-c:v",
+            "libx264",
+            "-preset",
+            "veryfast",
+            "-c:a",
+            "aac",
+            "-movflags",
+            "+faststart",
+        ]
+    };
+    args.extend(codecs.iter().map(|s| s.to_string()));
+    args.extend([
+        "-b:v".to_string(),
+        video_bitrate.to_string(),
+        "-maxrate".to_string(),
+        video_bitrate.to_string(),
+        "-bufsize".to_string(),
+        (video_bitrate * 2).to_string(),
+        "-b:a".to_string(),
+        AUDIO_BITRATE.to_string(),
+        "-progress".to_string(),
+        "pipe:1".to_string(),
+        output.to_string(),
+    ]);
+    args
+}
+
+/// Clip length in seconds. FFmpeg run with only an input prints its details and
+/// exits with an error, which is expected here.
+async fn probe_duration(ffmpeg: &Path, input: &str) -> AppResult<f64> {
+    let output = ffmpeg_command(ffmpeg)
+        .args(["-hide_banner", "-i", input])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
+    parse_duration(&String::from_utf8_lossy(&output.stderr)).ok_or_else(|| {
+        AppError::ImageProcessing(format!("FFmpeg couldn't read the length of {input}"))
+    })
+}
+
+/// Re-encode a clip to fit in `size_limit` bytes, calling `on_progress` with the
+/// percentage done. Returns the temp copy, or None if cancelled first.
+pub async fn compress_clip(
+    ffmpeg: &Path,
+    input: &str,
+    size_limit: u64,
+    cancel_token: &CancellationToken,
+    mut on_progress: impl FnMut(f32),
+) -> AppResult<Option<String>> {
+    let duration = probe_duration(ffmpeg, input).await?;
+    let video_bitrate =
+        target_video_bitrate(size_limit, duration).ok_or_else(|| AppError::UploadFailed {
+            reason: format!(
+                "{input} is too long ({duration:.0}s) to fit the {:.0} MB upload limit at a \
+                 watchable quality",
+                size_limit as f64 / 1024.0 / 1024.0
+            ),
+        })?;
+
+    let output = FileSystemGuard::create_secure_temp_file(input)?
+        .to_string_lossy()
+        .to_string();
+    log::info!(
+        "Re-encoding {input} ({duration:.1}s) at {} kbps with {}",
+        video_bitrate / 1000,
+        ffmpeg.display()
+    );
+
+    let mut child = ffmpeg_command(ffmpeg)
+        .args(encode_args(input, &output, video_bitrate))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stderr = child.stderr.take();
+    let errors = tokio::spawn(async move {
+        let mut text = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            stderr.read_to_string(&mut text).await.ok();
+        }
+        text
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        loop {
+            tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => {
+                        if let Some(done) = parse_progress_line(&line) {
+                            on_progress((done / duration * 100.0).clamp(0.0, 100.0) as f32);
+                        }
+                    }
+                    _ => break,
+                },
+                _ = cancel_token.cancelled() => {
+                    child.kill().await.ok();
+                    tokio::fs::remove_file(&output).await.ok();
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        tokio::fs::remove_file(&output).await.ok();
+        let errors = errors.await.unwrap_or_default();
+        return Err(AppError::ImageProcessing(format!(
+            "FFmpeg failed to re-encode {input}: {}",
+            errors.lines().last().unwrap_or("unknown error")
+        )));
+    }
+    Ok(Some(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_ffmpeg_duration_and_progress() {
+        let probe = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'clip.mp4':\n  \
+                     Duration: 00:01:02.50, start: 0.000000, bitrate: 8123 kb/s";
+        assert_eq!(parse_duration(probe), Some(62.5));
+        assert_eq!(parse_duration("Duration: N/A, bitrate: N/A"), None);
+
+        assert_eq!(parse_progress_line("out_time_us=31250000"), Some(31.25));
+        assert_eq!(parse_progress_line("progress=continue"), None);
+    }
+
+    #[test]
+    fn test_target_bitrate_fits_limit() {
+        const MB: u64 = 1024 * 1024;
+        // 10 MB over 20s leaves about 3.4 Mbps for video next to the audio
+        let bitrate = target_video_bitrate(10 * MB, 20.0).unwrap();
+        assert!(bitrate > 3_000_000 && bitrate < 3_600_000);
+        // Ten minutes into 10 MB isn't worth sending
+        assert_eq!(target_video_bitrate(10 * MB, 600.0), None);
+
+        let args = encode_args("clip.webm", "out.webm", bitrate);
+        assert!(args.contains(&"libvpx-vp9".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("out.webm"));
+        assert!(encode_args("clip.mp4", "out.mp4", bitrate).contains(&"libx264".to_string()));
+    }
+}
@@ -0,0 +1,205 @@
+// Compression worker pool - pre-compresses files ahead of their upload
+//
+// Chunks that are known to exceed the upload budget are queued here when a session
+// starts. A small pool of workers compresses them in order while earlier chunks are
+// still uploading, and the upload path picks up the finished files instead of
+// compressing inline.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::{image_processor, temp_manifest};
+
+static PREFETCH_JOBS: OnceLock<Mutex<HashMap<String, SessionJobs>>> = OnceLock::new();
+
+struct SessionJobs {
+    quality: u8,
+    format: String,
+    cancel_token: CancellationToken,
+    jobs: HashMap<String, JoinHandle<Option<String>>>,
+}
+
+fn jobs() -> &'static Mutex<HashMap<String, SessionJobs>> {
+    PREFETCH_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Stops the session's remaining prefetch work and removes unused outputs when dropped
+pub struct PrefetchGuard {
+    session_id: String,
+}
+
+impl Drop for PrefetchGuard {
+    fn drop(&mut self) {
+        finish(&self.session_id);
+    }
+}
+
+/// Workers leave a core free for the upload itself
+fn worker_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|p| p.get())
+        .unwrap_or(4)
+        .saturating_sub(1)
+        .clamp(1, 8)
+}
+
+/// Start compressing `file_paths` in the background for the session. Files are
+/// picked up in the given order, so pass them in upload order. Queued work stops
+/// when `session_token` is cancelled.
+pub fn start(
+    session_id: &str,
+    file_paths: Vec<String>,
+    quality: u8,
+    format: &str,
+    session_token: &CancellationToken,
+) -> PrefetchGuard {
+    let guard = PrefetchGuard {
+        session_id: session_id.to_string(),
+    };
+    if file_paths.is_empty() {
+        return guard;
+    }
+
+    log::info!(
+        "Pre-compressing {} file(s) for session {session_id} ({format}, quality {quality})",
+        file_paths.len()
+    );
+
+    let semaphore = Arc::new(Semaphore::new(worker_count()));
+    let cancel_token = session_token.child_token();
+    let mut session_jobs = SessionJobs {
+        quality,
+        format: format.to_string(),
+        cancel_token: cancel_token.clone(),
+        jobs: HashMap::with_capacity(file_paths.len()),
+    };
+
+    for file_path in file_paths {
+        if session_jobs.jobs.contains_key(&file_path) {
+            continue;
+        }
+        let semaphore = semaphore.clone();
+        let cancel_token = cancel_token.clone();
+        let format = format.to_string();
+        let path = file_path.clone();
+        let handle = tokio::spawn(temp_manifest::inherit(async move {
+            let _permit = tokio::select! {
+                _ = cancel_token.cancelled() => return None,
+                permit = semaphore.acquire_owned() => permit.ok()?,
+            };
+            match image_processor::compress_image_with_format(&path, quality, &format, None).await {
+                Ok(compressed) => Some(compressed),
+                Err(e) => {
+                    log::warn!("Pre-compression failed for {path}: {e}");
+                    None
+                }
+            }
+        }));
+        session_jobs.jobs.insert(file_path, handle);
+    }
+
+    if let Some(previous) = jobs()
+        .lock()
+        .unwrap()
+        .insert(session_id.to_string(), session_jobs)
+    {
+        discard(previous);
+    }
+    guard
+}
+
+/// Take the pre-compressed output for a file, waiting if its worker is still running.
+/// Returns `None` when the file wasn't queued or was queued with other settings;
+/// the caller owns (and cleans up) the returned file.
+pub async fn take(session_id: &str, file_path: &str, quality: u8, format: &str) -> Option<String> {
+    let handle = {
+        let mut jobs = jobs().lock().unwrap();
+        let session_jobs = jobs.get_mut(session_id)?;
+        if session_jobs.quality != quality || session_jobs.format != format {
+            return None;
+        }
+        session_jobs.jobs.remove(file_path)?
+    };
+    handle.await.ok().flatten()
+}
+
+fn finish(session_id: &str) {
+    let session_jobs = jobs().lock().unwrap().remove(session_id);
+    if let Some(session_jobs) = session_jobs {
+        discard(session_jobs);
+    }
+}
+
+/// Cancel queued work and delete whatever finishes without being taken
+fn discard(session_jobs: SessionJobs) {
+    session_jobs.cancel_token.cancel();
+    if session_jobs.jobs.is_empty() {
+        return;
+    }
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    runtime.spawn(async move {
+        for (_, handle) in session_jobs.jobs {
+            if let Ok(Some(path)) = handle.await {
+                tokio::fs::remove_file(&path).await.ok();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_test_png() -> String {
+        let path = std::env::temp_dir().join(format!("prefetch_test_{}.png", uuid::Uuid::new_v4()));
+        image::RgbaImage::from_pixel(16, 16, image::Rgba([200, 100, 50, 255]))
+            .save(&path)
+            .unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_take_returns_prefetched_file_once() {
+        let source = write_test_png();
+        let session_id = format!("prefetch-{}", uuid::Uuid::new_v4());
+        let _guard = start(
+            &session_id,
+            vec![source.clone()],
+            80,
+            "webp",
+            &CancellationToken::new(),
+        );
+
+        // Other settings don't match what was queued
+        assert!(take(&session_id, &source, 80, "jpg").await.is_none());
+
+        let compressed = take(&session_id, &source, 80, "webp").await.unwrap();
+        assert!(std::path::Path::new(&compressed).exists());
+        assert!(take(&session_id, &source, 80, "webp").await.is_none());
+
+        let _ = std::fs::remove_file(&compressed);
+        let _ = std::fs::remove_file(&source);
+    }
+
+    #[tokio::test]
+    async fn test_dropping_guard_forgets_session() {
+        let source = write_test_png();
+        let session_id = format!("prefetch-{}", uuid::Uuid::new_v4());
+        drop(start(
+            &session_id,
+            vec![source.clone()],
+            80,
+            "webp",
+            &CancellationToken::new(),
+        ));
+
+        assert!(take(&session_id, &source, 80, "webp").await.is_none());
+        let _ = std::fs::remove_file(&source);
+    }
+}
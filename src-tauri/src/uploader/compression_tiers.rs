@@ -0,0 +1,110 @@
+// Compression tiers - how far a chunk is degraded when it's still too large
+//
+// After the first compressed attempt hits Discord's size limit, each retry steps down
+// one tier: lossless WebP, then lossy WebP at falling quality, then lossy WebP at
+// falling resolution. The lowest quality and resolution are configurable, so users
+// who'd rather have a failed upload than a blurry one can stop the ladder early.
+
+/// Lossy qualities tried in order before any downscaling
+const QUALITY_STEPS: &[u8] = &[90, 70, 55];
+/// Resolution scales tried in order once quality is at its floor
+const SCALE_STEPS: &[u8] = &[75, 50, 25];
+
+pub const DEFAULT_MIN_QUALITY: u8 = 55;
+pub const DEFAULT_MIN_SCALE_PERCENT: u8 = 25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionTier {
+    pub format: String,
+    pub quality: u8,
+    /// Resolution scale; None keeps the original size
+    pub scale: Option<f32>,
+}
+
+/// Retry tiers after the first compressed attempt, never going below `min_quality`
+/// or `min_scale_percent` of the original resolution
+pub fn fallback_tiers(
+    start_quality: u8,
+    min_quality: u8,
+    min_scale_percent: u8,
+) -> Vec<CompressionTier> {
+    let min_quality = min_quality.clamp(1, 100);
+    let min_scale_percent = min_scale_percent.clamp(1, 100);
+
+    let mut tiers = vec![CompressionTier {
+        format: "lossless_webp".to_string(),
+        quality: start_quality,
+        scale: None,
+    }];
+    let mut qualities: Vec<u8> = QUALITY_STEPS
+        .iter()
+        .map(|&quality| quality.max(min_quality))
+        .collect();
+    qualities.dedup();
+    tiers.extend(qualities.into_iter().map(|quality| CompressionTier {
+        format: "webp".to_string(),
+        quality,
+        scale: None,
+    }));
+    tiers.extend(
+        SCALE_STEPS
+            .iter()
+            .filter(|&&percent| percent >= min_scale_percent)
+            .map(|&percent| CompressionTier {
+                format: "webp".to_string(),
+                quality: min_quality,
+                scale: Some(f32::from(percent) / 100.0),
+            }),
+    );
+    tiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(tiers: &[CompressionTier]) -> Vec<(&str, u8, Option<f32>)> {
+        tiers
+            .iter()
+            .map(|t| (t.format.as_str(), t.quality, t.scale))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_tiers_lower_quality_then_resolution() {
+        let tiers = fallback_tiers(85, DEFAULT_MIN_QUALITY, DEFAULT_MIN_SCALE_PERCENT);
+        assert_eq!(
+            summary(&tiers),
+            vec![
+                ("lossless_webp", 85, None),
+                ("webp", 90, None),
+                ("webp", 70, None),
+                ("webp", 55, None),
+                ("webp", 55, Some(0.75)),
+                ("webp", 55, Some(0.5)),
+                ("webp", 55, Some(0.25)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tiers_respect_configured_bounds() {
+        // Never below 80% quality, never below half resolution
+        let tiers = fallback_tiers(85, 80, 50);
+        assert_eq!(
+            summary(&tiers),
+            vec![
+                ("lossless_webp", 85, None),
+                ("webp", 90, None),
+                ("webp", 80, None),
+                ("webp", 80, Some(0.75)),
+                ("webp", 80, Some(0.5)),
+            ]
+        );
+
+        // No downscaling at all
+        assert!(fallback_tiers(85, 55, 100)
+            .iter()
+            .all(|t| t.scale.is_none()));
+    }
+}
@@ -1,10 +1,17 @@
+use super::rate_scheduler::RateScheduler;
+use super::verification::UploadVerification;
 use crate::errors::{AppError, AppResult};
 use reqwest::{multipart, Client};
 use std::cmp::min;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use tauri::Manager;
 use tokio::time::{sleep, Duration, Instant};
+use tokio_util::io::ReaderStream;
+use tokio_util::sync::CancellationToken;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
@@ -25,11 +32,166 @@ impl Default for RetryConfig {
     }
 }
 
+/// Don't unfurl link embeds (the VRChat/VRCX world links) under the message
+pub const FLAG_SUPPRESS_EMBEDS: u64 = 1 << 2;
+/// Post without sending push/desktop notifications
+pub const FLAG_SUPPRESS_NOTIFICATIONS: u64 = 1 << 12;
+
+pub fn message_flags(suppress_embeds: bool, suppress_notifications: bool) -> u64 {
+    let mut flags = 0;
+    if suppress_embeds {
+        flags |= FLAG_SUPPRESS_EMBEDS;
+    }
+    if suppress_notifications {
+        flags |= FLAG_SUPPRESS_NOTIFICATIONS;
+    }
+    flags
+}
+
+/// Discord accepts at most this many user IDs in `allowed_mentions.users`
+const MAX_ALLOWED_MENTION_USERS: usize = 100;
+
+/// User IDs of the `<@id>` / `<@!id>` mentions in message text, in order, without repeats
+fn mentioned_user_ids(content: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("<@") {
+        rest = &rest[start + 2..];
+        let candidate = rest.strip_prefix('!').unwrap_or(rest);
+        let digits = candidate
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(candidate.len());
+        if digits > 0 && candidate[digits..].starts_with('>') {
+            let id = &candidate[..digits];
+            if !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+    ids
+}
+
+/// `allowed_mentions` that only lets the message ping the mapped Discord users it
+/// mentions. Everything else (`@everyone`, roles, mention text typed into a display
+/// name) is shown without notifying anyone.
+pub fn allowed_mentions(content: &str, mention_users: &[String]) -> serde_json::Value {
+    let users: Vec<String> = mentioned_user_ids(content)
+        .into_iter()
+        .filter(|id| mention_users.contains(id))
+        .take(MAX_ALLOWED_MENTION_USERS)
+        .collect();
+    serde_json::json!({ "parse": [], "users": users })
+}
+
+/// End of a global rate limit, shared by every client since it covers all webhooks
+static GLOBAL_RATE_LIMIT_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Hold off all requests for `delay` after a global 429
+fn set_global_rate_limit(delay: Duration) {
+    if let Ok(mut until) = GLOBAL_RATE_LIMIT_UNTIL.lock() {
+        let new_until = Instant::now() + delay;
+        // Never shorten a pause another request already started
+        if until.is_none_or(|current| current < new_until) {
+            *until = Some(new_until);
+        }
+    }
+}
+
+/// How much longer a global rate limit lasts, if one is active
+fn global_rate_limit_remaining() -> Option<Duration> {
+    let until = (*GLOBAL_RATE_LIMIT_UNTIL.lock().ok()?)?;
+    until
+        .checked_duration_since(Instant::now())
+        .filter(|d| !d.is_zero())
+}
+
+async fn wait_for_global_rate_limit() {
+    while let Some(remaining) = global_rate_limit_remaining() {
+        log::debug!("Waiting {remaining:?} for Discord's global rate limit");
+        sleep(remaining).await;
+    }
+}
+
+/// Log how long Discord took to answer. Requests on a reused connection skip the
+/// TCP/TLS setup, so a first request that's much slower than the rest is that cost.
+fn log_request_timing(started: Instant, response: &reqwest::Response) {
+    log::debug!(
+        "Discord responded {} in {} ms over {:?}",
+        response.status(),
+        started.elapsed().as_millis(),
+        response.version()
+    );
+}
+
+/// Whether a 429 body says the limit is global rather than per route
+fn is_global_rate_limit(error_text: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(error_text)
+        .ok()
+        .and_then(|json| json.get("global").and_then(|g| g.as_bool()))
+        .unwrap_or(false)
+}
+
+/// Connection pool and per-webhook rate scheduler shared by every client. Kept in Tauri
+/// state so sessions, retries and message edits reuse open connections to Discord
+/// instead of paying for a new TLS handshake each time.
+#[derive(Clone)]
+pub struct DiscordConnectionPool {
+    client: Client,
+    scheduler: Arc<RateScheduler>,
+}
+
+impl Default for DiscordConnectionPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiscordConnectionPool {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(120))
+                // Keep connections open between chunks and across sessions
+                .pool_idle_timeout(Duration::from_secs(90))
+                .pool_max_idle_per_host(4)
+                .tcp_keepalive(Duration::from_secs(60))
+                .build()
+                .unwrap(),
+            scheduler: Arc::new(RateScheduler::new()),
+        }
+    }
+}
+
 /// Discord API client with rate limiting
 pub struct DiscordClient {
     client: Client,
-    rate_limiter: Arc<Mutex<HashMap<String, Instant>>>,
+    scheduler: Arc<RateScheduler>,
     retry_config: RetryConfig,
+    /// Webhook details per URL (None if the lookup failed), needed for jump links
+    /// and for addressing messages posted in threads
+    webhook_details: Mutex<HashMap<String, Option<WebhookDetails>>>,
+    /// Message flags set on everything posted through a webhook, per URL
+    message_flags: Mutex<HashMap<String, u64>>,
+    /// Forum tag IDs applied to threads created through a webhook, per URL
+    forum_tags: Mutex<HashMap<String, Vec<String>>>,
+    /// Send `allowed_mentions` with every message so mention text in captions (player
+    /// names, comments) can't ping anyone beyond `mention_users`
+    restrict_mentions: AtomicBool,
+    /// Discord user IDs captions may @mention
+    mention_users: Mutex<Vec<String>>,
+    /// Send attachment names as they are on disk instead of their ASCII form
+    keep_original_filenames: AtomicBool,
+    /// How attachments are checked after a successful upload
+    upload_verification: Mutex<UploadVerification>,
+    /// Session ID and token that abort requests and retry waits once cancelled
+    cancellation: Option<(String, CancellationToken)>,
+}
+
+/// Parts of the GET webhook response the uploader cares about
+#[derive(Debug, Clone)]
+struct WebhookDetails {
+    guild_id: Option<String>,
+    channel_id: Option<String>,
 }
 
 impl Default for DiscordClient {
@@ -39,14 +201,221 @@ impl Default for DiscordClient {
 }
 
 impl DiscordClient {
+    /// A client with its own connections; prefer `for_app` where an app handle is around
     pub fn new() -> Self {
+        Self::with_pool(&DiscordConnectionPool::new())
+    }
+
+    /// A client on the app's shared connection pool
+    pub fn for_app(app_handle: &tauri::AppHandle) -> Self {
+        match app_handle.try_state::<DiscordConnectionPool>() {
+            Some(pool) => Self::with_pool(&pool),
+            None => Self::new(),
+        }
+    }
+
+    pub fn with_pool(pool: &DiscordConnectionPool) -> Self {
         Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(120))
-                .build()
-                .unwrap(),
-            rate_limiter: Arc::new(Mutex::new(HashMap::new())),
+            client: pool.client.clone(),
+            scheduler: Arc::clone(&pool.scheduler),
             retry_config: RetryConfig::default(),
+            webhook_details: Mutex::new(HashMap::new()),
+            message_flags: Mutex::new(HashMap::new()),
+            forum_tags: Mutex::new(HashMap::new()),
+            restrict_mentions: AtomicBool::new(true),
+            mention_users: Mutex::new(Vec::new()),
+            keep_original_filenames: AtomicBool::new(false),
+            upload_verification: Mutex::new(UploadVerification::Off),
+            cancellation: None,
+        }
+    }
+
+    /// Tie this client to an upload session; once `token` is cancelled, in-flight
+    /// requests are dropped and calls fail with `UploadCancelled`
+    pub fn with_cancellation(mut self, session_id: &str, token: CancellationToken) -> Self {
+        self.cancellation = Some((session_id.to_string(), token));
+        self
+    }
+
+    /// Run `future` unless the session is cancelled first
+    async fn cancellable<T>(&self, future: impl std::future::Future<Output = T>) -> AppResult<T> {
+        match &self.cancellation {
+            Some((session_id, token)) => tokio::select! {
+                biased;
+                _ = token.cancelled() => {
+                    Err(AppError::upload_cancelled("Discord request", session_id))
+                }
+                output = future => Ok(output),
+            },
+            None => Ok(future.await),
+        }
+    }
+
+    /// Set the message flags (see `message_flags`) used for every message this client
+    /// posts through the webhook
+    pub fn set_message_flags(&self, webhook_url: &str, flags: u64) {
+        if let Ok(mut message_flags) = self.message_flags.lock() {
+            message_flags.insert(webhook_url.to_string(), flags);
+        }
+    }
+
+    /// Space requests to the webhook at least `interval` apart, for every client on
+    /// the pool
+    pub fn set_request_interval(&self, webhook_url: &str, interval: Duration) {
+        self.scheduler
+            .set_interval(&self.extract_webhook_id(webhook_url), interval);
+    }
+
+    fn message_flags(&self, webhook_url: &str) -> u64 {
+        self.message_flags
+            .lock()
+            .ok()
+            .and_then(|message_flags| message_flags.get(webhook_url).copied())
+            .unwrap_or(0)
+    }
+
+    /// Set the forum tags applied to threads this client creates through the webhook
+    pub fn set_forum_tags(&self, webhook_url: &str, tag_ids: Vec<String>) {
+        if let Ok(mut forum_tags) = self.forum_tags.lock() {
+            forum_tags.insert(webhook_url.to_string(), tag_ids);
+        }
+    }
+
+    /// Set the Discord users that mapped players may ping (see `allowed_mentions`)
+    pub fn set_mention_users(&self, user_ids: Vec<String>) {
+        if let Ok(mut mention_users) = self.mention_users.lock() {
+            *mention_users = user_ids;
+        }
+    }
+
+    /// Turn `allowed_mentions` off to let Discord resolve every mention in the text
+    pub fn set_restrict_mentions(&self, restrict: bool) {
+        self.restrict_mentions.store(restrict, Ordering::Relaxed);
+    }
+
+    /// Keep non-ASCII attachment names (see `multipart_filename`) instead of transliterating them
+    pub fn set_keep_original_filenames(&self, keep: bool) {
+        self.keep_original_filenames.store(keep, Ordering::Relaxed);
+    }
+
+    pub fn set_upload_verification(&self, verification: UploadVerification) {
+        if let Ok(mut current) = self.upload_verification.lock() {
+            *current = verification;
+        }
+    }
+
+    pub fn upload_verification(&self) -> UploadVerification {
+        self.upload_verification
+            .lock()
+            .map(|v| *v)
+            .unwrap_or_default()
+    }
+
+    /// `allowed_mentions` for a message with this text, if mentions are being restricted
+    fn allowed_mentions(&self, content: Option<&str>) -> Option<serde_json::Value> {
+        if !self.restrict_mentions.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mention_users = self.mention_users.lock().ok()?;
+        Some(allowed_mentions(
+            content.unwrap_or_default(),
+            &mention_users,
+        ))
+    }
+
+    fn forum_tags(&self, webhook_url: &str) -> Vec<String> {
+        self.forum_tags
+            .lock()
+            .ok()
+            .and_then(|forum_tags| forum_tags.get(webhook_url).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Guild the webhook posts into (GET on the webhook URL), cached per URL.
+    /// None if Discord doesn't report one, in which case no jump links can be built.
+    pub async fn get_webhook_guild_id(&self, webhook_url: &str) -> Option<String> {
+        self.get_webhook_details(webhook_url).await?.guild_id
+    }
+
+    /// Channel the webhook posts into; messages in any other channel were posted in a thread
+    pub async fn get_webhook_channel_id(&self, webhook_url: &str) -> Option<String> {
+        self.get_webhook_details(webhook_url).await?.channel_id
+    }
+
+    async fn get_webhook_details(&self, webhook_url: &str) -> Option<WebhookDetails> {
+        if let Some(cached) = self
+            .webhook_details
+            .lock()
+            .ok()
+            .and_then(|m| m.get(webhook_url).cloned())
+        {
+            return cached;
+        }
+
+        let details = match self.fetch_webhook_details(webhook_url).await {
+            Ok(details) => details,
+            // Not cached, the next session looks it up again
+            Err(AppError::UploadCancelled { .. }) => return None,
+            Err(e) => {
+                log::warn!("Webhook lookup failed: {e}");
+                None
+            }
+        };
+
+        if let Ok(mut cache) = self.webhook_details.lock() {
+            cache.insert(webhook_url.to_string(), details.clone());
+        }
+        details
+    }
+
+    /// GET on the webhook URL, taking its turn in the webhook's rate limit bucket like
+    /// posts do and dropped if the session is cancelled
+    async fn fetch_webhook_details(&self, webhook_url: &str) -> AppResult<Option<WebhookDetails>> {
+        let webhook_id = self.extract_webhook_id(webhook_url);
+        self.cancellable(wait_for_global_rate_limit()).await?;
+        self.cancellable(self.scheduler.acquire(&webhook_id))
+            .await?;
+        let response = self
+            .cancellable(self.client.get(webhook_url).send())
+            .await??;
+        self.scheduler
+            .record_response(&webhook_id, response.headers());
+
+        if !response.status().is_success() {
+            log::warn!("Webhook lookup returned {}", response.status());
+            return Ok(None);
+        }
+        let json = self
+            .cancellable(response.json::<serde_json::Value>())
+            .await?
+            .ok();
+        Ok(json.map(|json| WebhookDetails {
+            guild_id: json
+                .get("guild_id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            channel_id: json
+                .get("channel_id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        }))
+    }
+
+    /// Quick connectivity probe: any HTTP response from Discord counts as reachable,
+    /// only connection failures and timeouts count as offline
+    pub async fn is_discord_reachable(&self) -> bool {
+        match self
+            .client
+            .get("https://discord.com/api/v10/gateway")
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+        {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("Discord connectivity check failed: {e}");
+                false
+            }
         }
     }
 
@@ -57,12 +426,24 @@ impl DiscordClient {
         thread_id: Option<&str>,
     ) -> AppResult<String> {
         let webhook_id = self.extract_webhook_id(webhook_url);
-        self.wait_for_rate_limit(&webhook_id).await;
-
+        let flags = self.message_flags(webhook_url);
+        let forum_tags = self.forum_tags(webhook_url);
+        let allowed_mentions =
+            self.allowed_mentions(payload.text_fields.get("content").map(String::as_str));
         let mut attempt = 0;
 
         loop {
-            let form = payload.build_form()?;
+            self.cancellable(wait_for_global_rate_limit()).await?;
+            self.cancellable(self.scheduler.acquire(&webhook_id))
+                .await?;
+            let form = payload
+                .build_form(
+                    flags,
+                    &forum_tags,
+                    allowed_mentions.as_ref(),
+                    self.keep_original_filenames.load(Ordering::Relaxed),
+                )
+                .await?;
 
             // Build URL with required query parameters
             let mut url_parts = vec![];
@@ -84,12 +465,17 @@ impl DiscordClient {
 
             log::debug!("Final webhook URL: {final_url}");
 
-            let response = self.client.post(&final_url).multipart(form).send().await?;
+            let started = Instant::now();
+            let response = self
+                .cancellable(self.client.post(&final_url).multipart(form).send())
+                .await??;
+            log_request_timing(started, &response);
 
             let status = response.status();
 
             // Update rate limit state based on response headers
-            self.update_rate_limit(&webhook_id, &response).await;
+            self.scheduler
+                .record_response(&webhook_id, response.headers());
 
             if status.is_success() {
                 let response_text = response.text().await?;
@@ -119,15 +505,10 @@ impl DiscordClient {
             // Check if we should retry
             attempt += 1;
             if should_retry_error(status.as_u16()) && attempt <= self.retry_config.max_retries {
-                let delay = if status == 429 {
-                    self.extract_retry_after(&error_text)
-                        .unwrap_or_else(|| self.calculate_backoff_delay(attempt))
-                } else {
-                    self.calculate_backoff_delay(attempt)
-                };
+                let delay = self.retry_delay(&webhook_id, status, &error_text, attempt);
 
                 log::warn!("Upload attempt {attempt} failed, retrying in {delay:?}: {error}");
-                sleep(delay).await;
+                self.cancellable(sleep(delay)).await?;
                 continue;
             }
 
@@ -142,45 +523,178 @@ impl DiscordClient {
         content: &str,
         thread_name: Option<&str>,
     ) -> AppResult<String> {
+        // Build JSON body with thread_name for forum channels
+        let mut body = serde_json::json!({ "content": content });
+        if let Some(name) = thread_name {
+            body["thread_name"] = serde_json::Value::String(name.to_string());
+        }
+
+        match self
+            .send_json_message(webhook_url, None, false, &body)
+            .await
+        {
+            Ok(response_text) => {
+                log::info!("✅ Forum text message sent successfully. Response: {response_text}");
+                Ok(response_text)
+            }
+            Err(e) => {
+                log::error!("❌ Failed to send forum text message: {e}");
+                Err(e)
+            }
+        }
+    }
+
+    /// Download a posted attachment from Discord's CDN
+    pub async fn download_attachment(&self, url: &str) -> AppResult<Vec<u8>> {
+        let response = self
+            .cancellable(self.client.get(url).send())
+            .await??
+            .error_for_status()?;
+        Ok(self.cancellable(response.bytes()).await??.to_vec())
+    }
+
+    /// Send text-only message (for player list follow-ups)
+    pub async fn send_text_message(
+        &self,
+        webhook_url: &str,
+        content: &str,
+        thread_id: Option<&str>,
+    ) -> AppResult<()> {
+        self.post_text_message(webhook_url, content, thread_id)
+            .await?;
+        log::debug!("Text message sent successfully");
+        Ok(())
+    }
+
+    /// Send a text-only message, returning Discord's response (the posted message)
+    pub async fn post_text_message(
+        &self,
+        webhook_url: &str,
+        content: &str,
+        thread_id: Option<&str>,
+    ) -> AppResult<String> {
+        let body = serde_json::json!({ "content": content });
+        self.send_json_message(webhook_url, thread_id, false, &body)
+            .await
+    }
+
+    /// Send a text message with link buttons. If Discord refuses the components (e.g.
+    /// the webhook isn't allowed to send them), `content`, which carries the same links
+    /// as text, is sent instead. Any other failure is returned as-is: after a timeout or
+    /// server error the first message may well have been posted.
+    pub async fn send_text_message_with_link_buttons(
+        &self,
+        webhook_url: &str,
+        content: &str,
+        link_buttons: &LinkButtons,
+        thread_id: Option<&str>,
+        thread_name: Option<&str>,
+    ) -> AppResult<String> {
+        let mut body = serde_json::json!({
+            "content": link_buttons.content,
+            "components": link_buttons.components,
+        });
+        if let Some(name) = thread_name {
+            body["thread_name"] = serde_json::Value::String(name.to_string());
+        }
+
+        match self
+            .post_json_message(webhook_url, thread_id, true, &body)
+            .await?
+        {
+            JsonPostOutcome::Sent(response_text) => Ok(response_text),
+            JsonPostOutcome::Rejected { status, error_text }
+                if components_rejected(status, &error_text) =>
+            {
+                log::warn!(
+                    "Link buttons were rejected ({}), falling back to text links",
+                    parse_discord_error_message(&error_text, status)
+                );
+                body["content"] = serde_json::Value::String(content.to_string());
+                if let Some(body) = body.as_object_mut() {
+                    body.remove("components");
+                }
+                self.send_json_message(webhook_url, thread_id, false, &body)
+                    .await
+            }
+            rejected => rejected.into_result(),
+        }
+    }
+
+    /// Post a JSON message with retries, returning the response body
+    async fn send_json_message(
+        &self,
+        webhook_url: &str,
+        thread_id: Option<&str>,
+        with_components: bool,
+        body: &serde_json::Value,
+    ) -> AppResult<String> {
+        self.post_json_message(webhook_url, thread_id, with_components, body)
+            .await?
+            .into_result()
+    }
+
+    /// Post a JSON message with retries. Network errors and cancellation are errors;
+    /// a response Discord refused after the retries is returned as `Rejected`.
+    async fn post_json_message(
+        &self,
+        webhook_url: &str,
+        thread_id: Option<&str>,
+        with_components: bool,
+        body: &serde_json::Value,
+    ) -> AppResult<JsonPostOutcome> {
         let webhook_id = self.extract_webhook_id(webhook_url);
-        self.wait_for_rate_limit(&webhook_id).await;
 
-        let mut attempt = 0;
+        // Build URL with required query parameters
+        let mut url_parts = vec!["wait=true".to_string()];
+        if let Some(tid) = thread_id {
+            url_parts.push(format!("thread_id={tid}"));
+        }
+        // Webhooks not owned by an application only send components when asked to
+        if with_components {
+            url_parts.push("with_components=true".to_string());
+        }
 
-        loop {
-            let final_url = if webhook_url.contains('?') {
-                format!("{webhook_url}&wait=true")
-            } else {
-                format!("{webhook_url}?wait=true")
-            };
+        let final_url = if webhook_url.contains('?') {
+            format!("{}&{}", webhook_url, url_parts.join("&"))
+        } else {
+            format!("{}?{}", webhook_url, url_parts.join("&"))
+        };
 
-            // Build JSON body with thread_name for forum channels
-            let body = if let Some(name) = thread_name {
-                serde_json::json!({
-                    "content": content,
-                    "thread_name": name
-                })
-            } else {
-                serde_json::json!({
-                    "content": content
-                })
-            };
+        let mut body = body.clone();
+        let flags = self.message_flags(webhook_url);
+        if flags != 0 {
+            body["flags"] = serde_json::Value::from(flags);
+        }
+        let forum_tags = self.forum_tags(webhook_url);
+        if body.get("thread_name").is_some() && !forum_tags.is_empty() {
+            body["applied_tags"] = serde_json::json!(forum_tags);
+        }
+        if let Some(allowed_mentions) = self.allowed_mentions(body["content"].as_str()) {
+            body["allowed_mentions"] = allowed_mentions;
+        }
 
-            let response = self
+        let mut attempt = 0;
+
+        loop {
+            self.cancellable(wait_for_global_rate_limit()).await?;
+            self.cancellable(self.scheduler.acquire(&webhook_id))
+                .await?;
+            let started = Instant::now();
+            let request = self
                 .client
                 .post(&final_url)
                 .header("Content-Type", "application/json")
-                .body(body.to_string())
-                .send()
-                .await?;
+                .body(body.to_string());
+            let response = self.cancellable(request.send()).await??;
+            log_request_timing(started, &response);
 
             let status = response.status();
-            self.update_rate_limit(&webhook_id, &response).await;
+            self.scheduler
+                .record_response(&webhook_id, response.headers());
 
             if status.is_success() {
-                let response_text = response.text().await?;
-                log::info!("✅ Forum text message sent successfully. Response: {response_text}");
-                return Ok(response_text);
+                return Ok(JsonPostOutcome::Sent(response.text().await?));
             }
 
             let error_text = response
@@ -188,78 +702,114 @@ impl DiscordClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
 
-            log::error!(
-                "❌ Failed to send forum text message. Status: {status}, Error: {error_text}"
-            );
-
             attempt += 1;
             if should_retry_error(status.as_u16()) && attempt <= self.retry_config.max_retries {
-                let delay = if status == 429 {
-                    self.extract_retry_after(&error_text)
-                        .unwrap_or_else(|| self.calculate_backoff_delay(attempt))
-                } else {
-                    self.calculate_backoff_delay(attempt)
-                };
-
-                log::warn!("Forum text message attempt {attempt} failed, retrying in {delay:?}");
-                sleep(delay).await;
+                let delay = self.retry_delay(&webhook_id, status, &error_text, attempt);
+
+                log::warn!("Text message attempt {attempt} failed, retrying in {delay:?}");
+                self.cancellable(sleep(delay)).await?;
                 continue;
             }
 
-            // Check for specific Discord error codes to provide better messages
-            let error_message = parse_discord_error_message(&error_text, status.as_u16());
-            return Err(AppError::UploadFailed {
-                reason: error_message,
+            return Ok(JsonPostOutcome::Rejected {
+                status: status.as_u16(),
+                error_text,
             });
         }
     }
 
-    /// Send text-only message (for player list follow-ups)
-    pub async fn send_text_message(
+    /// Delete a message previously posted through this webhook.
+    /// A message Discord no longer knows about counts as already deleted.
+    pub async fn delete_webhook_message(
         &self,
         webhook_url: &str,
-        content: &str,
+        message_id: &str,
         thread_id: Option<&str>,
     ) -> AppResult<()> {
+        let status = self
+            .webhook_message_request(
+                reqwest::Method::DELETE,
+                webhook_url,
+                message_id,
+                thread_id,
+                None,
+            )
+            .await?;
+
+        if status == 404 {
+            log::info!("Discord message {message_id} was already deleted");
+        } else {
+            log::info!("Deleted Discord message {message_id}");
+        }
+        Ok(())
+    }
+
+    /// Replace the text content of a message previously posted through this webhook
+    pub async fn edit_webhook_message(
+        &self,
+        webhook_url: &str,
+        message_id: &str,
+        thread_id: Option<&str>,
+        content: &str,
+    ) -> AppResult<()> {
+        let mut body = serde_json::json!({ "content": content });
+        if let Some(allowed_mentions) = self.allowed_mentions(Some(content)) {
+            body["allowed_mentions"] = allowed_mentions;
+        }
+        let status = self
+            .webhook_message_request(
+                reqwest::Method::PATCH,
+                webhook_url,
+                message_id,
+                thread_id,
+                Some(body),
+            )
+            .await?;
+
+        if status == 404 {
+            return Err(AppError::UploadFailed {
+                reason: "The message no longer exists on Discord".to_string(),
+            });
+        }
+        log::info!("Edited Discord message {message_id}");
+        Ok(())
+    }
+
+    /// Send a request to `{webhook}/messages/{id}` with retries.
+    /// Returns the status on success or 404, which callers interpret themselves.
+    async fn webhook_message_request(
+        &self,
+        method: reqwest::Method,
+        webhook_url: &str,
+        message_id: &str,
+        thread_id: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> AppResult<reqwest::StatusCode> {
+        let url = webhook_message_url(webhook_url, message_id, thread_id);
         let webhook_id = self.extract_webhook_id(webhook_url);
-        self.wait_for_rate_limit(&webhook_id).await;
 
         let mut attempt = 0;
 
         loop {
-            // Build URL with required query parameters
-            let mut url_parts = vec![];
-            url_parts.push("wait=true".to_string());
-
-            if let Some(tid) = thread_id {
-                url_parts.push(format!("thread_id={tid}"));
-            }
-
-            let final_url = if webhook_url.contains('?') {
-                format!("{}&{}", webhook_url, url_parts.join("&"))
-            } else {
-                format!("{}?{}", webhook_url, url_parts.join("&"))
-            };
-
-            // Send as JSON body
-            let body = serde_json::json!({
-                "content": content
-            });
-
-            let response = self
-                .client
-                .post(&final_url)
-                .header("Content-Type", "application/json")
-                .body(body.to_string())
-                .send()
+            self.cancellable(wait_for_global_rate_limit()).await?;
+            self.cancellable(self.scheduler.acquire(&webhook_id))
                 .await?;
+            let mut request = self.client.request(method.clone(), &url);
+            if let Some(body) = &body {
+                request = request
+                    .header("Content-Type", "application/json")
+                    .body(body.to_string());
+            }
+            let started = Instant::now();
+            let response = self.cancellable(request.send()).await??;
+            log_request_timing(started, &response);
 
             let status = response.status();
-            self.update_rate_limit(&webhook_id, &response).await;
+            self.scheduler
+                .record_response(&webhook_id, response.headers());
 
-            if status.is_success() {
-                log::debug!("Text message sent successfully");
-                return Ok(());
+            if status.is_success() || status == 404 {
+                return Ok(status);
             }
 
             let error_text = response
@@ -269,21 +819,15 @@ impl DiscordClient {
 
             attempt += 1;
             if should_retry_error(status.as_u16()) && attempt <= self.retry_config.max_retries {
-                let delay = if status == 429 {
-                    self.extract_retry_after(&error_text)
-                        .unwrap_or_else(|| self.calculate_backoff_delay(attempt))
-                } else {
-                    self.calculate_backoff_delay(attempt)
-                };
+                let delay = self.retry_delay(&webhook_id, status, &error_text, attempt);
 
-                log::warn!("Text message attempt {attempt} failed, retrying in {delay:?}");
-                sleep(delay).await;
+                log::warn!("{method} message attempt {attempt} failed, retrying in {delay:?}");
+                self.cancellable(sleep(delay)).await?;
                 continue;
             }
 
-            let error_message = parse_discord_error_message(&error_text, status.as_u16());
             return Err(AppError::UploadFailed {
-                reason: error_message,
+                reason: parse_discord_error_message(&error_text, status.as_u16()),
             });
         }
     }
@@ -292,44 +836,30 @@ impl DiscordClient {
         url.split('/').nth_back(1).unwrap_or("default").to_string()
     }
 
-    async fn wait_for_rate_limit(&self, webhook_id: &str) {
-        let wait_time = {
-            match self.rate_limiter.lock() {
-                Ok(rate_limiter) => {
-                    if let Some(&last_request) = rate_limiter.get(webhook_id) {
-                        let elapsed = last_request.elapsed();
-                        const MIN_DELAY: Duration = Duration::from_millis(1000); // Discord rate limit
-
-                        if elapsed < MIN_DELAY {
-                            Some(MIN_DELAY - elapsed)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                }
-                Err(e) => {
-                    log::warn!("Failed to acquire rate limiter lock (non-critical): {e}");
-                    None
-                }
-            }
-        }; // MutexGuard is dropped here
-
-        if let Some(wait_time) = wait_time {
-            sleep(wait_time).await;
+    /// Delay before retrying a failed request: Discord's retry_after for 429s, which also
+    /// pauses every other request to the webhook (or to all webhooks when the limit is
+    /// global), otherwise exponential backoff
+    fn retry_delay(
+        &self,
+        webhook_id: &str,
+        status: reqwest::StatusCode,
+        error_text: &str,
+        attempt: u32,
+    ) -> Duration {
+        if status != 429 {
+            return self.calculate_backoff_delay(attempt);
         }
-    }
 
-    async fn update_rate_limit(&self, webhook_id: &str, _response: &reqwest::Response) {
-        match self.rate_limiter.lock() {
-            Ok(mut rate_limiter) => {
-                rate_limiter.insert(webhook_id.to_string(), Instant::now());
-            }
-            Err(e) => {
-                log::warn!("Failed to update rate limiter (non-critical): {e}");
-            }
+        let delay = self
+            .extract_retry_after(error_text)
+            .unwrap_or_else(|| self.calculate_backoff_delay(attempt));
+        if is_global_rate_limit(error_text) {
+            log::warn!("Hit Discord's global rate limit, pausing all uploads for {delay:?}");
+            set_global_rate_limit(delay);
+        } else {
+            self.scheduler.defer(webhook_id, delay);
         }
+        delay
     }
 
     fn calculate_backoff_delay(&self, attempt: u32) -> Duration {
@@ -357,11 +887,36 @@ impl DiscordClient {
     }
 }
 
+/// Maximum length of a Discord message's text content
+pub const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Link buttons to send with a caption in place of its text links
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkButtons {
+    /// The caption with the text links taken out
+    pub content: String,
+    /// Action rows of link buttons
+    pub components: serde_json::Value,
+}
+
+/// A file attached to a payload. Only its path is kept; the contents are streamed
+/// from disk each time the form is built.
+#[derive(Debug, Clone)]
+struct PayloadFile {
+    path: String,
+    filename: String,
+    size: u64,
+    mime_type: &'static str,
+    field_name: String,
+}
+
 /// Upload payload with files and text fields
 #[derive(Debug, Clone)]
 pub struct UploadPayload {
-    files: Vec<(String, Vec<u8>, String, String)>, // (filename, data, mime_type, field_name)
+    files: Vec<PayloadFile>,
     text_fields: HashMap<String, String>,
+    /// Alt text per file, by the order files were added
+    attachment_descriptions: HashMap<usize, String>,
 }
 
 impl Default for UploadPayload {
@@ -375,6 +930,7 @@ impl UploadPayload {
         Self {
             files: Vec::new(),
             text_fields: HashMap::new(),
+            attachment_descriptions: HashMap::new(),
         }
     }
 
@@ -382,53 +938,240 @@ impl UploadPayload {
         self.text_fields.insert(key, value);
     }
 
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Paths and sizes of the added files, in the order they were added
+    pub fn files(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.files
+            .iter()
+            .map(|file| (file.path.as_str(), file.size))
+    }
+
+    /// Set the alt text of the file at `index` (in the order files are added)
+    pub fn set_attachment_description(&mut self, index: usize, description: String) {
+        self.attachment_descriptions.insert(index, description);
+    }
+
     pub async fn add_file(&mut self, file_path: &str, field_name: String) -> AppResult<()> {
-        let file_contents = tokio::fs::read(file_path).await?;
+        let size = tokio::fs::metadata(file_path).await?.len();
         let filename = Path::new(file_path)
             .file_name()
             .unwrap_or_default()
             .to_string_lossy()
             .to_string();
 
-        // Detect MIME type based on file extension
-        let mime_type = match Path::new(file_path).extension().and_then(|e| e.to_str()) {
-            Some("png") => "image/png",
-            Some("jpg") | Some("jpeg") => "image/jpeg",
-            Some("webp") => "image/webp",
-            Some("gif") => "image/gif",
-            _ => "image/png", // Default fallback
-        };
-
-        self.files
-            .push((filename, file_contents, mime_type.to_string(), field_name));
+        self.files.push(PayloadFile {
+            path: file_path.to_string(),
+            filename,
+            size,
+            mime_type: crate::security::attachment_mime_type(file_path),
+            field_name,
+        });
         Ok(())
     }
 
-    pub fn build_form(&self) -> AppResult<multipart::Form> {
+    /// Attachment descriptions, message flags, forum tags and allowed mentions can only be
+    /// sent through `payload_json`, which then also has to carry the text fields since
+    /// Discord ignores them alongside it. Tags only apply to the message that creates a
+    /// forum thread.
+    fn payload_json(
+        &self,
+        flags: u64,
+        forum_tags: &[String],
+        allowed_mentions: Option<&serde_json::Value>,
+    ) -> Option<String> {
+        let applies_tags = self.text_fields.contains_key("thread_name") && !forum_tags.is_empty();
+        if self.attachment_descriptions.is_empty()
+            && flags == 0
+            && !applies_tags
+            && allowed_mentions.is_none()
+        {
+            return None;
+        }
+
+        let mut payload: serde_json::Map<String, serde_json::Value> = self
+            .text_fields
+            .iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+            .collect();
+        let attachments: Vec<serde_json::Value> = (0..self.files.len())
+            .map(|index| match self.attachment_descriptions.get(&index) {
+                Some(description) => serde_json::json!({ "id": index, "description": description }),
+                None => serde_json::json!({ "id": index }),
+            })
+            .collect();
+        payload.insert(
+            "attachments".to_string(),
+            serde_json::Value::Array(attachments),
+        );
+        if flags != 0 {
+            payload.insert("flags".to_string(), serde_json::Value::from(flags));
+        }
+        if applies_tags {
+            payload.insert("applied_tags".to_string(), serde_json::json!(forum_tags));
+        }
+        if let Some(allowed_mentions) = allowed_mentions {
+            payload.insert("allowed_mentions".to_string(), allowed_mentions.clone());
+        }
+
+        Some(serde_json::Value::Object(payload).to_string())
+    }
+
+    /// Multipart form for the payload. File parts are named with `multipart_filenames`;
+    /// the files on disk keep their names either way. Each file is opened again and
+    /// streamed, so memory use doesn't grow with the chunk and retries don't copy it.
+    pub async fn build_form(
+        &self,
+        flags: u64,
+        forum_tags: &[String],
+        allowed_mentions: Option<&serde_json::Value>,
+        keep_original_filenames: bool,
+    ) -> AppResult<multipart::Form> {
         let mut form = multipart::Form::new();
 
-        // Add text fields
-        for (key, value) in &self.text_fields {
-            form = form.text(key.clone(), value.clone());
+        // Add text fields, as payload_json when attachments, flags, tags or mentions need it
+        if let Some(payload_json) = self.payload_json(flags, forum_tags, allowed_mentions) {
+            form = form.text("payload_json", payload_json);
+        } else {
+            for (key, value) in &self.text_fields {
+                form = form.text(key.clone(), value.clone());
+            }
         }
 
         // Add files
-        for (filename, data, mime_type, field_name) in &self.files {
-            let part = multipart::Part::bytes(data.clone())
-                .file_name(filename.clone())
-                .mime_str(mime_type)?;
-
-            form = form.part(field_name.clone(), part);
+        let filenames = multipart_filenames(
+            self.files.iter().map(|file| file.filename.as_str()),
+            keep_original_filenames,
+        );
+        for (file, filename) in self.files.iter().zip(filenames) {
+            let handle = tokio::fs::File::open(&file.path).await?;
+            let size = handle.metadata().await?.len();
+            let body = reqwest::Body::wrap_stream(ReaderStream::new(handle));
+            let part = multipart::Part::stream_with_length(body, size)
+                .file_name(filename)
+                .mime_str(file.mime_type)?;
+
+            form = form.part(file.field_name.clone(), part);
         }
 
         Ok(form)
     }
 }
 
+/// Multipart filenames for one message's attachments. Names that come out the same
+/// (ignoring case), such as two Japanese names both becoming `image.jpg`, get a
+/// `_2`, `_3`, ... suffix so Discord doesn't show them as duplicates.
+pub fn multipart_filenames<'a>(
+    filenames: impl IntoIterator<Item = &'a str>,
+    keep_original: bool,
+) -> Vec<String> {
+    let mut taken = HashSet::new();
+    filenames
+        .into_iter()
+        .map(|filename| {
+            let name = multipart_filename(filename, keep_original);
+            if taken.insert(name.to_lowercase()) {
+                return name;
+            }
+            let (stem, extension) = match name.rsplit_once('.') {
+                Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+                _ => (name.as_str(), String::new()),
+            };
+            (2..)
+                .map(|n| format!("{stem}_{n}{extension}"))
+                .find(|candidate| taken.insert(candidate.to_lowercase()))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Filename for an attachment's multipart part. Non-ASCII names can come out mangled
+/// or be rejected, so they are transliterated where Unicode has an ASCII equivalent
+/// (accents, full-width characters) and anything else becomes `_`; a name left with
+/// nothing readable becomes `image`. With `keep_original` the name is sent as-is
+/// unless it contains characters the part header can't carry.
+pub fn multipart_filename(filename: &str, keep_original: bool) -> String {
+    if keep_original
+        && !filename
+            .chars()
+            .any(|c| c.is_control() || c == '"' || c == '\\')
+    {
+        return filename.to_string();
+    }
+
+    let (stem, extension) = match filename.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (filename, None),
+    };
+
+    let mut ascii_stem = String::with_capacity(stem.len());
+    for c in stem.nfkd().filter(|c| !is_combining_mark(*c)) {
+        if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '(' | ')') {
+            ascii_stem.push(c);
+        } else if !ascii_stem.ends_with('_') {
+            ascii_stem.push('_');
+        }
+    }
+    let ascii_stem = ascii_stem.trim_matches('_');
+    let ascii_stem = if ascii_stem.chars().any(|c| c.is_ascii_alphanumeric()) {
+        ascii_stem
+    } else {
+        "image"
+    };
+
+    match extension
+        .map(|ext| {
+            ext.nfkd()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+        })
+        .filter(|ext| !ext.is_empty())
+    {
+        Some(extension) => format!("{ascii_stem}.{}", extension.to_ascii_lowercase()),
+        None => ascii_stem.to_string(),
+    }
+}
+
 fn should_retry_error(status_code: u16) -> bool {
     matches!(status_code, 429 | 500 | 502 | 503 | 504)
 }
 
+/// Result of posting a JSON message that got a response from Discord
+enum JsonPostOutcome {
+    Sent(String),
+    Rejected { status: u16, error_text: String },
+}
+
+impl JsonPostOutcome {
+    fn into_result(self) -> AppResult<String> {
+        match self {
+            Self::Sent(response_text) => Ok(response_text),
+            // Check for specific Discord error codes to provide better messages
+            Self::Rejected { status, error_text } => Err(AppError::UploadFailed {
+                reason: parse_discord_error_message(&error_text, status),
+            }),
+        }
+    }
+}
+
+/// Whether Discord refused a message because of its components (400 with 50035
+/// Invalid Form Body pointing at `components`), which means it wasn't posted and can
+/// be resent without them. Other 400s would fail the same way again.
+fn components_rejected(status: u16, error_text: &str) -> bool {
+    if status != 400 {
+        return false;
+    }
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(error_text) else {
+        return false;
+    };
+    json.get("code").and_then(|v| v.as_u64()) == Some(50035)
+        && json
+            .get("errors")
+            .is_some_and(|errors| errors.get("components").is_some())
+}
+
 /// Parse Discord error response and provide user-friendly error messages
 fn parse_discord_error_message(error_text: &str, status_code: u16) -> String {
     // Try to parse the error as JSON to extract the code
@@ -529,10 +1272,134 @@ pub fn extract_thread_id(response_data: &str) -> Option<String> {
     None
 }
 
+/// `{webhook}/messages/{id}` endpoint URL, keeping any query the webhook URL already has
+pub fn webhook_message_url(webhook_url: &str, message_id: &str, thread_id: Option<&str>) -> String {
+    let (base, query) = match webhook_url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (webhook_url, None),
+    };
+
+    let mut params: Vec<String> = query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .filter(|p| !p.is_empty() && !p.starts_with("thread_id=") && !p.starts_with("wait="))
+        .map(str::to_string)
+        .collect();
+    if let Some(tid) = thread_id {
+        params.push(format!("thread_id={tid}"));
+    }
+
+    let url = format!("{}/messages/{message_id}", base.trim_end_matches('/'));
+    if params.is_empty() {
+        url
+    } else {
+        format!("{url}?{}", params.join("&"))
+    }
+}
+
+/// A message posted through a webhook, parsed from the `wait=true` response
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostedMessage {
+    pub message_id: String,
+    pub channel_id: String,
+    /// https://discord.com/channels/... link; needs the guild ID so it may be missing
+    pub jump_url: Option<String>,
+    /// CDN URLs of the message attachments, in the order the files were added
+    pub attachment_urls: Vec<String>,
+}
+
+impl PostedMessage {
+    /// CDN URL of the file sent as `files[index]`
+    pub fn attachment_url(&self, index: usize) -> Option<String> {
+        self.attachment_urls.get(index).cloned()
+    }
+}
+
+/// Extract message/channel IDs and attachment URLs from a webhook message response
+pub fn extract_posted_message(
+    response_data: &str,
+    guild_id: Option<&str>,
+) -> Option<PostedMessage> {
+    let json: serde_json::Value = serde_json::from_str(response_data).ok()?;
+    let message_id = json.get("id")?.as_str()?.to_string();
+    let channel_id = json.get("channel_id")?.as_str()?.to_string();
+    let jump_url = guild_id
+        .map(|guild| format!("https://discord.com/channels/{guild}/{channel_id}/{message_id}"));
+    let attachment_urls = json
+        .get("attachments")
+        .and_then(|a| a.as_array())
+        .map(|attachments| {
+            attachments
+                .iter()
+                .filter_map(|a| a.get("url")?.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(PostedMessage {
+        message_id,
+        channel_id,
+        jump_url,
+        attachment_urls,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // --- extract_posted_message tests ---
+
+    #[test]
+    fn test_extract_posted_message_with_guild() {
+        let response = r#"{"id": "111", "channel_id": "222", "type": 0, "content": "hi"}"#;
+        let message = extract_posted_message(response, Some("333")).unwrap();
+        assert_eq!(message.message_id, "111");
+        assert_eq!(message.channel_id, "222");
+        assert_eq!(
+            message.jump_url.as_deref(),
+            Some("https://discord.com/channels/333/222/111")
+        );
+
+        let without_guild = extract_posted_message(response, None).unwrap();
+        assert!(without_guild.jump_url.is_none());
+        assert!(without_guild.attachment_urls.is_empty());
+        assert!(extract_posted_message("not json", None).is_none());
+    }
+
+    #[test]
+    fn test_extract_posted_message_attachment_urls() {
+        let response = r#"{"id": "111", "channel_id": "222", "attachments": [
+            {"id": "1", "filename": "a.png", "url": "https://cdn.discordapp.com/attachments/222/1/a.png"},
+            {"id": "2", "filename": "b.png", "url": "https://cdn.discordapp.com/attachments/222/2/b.png"}
+        ]}"#;
+        let message = extract_posted_message(response, None).unwrap();
+        assert_eq!(message.attachment_urls.len(), 2);
+        assert_eq!(
+            message.attachment_url(1).as_deref(),
+            Some("https://cdn.discordapp.com/attachments/222/2/b.png")
+        );
+        assert!(message.attachment_url(2).is_none());
+    }
+
+    // --- webhook_message_url tests ---
+
+    #[test]
+    fn test_webhook_message_url() {
+        assert_eq!(
+            webhook_message_url("https://discord.com/api/webhooks/1/abc", "42", None),
+            "https://discord.com/api/webhooks/1/abc/messages/42"
+        );
+        assert_eq!(
+            webhook_message_url(
+                "https://discord.com/api/webhooks/1/abc?thread_id=7&wait=true",
+                "42",
+                Some("9")
+            ),
+            "https://discord.com/api/webhooks/1/abc/messages/42?thread_id=9"
+        );
+    }
+
     // --- parse_discord_error_message tests ---
 
     #[test]
@@ -646,6 +1513,52 @@ mod tests {
 
     // --- should_retry_error tests ---
 
+    #[test]
+    fn test_clients_share_pool_rate_limits() {
+        let pool = DiscordConnectionPool::new();
+        let a = DiscordClient::with_pool(&pool);
+        let b = DiscordClient::with_pool(&pool);
+        assert!(Arc::ptr_eq(&a.scheduler, &b.scheduler));
+        assert!(!Arc::ptr_eq(&a.scheduler, &DiscordClient::new().scheduler));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_client_skips_requests() {
+        let token = CancellationToken::new();
+        let client = DiscordClient::new().with_cancellation("session_123", token.clone());
+        token.cancel();
+
+        let result = client
+            .send_text_message("https://discord.com/api/webhooks/1/token", "hello", None)
+            .await;
+        assert!(matches!(result, Err(AppError::UploadCancelled { .. })));
+
+        // Webhook lookups are dropped too, and not cached as missing
+        let webhook_url = "https://discord.com/api/webhooks/1/token";
+        assert_eq!(client.get_webhook_guild_id(webhook_url).await, None);
+        assert!(client.webhook_details.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_is_global_rate_limit() {
+        assert!(is_global_rate_limit(
+            r#"{"message": "You are being rate limited.", "retry_after": 0.5, "global": true}"#
+        ));
+        assert!(!is_global_rate_limit(
+            r#"{"message": "You are being rate limited.", "retry_after": 0.5, "global": false}"#
+        ));
+        assert!(!is_global_rate_limit("Too Many Requests"));
+    }
+
+    #[test]
+    fn test_global_rate_limit_gate() {
+        set_global_rate_limit(Duration::from_millis(200));
+        // A shorter limit doesn't cut the running pause short
+        set_global_rate_limit(Duration::from_millis(1));
+        let remaining = global_rate_limit_remaining().unwrap();
+        assert!(remaining > Duration::from_millis(100) && remaining <= Duration::from_millis(200));
+    }
+
     #[test]
     fn test_should_retry_429_rate_limit() {
         assert!(should_retry_error(429));
@@ -852,18 +1765,216 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_upload_payload_build_form_empty() {
+    #[tokio::test]
+    async fn test_upload_payload_build_form_empty() {
         let payload = UploadPayload::new();
-        let result = payload.build_form();
+        let result = payload.build_form(0, &[], None, false).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_upload_payload_streams_files_from_disk() {
+        let path = std::env::temp_dir().join(format!("payload_{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&path, vec![7u8; 4096]).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let mut payload = UploadPayload::new();
+        payload
+            .add_file(&path, "files[0]".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            payload.files().collect::<Vec<_>>(),
+            vec![(path.as_str(), 4096)]
+        );
+        // Every attempt streams the file again
+        assert!(payload.build_form(0, &[], None, false).await.is_ok());
+        assert!(payload.build_form(0, &[], None, false).await.is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(payload.build_form(0, &[], None, false).await.is_err());
+        assert!(payload
+            .add_file(&path, "files[1]".to_string())
+            .await
+            .is_err());
+    }
+
     #[test]
-    fn test_upload_payload_build_form_with_text() {
+    fn test_only_component_rejections_fall_back_to_text_links() {
+        let components_error = r#"{"code": 50035, "message": "Invalid Form Body",
+            "errors": {"components": {"0": {"components": {"0": {"url": {"_errors": []}}}}}}}"#;
+        assert!(components_rejected(400, components_error));
+        // Discord may have posted the message already, or never will
+        for status in [401, 403, 404, 429, 500, 502, 503] {
+            assert!(!components_rejected(status, components_error), "{status}");
+        }
+
+        // Other invalid bodies would fail again without the buttons
+        let content_error = r#"{"code": 50035, "message": "Invalid Form Body",
+            "errors": {"content": {"_errors": [{"code": "BASE_TYPE_MAX_LENGTH"}]}}}"#;
+        assert!(!components_rejected(400, content_error));
+        assert!(!components_rejected(
+            400,
+            r#"{"code": 10003, "message": "Unknown Channel"}"#
+        ));
+        assert!(!components_rejected(400, "Bad Request"));
+
+        let rejected = JsonPostOutcome::Rejected {
+            status: 400,
+            error_text: content_error.to_string(),
+        };
+        assert!(matches!(
+            rejected.into_result(),
+            Err(AppError::UploadFailed { .. })
+        ));
+        assert_eq!(
+            JsonPostOutcome::Sent("{}".to_string())
+                .into_result()
+                .unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn test_multipart_filename_transliterates() {
+        assert_eq!(
+            multipart_filename("VRChat_2024-01-01_12-00-00.123_1920x1080.png", false),
+            "VRChat_2024-01-01_12-00-00.123_1920x1080.png"
+        );
+        assert_eq!(
+            multipart_filename("Café ｗｏｒｌｄ.PNG", false),
+            "Cafe_world.png"
+        );
+        assert_eq!(
+            multipart_filename("夜景📸_ab12cd34.webp", false),
+            "ab12cd34.webp"
+        );
+        assert_eq!(multipart_filename("夜景.jpg", false), "image.jpg");
+    }
+
+    #[test]
+    fn test_multipart_filename_keeps_original_when_possible() {
+        assert_eq!(multipart_filename("夜景📸.webp", true), "夜景📸.webp");
+        assert_eq!(multipart_filename("say \"hi\".png", true), "say_hi.png");
+    }
+
+    #[test]
+    fn test_multipart_filenames_are_unique_within_a_message() {
+        assert_eq!(
+            multipart_filenames(["夜景.jpg", "風景.jpg", "image_2.JPG", "Image.jpg"], false),
+            vec!["image.jpg", "image_2.jpg", "image_2_2.jpg", "Image_3.jpg"]
+        );
+        // Distinct names are left alone
+        assert_eq!(
+            multipart_filenames(["a.png", "b.png"], false),
+            vec!["a.png", "b.png"]
+        );
+    }
+
+    #[test]
+    fn test_upload_payload_json_carries_descriptions() {
+        let mut payload = UploadPayload::new();
+        payload.add_text_field("content".to_string(), "📸 Photos".to_string());
+        assert!(payload.payload_json(0, &[], None).is_none());
+
+        for name in ["a.png", "b.png"] {
+            payload.files.push(PayloadFile {
+                path: name.to_string(),
+                filename: name.to_string(),
+                size: 0,
+                mime_type: "image/png",
+                field_name: String::new(),
+            });
+        }
+        payload.set_attachment_description(1, "Two friends on a bench".to_string());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&payload.payload_json(0, &[], None).unwrap()).unwrap();
+        assert_eq!(json["content"], "📸 Photos");
+        assert_eq!(
+            json["attachments"],
+            serde_json::json!([
+                { "id": 0 },
+                { "id": 1, "description": "Two friends on a bench" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_upload_payload_json_carries_flags() {
+        let mut payload = UploadPayload::new();
+        payload.add_text_field("content".to_string(), "📸 Photos".to_string());
+
+        let flags = message_flags(true, true);
+        assert_eq!(flags, FLAG_SUPPRESS_EMBEDS | FLAG_SUPPRESS_NOTIFICATIONS);
+        let json: serde_json::Value =
+            serde_json::from_str(&payload.payload_json(flags, &[], None).unwrap()).unwrap();
+        assert_eq!(json["flags"], 4100);
+        assert_eq!(json["content"], "📸 Photos");
+        assert_eq!(message_flags(false, false), 0);
+    }
+
+    #[test]
+    fn test_upload_payload_json_applies_tags_to_new_threads() {
+        let tags = vec!["1234".to_string()];
+        let mut payload = UploadPayload::new();
+        payload.add_text_field("content".to_string(), "📸 Photos".to_string());
+        assert!(payload.payload_json(0, &tags, None).is_none());
+
+        payload.add_text_field("thread_name".to_string(), "Photos".to_string());
+        let json: serde_json::Value =
+            serde_json::from_str(&payload.payload_json(0, &tags, None).unwrap()).unwrap();
+        assert_eq!(json["applied_tags"], serde_json::json!(["1234"]));
+        assert_eq!(json["thread_name"], "Photos");
+    }
+
+    #[test]
+    fn test_allowed_mentions_only_lets_mapped_users_ping() {
+        let mapped = vec!["111".to_string(), "222".to_string()];
+        let content = "with <@111>, <@!222>, <@333>, <@111> and **@everyone <@&444>**";
+        assert_eq!(
+            allowed_mentions(content, &mapped),
+            serde_json::json!({ "parse": [], "users": ["111", "222"] })
+        );
+
+        let mut payload = UploadPayload::new();
+        payload.add_text_field("content".to_string(), content.to_string());
+        let restricted = allowed_mentions(content, &mapped);
+        let json: serde_json::Value =
+            serde_json::from_str(&payload.payload_json(0, &[], Some(&restricted)).unwrap())
+                .unwrap();
+        assert_eq!(
+            json["allowed_mentions"]["users"],
+            serde_json::json!(["111", "222"])
+        );
+        assert_eq!(json["content"], content);
+    }
+
+    #[test]
+    fn test_client_restricts_mentions_by_default() {
+        let client = DiscordClient::new();
+        assert_eq!(
+            client.allowed_mentions(Some("**@everyone** <@111>")),
+            Some(serde_json::json!({ "parse": [], "users": [] }))
+        );
+
+        client.set_mention_users(vec!["111".to_string()]);
+        assert_eq!(
+            client
+                .allowed_mentions(Some("**@everyone** <@111>"))
+                .unwrap()["users"],
+            serde_json::json!(["111"])
+        );
+
+        client.set_restrict_mentions(false);
+        assert!(client.allowed_mentions(Some("<@111>")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upload_payload_build_form_with_text() {
         let mut payload = UploadPayload::new();
         payload.add_text_field("content".to_string(), "test message".to_string());
-        let result = payload.build_form();
+        let result = payload.build_form(0, &[], None, false).await;
         assert!(result.is_ok());
     }
 }
@@ -0,0 +1,107 @@
+// Disk space pre-flight - makes sure compression has room before a session starts
+//
+// Compressed copies are written to the temp directory and the compression pool can
+// hold many of them at once, so a large session on a nearly full drive used to fail
+// part-way through. The estimate is deliberately generous: it assumes every file may
+// need a compressed copy.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::chunk_planner::predicted_upload_size;
+use crate::security::FileSystemGuard;
+
+/// Extra room on top of the predicted sizes, for outputs landing above the prediction
+const ESTIMATE_HEADROOM: f64 = 1.2;
+
+/// Result of the pre-flight check, shown to the user before starting a session
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskSpaceCheck {
+    pub temp_directory: String,
+    /// Estimated bytes of temp files the session could write
+    pub required_bytes: u64,
+    /// Free bytes on the temp directory's drive; None when it couldn't be determined
+    pub available_bytes: Option<u64>,
+    /// False only when the drive is known to be too full
+    pub sufficient: bool,
+}
+
+/// Estimated temp space for compressing `file_paths` into `compression_format`
+pub fn estimate_temp_space(file_paths: &[String], compression_format: &str) -> u64 {
+    let predicted: u64 = file_paths
+        .iter()
+        .map(|path| {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            predicted_upload_size(path, size, compression_format)
+        })
+        .sum();
+    (predicted as f64 * ESTIMATE_HEADROOM) as u64
+}
+
+/// Free space on the drive holding `path`, from the disk with the longest mount point
+/// that contains it
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = nearest_existing(path)?.canonicalize().ok()?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// `path` itself or the closest ancestor that exists (the temp folder is created lazily)
+fn nearest_existing(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Compare the estimated temp space for a session with what the drive has free
+pub fn check_disk_space(file_paths: &[String], compression_format: &str) -> DiskSpaceCheck {
+    let temp_directory = FileSystemGuard::get_temp_directory();
+    let required_bytes = estimate_temp_space(file_paths, compression_format);
+    let available_bytes = available_space(&temp_directory);
+
+    DiskSpaceCheck {
+        temp_directory: temp_directory.to_string_lossy().to_string(),
+        required_bytes,
+        available_bytes,
+        sufficient: available_bytes.is_none_or(|available| available >= required_bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_uses_predicted_sizes() {
+        let dir = std::env::temp_dir().join(format!("disk_check_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let png = dir.join("a.png");
+        let jpg = dir.join("b.jpg");
+        std::fs::write(&png, vec![0u8; 1000]).unwrap();
+        std::fs::write(&jpg, vec![0u8; 1000]).unwrap();
+        let files = vec![
+            png.to_string_lossy().to_string(),
+            jpg.to_string_lossy().to_string(),
+        ];
+
+        // Lossy WebP shrinks the PNG to ~300 bytes; the JPEG counts as-is
+        assert_eq!(estimate_temp_space(&files, "webp"), 1560);
+        assert_eq!(estimate_temp_space(&files, "png"), 2400);
+        assert_eq!(estimate_temp_space(&[], "webp"), 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_available_space_for_missing_folder() {
+        let missing = std::env::temp_dir().join(format!("not_created_{}", uuid::Uuid::new_v4()));
+        assert_eq!(
+            available_space(&missing),
+            available_space(&std::env::temp_dir())
+        );
+    }
+}
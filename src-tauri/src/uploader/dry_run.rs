@@ -0,0 +1,732 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::commands::{
+    AuthorInfo, ImageMetadata, MessagePreviewOptions, PlayerInfo, Webhook, WorldInfo,
+};
+use crate::database::{self, IndexedPhoto};
+use crate::errors::AppResult;
+use crate::image_processor;
+
+use super::chunk_planner::{plan_chunks, webhook_upload_limit};
+use super::disk_space::{check_disk_space, DiskSpaceCheck};
+use super::image_groups::{
+    append_avatar_caption, apply_caption_customizations, assign_group_keys, create_discord_payload,
+    timestamp_style_for, ImageGroup, DEFAULT_TIMESTAMP_STYLE,
+};
+use super::session_manager::SessionOptions;
+use super::upload_queue::{
+    load_discord_user_map, load_user_webhook_override_map, resolve_group_webhook,
+};
+
+/// Payload size above which Discord rejects a webhook message and the uploader
+/// falls back to compressing the chunk (default attachment limit for webhooks)
+pub const DISCORD_UPLOAD_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// Structured result of a dry-run session
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadPreview {
+    pub total_images: usize,
+    pub total_groups: usize,
+    pub upload_quality: u8,
+    pub compression_format: String,
+    pub webhooks: Vec<WebhookPreview>,
+    /// Files that would likely need compressing before Discord accepts them
+    pub files_to_compress: Vec<String>,
+    /// Whether the temp directory has room for the session's compressed copies
+    pub disk_space: DiskSpaceCheck,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPreview {
+    pub webhook_id: i64,
+    pub webhook_name: String,
+    pub is_forum: bool,
+    pub groups: Vec<GroupPreview>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupPreview {
+    pub group_id: String,
+    pub timestamp: Option<i64>,
+    pub file_paths: Vec<String>,
+    /// Set when a user webhook override redirects this group elsewhere
+    pub target_webhook_name: Option<String>,
+    pub thread_title: Option<String>,
+    /// Main caption sent with the first message of the group
+    pub content: Option<String>,
+    /// Extra player-list messages sent after the main caption
+    pub overflow_messages: Vec<String>,
+    pub chunks: Vec<ChunkPreview>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkPreview {
+    pub file_paths: Vec<String>,
+    pub total_bytes: u64,
+    pub would_compress: bool,
+}
+
+/// Runs grouping, caption generation and chunking for every webhook without any HTTP calls
+pub async fn build_upload_preview(
+    options: &SessionOptions,
+    webhooks: Vec<Webhook>,
+) -> AppResult<UploadPreview> {
+    log::info!(
+        "Building dry-run preview for {} files across {} webhook(s)",
+        options.file_paths.len(),
+        webhooks.len()
+    );
+
+    let config = crate::config::load_config().ok();
+    let include_avatar_in_caption = config.as_ref().is_some_and(|c| c.include_avatar_in_caption);
+    let link_player_profiles = config.as_ref().is_some_and(|c| c.link_player_profiles);
+    let default_timestamp_style = config
+        .as_ref()
+        .map(|c| c.discord_timestamp_style.clone())
+        .unwrap_or_else(|| DEFAULT_TIMESTAMP_STYLE.to_string());
+    let upload_quality = options
+        .upload_quality
+        .or(config.as_ref().map(|c| c.upload_quality))
+        .unwrap_or(85);
+    let compression_format = options
+        .compression_format
+        .clone()
+        .or(config.as_ref().map(|c| c.compression_format.clone()))
+        .unwrap_or_else(|| "webp".to_string());
+
+    let timezone = image_processor::resolve_screenshot_timezone(
+        options.screenshot_timezone.as_deref().or(config
+            .as_ref()
+            .and_then(|c| c.screenshot_timezone.as_deref())),
+    );
+
+    // Grouping only depends on the files, so it is shared by every webhook
+    let groups = if let Some(custom_groups) = &options.custom_groups {
+        super::image_groups::build_custom_groups(custom_groups, &options.file_paths, timezone).await
+    } else if options.group_by_metadata {
+        super::image_groups::group_images_by_metadata(
+            options.file_paths.clone(),
+            options.grouping_time_window,
+            options.group_by_world,
+            options.group_by_instance,
+            options.merge_no_metadata,
+            options.adopt_orphans,
+            timezone,
+            None,
+        )
+        .await
+    } else {
+        super::image_groups::create_individual_groups_with_metadata(
+            options.file_paths.clone(),
+            timezone,
+        )
+        .await
+    };
+
+    let override_map = load_user_webhook_override_map().await;
+    let discord_user_map = load_discord_user_map().await;
+
+    let mut webhook_previews = Vec::with_capacity(webhooks.len());
+    let mut files_to_compress: Vec<String> = Vec::new();
+
+    for webhook in webhooks {
+        let mut group_previews = Vec::with_capacity(groups.len());
+
+        for (group_index, group) in groups.iter().enumerate() {
+            let target_webhook = resolve_group_webhook(
+                group,
+                &webhook,
+                &options.group_webhook_overrides,
+                &override_map,
+            )
+            .await;
+            // In single thread mode only the first group creates the forum thread
+            let creates_thread =
+                target_webhook.is_forum && (!options.single_thread_mode || group_index == 0);
+
+            let preview_options = GroupPreviewOptions {
+                is_forum_channel: target_webhook.is_forum,
+                max_images_per_message: options.max_images_per_message,
+                upload_limit: webhook_upload_limit(&target_webhook),
+                compression_format: &compression_format,
+                include_player_names: options.include_player_names,
+                include_avatar_in_caption,
+                timestamp_style: timestamp_style_for(&target_webhook, &default_timestamp_style),
+                discord_user_map: &discord_user_map,
+                link_player_profiles,
+                extra_message: options.extra_message.as_deref(),
+            };
+            let mut preview = preview_group(
+                group,
+                &preview_options,
+                creates_thread,
+                options
+                    .caption_overrides
+                    .get(&group.group_id)
+                    .map(String::as_str),
+            );
+            if target_webhook.id != webhook.id {
+                preview.target_webhook_name = Some(target_webhook.name.clone());
+            }
+
+            for chunk in preview.chunks.iter().filter(|c| c.would_compress) {
+                for file_path in &chunk.file_paths {
+                    if !files_to_compress.contains(file_path) {
+                        files_to_compress.push(file_path.clone());
+                    }
+                }
+            }
+
+            group_previews.push(preview);
+        }
+
+        webhook_previews.push(WebhookPreview {
+            webhook_id: webhook.id,
+            webhook_name: webhook.name,
+            is_forum: webhook.is_forum,
+            groups: group_previews,
+        });
+    }
+
+    let disk_space = check_disk_space(&options.file_paths, &compression_format);
+
+    Ok(UploadPreview {
+        total_images: options.file_paths.len(),
+        total_groups: groups.len(),
+        upload_quality,
+        compression_format,
+        webhooks: webhook_previews,
+        files_to_compress,
+        disk_space,
+    })
+}
+
+/// Groups the files and renders the messages each group would post, independent of any webhook
+pub async fn preview_messages(
+    file_paths: Vec<String>,
+    options: &MessagePreviewOptions,
+) -> AppResult<Vec<GroupPreview>> {
+    let config = crate::config::load_config().ok();
+    let include_avatar_in_caption = config.as_ref().is_some_and(|c| c.include_avatar_in_caption);
+    let link_player_profiles = config.as_ref().is_some_and(|c| c.link_player_profiles);
+    let default_timestamp_style = config
+        .as_ref()
+        .map(|c| c.discord_timestamp_style.clone())
+        .unwrap_or_else(|| DEFAULT_TIMESTAMP_STYLE.to_string());
+    let timezone = image_processor::resolve_screenshot_timezone(
+        options.screenshot_timezone.as_deref().or(config
+            .as_ref()
+            .and_then(|c| c.screenshot_timezone.as_deref())),
+    );
+
+    let groups = if options.group_by_metadata {
+        super::image_groups::group_images_by_metadata(
+            file_paths,
+            options.grouping_time_window,
+            options.group_by_world,
+            options.group_by_instance,
+            options.merge_no_metadata,
+            options.adopt_orphans,
+            timezone,
+            None,
+        )
+        .await
+    } else {
+        super::image_groups::create_individual_groups_with_metadata(file_paths, timezone).await
+    };
+
+    let discord_user_map = load_discord_user_map().await;
+    let compression_format = config
+        .as_ref()
+        .map(|c| c.compression_format.clone())
+        .unwrap_or_else(|| "webp".to_string());
+
+    let preview_options = GroupPreviewOptions {
+        is_forum_channel: options.is_forum,
+        max_images_per_message: options.max_images_per_message,
+        upload_limit: DISCORD_UPLOAD_LIMIT,
+        compression_format: &compression_format,
+        include_player_names: options.include_player_names,
+        include_avatar_in_caption,
+        timestamp_style: &default_timestamp_style,
+        discord_user_map: &discord_user_map,
+        link_player_profiles,
+        extra_message: None,
+    };
+
+    Ok(groups
+        .iter()
+        .enumerate()
+        .map(|(group_index, group)| {
+            let creates_thread =
+                options.is_forum && (!options.single_thread_mode || group_index == 0);
+            preview_group(group, &preview_options, creates_thread, None)
+        })
+        .collect())
+}
+
+/// Rough size of an upload, for showing before it starts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupEstimate {
+    pub groups: usize,
+    /// Messages with attachments across all groups
+    pub messages: usize,
+    /// Photos without metadata in the index, including ones never read before
+    pub without_metadata: usize,
+}
+
+/// Metadata as far as the index records it (no instance or player IDs), which is
+/// enough to group by world
+fn indexed_metadata(photo: &IndexedPhoto) -> ImageMetadata {
+    ImageMetadata {
+        author: photo.author_name.clone().map(|display_name| AuthorInfo {
+            display_name,
+            id: String::new(),
+        }),
+        world: photo.world_id.clone().map(|id| WorldInfo {
+            name: photo.world_name.clone().unwrap_or_default(),
+            id,
+            instance_id: String::new(),
+        }),
+        players: photo
+            .players
+            .iter()
+            .map(|display_name| PlayerInfo {
+                display_name: display_name.clone(),
+                id: String::new(),
+            })
+            .collect(),
+        avatar: None,
+    }
+}
+
+/// Files split into groups the way the upload would, given each file's metadata
+/// and timestamp
+fn estimate_file_groups(
+    entries: &[(String, Option<ImageMetadata>, Option<i64>)],
+    options: &MessagePreviewOptions,
+) -> Vec<Vec<String>> {
+    if !options.group_by_metadata {
+        return entries
+            .iter()
+            .map(|(path, _, _)| vec![path.clone()])
+            .collect();
+    }
+
+    let no_time_limit = options.grouping_time_window == 0;
+    let keys = assign_group_keys(
+        entries,
+        (options.grouping_time_window as i64 * 60).max(1),
+        no_time_limit,
+        options.group_by_world,
+        options.group_by_instance,
+        options.merge_no_metadata,
+        options.adopt_orphans,
+    );
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for ((path, _, _), key) in entries.iter().zip(keys) {
+        let index = *group_index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(path.clone());
+    }
+    groups
+}
+
+/// Files with their indexed metadata and timestamp, grouped as the upload would
+struct EstimatedPlan {
+    entries: Vec<(String, Option<ImageMetadata>, Option<i64>)>,
+    groups: Vec<Vec<String>>,
+    estimate: GroupEstimate,
+}
+
+/// Groups and messages an upload of `file_paths` would create, from indexed metadata
+/// and file-name timestamps only, without reading the photos
+pub async fn estimate_groups(
+    file_paths: Vec<String>,
+    options: &MessagePreviewOptions,
+) -> GroupEstimate {
+    estimate_plan(file_paths, options).await.estimate
+}
+
+async fn estimate_plan(file_paths: Vec<String>, options: &MessagePreviewOptions) -> EstimatedPlan {
+    let config = crate::config::load_config().ok();
+    let timezone = image_processor::resolve_screenshot_timezone(
+        options.screenshot_timezone.as_deref().or(config
+            .as_ref()
+            .and_then(|c| c.screenshot_timezone.as_deref())),
+    );
+    let compression_format = config
+        .as_ref()
+        .map(|c| c.compression_format.clone())
+        .unwrap_or_else(|| "webp".to_string());
+
+    let indexed = database::get_indexed_photos_for(&file_paths)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Could not read indexed metadata for the group estimate: {e}");
+            HashMap::new()
+        });
+    let entries: Vec<(String, Option<ImageMetadata>, Option<i64>)> = file_paths
+        .into_iter()
+        .map(|path| {
+            let metadata = indexed.get(&path).map(indexed_metadata);
+            let timestamp =
+                image_processor::get_timestamp_from_filename_in_timezone(&path, timezone);
+            (path, metadata, timestamp)
+        })
+        .collect();
+
+    // Forum channels are capped at 10 images per message, same as the upload queue
+    let max_images = if options.is_forum {
+        options.max_images_per_message.clamp(1, 10)
+    } else {
+        options.max_images_per_message.max(1)
+    };
+    let groups = estimate_file_groups(&entries, options);
+    let messages = groups
+        .iter()
+        .map(|group| {
+            plan_chunks(
+                group,
+                max_images as usize,
+                DISCORD_UPLOAD_LIMIT,
+                &compression_format,
+            )
+            .len()
+        })
+        .sum();
+
+    let estimate = GroupEstimate {
+        groups: groups.len(),
+        messages,
+        without_metadata: entries.iter().filter(|(_, m, _)| m.is_none()).count(),
+    };
+    EstimatedPlan {
+        entries,
+        groups,
+        estimate,
+    }
+}
+
+/// Single-photo posts it takes before a split is worth warning about
+const SINGLE_GROUP_WARNING_THRESHOLD: usize = 5;
+
+/// Something about an upload plan the user probably didn't intend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanWarning {
+    /// "no_metadata_split" or "many_single_groups"
+    pub kind: &'static str,
+    pub message: String,
+    /// Photos that would each be posted on their own
+    pub file_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadPlanValidation {
+    pub estimate: GroupEstimate,
+    pub warnings: Vec<PlanWarning>,
+}
+
+/// Warn when grouping would post many photos one at a time: usually photos without
+/// metadata each getting their own "unknown" group, or a time window too short for
+/// the batch. Each post is a separate upload and a failed one stops the rest, so a
+/// batch that splits this way tends to look like it "failed after the first group".
+fn plan_warnings(
+    entries: &[(String, Option<ImageMetadata>, Option<i64>)],
+    groups: &[Vec<String>],
+    options: &MessagePreviewOptions,
+) -> Vec<PlanWarning> {
+    if !options.group_by_metadata {
+        return Vec::new();
+    }
+    let singles: Vec<&String> = groups
+        .iter()
+        .filter(|group| group.len() == 1)
+        .map(|group| &group[0])
+        .collect();
+    if singles.len() < SINGLE_GROUP_WARNING_THRESHOLD || singles.len() * 2 < groups.len() {
+        return Vec::new();
+    }
+
+    let no_metadata: std::collections::HashSet<&str> = entries
+        .iter()
+        .filter(|(_, metadata, _)| metadata.is_none())
+        .map(|(path, _, _)| path.as_str())
+        .collect();
+    let without_metadata: Vec<String> = singles
+        .iter()
+        .filter(|path| no_metadata.contains(path.as_str()))
+        .map(|path| path.to_string())
+        .collect();
+
+    let mut warnings = Vec::new();
+    if without_metadata.len() >= SINGLE_GROUP_WARNING_THRESHOLD && !options.merge_no_metadata {
+        warnings.push(PlanWarning {
+            kind: "no_metadata_split",
+            message: format!(
+                "{} photos have no metadata and will each be posted on their own. Turn on \
+                 merging photos without metadata to keep them with the photos around them.",
+                without_metadata.len()
+            ),
+            file_paths: without_metadata,
+        });
+    } else {
+        warnings.push(PlanWarning {
+            kind: "many_single_groups",
+            message: format!(
+                "{} of {} posts will have a single photo. A longer grouping time window \
+                 would keep more of them together.",
+                singles.len(),
+                groups.len()
+            ),
+            file_paths: singles.into_iter().cloned().collect(),
+        });
+    }
+    warnings
+}
+
+/// Check an upload plan for batches that would split into many single-photo posts
+pub async fn validate_upload_plan(
+    file_paths: Vec<String>,
+    options: &MessagePreviewOptions,
+) -> UploadPlanValidation {
+    let plan = estimate_plan(file_paths, options).await;
+    UploadPlanValidation {
+        warnings: plan_warnings(&plan.entries, &plan.groups, options),
+        estimate: plan.estimate,
+    }
+}
+
+/// Chunking and caption settings shared by every group previewed for one webhook
+pub struct GroupPreviewOptions<'a> {
+    pub is_forum_channel: bool,
+    pub max_images_per_message: u8,
+    pub upload_limit: u64,
+    pub compression_format: &'a str,
+    pub include_player_names: bool,
+    pub include_avatar_in_caption: bool,
+    pub timestamp_style: &'a str,
+    pub discord_user_map: &'a HashMap<String, String>,
+    pub link_player_profiles: bool,
+    pub extra_message: Option<&'a str>,
+}
+
+/// Builds the preview for a single group, mirroring the chunking and captions of a real upload
+pub fn preview_group(
+    group: &ImageGroup,
+    options: &GroupPreviewOptions,
+    creates_thread: bool,
+    caption_override: Option<&str>,
+) -> GroupPreview {
+    // Forum channels are capped at 10 images per message, same as the upload queue
+    let effective_max_images = if options.is_forum_channel && options.max_images_per_message > 10 {
+        10
+    } else {
+        options.max_images_per_message.max(1)
+    };
+
+    let (mut text_fields, mut overflow_messages) = create_discord_payload(
+        &group.all_worlds,
+        &group.all_players,
+        group.timestamp,
+        options.timestamp_style,
+        true,
+        0,
+        creates_thread,
+        None,
+        options.include_player_names,
+        group.images.len(),
+        options.discord_user_map,
+        options.link_player_profiles,
+    );
+    if options.include_avatar_in_caption {
+        append_avatar_caption(&mut text_fields, &group.all_avatars);
+    }
+    apply_caption_customizations(
+        &mut text_fields,
+        &mut overflow_messages,
+        caption_override,
+        options.extra_message,
+    );
+
+    let chunks = plan_chunks(
+        &group.images,
+        effective_max_images as usize,
+        options.upload_limit,
+        options.compression_format,
+    )
+    .into_iter()
+    .map(|chunk| {
+        let total_bytes: u64 = chunk
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        ChunkPreview {
+            file_paths: chunk,
+            total_bytes,
+            would_compress: total_bytes > options.upload_limit,
+        }
+    })
+    .collect();
+
+    GroupPreview {
+        group_id: group.group_id.clone(),
+        timestamp: group.timestamp,
+        file_paths: group.images.clone(),
+        target_webhook_name: None,
+        thread_title: text_fields.get("thread_name").cloned(),
+        content: text_fields.get("content").cloned(),
+        overflow_messages,
+        chunks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::WorldInfo;
+
+    fn make_group(image_count: usize) -> ImageGroup {
+        ImageGroup {
+            images: (0..image_count)
+                .map(|i| format!("nonexistent_dry_run_{i}.png"))
+                .collect(),
+            timestamp: Some(1_700_000_000),
+            group_id: "group_1".to_string(),
+            all_players: Vec::new(),
+            all_worlds: vec![WorldInfo {
+                name: "Test World".to_string(),
+                id: "wrld_test".to_string(),
+                instance_id: String::new(),
+            }],
+            all_avatars: Vec::new(),
+        }
+    }
+
+    fn preview_options<'a>(
+        is_forum_channel: bool,
+        max_images_per_message: u8,
+        include_player_names: bool,
+        discord_user_map: &'a HashMap<String, String>,
+    ) -> GroupPreviewOptions<'a> {
+        GroupPreviewOptions {
+            is_forum_channel,
+            max_images_per_message,
+            upload_limit: DISCORD_UPLOAD_LIMIT,
+            compression_format: "webp",
+            include_player_names,
+            include_avatar_in_caption: false,
+            timestamp_style: DEFAULT_TIMESTAMP_STYLE,
+            discord_user_map,
+            link_player_profiles: false,
+            extra_message: None,
+        }
+    }
+
+    #[test]
+    fn test_preview_group_chunks_and_caption() {
+        let group = make_group(12);
+        let user_map = HashMap::new();
+        let preview = preview_group(
+            &group,
+            &preview_options(false, 5, true, &user_map),
+            false,
+            None,
+        );
+
+        assert_eq!(preview.chunks.len(), 3);
+        assert_eq!(preview.chunks[2].file_paths.len(), 2);
+        assert!(preview.chunks.iter().all(|c| !c.would_compress));
+        assert!(preview.overflow_messages.is_empty());
+        assert!(preview.content.unwrap().contains("Test World"));
+        assert!(preview.thread_title.is_none());
+    }
+
+    #[test]
+    fn test_preview_group_forum_caps_chunk_size_and_titles_thread() {
+        let group = make_group(12);
+        let user_map = HashMap::new();
+        let preview = preview_group(
+            &group,
+            &preview_options(true, 20, false, &user_map),
+            true,
+            None,
+        );
+
+        assert_eq!(preview.chunks.len(), 2);
+        assert_eq!(preview.chunks[0].file_paths.len(), 10);
+        assert!(preview.thread_title.is_some());
+    }
+
+    fn indexed_in(world_id: &str) -> Option<ImageMetadata> {
+        Some(indexed_metadata(&IndexedPhoto {
+            world_id: Some(world_id.to_string()),
+            world_name: Some(world_id.to_string()),
+            ..IndexedPhoto::default()
+        }))
+    }
+
+    #[test]
+    fn test_estimate_file_groups_from_indexed_metadata() {
+        let entries = vec![
+            ("a.png".to_string(), indexed_in("wrld_a"), Some(1_000)),
+            ("b.png".to_string(), indexed_in("wrld_a"), Some(1_060)),
+            ("c.png".to_string(), indexed_in("wrld_b"), Some(1_120)),
+            // Over an hour later, so a new group even in the same world
+            ("d.png".to_string(), indexed_in("wrld_a"), Some(9_000)),
+            ("e.png".to_string(), None, Some(9_060)),
+        ];
+        let mut options: MessagePreviewOptions = serde_json::from_str("{}").unwrap();
+        options.grouping_time_window = 60;
+
+        let groups = estimate_file_groups(&entries, &options);
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups[0], vec!["a.png", "b.png"]);
+
+        options.merge_no_metadata = true;
+        assert_eq!(estimate_file_groups(&entries, &options).len(), 3);
+
+        options.group_by_metadata = false;
+        assert_eq!(estimate_file_groups(&entries, &options).len(), 5);
+    }
+
+    #[test]
+    fn test_plan_warnings_for_split_batches() {
+        let mut options: MessagePreviewOptions = serde_json::from_str("{}").unwrap();
+        options.grouping_time_window = 60;
+
+        // Two photos with metadata, then six without and no file-name timestamps
+        let mut entries = vec![
+            ("a.png".to_string(), indexed_in("wrld_a"), Some(1_000)),
+            ("b.png".to_string(), indexed_in("wrld_a"), Some(1_060)),
+        ];
+        entries.extend((0..6).map(|i| (format!("photo_{i}.png"), None, None)));
+        let groups = estimate_file_groups(&entries, &options);
+        assert_eq!(groups.len(), 7);
+
+        let warnings = plan_warnings(&entries, &groups, &options);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, "no_metadata_split");
+        assert_eq!(warnings[0].file_paths.len(), 6);
+
+        // Merging keeps them with the previous group
+        options.merge_no_metadata = true;
+        let groups = estimate_file_groups(&entries, &options);
+        assert!(plan_warnings(&entries, &groups, &options).is_empty());
+
+        // Photos with metadata but far apart in time
+        let spread: Vec<_> = (0..6)
+            .map(|i| (format!("s{i}.png"), indexed_in("wrld_a"), Some(i * 10_000)))
+            .collect();
+        let groups = estimate_file_groups(&spread, &options);
+        assert_eq!(
+            plan_warnings(&spread, &groups, &options)[0].kind,
+            "many_single_groups"
+        );
+    }
+}
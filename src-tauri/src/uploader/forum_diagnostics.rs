@@ -0,0 +1,214 @@
+// Forum webhook diagnostics
+//
+// Forum uploads post the first message with a thread name and send every later chunk
+// into the thread whose ID is read back from that response. When that ID is wrong,
+// Discord answers the follow-ups with error 220001. This runs the same steps with a
+// tiny test post and reports which one broke, then deletes what it posted.
+
+use serde::Serialize;
+
+use crate::database;
+use crate::errors::AppResult;
+
+use super::discord_client::{extract_thread_id, DiscordClient};
+
+const TEST_THREAD_NAME: &str = "Uploader forum test";
+const TEST_MESSAGE: &str =
+    "Forum connection test from VRChat Photo Uploader. This post is deleted automatically.";
+const TEST_REPLY: &str = "Thread reply test.";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticStep {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForumDiagnosticReport {
+    pub webhook_id: i64,
+    pub steps: Vec<DiagnosticStep>,
+    /// First step that failed; None when everything worked
+    pub failed_step: Option<&'static str>,
+    pub thread_id: Option<String>,
+}
+
+impl ForumDiagnosticReport {
+    fn new(webhook_id: i64) -> Self {
+        Self {
+            webhook_id,
+            steps: Vec::new(),
+            failed_step: None,
+            thread_id: None,
+        }
+    }
+
+    fn pass(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.steps.push(DiagnosticStep {
+            name,
+            ok: true,
+            detail: detail.into(),
+        });
+    }
+
+    fn fail(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.steps.push(DiagnosticStep {
+            name,
+            ok: false,
+            detail: detail.into(),
+        });
+        self.failed_step.get_or_insert(name);
+    }
+}
+
+/// ID of the message in a webhook response
+fn response_message_id(response: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(response)
+        .ok()?
+        .get("id")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Thread ID from the response to the thread-creating post, or why it couldn't be read.
+/// The first message of a forum post shares its ID with the thread, and `channel_id`
+/// points at the thread; a `channel_id` different from the message ID means the post
+/// didn't create a thread.
+fn check_thread_id(response: &str) -> Result<String, String> {
+    if response.trim().is_empty() {
+        return Err(
+            "Discord returned an empty response, so there is nothing to read the thread ID from"
+                .to_string(),
+        );
+    }
+    let json: serde_json::Value = serde_json::from_str(response)
+        .map_err(|e| format!("Discord's response isn't valid JSON: {e}"))?;
+    let message_id = json.get("id").and_then(|v| v.as_str());
+    let channel_id = json.get("channel_id").and_then(|v| v.as_str());
+
+    let thread_id = extract_thread_id(response)
+        .ok_or_else(|| "The response has neither a channel_id nor an id field".to_string())?;
+    match (message_id, channel_id) {
+        (Some(message_id), Some(channel_id)) if message_id != channel_id => Err(format!(
+            "The post landed in channel {channel_id} instead of a new thread; is the webhook's channel a forum?"
+        )),
+        (_, None) => Err(format!(
+            "The response has no channel_id, only the message ID {thread_id}"
+        )),
+        _ => Ok(thread_id),
+    }
+}
+
+/// Post a test thread through the webhook, reply in it using the extracted thread ID,
+/// then delete both messages
+pub async fn diagnose_forum_webhook(
+    client: &DiscordClient,
+    webhook_id: i64,
+) -> AppResult<ForumDiagnosticReport> {
+    let webhook = database::get_webhook_by_id(webhook_id).await?;
+    let mut report = ForumDiagnosticReport::new(webhook_id);
+    log::info!("Running forum diagnostics for webhook '{}'", webhook.name);
+
+    if webhook.is_forum {
+        report.pass("forum_setting", "Webhook is marked as a forum channel");
+    } else {
+        report.fail(
+            "forum_setting",
+            "Webhook isn't marked as a forum channel, so uploads won't create threads",
+        );
+    }
+
+    let response = match client
+        .send_forum_text_message(&webhook.url, TEST_MESSAGE, Some(TEST_THREAD_NAME))
+        .await
+    {
+        Ok(response) => {
+            report.pass("create_thread", "Posted a test thread");
+            response
+        }
+        Err(e) => {
+            report.fail(
+                "create_thread",
+                format!("Discord rejected the test thread: {e}"),
+            );
+            return Ok(report);
+        }
+    };
+    let starter_id = response_message_id(&response);
+
+    let thread_id = match check_thread_id(&response) {
+        Ok(thread_id) => {
+            report.pass("extract_thread_id", format!("Read thread ID {thread_id}"));
+            report.thread_id = Some(thread_id.clone());
+            Some(thread_id)
+        }
+        Err(reason) => {
+            report.fail("extract_thread_id", reason);
+            None
+        }
+    };
+
+    let mut reply_id = None;
+    if let Some(thread_id) = &thread_id {
+        match client
+            .post_text_message(&webhook.url, TEST_REPLY, Some(thread_id))
+            .await
+        {
+            Ok(response) => {
+                report.pass("post_in_thread", "Posted a reply into the thread");
+                reply_id = response_message_id(&response);
+            }
+            Err(e) => report.fail(
+                "post_in_thread",
+                format!("Posting into thread {thread_id} failed: {e}"),
+            ),
+        }
+    }
+
+    // Clean up whatever was posted; the reply goes first so the thread stays addressable
+    let mut cleanup_errors = Vec::new();
+    for message_id in [reply_id, starter_id].into_iter().flatten() {
+        let thread = thread_id.as_deref().or(Some(message_id.as_str()));
+        if let Err(e) = client
+            .delete_webhook_message(&webhook.url, &message_id, thread)
+            .await
+        {
+            cleanup_errors.push(format!("message {message_id}: {e}"));
+        }
+    }
+    if cleanup_errors.is_empty() {
+        report.pass(
+            "delete_test_posts",
+            "Deleted the test messages (Discord keeps the empty thread until a moderator removes it)",
+        );
+    } else {
+        report.fail(
+            "delete_test_posts",
+            format!("Couldn't delete {}", cleanup_errors.join(", ")),
+        );
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_thread_id_explains_failures() {
+        let thread_post = r#"{"id":"555","channel_id":"555","content":"test"}"#;
+        assert_eq!(check_thread_id(thread_post), Ok("555".to_string()));
+
+        // Posted to a plain text channel: no thread was created
+        let channel_post = r#"{"id":"555","channel_id":"100"}"#;
+        assert!(check_thread_id(channel_post)
+            .unwrap_err()
+            .contains("instead of a new thread"));
+
+        assert!(check_thread_id("").unwrap_err().contains("empty response"));
+        assert!(check_thread_id(r#"{"id":"555"}"#)
+            .unwrap_err()
+            .contains("no channel_id"));
+    }
+}
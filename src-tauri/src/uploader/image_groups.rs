@@ -1,6 +1,15 @@
-use crate::commands::{ImageMetadata, PlayerInfo, WorldInfo};
+use crate::commands::{AvatarInfo, CustomGroup, ImageMetadata, PlayerInfo, Webhook, WorldInfo};
+use crate::database::ForumTag;
+use crate::errors::{AppError, AppResult};
 use crate::image_processor;
 use std::collections::HashMap;
+
+use super::discord_client::LinkButtons;
+use super::discord_client::DISCORD_MESSAGE_LIMIT;
+use super::message_text::{
+    char_len, split_list, truncate_graphemes, truncate_with_ellipsis, MessageBuilder,
+    CAPTION_BUDGET,
+};
 use std::path::Path;
 
 #[derive(Debug, Clone)]
@@ -10,17 +19,32 @@ pub struct ImageGroup {
     pub group_id: String,
     pub all_players: Vec<PlayerInfo>,
     pub all_worlds: Vec<WorldInfo>,
+    pub all_avatars: Vec<AvatarInfo>,
+}
+
+/// Avatars are keyed by ID, or by name when the metadata has no ID
+fn avatar_key(avatar: &AvatarInfo) -> String {
+    if avatar.id.is_empty() {
+        format!("name:{}", avatar.name)
+    } else {
+        avatar.id.clone()
+    }
 }
 
-/// Groups images by world and time window
+/// Groups images by world and time: photos stay together while the gap between
+/// consecutive shots is within the time window. Metadata progress is reported to
+/// `progress` (app handle and session id); previews pass `None` since no session exists.
 // Update signature and implementation
+#[allow(clippy::too_many_arguments)]
 pub async fn group_images_by_metadata(
     file_paths: Vec<String>,
     time_window_minutes: u32,
     group_by_world: bool,
+    group_by_instance: bool,
     merge_no_metadata: bool,
-    app_handle: tauri::AppHandle,
-    session_id: String,
+    adopt_orphans: bool,
+    timezone: Option<chrono_tz::Tz>,
+    progress: Option<(tauri::AppHandle, String)>,
 ) -> Vec<ImageGroup> {
     let mut image_data: Vec<(String, Option<ImageMetadata>, Option<i64>, String)> = Vec::new();
     let no_time_limit = time_window_minutes == 0;
@@ -54,18 +78,18 @@ pub async fn group_images_by_metadata(
         let sem = semaphore.clone();
         let results = results_mutex.clone();
         let completed = completed_counter.clone();
-        let app_handle = app_handle.clone();
-        let session_id = session_id.clone();
+        let progress = progress.clone();
 
         handles.push(tokio::spawn(async move {
             let _permit = sem.acquire().await.unwrap();
             log::debug!("Extracting metadata for: {file_path}");
 
-            let metadata = image_processor::extract_metadata(&file_path)
+            let metadata = image_processor::extract_metadata(&file_path, timezone)
                 .await
                 .ok()
                 .flatten();
-            let timestamp = image_processor::get_timestamp_from_filename(&file_path);
+            let timestamp =
+                image_processor::get_timestamp_from_filename_in_timezone(&file_path, timezone);
 
             let mut guard = results.lock().unwrap();
             guard.push((index, file_path, metadata, timestamp));
@@ -73,6 +97,9 @@ pub async fn group_images_by_metadata(
             // Emit progress
             let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
             // Emit batch updates to avoid flooding event loop for 5000 items
+            let Some((app_handle, session_id)) = &progress else {
+                return;
+            };
             if done.is_multiple_of(5) || done == total_files {
                 app_handle.emit("upload-progress", serde_json::json!({
                     "session_id": session_id,
@@ -100,51 +127,39 @@ pub async fn group_images_by_metadata(
     };
     collected_results.sort_by_key(|r| r.0);
 
-    let mut last_valid_group_key: Option<String> = None;
-
-    // Sequential grouping logic (must be sequential for context)
-    for (_index, file_path, metadata, timestamp) in collected_results {
-        let group_key = if let Some(ref meta) = metadata {
-            let key = create_metadata_key(
-                meta,
-                timestamp,
-                time_window_seconds,
-                no_time_limit,
-                group_by_world,
-            );
-            // Update last valid key if we found metadata
-            if merge_no_metadata {
-                last_valid_group_key = Some(key.clone());
-            }
-            key
-        } else if let Some(prev_key) = last_valid_group_key.as_ref().filter(|_| merge_no_metadata) {
-            // If merging is enabled and we have a previous group, use it!
-            let key = prev_key.clone();
-            log::info!("Merging no-metadata file {file_path} into previous group: {key}");
-            key
-        } else if no_time_limit {
-            "unknown_all".to_string()
-        } else if let Some(ts) = timestamp {
-            format!("unknown_{}", ts / time_window_seconds)
-        } else {
-            format!("unknown_{file_path}")
-        };
+    let entries: Vec<(String, Option<ImageMetadata>, Option<i64>)> = collected_results
+        .into_iter()
+        .map(|(_index, file_path, metadata, timestamp)| (file_path, metadata, timestamp))
+        .collect();
+    let group_keys = assign_group_keys(
+        &entries,
+        time_window_seconds,
+        no_time_limit,
+        group_by_world,
+        group_by_instance,
+        merge_no_metadata,
+        adopt_orphans,
+    );
 
+    for ((file_path, metadata, timestamp), group_key) in entries.into_iter().zip(group_keys) {
         image_data.push((file_path, metadata, timestamp, group_key));
     }
 
     log::info!(
-        "Grouping {} images (window: {}m, world: {}, merge_no_meta: {})",
+        "Grouping {} images (window: {}m, world: {}, instance: {}, merge_no_meta: {}, adopt_orphans: {})",
         image_data.len(),
         time_window_minutes,
         group_by_world,
-        merge_no_metadata
+        group_by_instance,
+        merge_no_metadata,
+        adopt_orphans
     );
 
     // Group images and collect players and worlds
     let mut groups: HashMap<String, ImageGroup> = HashMap::new();
     let mut group_players: HashMap<String, HashMap<String, PlayerInfo>> = HashMap::new();
     let mut group_worlds: HashMap<String, HashMap<String, WorldInfo>> = HashMap::new();
+    let mut group_avatars: HashMap<String, HashMap<String, AvatarInfo>> = HashMap::new();
 
     for (file_path, metadata, timestamp, group_key) in image_data {
         if let Some(ref meta) = metadata {
@@ -163,6 +178,13 @@ pub async fn group_images_by_metadata(
                     .entry(world.id.clone())
                     .or_insert_with(|| world.clone());
             }
+
+            if let Some(ref avatar) = meta.avatar {
+                let avatar_map = group_avatars.entry(group_key.clone()).or_default();
+                avatar_map
+                    .entry(avatar_key(avatar))
+                    .or_insert_with(|| avatar.clone());
+            }
         }
 
         let group = groups
@@ -173,6 +195,7 @@ pub async fn group_images_by_metadata(
                 group_id: group_key.clone(),
                 all_players: Vec::new(),
                 all_worlds: Vec::new(),
+                all_avatars: Vec::new(),
             });
 
         group.images.push(file_path);
@@ -190,6 +213,10 @@ pub async fn group_images_by_metadata(
             group.all_worlds = world_map.values().cloned().collect();
             group.all_worlds.sort_by(|a, b| a.name.cmp(&b.name));
         }
+        if let Some(avatar_map) = group_avatars.get(group_key) {
+            group.all_avatars = avatar_map.values().cloned().collect();
+            group.all_avatars.sort_by(|a, b| a.name.cmp(&b.name));
+        }
     }
 
     // Sort by timestamp
@@ -206,15 +233,19 @@ pub async fn group_images_by_metadata(
 }
 
 /// Creates one group per image (no grouping)
-pub async fn create_individual_groups_with_metadata(file_paths: Vec<String>) -> Vec<ImageGroup> {
+pub async fn create_individual_groups_with_metadata(
+    file_paths: Vec<String>,
+    timezone: Option<chrono_tz::Tz>,
+) -> Vec<ImageGroup> {
     let mut groups = Vec::new();
 
     for (i, file_path) in file_paths.into_iter().enumerate() {
-        let metadata = image_processor::extract_metadata(&file_path)
+        let metadata = image_processor::extract_metadata(&file_path, timezone)
             .await
             .ok()
             .flatten();
-        let timestamp = image_processor::get_timestamp_from_filename(&file_path);
+        let timestamp =
+            image_processor::get_timestamp_from_filename_in_timezone(&file_path, timezone);
         let all_players = metadata
             .as_ref()
             .map(|m| m.players.clone())
@@ -224,6 +255,11 @@ pub async fn create_individual_groups_with_metadata(file_paths: Vec<String>) ->
             .and_then(|m| m.world.clone())
             .map(|w| vec![w])
             .unwrap_or_default();
+        let all_avatars = metadata
+            .as_ref()
+            .and_then(|m| m.avatar.clone())
+            .map(|a| vec![a])
+            .unwrap_or_default();
 
         groups.push(ImageGroup {
             images: vec![file_path.clone()],
@@ -238,6 +274,7 @@ pub async fn create_individual_groups_with_metadata(file_paths: Vec<String>) ->
             ),
             all_players,
             all_worlds,
+            all_avatars,
         });
     }
 
@@ -245,14 +282,248 @@ pub async fn create_individual_groups_with_metadata(file_paths: Vec<String>) ->
     groups
 }
 
-fn create_metadata_key(
-    metadata: &ImageMetadata,
-    timestamp: Option<i64>,
+/// Builds upload groups from user-arranged groups, keeping their order. Files not in
+/// `valid_files` are dropped, and groups left empty are skipped.
+pub async fn build_custom_groups(
+    custom_groups: &[CustomGroup],
+    valid_files: &[String],
+    timezone: Option<chrono_tz::Tz>,
+) -> Vec<ImageGroup> {
+    let mut groups = Vec::new();
+
+    for custom_group in custom_groups {
+        let images: Vec<String> = custom_group
+            .file_paths
+            .iter()
+            .filter(|p| valid_files.contains(p))
+            .cloned()
+            .collect();
+        if images.is_empty() {
+            continue;
+        }
+
+        let mut player_map: HashMap<String, PlayerInfo> = HashMap::new();
+        let mut world_map: HashMap<String, WorldInfo> = HashMap::new();
+        let mut avatar_map: HashMap<String, AvatarInfo> = HashMap::new();
+        let mut timestamp: Option<i64> = None;
+
+        for file_path in &images {
+            if let Some(meta) = image_processor::extract_metadata(file_path, timezone)
+                .await
+                .ok()
+                .flatten()
+            {
+                for player in meta.players {
+                    player_map.entry(player.id.clone()).or_insert(player);
+                }
+                if let Some(world) = meta.world {
+                    world_map.entry(world.id.clone()).or_insert(world);
+                }
+                if let Some(avatar) = meta.avatar {
+                    avatar_map.entry(avatar_key(&avatar)).or_insert(avatar);
+                }
+            }
+
+            // The earliest photo dates the group
+            if let Some(ts) =
+                image_processor::get_timestamp_from_filename_in_timezone(file_path, timezone)
+            {
+                timestamp = Some(timestamp.map_or(ts, |current| current.min(ts)));
+            }
+        }
+
+        let mut all_players: Vec<PlayerInfo> = player_map.into_values().collect();
+        all_players.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        let mut all_worlds: Vec<WorldInfo> = world_map.into_values().collect();
+        all_worlds.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut all_avatars: Vec<AvatarInfo> = avatar_map.into_values().collect();
+        all_avatars.sort_by(|a, b| a.name.cmp(&b.name));
+
+        groups.push(ImageGroup {
+            images,
+            timestamp,
+            group_id: custom_group.group_id.clone(),
+            all_players,
+            all_worlds,
+            all_avatars,
+        });
+    }
+
+    log::info!(
+        "Built {} custom groups from {} requested",
+        groups.len(),
+        custom_groups.len()
+    );
+
+    groups
+}
+
+/// Clusters photos into shooting sessions: sorted by time, a new cluster starts whenever
+/// the gap to the previous photo exceeds the window. Returns each timestamp's cluster start.
+fn cluster_by_time_gaps(timestamps: &[i64], window_seconds: i64) -> Vec<i64> {
+    let mut order: Vec<usize> = (0..timestamps.len()).collect();
+    order.sort_by_key(|&i| timestamps[i]);
+
+    let mut cluster_starts = vec![0; timestamps.len()];
+    let mut current: Option<(i64, i64)> = None; // (cluster start, previous timestamp)
+
+    for i in order {
+        let ts = timestamps[i];
+        let start = match current {
+            Some((start, prev)) if ts - prev <= window_seconds => start,
+            _ => ts,
+        };
+        cluster_starts[i] = start;
+        current = Some((start, ts));
+    }
+
+    cluster_starts
+}
+
+/// Computes the group key of every entry (file path, metadata, timestamp), in input order.
+/// Photos are partitioned by world (when enabled) and then split into time clusters;
+/// files without metadata either join the previous metadata group (merge mode) or are
+/// clustered among themselves. With `adopt_orphans`, a file without metadata whose
+/// timestamp falls within a metadata group's time span (widened by the time window)
+/// joins that group instead, which recovers photos VRChat failed to tag.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn assign_group_keys(
+    entries: &[(String, Option<ImageMetadata>, Option<i64>)],
     time_window_seconds: i64,
     no_time_limit: bool,
     group_by_world: bool,
+    group_by_instance: bool,
+    merge_no_metadata: bool,
+    adopt_orphans: bool,
+) -> Vec<String> {
+    // Partition timestamped files: metadata files by world, unmerged no-metadata files together
+    let mut partitions: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut seen_metadata = false;
+    for (i, (_, metadata, timestamp)) in entries.iter().enumerate() {
+        let partition = match metadata {
+            Some(meta) => {
+                seen_metadata = true;
+                Some(world_partition_key(meta, group_by_world, group_by_instance))
+            }
+            None if merge_no_metadata && seen_metadata => None,
+            None => Some(String::new()),
+        };
+        if let (Some(partition), Some(_)) = (partition, timestamp) {
+            partitions.entry(partition).or_default().push(i);
+        }
+    }
+
+    let mut cluster_starts: HashMap<usize, i64> = HashMap::new();
+    if !no_time_limit {
+        for indices in partitions.values() {
+            let timestamps: Vec<i64> = indices.iter().filter_map(|&i| entries[i].2).collect();
+            let starts = cluster_by_time_gaps(&timestamps, time_window_seconds);
+            cluster_starts.extend(indices.iter().copied().zip(starts));
+        }
+    }
+
+    // Sequential pass (must be sequential for merge context)
+    let mut last_valid_group_key: Option<String> = None;
+    let mut keys = Vec::with_capacity(entries.len());
+
+    for (i, (file_path, metadata, timestamp)) in entries.iter().enumerate() {
+        let group_key = if let Some(ref meta) = metadata {
+            let key = create_metadata_key(
+                meta,
+                cluster_starts.get(&i).copied(),
+                no_time_limit,
+                group_by_world,
+                group_by_instance,
+            );
+            // Update last valid key if we found metadata
+            if merge_no_metadata {
+                last_valid_group_key = Some(key.clone());
+            }
+            key
+        } else if let Some(prev_key) = last_valid_group_key.as_ref().filter(|_| merge_no_metadata) {
+            // If merging is enabled and we have a previous group, use it!
+            let key = prev_key.clone();
+            log::info!("Merging no-metadata file {file_path} into previous group: {key}");
+            key
+        } else if no_time_limit {
+            "unknown_all".to_string()
+        } else if timestamp.is_some() {
+            format!("unknown_{}", cluster_starts.get(&i).copied().unwrap_or(0))
+        } else {
+            format!("unknown_{file_path}")
+        };
+
+        keys.push(group_key);
+    }
+
+    if adopt_orphans {
+        adopt_orphans_by_timestamp(entries, &mut keys, time_window_seconds, no_time_limit);
+    }
+
+    keys
+}
+
+/// Moves timestamped files without metadata into the metadata group whose time span
+/// contains them, picking the nearest span when several qualify. Without a time limit
+/// a group's span isn't widened, so only photos taken between its first and last shot
+/// are adopted.
+fn adopt_orphans_by_timestamp(
+    entries: &[(String, Option<ImageMetadata>, Option<i64>)],
+    keys: &mut [String],
+    time_window_seconds: i64,
+    no_time_limit: bool,
+) {
+    let mut spans: HashMap<&str, (i64, i64)> = HashMap::new();
+    for ((_, metadata, timestamp), key) in entries.iter().zip(keys.iter()) {
+        if let (Some(_), Some(ts)) = (metadata, timestamp) {
+            let span = spans.entry(key.as_str()).or_insert((*ts, *ts));
+            span.0 = span.0.min(*ts);
+            span.1 = span.1.max(*ts);
+        }
+    }
+    if spans.is_empty() {
+        return;
+    }
+
+    let margin = if no_time_limit {
+        0
+    } else {
+        time_window_seconds
+    };
+    let adoptions: Vec<(usize, String)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (file_path, metadata, timestamp))| {
+            let ts = timestamp.filter(|_| metadata.is_none())?;
+            let (key, _) = spans
+                .iter()
+                .filter(|(_, (start, end))| ts >= start - margin && ts <= end + margin)
+                .map(|(key, (start, end))| (*key, (start - ts).max(ts - end).max(0)))
+                .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))?;
+            log::info!("Adopting no-metadata file {file_path} into group {key} by timestamp");
+            Some((i, key.to_string()))
+        })
+        .collect();
+
+    for (i, key) in adoptions {
+        keys[i] = key;
+    }
+}
+
+/// World part of a group key. With instance grouping, photos from different instances of
+/// the same world are kept apart (when the metadata carries an instance ID, e.g. VRCX).
+fn world_partition_key(
+    metadata: &ImageMetadata,
+    group_by_world: bool,
+    group_by_instance: bool,
 ) -> String {
-    let world_part = if group_by_world {
+    if let Some(world) = metadata
+        .world
+        .as_ref()
+        .filter(|w| group_by_instance && !w.instance_id.is_empty())
+    {
+        format!("{}#{}", world.id, world.instance_id)
+    } else if group_by_world {
         metadata
             .world
             .as_ref()
@@ -260,23 +531,35 @@ fn create_metadata_key(
             .unwrap_or_else(|| "unknown".to_string())
     } else {
         "any_world".to_string()
-    };
+    }
+}
+
+fn create_metadata_key(
+    metadata: &ImageMetadata,
+    cluster_start: Option<i64>,
+    no_time_limit: bool,
+    group_by_world: bool,
+    group_by_instance: bool,
+) -> String {
+    let world_part = world_partition_key(metadata, group_by_world, group_by_instance);
 
     if no_time_limit {
         format!("{world_part}_all")
     } else {
-        format!(
-            "{}_t{}",
-            world_part,
-            timestamp.unwrap_or(0) / time_window_seconds
-        )
+        format!("{}_t{}", world_part, cluster_start.unwrap_or(0))
     }
 }
 
-/// Format a player for Discord: returns `<@discord_id>` if mapped, else `**PlayerName**`
+fn player_profile_url(user_id: &str) -> String {
+    format!("https://vrchat.com/home/user/{user_id}")
+}
+
+/// Format a player for Discord: returns `<@discord_id>` if mapped, else a masked link to
+/// their VRChat profile when `link_profiles` is set and the ID is known, else `**PlayerName**`
 fn format_player_for_discord(
     player: &PlayerInfo,
     discord_mappings: &HashMap<String, String>,
+    link_profiles: bool,
 ) -> String {
     // Check by VRChat user ID first (more reliable), then by display name
     // Keys in the map are lowercased for case-insensitive matching
@@ -285,17 +568,44 @@ fn format_player_for_discord(
         .or_else(|| discord_mappings.get(&player.display_name.to_lowercase()))
     {
         format!("<@{discord_id}>")
+    } else if link_profiles && !player.id.is_empty() {
+        // Brackets in the name would end the link text early
+        let name = player.display_name.replace('[', "\\[").replace(']', "\\]");
+        format!("[{name}](<{}>)", player_profile_url(&player.id))
     } else {
         format!("**{}**", player.display_name)
     }
 }
 
+/// Discord timestamp styles: short/long time, short/long date, short/long date and
+/// time, and relative ("2 hours ago")
+pub const TIMESTAMP_STYLES: &[&str] = &["t", "T", "d", "D", "f", "F", "R"];
+pub const DEFAULT_TIMESTAMP_STYLE: &str = "f";
+
+/// `<t:ts:style>` markup Discord renders in each reader's own time zone. Unknown
+/// styles fall back to the default.
+pub fn discord_timestamp(ts: i64, style: &str) -> String {
+    let style = if TIMESTAMP_STYLES.contains(&style) {
+        style
+    } else {
+        DEFAULT_TIMESTAMP_STYLE
+    };
+    format!("<t:{ts}:{style}>")
+}
+
+/// Timestamp style for captions posted through a webhook: its own override, else
+/// the configured default
+pub fn timestamp_style_for<'a>(webhook: &'a Webhook, default_style: &'a str) -> &'a str {
+    webhook.timestamp_style.as_deref().unwrap_or(default_style)
+}
+
 /// Creates Discord payload. Returns (main_payload, overflow_messages)
 #[allow(clippy::too_many_arguments)]
 pub fn create_discord_payload(
     all_worlds: &[WorldInfo],
     all_players: &[PlayerInfo],
     timestamp: Option<i64>,
+    timestamp_style: &str,
     is_first_message: bool,
     chunk_index: usize,
     is_forum_post: bool,
@@ -303,20 +613,42 @@ pub fn create_discord_payload(
     include_player_names: bool,
     image_count: usize,
     discord_mappings: &HashMap<String, String>,
+    link_player_profiles: bool,
 ) -> (HashMap<String, String>, Vec<String>) {
     let mut payload = HashMap::new();
     let mut overflow_messages = Vec::new();
 
     if is_first_message {
         // Create content with worlds, timestamp, and as many players as fit
-        let (content, remaining_players, had_players_in_main) = create_message_content_with_players(
-            all_worlds,
-            all_players,
-            timestamp,
-            include_player_names,
-            image_count,
-            discord_mappings,
-        );
+        let mut link_player_profiles = link_player_profiles;
+        let (mut content, mut remaining_players, mut had_players_in_main) =
+            create_message_content_with_players(
+                all_worlds,
+                all_players,
+                timestamp,
+                timestamp_style,
+                include_player_names,
+                image_count,
+                discord_mappings,
+                link_player_profiles,
+            );
+
+        // Profile links are several times longer than names; rather than spilling the
+        // player list into extra messages for them, fall back to plain names
+        if link_player_profiles && !remaining_players.is_empty() {
+            log::info!("Player profile links don't fit in one message, using plain names");
+            link_player_profiles = false;
+            (content, remaining_players, had_players_in_main) = create_message_content_with_players(
+                all_worlds,
+                all_players,
+                timestamp,
+                timestamp_style,
+                include_player_names,
+                image_count,
+                discord_mappings,
+                false,
+            );
+        }
         payload.insert("content".to_string(), content);
 
         if is_forum_post {
@@ -330,6 +662,7 @@ pub fn create_discord_payload(
                 &remaining_players,
                 had_players_in_main,
                 discord_mappings,
+                link_player_profiles,
             );
         }
     } else if chunk_index > 0 {
@@ -339,17 +672,100 @@ pub fn create_discord_payload(
     (payload, overflow_messages)
 }
 
+fn world_launch_url(world_id: &str) -> String {
+    format!("https://vrchat.com/home/launch?worldId={world_id}")
+}
+
+fn world_vrcx_url(world_id: &str) -> String {
+    format!("https://vrcx.azurewebsites.net/world/{world_id}")
+}
+
+/// The " ([VRChat](...), [VRCX](...))" links that follow a world's name in captions
+fn world_text_links(world: &WorldInfo) -> String {
+    format!(
+        " ([VRChat](<{}>), [VRCX](<{}>))",
+        world_launch_url(&world.id),
+        world_vrcx_url(&world.id)
+    )
+}
+
+/// Builds link buttons for a caption's worlds, one row per world, and takes the
+/// matching text links out of the caption. Returns None when there are more worlds
+/// than Discord allows rows, leaving the text links in place.
+pub fn create_world_link_buttons(all_worlds: &[WorldInfo], content: &str) -> Option<LinkButtons> {
+    const MAX_ACTION_ROWS: usize = 5;
+    const MAX_LABEL_LENGTH: usize = 80;
+
+    if all_worlds.is_empty() || all_worlds.len() > MAX_ACTION_ROWS {
+        return None;
+    }
+
+    let mut stripped = content.to_string();
+    let rows: Vec<serde_json::Value> = all_worlds
+        .iter()
+        .map(|world| {
+            stripped = stripped.replace(&world_text_links(world), "");
+            let label = format!("Launch {}", world.name);
+            let label = truncate_graphemes(&label, MAX_LABEL_LENGTH);
+            // Type 1 is an action row, type 2 a button; style 5 makes it a link button
+            serde_json::json!({
+                "type": 1,
+                "components": [
+                    { "type": 2, "style": 5, "label": label, "url": world_launch_url(&world.id) },
+                    { "type": 2, "style": 5, "label": "VRCX", "url": world_vrcx_url(&world.id) },
+                ],
+            })
+        })
+        .collect();
+
+    Some(LinkButtons {
+        content: stripped,
+        components: serde_json::Value::Array(rows),
+    })
+}
+
+/// Discord applies at most this many tags to a forum post
+pub const MAX_FORUM_TAGS: usize = 5;
+
+/// Forum tag IDs for a group's post: tags for every post first, then the ones mapped
+/// to the group's worlds, capped at Discord's limit
+pub fn applied_forum_tags(tags: &[ForumTag], all_worlds: &[WorldInfo]) -> Vec<String> {
+    let general = tags.iter().filter(|tag| tag.world_id.is_none());
+    let per_world = tags.iter().filter(|tag| {
+        tag.world_id
+            .as_deref()
+            .is_some_and(|world_id| all_worlds.iter().any(|world| world.id == world_id))
+    });
+
+    let mut tag_ids = Vec::new();
+    for tag in general.chain(per_world) {
+        if !tag_ids.contains(&tag.tag_id) {
+            tag_ids.push(tag.tag_id.clone());
+        }
+    }
+    if tag_ids.len() > MAX_FORUM_TAGS {
+        log::warn!(
+            "{} forum tags apply, only the first {MAX_FORUM_TAGS} are used",
+            tag_ids.len()
+        );
+        tag_ids.truncate(MAX_FORUM_TAGS);
+    }
+    tag_ids
+}
+
 /// Creates message with worlds, timestamp, and as many players as fit
+#[allow(clippy::too_many_arguments)]
 fn create_message_content_with_players(
     all_worlds: &[WorldInfo],
     all_players: &[PlayerInfo],
     timestamp: Option<i64>,
+    timestamp_style: &str,
     include_player_names: bool,
     image_count: usize,
     discord_mappings: &HashMap<String, String>,
+    link_player_profiles: bool,
 ) -> (String, Vec<PlayerInfo>, bool) {
-    const MAX_LENGTH: usize = 1900;
-    let mut content = String::new();
+    let mut content = MessageBuilder::new(CAPTION_BUDGET);
     let mut remaining_players: Vec<PlayerInfo> = Vec::new();
     let mut had_players_in_main = false;
 
@@ -357,46 +773,37 @@ fn create_message_content_with_players(
     let photo_word = if image_count == 1 { "Photo" } else { "Photos" };
 
     if !all_worlds.is_empty() {
-        content.push_str(&format!("📸 {photo_word} taken at "));
+        content.push(&format!("📸 {photo_word} taken at "));
 
         let world_parts: Vec<String> = all_worlds
             .iter()
-            .map(|world| {
-                let vrchat_link = format!("https://vrchat.com/home/launch?worldId={}", world.id);
-                let vrcx_link = format!("https://vrcx.azurewebsites.net/world/{}", world.id);
-                format!(
-                    "**{}** ([VRChat](<{}>), [VRCX](<{}>))",
-                    world.name, vrchat_link, vrcx_link
-                )
-            })
+            .map(|world| format!("**{}**{}", world.name, world_text_links(world)))
             .collect();
 
-        content.push_str(&world_parts.join(", "));
+        content.push(&world_parts.join(", "));
 
         if let Some(ts) = timestamp {
-            content.push_str(&format!(" at <t:{ts}:f>"));
+            content.push(&format!(" at {}", discord_timestamp(ts, timestamp_style)));
         }
 
         // Add players if requested
         if include_player_names && !all_players.is_empty() {
             // Check if we can fit at least "with " + one player name
-            let first_player = format_player_for_discord(&all_players[0], discord_mappings);
-            let with_prefix = " with ";
+            let first_player =
+                format_player_for_discord(&all_players[0], discord_mappings, link_player_profiles);
 
-            if content.len() + with_prefix.len() + first_player.len() <= MAX_LENGTH {
-                content.push_str(with_prefix);
-                content.push_str(&first_player);
+            if content.try_push(&format!(" with {first_player}")) {
                 had_players_in_main = true;
 
                 for (players_added, player) in (1..).zip(all_players.iter().skip(1)) {
-                    let player_str = format_player_for_discord(player, discord_mappings);
-                    let addition = format!(", {player_str}");
+                    let player_str =
+                        format_player_for_discord(player, discord_mappings, link_player_profiles);
 
-                    if content.len() + addition.len() > MAX_LENGTH {
+                    if !content.try_push(&format!(", {player_str}")) {
                         // Can't fit more players, save remaining
                         remaining_players = all_players[players_added..].to_vec();
                         // End with comma to indicate continuation
-                        content.push(',');
+                        content.push(",");
                         log::info!(
                             "First message has {} players, {} overflow to next message(s)",
                             players_added,
@@ -404,7 +811,6 @@ fn create_message_content_with_players(
                         );
                         break;
                     }
-                    content.push_str(&addition);
                 }
             } else {
                 // Can't fit any players, all go to overflow
@@ -416,15 +822,25 @@ fn create_message_content_with_players(
             }
         }
     } else {
-        content.push_str(&format!("📸 {photo_word}"));
+        content.push(&format!("📸 {photo_word}"));
         if let Some(ts) = timestamp {
-            content.push_str(&format!(" taken at <t:{ts}:f>"));
+            content.push(&format!(
+                " taken at {}",
+                discord_timestamp(ts, timestamp_style)
+            ));
         }
     }
 
-    log::debug!("Final message content length: {} chars", content.len());
+    log::debug!(
+        "Final message content length: {} chars",
+        content.char_count()
+    );
 
-    (content, remaining_players, had_players_in_main)
+    (
+        content.into_string(),
+        remaining_players,
+        had_players_in_main,
+    )
 }
 
 /// Creates overflow messages for remaining players
@@ -432,37 +848,16 @@ fn create_overflow_player_messages(
     remaining_players: &[PlayerInfo],
     had_players_in_main: bool,
     discord_mappings: &HashMap<String, String>,
+    link_player_profiles: bool,
 ) -> Vec<String> {
-    const MAX_LENGTH: usize = 1900; // Leave buffer for Discord's 2000 char limit
-    let mut messages = Vec::new();
-
     // If no players were in the main message, start with "with "
-    let mut current = if !had_players_in_main {
-        String::from("with ")
-    } else {
-        String::new()
-    };
-    let prefix_len = current.len();
-
-    for player in remaining_players.iter() {
-        let player_str = format_player_for_discord(player, discord_mappings);
-        let separator = if current.len() > prefix_len { ", " } else { "" };
-        let addition = format!("{separator}{player_str}");
-
-        if current.len() > prefix_len && current.len() + addition.len() > MAX_LENGTH {
-            // Current message is full, end with comma and start new one
-            current.push(',');
-            messages.push(current);
-            current = player_str;
-        } else {
-            current.push_str(&addition);
-        }
-    }
-
-    // Don't forget the last message (no trailing comma on final message)
-    if current.len() > prefix_len || (!had_players_in_main && !current.is_empty()) {
-        messages.push(current);
-    }
+    let prefix = if had_players_in_main { "" } else { "with " };
+    let player_strs: Vec<String> = remaining_players
+        .iter()
+        .map(|player| format_player_for_discord(player, discord_mappings, link_player_profiles))
+        .collect();
+    // Messages the list carries on from end with a comma to show it continues
+    let messages = split_list(prefix, &player_strs, ", ", ",", CAPTION_BUDGET);
 
     log::info!(
         "Created {} overflow message(s) for {} remaining players",
@@ -472,16 +867,15 @@ fn create_overflow_player_messages(
     messages
 }
 
+/// Discord's limit on forum thread names
+const MAX_THREAD_NAME_LENGTH: usize = 100;
+
 fn create_thread_title(all_worlds: &[WorldInfo], image_count: usize) -> String {
     let photo_word = if image_count == 1 { "Photo" } else { "Photos" };
     if !all_worlds.is_empty() {
         let world_names: Vec<&str> = all_worlds.iter().map(|w| w.name.as_str()).collect();
         let title = format!("📸 {} from {}", photo_word, world_names.join(", "));
-        if title.len() > 100 {
-            format!("{}...", &title[..97])
-        } else {
-            title
-        }
+        truncate_with_ellipsis(&title, MAX_THREAD_NAME_LENGTH)
     } else {
         format!("📸 {photo_word}")
     }
@@ -491,13 +885,17 @@ fn create_thread_title(all_worlds: &[WorldInfo], image_count: usize) -> String {
 pub fn create_worlds_only_message(
     all_worlds: &[WorldInfo],
     timestamp: Option<i64>,
+    timestamp_style: &str,
     image_count: usize,
 ) -> String {
     let photo_word = if image_count == 1 { "Photo" } else { "Photos" };
     if all_worlds.is_empty() {
         let mut content = format!("📸 {photo_word}");
         if let Some(ts) = timestamp {
-            content.push_str(&format!(" taken at <t:{ts}:f>"));
+            content.push_str(&format!(
+                " taken at {}",
+                discord_timestamp(ts, timestamp_style)
+            ));
         }
         return content;
     }
@@ -506,20 +904,13 @@ pub fn create_worlds_only_message(
 
     let world_parts: Vec<String> = all_worlds
         .iter()
-        .map(|world| {
-            let vrchat_link = format!("https://vrchat.com/home/launch?worldId={}", world.id);
-            let vrcx_link = format!("https://vrcx.azurewebsites.net/world/{}", world.id);
-            format!(
-                "**{}** ([VRChat](<{}>), [VRCX](<{}>))",
-                world.name, vrchat_link, vrcx_link
-            )
-        })
+        .map(|world| format!("**{}**{}", world.name, world_text_links(world)))
         .collect();
 
     content.push_str(&world_parts.join(", "));
 
     if let Some(ts) = timestamp {
-        content.push_str(&format!(" at <t:{ts}:f>"));
+        content.push_str(&format!(" at {}", discord_timestamp(ts, timestamp_style)));
     }
 
     content
@@ -531,7 +922,6 @@ pub fn create_compact_world_messages(
     all_worlds: &[WorldInfo],
     image_count: usize,
 ) -> (String, Vec<String>) {
-    const MAX_LENGTH: usize = 1900;
     let photo_word = if image_count == 1 { "Photo" } else { "Photos" };
 
     if all_worlds.is_empty() {
@@ -539,34 +929,26 @@ pub fn create_compact_world_messages(
     }
 
     // Build summary message with world names (bullet list)
-    let mut summary = format!("📸 {} from {} worlds:\n", photo_word, all_worlds.len());
-    for world in all_worlds.iter() {
-        summary.push_str(&format!("• {}\n", world.name));
-    }
+    let mut summary = MessageBuilder::with_text(
+        format!("📸 {} from {} worlds:", photo_word, all_worlds.len()),
+        DISCORD_MESSAGE_LIMIT,
+    );
+    let name_lines: Vec<String> = all_worlds
+        .iter()
+        .map(|world| format!("\n• {}", world.name))
+        .collect();
+    summary.push_items_or_more(&name_lines, |remaining| format!("\n…and {remaining} more"));
 
     // Build links messages (chunked to fit Discord limit)
-    let mut link_messages = Vec::new();
-    let mut current_links = String::from("World Links:\n");
-    let prefix_len = current_links.len();
-
-    for world in all_worlds.iter() {
-        let vrchat_link = format!("https://vrchat.com/home/launch?worldId={}", world.id);
-        let vrcx_link = format!("https://vrcx.azurewebsites.net/world/{}", world.id);
-        let link_line = format!("• [VRChat](<{vrchat_link}>) | [VRCX](<{vrcx_link}>)\n");
-
-        if current_links.len() + link_line.len() > MAX_LENGTH {
-            // Current message full, save and start new one
-            link_messages.push(current_links.trim_end().to_string());
-            current_links = link_line;
-        } else {
-            current_links.push_str(&link_line);
-        }
-    }
-
-    // Don't forget the last links message
-    if current_links.len() > prefix_len || !current_links.is_empty() {
-        link_messages.push(current_links.trim_end().to_string());
-    }
+    let link_lines: Vec<String> = all_worlds
+        .iter()
+        .map(|world| {
+            let vrchat_link = world_launch_url(&world.id);
+            let vrcx_link = world_vrcx_url(&world.id);
+            format!("• [VRChat](<{vrchat_link}>) | [VRCX](<{vrcx_link}>)")
+        })
+        .collect();
+    let link_messages = split_list("World Links:\n", &link_lines, "\n", "", CAPTION_BUDGET);
 
     log::info!(
         "Created compact world summary and {} link message(s) for {} worlds",
@@ -574,62 +956,221 @@ pub fn create_compact_world_messages(
         all_worlds.len()
     );
 
-    (summary.trim_end().to_string(), link_messages)
+    (summary.into_string(), link_messages)
 }
 
 /// Creates player messages that fit within Discord's limit (used when combined message is too long)
 pub fn create_split_player_messages(
     all_players: &[PlayerInfo],
     discord_mappings: &HashMap<String, String>,
+    link_player_profiles: bool,
 ) -> Vec<String> {
-    const MAX_LENGTH: usize = 1900;
-    let mut messages = Vec::new();
+    let messages =
+        create_overflow_player_messages(all_players, false, discord_mappings, link_player_profiles);
 
-    if all_players.is_empty() {
-        return messages;
+    log::info!(
+        "Created {} split player message(s) for {} players",
+        messages.len(),
+        all_players.len()
+    );
+    messages
+}
+
+/// Whether `addition` can be appended to `content` without going over Discord's limit
+fn fits_discord_message(content: &str, addition: &str) -> bool {
+    MessageBuilder::with_text(content, DISCORD_MESSAGE_LIMIT).fits(addition)
+}
+
+/// Adds the avatars worn in a group to its first message ("Wearing **AvatarName**"), on
+/// its own line. Skipped when there are none or the line would exceed Discord's limit.
+pub fn append_avatar_caption(text_fields: &mut HashMap<String, String>, avatars: &[AvatarInfo]) {
+    let names: Vec<String> = avatars
+        .iter()
+        .filter(|avatar| !avatar.name.trim().is_empty())
+        .map(|avatar| format!("**{}**", avatar.name))
+        .collect();
+    if names.is_empty() {
+        return;
     }
+    let line = format!("Wearing {}", names.join(", "));
 
-    let mut current = String::from("with ");
-    let prefix_len = current.len();
+    match text_fields.get_mut("content").filter(|c| !c.is_empty()) {
+        Some(content) if fits_discord_message(content, &format!("\n{line}")) => {
+            content.push('\n');
+            content.push_str(&line);
+        }
+        Some(_) => log::debug!("Avatar line doesn't fit in the caption, skipping it"),
+        None => {
+            text_fields.insert("content".to_string(), line);
+        }
+    }
+}
 
-    for player in all_players.iter() {
-        let player_str = format_player_for_discord(player, discord_mappings);
-        let separator = if current.len() > prefix_len { ", " } else { "" };
-        let addition = format!("{separator}{player_str}");
+/// Rejects an extra message too long to post even as a message of its own
+pub fn validate_extra_message(extra_message: Option<&str>) -> AppResult<()> {
+    if extra_message.is_some_and(|extra| char_len(extra.trim()) > DISCORD_MESSAGE_LIMIT) {
+        return Err(AppError::validation(
+            "extra_message",
+            &format!("Extra message must be {DISCORD_MESSAGE_LIMIT} characters or fewer"),
+        ));
+    }
+    Ok(())
+}
+
+/// Applies user caption customizations to a group's first message: an optional caption
+/// override replaces the generated content along with its overflow player-list messages,
+/// then the session-wide extra message is appended.
+/// If the extra message would push the caption past Discord's 2000 char limit it is sent
+/// as its own message after the caption instead.
+pub fn apply_caption_customizations(
+    text_fields: &mut HashMap<String, String>,
+    overflow_messages: &mut Vec<String>,
+    caption_override: Option<&str>,
+    extra_message: Option<&str>,
+) {
+    if let Some(caption) = caption_override {
+        text_fields.insert("content".to_string(), caption.to_string());
+        // The player list continued there was replaced along with the caption
+        overflow_messages.clear();
+    }
+
+    let Some(extra) = extra_message.map(str::trim).filter(|e| !e.is_empty()) else {
+        return;
+    };
+
+    match text_fields.get_mut("content").filter(|c| !c.is_empty()) {
+        Some(content) if fits_discord_message(content, &format!("\n\n{extra}")) => {
+            content.push_str("\n\n");
+            content.push_str(extra);
+        }
+        Some(_) => overflow_messages.insert(0, extra.to_string()),
+        None => {
+            text_fields.insert("content".to_string(), extra.to_string());
+        }
+    }
+}
+
+/// One uploaded group as listed in the post-upload summary
+#[derive(Debug, Clone)]
+pub struct SummaryEntry {
+    pub worlds: Vec<WorldInfo>,
+    pub photo_count: usize,
+    pub jump_url: Option<String>,
+}
+
+/// Builds the final "Uploaded 87 photos from 3 worlds" message with one line (and jump
+/// link, when known) per group, dropping lines that would exceed Discord's 2000 characters
+pub fn build_session_summary(entries: &[SummaryEntry]) -> String {
+    let total_photos: usize = entries.iter().map(|e| e.photo_count).sum();
+    let mut world_ids: Vec<&str> = entries
+        .iter()
+        .flat_map(|e| e.worlds.iter().map(|w| w.id.as_str()))
+        .collect();
+    world_ids.sort_unstable();
+    world_ids.dedup();
+
+    let photos = if total_photos == 1 { "photo" } else { "photos" };
+    let headline = match world_ids.len() {
+        0 => format!("📸 Uploaded {total_photos} {photos}"),
+        1 => format!("📸 Uploaded {total_photos} {photos} from 1 world"),
+        n => format!("📸 Uploaded {total_photos} {photos} from {n} worlds"),
+    };
+
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let label = if entry.worlds.is_empty() {
+                "Unknown world".to_string()
+            } else {
+                entry
+                    .worlds
+                    .iter()
+                    .map(|w| w.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            match &entry.jump_url {
+                Some(url) => format!("\n• {label} ({}) — {url}", entry.photo_count),
+                None => format!("\n• {label} ({})", entry.photo_count),
+            }
+        })
+        .collect();
+
+    let mut summary = MessageBuilder::with_text(headline, DISCORD_MESSAGE_LIMIT);
+    summary.push_items_or_more(&lines, |remaining| format!("\n…and {remaining} more"));
+    summary.into_string()
+}
+
+/// What a session posted into one forum thread, for the thread's closing message
+#[derive(Debug, Clone, Default)]
+pub struct ThreadRecap {
+    pub photo_count: usize,
+    pub players: Vec<PlayerInfo>,
+    /// Unix seconds of the first and last photo
+    pub first_taken: Option<i64>,
+    pub last_taken: Option<i64>,
+}
+
+impl ThreadRecap {
+    pub fn add_photos(&mut self, photo_count: usize, players: &[PlayerInfo], timestamps: &[i64]) {
+        self.photo_count += photo_count;
+        for player in players {
+            if !self
+                .players
+                .iter()
+                .any(|p| p.display_name == player.display_name)
+            {
+                self.players.push(player.clone());
+            }
+        }
+        for &ts in timestamps {
+            self.first_taken = Some(self.first_taken.map_or(ts, |t| t.min(ts)));
+            self.last_taken = Some(self.last_taken.map_or(ts, |t| t.max(ts)));
+        }
+    }
+}
+
+/// Builds the message closing a forum thread after a session: photo count, time range
+/// and as many players as fit in one message
+pub fn build_thread_closing_message(recap: &ThreadRecap) -> String {
+    let photos = if recap.photo_count == 1 {
+        "photo"
+    } else {
+        "photos"
+    };
+    let mut message = format!("🏁 That's all for now: {} {photos}", recap.photo_count);
 
-        if current.len() > prefix_len && current.len() + addition.len() > MAX_LENGTH {
-            // Current message is full, end with comma and start new one
-            current.push(',');
-            messages.push(current);
-            current = format_player_for_discord(player, discord_mappings);
-        } else {
-            current.push_str(&addition);
+    match (recap.first_taken, recap.last_taken) {
+        (Some(first), Some(last)) if first != last => {
+            message.push_str(&format!("\n🕒 <t:{first}:f> – <t:{last}:f>"));
         }
+        (Some(first), _) => message.push_str(&format!("\n🕒 <t:{first}:f>")),
+        _ => {}
     }
 
-    // Don't forget the last message
-    if current.len() > prefix_len {
-        messages.push(current);
-    } else if current == "with " && !all_players.is_empty() {
-        // Edge case: first player name alone
-        messages.push(format!(
-            "with {}",
-            format_player_for_discord(&all_players[0], discord_mappings)
-        ));
+    let mut message = MessageBuilder::with_text(message, DISCORD_MESSAGE_LIMIT);
+    if !recap.players.is_empty() {
+        message.push("\n👥 With ");
+        let names: Vec<String> = recap
+            .players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                let separator = if index == 0 { "" } else { ", " };
+                format!("{separator}**{}**", player.display_name)
+            })
+            .collect();
+        message.push_items_or_more(&names, |remaining| format!(" and {remaining} more"));
     }
 
-    log::info!(
-        "Created {} split player message(s) for {} players",
-        messages.len(),
-        all_players.len()
-    );
-    messages
+    message.into_string()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::message_text::char_len;
     use super::*;
-    use crate::commands::{ImageMetadata, PlayerInfo, WorldInfo};
+    use crate::commands::{AvatarInfo, CustomGroup, ImageMetadata, PlayerInfo, WorldInfo};
 
     fn make_world(name: &str, id: &str) -> WorldInfo {
         WorldInfo {
@@ -651,6 +1192,7 @@ mod tests {
             author: None,
             world: Some(make_world(world_name, world_id)),
             players: vec![],
+            avatar: None,
         }
     }
 
@@ -665,6 +1207,7 @@ mod tests {
             &worlds,
             &players,
             Some(1705312200),
+            "f",
             true,
             0,
             false,
@@ -672,6 +1215,7 @@ mod tests {
             false,
             3,
             &no_mappings,
+            false,
         );
         let content = payload.get("content").unwrap();
         assert!(content.contains("Photos taken at"));
@@ -687,6 +1231,7 @@ mod tests {
             &[],
             &[],
             Some(1705312200),
+            "f",
             true,
             0,
             false,
@@ -694,6 +1239,7 @@ mod tests {
             false,
             5,
             &no_mappings,
+            false,
         );
         let content = payload.get("content").unwrap();
         assert!(content.contains("Photos"));
@@ -708,6 +1254,7 @@ mod tests {
             &worlds,
             &[],
             None,
+            "f",
             false,
             1,
             false,
@@ -715,6 +1262,7 @@ mod tests {
             false,
             2,
             &no_mappings,
+            false,
         );
         // Continuation chunks should have no content
         assert!(!payload.contains_key("content"));
@@ -728,6 +1276,7 @@ mod tests {
             &worlds,
             &[],
             None,
+            "f",
             true,
             0,
             true,
@@ -735,6 +1284,7 @@ mod tests {
             false,
             2,
             &no_mappings,
+            false,
         );
         assert!(payload.contains_key("thread_name"));
         let thread_name = payload.get("thread_name").unwrap();
@@ -744,8 +1294,20 @@ mod tests {
     #[test]
     fn test_payload_singular_photo() {
         let no_mappings = HashMap::new();
-        let (payload, _) =
-            create_discord_payload(&[], &[], None, true, 0, false, None, false, 1, &no_mappings);
+        let (payload, _) = create_discord_payload(
+            &[],
+            &[],
+            None,
+            "f",
+            true,
+            0,
+            false,
+            None,
+            false,
+            1,
+            &no_mappings,
+            false,
+        );
         let content = payload.get("content").unwrap();
         assert!(content.contains("Photo"));
         assert!(!content.contains("Photos"));
@@ -754,8 +1316,20 @@ mod tests {
     #[test]
     fn test_payload_plural_photos() {
         let no_mappings = HashMap::new();
-        let (payload, _) =
-            create_discord_payload(&[], &[], None, true, 0, false, None, false, 2, &no_mappings);
+        let (payload, _) = create_discord_payload(
+            &[],
+            &[],
+            None,
+            "f",
+            true,
+            0,
+            false,
+            None,
+            false,
+            2,
+            &no_mappings,
+            false,
+        );
         let content = payload.get("content").unwrap();
         assert!(content.contains("Photos"));
     }
@@ -769,6 +1343,7 @@ mod tests {
             &worlds,
             &players,
             None,
+            "f",
             true,
             0,
             false,
@@ -776,6 +1351,7 @@ mod tests {
             true,
             2,
             &no_mappings,
+            false,
         );
         let content = payload.get("content").unwrap();
         assert!(content.contains("Alice"));
@@ -792,6 +1368,7 @@ mod tests {
             &worlds,
             &players,
             None,
+            "f",
             true,
             0,
             false,
@@ -799,6 +1376,7 @@ mod tests {
             false,
             2,
             &no_mappings,
+            false,
         );
         let content = payload.get("content").unwrap();
         assert!(!content.contains("Alice"));
@@ -809,28 +1387,28 @@ mod tests {
     #[test]
     fn test_metadata_key_with_world_and_time() {
         let meta = make_metadata("W", "wrld_abc");
-        let key = create_metadata_key(&meta, Some(3600), 3600, false, true);
-        assert_eq!(key, "wrld_abc_t1");
+        let key = create_metadata_key(&meta, Some(3600), false, true, false);
+        assert_eq!(key, "wrld_abc_t3600");
     }
 
     #[test]
     fn test_metadata_key_no_time_limit() {
         let meta = make_metadata("W", "wrld_abc");
-        let key = create_metadata_key(&meta, Some(3600), 3600, true, true);
+        let key = create_metadata_key(&meta, Some(3600), true, true, false);
         assert_eq!(key, "wrld_abc_all");
     }
 
     #[test]
     fn test_metadata_key_no_world_grouping() {
         let meta = make_metadata("W", "wrld_abc");
-        let key = create_metadata_key(&meta, Some(3600), 3600, false, false);
-        assert_eq!(key, "any_world_t1");
+        let key = create_metadata_key(&meta, Some(3600), false, false, false);
+        assert_eq!(key, "any_world_t3600");
     }
 
     #[test]
     fn test_metadata_key_no_world_no_time() {
         let meta = make_metadata("W", "wrld_abc");
-        let key = create_metadata_key(&meta, Some(3600), 3600, true, false);
+        let key = create_metadata_key(&meta, Some(3600), true, false, false);
         assert_eq!(key, "any_world_all");
     }
 
@@ -838,8 +1416,116 @@ mod tests {
     fn test_metadata_key_no_world_in_metadata() {
         let mut meta = make_metadata("W", "wrld_abc");
         meta.world = None;
-        let key = create_metadata_key(&meta, Some(3600), 3600, false, true);
-        assert_eq!(key, "unknown_t1");
+        let key = create_metadata_key(&meta, Some(3600), false, true, false);
+        assert_eq!(key, "unknown_t3600");
+    }
+
+    #[test]
+    fn test_metadata_key_with_instance_grouping() {
+        let mut meta = make_metadata("W", "wrld_abc");
+        meta.world.as_mut().unwrap().instance_id = "12345~private".to_string();
+        let key = create_metadata_key(&meta, Some(3600), false, true, true);
+        assert_eq!(key, "wrld_abc#12345~private_t3600");
+        let key = create_metadata_key(&meta, Some(3600), false, true, false);
+        assert_eq!(key, "wrld_abc_t3600");
+    }
+
+    #[test]
+    fn test_metadata_key_instance_grouping_without_instance_id() {
+        let meta = make_metadata("W", "wrld_abc");
+        let key = create_metadata_key(&meta, Some(3600), false, true, true);
+        assert_eq!(key, "wrld_abc_t3600");
+    }
+
+    // --- time clustering tests ---
+
+    #[test]
+    fn test_cluster_splits_on_gap_not_bucket_boundary() {
+        // 599s and 601s straddle a 600s bucket boundary but are 2s apart
+        let starts = cluster_by_time_gaps(&[599, 601, 2000], 600);
+        assert_eq!(starts, vec![599, 599, 2000]);
+    }
+
+    #[test]
+    fn test_cluster_chains_small_gaps_and_ignores_input_order() {
+        let starts = cluster_by_time_gaps(&[1000, 0, 500, 1700], 600);
+        assert_eq!(starts, vec![0, 0, 0, 1700]);
+    }
+
+    #[test]
+    fn test_assign_group_keys_splits_same_world_by_gap() {
+        let entries = vec![
+            (
+                "a.png".to_string(),
+                Some(make_metadata("W", "wrld_1")),
+                Some(0),
+            ),
+            (
+                "b.png".to_string(),
+                Some(make_metadata("W", "wrld_1")),
+                Some(300),
+            ),
+            (
+                "c.png".to_string(),
+                Some(make_metadata("W", "wrld_1")),
+                Some(5000),
+            ),
+            ("d.png".to_string(), None, Some(5010)),
+        ];
+        let keys = assign_group_keys(&entries, 600, false, true, false, true, false);
+        assert_eq!(
+            keys,
+            vec!["wrld_1_t0", "wrld_1_t0", "wrld_1_t5000", "wrld_1_t5000"]
+        );
+    }
+
+    #[test]
+    fn test_assign_group_keys_clusters_unmerged_no_metadata_files() {
+        let entries = vec![
+            ("a.png".to_string(), None, Some(100)),
+            ("b.png".to_string(), None, Some(200)),
+            ("c.png".to_string(), None, None),
+        ];
+        let keys = assign_group_keys(&entries, 600, false, true, false, false, false);
+        assert_eq!(keys, vec!["unknown_100", "unknown_100", "unknown_c.png"]);
+    }
+
+    #[test]
+    fn test_assign_group_keys_adopts_orphans_inside_group_time_span() {
+        let entries = vec![
+            ("orphan_first.png".to_string(), None, Some(50)),
+            (
+                "a.png".to_string(),
+                Some(make_metadata("A", "wrld_a")),
+                Some(100),
+            ),
+            (
+                "a2.png".to_string(),
+                Some(make_metadata("A", "wrld_a")),
+                Some(400),
+            ),
+            (
+                "b.png".to_string(),
+                Some(make_metadata("B", "wrld_b")),
+                Some(3000),
+            ),
+            ("orphan_a.png".to_string(), None, Some(700)),
+            ("orphan_far.png".to_string(), None, Some(10_000)),
+            ("orphan_untimed.png".to_string(), None, None),
+        ];
+        let keys = assign_group_keys(&entries, 600, false, true, false, false, true);
+        assert_eq!(
+            keys,
+            vec![
+                "wrld_a_t100",
+                "wrld_a_t100",
+                "wrld_a_t100",
+                "wrld_b_t3000",
+                "wrld_a_t100",
+                "unknown_10000",
+                "unknown_orphan_untimed.png",
+            ]
+        );
     }
 
     // --- create_thread_title tests ---
@@ -871,12 +1557,24 @@ mod tests {
         ];
         let title = create_thread_title(&worlds, 5);
         assert!(
-            title.len() <= 100,
+            char_len(&title) <= 100,
             "Title should be at most 100 chars: len={}",
-            title.len()
+            char_len(&title)
         );
     }
 
+    #[test]
+    fn test_thread_title_truncates_multibyte_names() {
+        // Cutting at byte 97 used to land inside one of these characters and panic
+        let worlds = vec![
+            make_world(&"ワールド".repeat(20), "wrld_1"),
+            make_world(&"🌸".repeat(30), "wrld_2"),
+        ];
+        let title = create_thread_title(&worlds, 5);
+        assert!(title.ends_with("..."));
+        assert!(char_len(&title) <= 100);
+    }
+
     #[test]
     fn test_thread_title_no_worlds() {
         let title = create_thread_title(&[], 3);
@@ -897,8 +1595,16 @@ mod tests {
         let worlds = vec![make_world("W", "wrld_1")];
         let players = vec![make_player("Alice"), make_player("Bob")];
         let no_mappings = HashMap::new();
-        let (content, remaining, had_players) =
-            create_message_content_with_players(&worlds, &players, None, true, 2, &no_mappings);
+        let (content, remaining, had_players) = create_message_content_with_players(
+            &worlds,
+            &players,
+            None,
+            "f",
+            true,
+            2,
+            &no_mappings,
+            false,
+        );
         assert!(content.contains("Alice"));
         assert!(content.contains("Bob"));
         assert!(remaining.is_empty());
@@ -910,8 +1616,16 @@ mod tests {
         let worlds = vec![make_world("W", "wrld_1")];
         let players = vec![make_player("Alice")];
         let no_mappings = HashMap::new();
-        let (content, remaining, had_players) =
-            create_message_content_with_players(&worlds, &players, None, false, 2, &no_mappings);
+        let (content, remaining, had_players) = create_message_content_with_players(
+            &worlds,
+            &players,
+            None,
+            "f",
+            false,
+            2,
+            &no_mappings,
+            false,
+        );
         assert!(!content.contains("Alice"));
         assert!(remaining.is_empty());
         assert!(!had_players);
@@ -925,20 +1639,48 @@ mod tests {
             .map(|i| make_player(&format!("Player_{i:04}")))
             .collect();
         let no_mappings = HashMap::new();
-        let (content, remaining, had_players) =
-            create_message_content_with_players(&worlds, &players, None, true, 5, &no_mappings);
+        let (content, remaining, had_players) = create_message_content_with_players(
+            &worlds,
+            &players,
+            None,
+            "f",
+            true,
+            5,
+            &no_mappings,
+            false,
+        );
         assert!(content.len() <= 1901, "Content too long: {}", content.len());
         assert!(!remaining.is_empty(), "Should have overflow players");
         assert!(had_players);
     }
 
+    #[test]
+    fn test_content_uses_timestamp_style() {
+        let worlds = vec![make_world("W", "wrld_1")];
+        let no_mappings = HashMap::new();
+        let (content, _, _) = create_message_content_with_players(
+            &worlds,
+            &[],
+            Some(1705312200),
+            "R",
+            false,
+            1,
+            &no_mappings,
+            false,
+        );
+        assert!(content.contains("<t:1705312200:R>"));
+
+        // Unknown styles fall back to the default
+        assert_eq!(discord_timestamp(1705312200, "x"), "<t:1705312200:f>");
+    }
+
     // --- create_overflow_player_messages tests ---
 
     #[test]
     fn test_overflow_single_message() {
         let players = vec![make_player("Alice"), make_player("Bob")];
         let no_mappings = HashMap::new();
-        let msgs = create_overflow_player_messages(&players, true, &no_mappings);
+        let msgs = create_overflow_player_messages(&players, true, &no_mappings, false);
         assert_eq!(msgs.len(), 1);
         assert!(msgs[0].contains("Alice"));
         assert!(msgs[0].contains("Bob"));
@@ -948,7 +1690,7 @@ mod tests {
     fn test_overflow_with_prefix_when_no_main_players() {
         let players = vec![make_player("Alice")];
         let no_mappings = HashMap::new();
-        let msgs = create_overflow_player_messages(&players, false, &no_mappings);
+        let msgs = create_overflow_player_messages(&players, false, &no_mappings, false);
         assert_eq!(msgs.len(), 1);
         assert!(msgs[0].starts_with("with "));
     }
@@ -960,7 +1702,7 @@ mod tests {
             .map(|i| make_player(&format!("LongPlayerName_{i:04}")))
             .collect();
         let no_mappings = HashMap::new();
-        let msgs = create_overflow_player_messages(&players, true, &no_mappings);
+        let msgs = create_overflow_player_messages(&players, true, &no_mappings, false);
         assert!(
             msgs.len() > 1,
             "Should need multiple messages for {} players",
@@ -976,14 +1718,14 @@ mod tests {
     #[test]
     fn test_worlds_only_with_worlds() {
         let worlds = vec![make_world("Cool Place", "wrld_1")];
-        let msg = create_worlds_only_message(&worlds, Some(12345), 3);
+        let msg = create_worlds_only_message(&worlds, Some(12345), "f", 3);
         assert!(msg.contains("Cool Place"));
         assert!(msg.contains("<t:12345:f>"));
     }
 
     #[test]
     fn test_worlds_only_no_worlds() {
-        let msg = create_worlds_only_message(&[], Some(12345), 2);
+        let msg = create_worlds_only_message(&[], Some(12345), "f", 2);
         assert!(msg.contains("Photos"));
         assert!(msg.contains("<t:12345:f>"));
     }
@@ -991,7 +1733,7 @@ mod tests {
     #[test]
     fn test_worlds_only_no_timestamp() {
         let worlds = vec![make_world("W", "wrld_1")];
-        let msg = create_worlds_only_message(&worlds, None, 1);
+        let msg = create_worlds_only_message(&worlds, None, "f", 1);
         assert!(!msg.contains("<t:"));
     }
 
@@ -1022,7 +1764,7 @@ mod tests {
     #[test]
     fn test_split_players_empty() {
         let no_mappings = HashMap::new();
-        let msgs = create_split_player_messages(&[], &no_mappings);
+        let msgs = create_split_player_messages(&[], &no_mappings, false);
         assert!(msgs.is_empty());
     }
 
@@ -1030,7 +1772,7 @@ mod tests {
     fn test_split_players_single() {
         let players = vec![make_player("Alice")];
         let no_mappings = HashMap::new();
-        let msgs = create_split_player_messages(&players, &no_mappings);
+        let msgs = create_split_player_messages(&players, &no_mappings, false);
         assert_eq!(msgs.len(), 1);
         assert!(msgs[0].contains("with "));
         assert!(msgs[0].contains("Alice"));
@@ -1044,7 +1786,7 @@ mod tests {
             make_player("Charlie"),
         ];
         let no_mappings = HashMap::new();
-        let msgs = create_split_player_messages(&players, &no_mappings);
+        let msgs = create_split_player_messages(&players, &no_mappings, false);
         assert_eq!(msgs.len(), 1);
         assert!(msgs[0].contains("Alice"));
         assert!(msgs[0].contains("Bob"));
@@ -1057,7 +1799,7 @@ mod tests {
             .map(|i| make_player(&format!("LongName_{i:04}")))
             .collect();
         let no_mappings = HashMap::new();
-        let msgs = create_split_player_messages(&players, &no_mappings);
+        let msgs = create_split_player_messages(&players, &no_mappings, false);
         assert!(msgs.len() > 1);
         for msg in &msgs {
             assert!(msg.len() <= 1901, "Message too long: {}", msg.len());
@@ -1070,7 +1812,7 @@ mod tests {
     fn test_format_player_unmapped() {
         let player = make_player("Alice");
         let no_mappings = HashMap::new();
-        let result = format_player_for_discord(&player, &no_mappings);
+        let result = format_player_for_discord(&player, &no_mappings, false);
         assert_eq!(result, "**Alice**");
     }
 
@@ -1079,7 +1821,7 @@ mod tests {
         let player = make_player("Alice");
         let mut mappings = HashMap::new();
         mappings.insert(player.id.clone(), "123456789".to_string());
-        let result = format_player_for_discord(&player, &mappings);
+        let result = format_player_for_discord(&player, &mappings, false);
         assert_eq!(result, "<@123456789>");
     }
 
@@ -1089,7 +1831,7 @@ mod tests {
         let mut mappings = HashMap::new();
         // Keys are lowercased (matching how the upload pipeline builds the map)
         mappings.insert("alice".to_string(), "987654321".to_string());
-        let result = format_player_for_discord(&player, &mappings);
+        let result = format_player_for_discord(&player, &mappings, false);
         assert_eq!(result, "<@987654321>");
     }
 
@@ -1099,7 +1841,7 @@ mod tests {
         let mut mappings = HashMap::new();
         mappings.insert(player.id.clone(), "111111111".to_string());
         mappings.insert("alice".to_string(), "222222222".to_string());
-        let result = format_player_for_discord(&player, &mappings);
+        let result = format_player_for_discord(&player, &mappings, false);
         // ID mapping should take priority
         assert_eq!(result, "<@111111111>");
     }
@@ -1110,10 +1852,73 @@ mod tests {
         let mut mappings = HashMap::new();
         // Lowercase key matches uppercase display name
         mappings.insert("alice".to_string(), "555555555".to_string());
-        let result = format_player_for_discord(&player, &mappings);
+        let result = format_player_for_discord(&player, &mappings, false);
         assert_eq!(result, "<@555555555>");
     }
 
+    #[test]
+    fn test_format_player_profile_link() {
+        let no_mappings = HashMap::new();
+        let result = format_player_for_discord(&make_player("Alice"), &no_mappings, true);
+        assert_eq!(result, "[Alice](<https://vrchat.com/home/user/usr_alice>)");
+
+        // Without an ID there is nothing to link to
+        let player = PlayerInfo {
+            display_name: "Bob".to_string(),
+            id: String::new(),
+        };
+        assert_eq!(
+            format_player_for_discord(&player, &no_mappings, true),
+            "**Bob**"
+        );
+    }
+
+    #[test]
+    fn test_profile_links_fall_back_when_players_overflow() {
+        let worlds = vec![make_world("W", "wrld_1")];
+        let no_mappings = HashMap::new();
+
+        let few = vec![make_player("Alice"), make_player("Bob")];
+        let (payload, overflow) = create_discord_payload(
+            &worlds,
+            &few,
+            None,
+            "f",
+            true,
+            0,
+            false,
+            None,
+            true,
+            1,
+            &no_mappings,
+            true,
+        );
+        assert!(payload["content"].contains("[Alice](<https://vrchat.com/home/user/usr_alice>)"));
+        assert!(overflow.is_empty());
+
+        // Enough players that the links can't all fit in the first message
+        let many: Vec<PlayerInfo> = (0..40)
+            .map(|i| make_player(&format!("Player {i}")))
+            .collect();
+        let (payload, overflow) = create_discord_payload(
+            &worlds,
+            &many,
+            None,
+            "f",
+            true,
+            0,
+            false,
+            None,
+            true,
+            1,
+            &no_mappings,
+            true,
+        );
+        assert!(payload["content"].contains("**Player 0**"));
+        assert!(!payload["content"].contains("vrchat.com/home/user"));
+        assert!(overflow.is_empty());
+    }
+
     #[test]
     fn test_payload_with_discord_mappings() {
         let worlds = vec![make_world("W", "wrld_1")];
@@ -1121,7 +1926,7 @@ mod tests {
         let mut mappings = HashMap::new();
         mappings.insert("usr_alice".to_string(), "123456789".to_string());
         let (payload, _) = create_discord_payload(
-            &worlds, &players, None, true, 0, false, None, true, 2, &mappings,
+            &worlds, &players, None, "f", true, 0, false, None, true, 2, &mappings, false,
         );
         let content = payload.get("content").unwrap();
         assert!(
@@ -1130,4 +1935,256 @@ mod tests {
         );
         assert!(content.contains("**Bob**"), "Bob should be bold: {content}");
     }
+
+    // --- apply_caption_customizations tests ---
+
+    #[test]
+    fn test_caption_override_replaces_content() {
+        let mut fields = HashMap::new();
+        fields.insert("content".to_string(), "generated".to_string());
+        let mut overflow = Vec::new();
+        apply_caption_customizations(&mut fields, &mut overflow, Some("custom"), None);
+        assert_eq!(fields.get("content").unwrap(), "custom");
+        assert!(overflow.is_empty());
+    }
+
+    #[test]
+    fn test_caption_override_drops_generated_player_list() {
+        let players: Vec<PlayerInfo> = (0..400)
+            .map(|i| make_player(&format!("Player{i}")))
+            .collect();
+        let (mut fields, mut overflow) = create_discord_payload(
+            &[make_world("W", "wrld_1")],
+            &players,
+            None,
+            "f",
+            true,
+            0,
+            false,
+            None,
+            true,
+            1,
+            &HashMap::new(),
+            false,
+        );
+        assert!(!overflow.is_empty());
+
+        apply_caption_customizations(&mut fields, &mut overflow, Some("custom"), Some("#Event"));
+        assert_eq!(fields.get("content").unwrap(), "custom\n\n#Event");
+        assert!(overflow.is_empty());
+    }
+
+    #[test]
+    fn test_extra_message_appended_to_content() {
+        let mut fields = HashMap::new();
+        fields.insert("content".to_string(), "Photo taken".to_string());
+        let mut overflow = Vec::new();
+        apply_caption_customizations(&mut fields, &mut overflow, None, Some(" #FurryFriday "));
+        assert_eq!(
+            fields.get("content").unwrap(),
+            "Photo taken\n\n#FurryFriday"
+        );
+        assert!(overflow.is_empty());
+    }
+
+    #[test]
+    fn test_extra_message_too_long_moves_to_overflow() {
+        let mut fields = HashMap::new();
+        fields.insert("content".to_string(), "a".repeat(1990));
+        let mut overflow = vec!["with **Bob**".to_string()];
+        apply_caption_customizations(&mut fields, &mut overflow, None, Some("#EventName"));
+        assert_eq!(fields.get("content").unwrap().len(), 1990);
+        assert_eq!(
+            overflow,
+            vec!["#EventName".to_string(), "with **Bob**".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_extra_message_over_discord_limit_rejected() {
+        assert!(validate_extra_message(None).is_ok());
+        assert!(validate_extra_message(Some(&"a".repeat(DISCORD_MESSAGE_LIMIT))).is_ok());
+        // Surrounding whitespace is trimmed before posting
+        assert!(validate_extra_message(Some(&format!(" {} ", "a".repeat(2000)))).is_ok());
+        assert!(matches!(
+            validate_extra_message(Some(&"a".repeat(DISCORD_MESSAGE_LIMIT + 1))),
+            Err(AppError::Validation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_avatar_caption_appended_on_own_line() {
+        let avatar = |name: &str, id: &str| AvatarInfo {
+            name: name.to_string(),
+            id: id.to_string(),
+        };
+        let mut fields = HashMap::new();
+        fields.insert("content".to_string(), "📸 Photo".to_string());
+        append_avatar_caption(
+            &mut fields,
+            &[avatar("Robo Cat", "avtr_1"), avatar(" ", "avtr_2")],
+        );
+        assert_eq!(
+            fields.get("content").unwrap(),
+            "📸 Photo\nWearing **Robo Cat**"
+        );
+
+        let mut fields = HashMap::new();
+        fields.insert("content".to_string(), "a".repeat(1995));
+        append_avatar_caption(&mut fields, &[avatar("Robo Cat", "avtr_1")]);
+        assert_eq!(fields.get("content").unwrap().len(), 1995);
+    }
+
+    #[test]
+    fn test_applied_forum_tags() {
+        let tag = |tag_id: &str, world_id: Option<&str>| ForumTag {
+            id: 0,
+            webhook_id: 1,
+            tag_id: tag_id.to_string(),
+            world_id: world_id.map(str::to_string),
+        };
+        let tags = vec![
+            tag("10", Some("wrld_club")),
+            tag("20", None),
+            tag("30", Some("wrld_cat")),
+            tag("20", Some("wrld_club")),
+        ];
+
+        let worlds = vec![make_world("Void Club", "wrld_club")];
+        assert_eq!(applied_forum_tags(&tags, &worlds), vec!["20", "10"]);
+        assert_eq!(applied_forum_tags(&tags, &[]), vec!["20"]);
+
+        let many: Vec<ForumTag> = (0..8).map(|i| tag(&i.to_string(), None)).collect();
+        assert_eq!(applied_forum_tags(&many, &[]).len(), MAX_FORUM_TAGS);
+    }
+
+    #[test]
+    fn test_world_link_buttons_replace_text_links() {
+        let worlds = vec![make_world("Void Club", "wrld_void")];
+        let (fields, _) = create_discord_payload(
+            &worlds,
+            &[],
+            None,
+            "f",
+            true,
+            0,
+            false,
+            None,
+            false,
+            1,
+            &HashMap::new(),
+            false,
+        );
+
+        let buttons = create_world_link_buttons(&worlds, &fields["content"]).unwrap();
+        assert_eq!(buttons.content, "📸 Photo taken at **Void Club**");
+        assert_eq!(
+            buttons.components[0]["components"][0]["url"],
+            "https://vrchat.com/home/launch?worldId=wrld_void"
+        );
+        assert_eq!(buttons.components[0]["components"][1]["label"], "VRCX");
+
+        let many: Vec<WorldInfo> = (0..6)
+            .map(|i| make_world(&format!("World {i}"), &format!("wrld_{i}")))
+            .collect();
+        assert!(create_world_link_buttons(&many, "").is_none());
+    }
+
+    // --- build_custom_groups tests ---
+
+    #[tokio::test]
+    async fn test_build_custom_groups_keeps_order_and_drops_invalid_files() {
+        let early = "VRChat_2024-01-15_10-00-00.000_1920x1080.png".to_string();
+        let late = "VRChat_2024-01-15_12-00-00.000_1920x1080.png".to_string();
+        let invalid = "VRChat_2024-01-15_09-00-00.000_1920x1080.png".to_string();
+        let custom = vec![
+            CustomGroup {
+                group_id: "mine_b".to_string(),
+                file_paths: vec![late.clone(), early.clone()],
+                caption: None,
+                webhook_id: None,
+            },
+            CustomGroup {
+                group_id: "mine_a".to_string(),
+                file_paths: vec![invalid],
+                caption: None,
+                webhook_id: None,
+            },
+        ];
+
+        let groups = build_custom_groups(&custom, &[early.clone(), late.clone()], None).await;
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].group_id, "mine_b");
+        assert_eq!(groups[0].images, vec![late, early.clone()]);
+        assert_eq!(
+            groups[0].timestamp,
+            image_processor::get_timestamp_from_filename_in_timezone(&early, None)
+        );
+    }
+
+    // --- build_thread_closing_message tests ---
+
+    #[test]
+    fn test_thread_closing_message() {
+        let mut recap = ThreadRecap::default();
+        recap.add_photos(2, &[make_player("Alice")], &[200, 100]);
+        recap.add_photos(1, &[make_player("Alice"), make_player("Bob")], &[300]);
+
+        assert_eq!(
+            build_thread_closing_message(&recap),
+            "🏁 That's all for now: 3 photos\n🕒 <t:100:f> – <t:300:f>\n👥 With **Alice**, **Bob**"
+        );
+
+        let mut crowd = ThreadRecap::default();
+        let players: Vec<PlayerInfo> = (0..300)
+            .map(|i| make_player(&format!("Player {i}")))
+            .collect();
+        crowd.add_photos(1, &players, &[]);
+        let message = build_thread_closing_message(&crowd);
+        assert!(message.starts_with("🏁 That's all for now: 1 photo\n👥 With **Player 0**"));
+        assert!(message.ends_with(" more"));
+        assert!(message.chars().count() <= super::super::discord_client::DISCORD_MESSAGE_LIMIT);
+    }
+
+    // --- build_session_summary tests ---
+
+    #[test]
+    fn test_session_summary_counts_unique_worlds() {
+        let entries = vec![
+            SummaryEntry {
+                worlds: vec![make_world("World A", "wrld_a")],
+                photo_count: 3,
+                jump_url: Some("https://discord.com/channels/1/2/3".to_string()),
+            },
+            SummaryEntry {
+                worlds: vec![
+                    make_world("World A", "wrld_a"),
+                    make_world("World B", "wrld_b"),
+                ],
+                photo_count: 2,
+                jump_url: None,
+            },
+        ];
+
+        let summary = build_session_summary(&entries);
+        assert!(summary.starts_with("📸 Uploaded 5 photos from 2 worlds"));
+        assert!(summary.contains("• World A (3) — https://discord.com/channels/1/2/3"));
+        assert!(summary.contains("• World A, World B (2)"));
+    }
+
+    #[test]
+    fn test_session_summary_stays_within_limit() {
+        let entries: Vec<SummaryEntry> = (0..200)
+            .map(|i| SummaryEntry {
+                worlds: vec![make_world(&format!("World {i}"), &format!("wrld_{i}"))],
+                photo_count: 1,
+                jump_url: Some(format!("https://discord.com/channels/1/2/{i}")),
+            })
+            .collect();
+
+        let summary = build_session_summary(&entries);
+        assert!(summary.chars().count() <= 2000);
+        assert!(summary.contains("more"));
+    }
 }
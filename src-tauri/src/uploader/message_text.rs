@@ -0,0 +1,216 @@
+// Message text - length handling for text sent to Discord
+//
+// Discord counts message, thread name and button label limits in characters, not
+// bytes. Cutting text to a limit also has to land between grapheme clusters so emoji,
+// flags and combined characters in world or player names are never split in half.
+// Captions, overflow messages, world lists and summaries are all assembled through
+// `MessageBuilder` so they agree on how much fits.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Length of `text` the way Discord counts it against its limits
+pub fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Longest prefix of `text` made of whole grapheme clusters that is at most `max_chars`
+/// characters long
+pub fn truncate_graphemes(text: &str, max_chars: usize) -> &str {
+    let mut chars = 0;
+    let mut end = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        chars += char_len(grapheme);
+        if chars > max_chars {
+            break;
+        }
+        end = index + grapheme.len();
+    }
+    &text[..end]
+}
+
+/// `text` cut down to `max_chars` characters with "..." marking the cut, or unchanged
+/// when it already fits
+pub fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
+    const ELLIPSIS: &str = "...";
+
+    if char_len(text) <= max_chars {
+        return text.to_string();
+    }
+    let kept = truncate_graphemes(text, max_chars.saturating_sub(ELLIPSIS.len()));
+    format!("{}{ELLIPSIS}", kept.trim_end())
+}
+
+/// Budget for generated captions and overflow messages, leaving headroom under Discord's
+/// 2000 character limit for text added afterwards (avatar line, closing comma)
+pub const CAPTION_BUDGET: usize = 1900;
+
+/// Message text that tracks its length against a character budget. Lengths are of the
+/// text as sent, so masked links and `<@id>` mentions count at their full markup length
+/// rather than how Discord renders them.
+#[derive(Debug, Clone)]
+pub struct MessageBuilder {
+    text: String,
+    chars: usize,
+    limit: usize,
+}
+
+impl MessageBuilder {
+    pub fn new(limit: usize) -> Self {
+        Self::with_text(String::new(), limit)
+    }
+
+    pub fn with_text(text: impl Into<String>, limit: usize) -> Self {
+        let text = text.into();
+        let chars = char_len(&text);
+        Self { text, chars, limit }
+    }
+
+    /// Length in characters so far
+    pub fn char_count(&self) -> usize {
+        self.chars
+    }
+
+    /// Characters left before the budget is used up
+    pub fn remaining(&self) -> usize {
+        self.limit.saturating_sub(self.chars)
+    }
+
+    /// Whether `addition` would still fit
+    pub fn fits(&self, addition: &str) -> bool {
+        char_len(addition) <= self.remaining()
+    }
+
+    /// Append regardless of the budget; for text that has to be there either way
+    pub fn push(&mut self, addition: &str) {
+        self.text.push_str(addition);
+        self.chars += char_len(addition);
+    }
+
+    /// Append `addition` if it fits, returning whether it did
+    pub fn try_push(&mut self, addition: &str) -> bool {
+        if !self.fits(addition) {
+            return false;
+        }
+        self.push(addition);
+        true
+    }
+
+    /// Append `items` in order while they fit. Once one doesn't, `more(left_out)` is
+    /// appended in place of it and the rest; room for that note is always kept.
+    pub fn push_items_or_more(&mut self, items: &[String], more: impl Fn(usize) -> String) {
+        for (index, item) in items.iter().enumerate() {
+            let note = more(items.len() - index);
+            if char_len(item) + char_len(&note) > self.remaining() {
+                self.push(&note);
+                return;
+            }
+            self.push(item);
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+/// Lays `items` out over as many messages as needed, with `separator` between items in
+/// the same message. `prefix` starts the first message and `continued` ends every
+/// message the list carries on from. An item too long for a message of its own is
+/// still sent alone rather than dropped.
+pub fn split_list(
+    prefix: &str,
+    items: &[String],
+    separator: &str,
+    continued: &str,
+    limit: usize,
+) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current = MessageBuilder::with_text(prefix, limit);
+    let mut has_items = false;
+
+    for item in items {
+        if has_items {
+            let addition = format!("{separator}{item}");
+            // Leave room to mark the message as continued in case this is its last item
+            if char_len(&addition) + char_len(continued) <= current.remaining() {
+                current.push(&addition);
+                continue;
+            }
+            current.push(continued);
+            let full = std::mem::replace(&mut current, MessageBuilder::new(limit));
+            messages.push(full.into_string());
+        }
+        current.push(item);
+        has_items = true;
+    }
+
+    if has_items {
+        messages.push(current.into_string());
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_graphemes_keeps_clusters_whole() {
+        assert_eq!(truncate_graphemes("abc", 5), "abc");
+        assert_eq!(truncate_graphemes("日本語のワールド", 3), "日本語");
+        // The family emoji is five characters joined into one cluster
+        let family = "👨‍👩‍👧";
+        assert_eq!(truncate_graphemes(&format!("a{family}b"), 4), "a");
+        assert_eq!(
+            truncate_graphemes(&format!("a{family}b"), 6),
+            format!("a{family}")
+        );
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("short", 10), "short");
+        assert_eq!(truncate_with_ellipsis("🌸🌸🌸🌸🌸🌸", 5), "🌸🌸...");
+        assert!(char_len(&truncate_with_ellipsis(&"ワ".repeat(200), 100)) <= 100);
+    }
+
+    #[test]
+    fn test_message_builder_counts_characters() {
+        let mut message = MessageBuilder::with_text("🌸 ワールド", 10);
+        assert_eq!(message.char_count(), 6);
+        assert!(message.try_push("abc"));
+        assert!(!message.try_push("ab"));
+        assert_eq!(message.remaining(), 1);
+        assert_eq!(message.into_string(), "🌸 ワールドabc");
+    }
+
+    #[test]
+    fn test_push_items_or_more() {
+        let items: Vec<String> = (1..=5).map(|i| format!(" item{i}")).collect();
+        let mut message = MessageBuilder::with_text("List:", 30);
+        message.push_items_or_more(&items, |left| format!(" +{left}"));
+        assert_eq!(message.into_string(), "List: item1 item2 item3 +2");
+
+        let mut message = MessageBuilder::with_text("List:", 100);
+        message.push_items_or_more(&items, |left| format!(" +{left}"));
+        assert_eq!(message.into_string(), "List: item1 item2 item3 item4 item5");
+    }
+
+    #[test]
+    fn test_split_list_marks_continued_messages() {
+        let items: Vec<String> = ["aaaa", "bbbb", "cccc", "dd"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let messages = split_list("with ", &items, ", ", ",", 16);
+        assert_eq!(messages, vec!["with aaaa, bbbb,", "cccc, dd"]);
+        for message in &messages {
+            assert!(char_len(message) <= 16);
+        }
+
+        assert!(split_list("with ", &[], ", ", ",", 16).is_empty());
+        // Items longer than a message still go out on their own
+        let long = vec!["x".repeat(30), "y".to_string()];
+        assert_eq!(split_list("", &long, ", ", ",", 16).len(), 2);
+    }
+}
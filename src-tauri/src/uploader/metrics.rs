@@ -0,0 +1,142 @@
+// Performance metrics - opt-in timings of each upload phase
+//
+// With metrics collection turned on in Settings, reading a session's metadata,
+// compressing each chunk and posting each chunk are timed and stored in the local
+// `metrics` table along with how many files and bytes they handled. Nothing leaves the
+// machine; the summary is for telling whether large sessions are held up by the CPU
+// (metadata and compression) or by the network (uploads) before tuning settings.
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::database::{self, PhaseMetrics};
+use crate::errors::AppResult;
+
+pub const PHASE_METADATA: &str = "metadata";
+pub const PHASE_COMPRESSION: &str = "compression";
+/// Posting a chunk, including any wait for Discord's rate limits
+pub const PHASE_UPLOAD: &str = "upload";
+
+fn is_enabled() -> bool {
+    crate::config::load_config().is_ok_and(|c| c.collect_metrics)
+}
+
+/// A phase being timed; nothing is recorded when metrics are turned off
+pub struct PhaseTimer {
+    session_id: String,
+    phase: &'static str,
+    started: Option<Instant>,
+}
+
+pub fn start(session_id: &str, phase: &'static str) -> PhaseTimer {
+    PhaseTimer {
+        session_id: session_id.to_string(),
+        phase,
+        started: is_enabled().then(Instant::now),
+    }
+}
+
+impl PhaseTimer {
+    /// Store the elapsed time in the background with the files and bytes handled
+    pub fn finish(self, items: usize, bytes: u64) {
+        let Some(started) = self.started else {
+            return;
+        };
+        let duration_ms = started.elapsed().as_millis() as u64;
+        tokio::spawn(async move {
+            if let Err(e) = database::record_metric(
+                &self.session_id,
+                self.phase,
+                duration_ms,
+                items as u64,
+                bytes,
+            )
+            .await
+            {
+                log::warn!("Failed to record {} metric: {e}", self.phase);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceMetrics {
+    pub enabled: bool,
+    pub phases: Vec<PhaseMetrics>,
+    /// "cpu" when metadata and compression took longer than uploads, else "network";
+    /// None until something has been recorded
+    pub bottleneck: Option<&'static str>,
+    /// Average upload throughput in MB/s
+    pub upload_mb_per_sec: Option<f64>,
+}
+
+fn total_ms(phases: &[PhaseMetrics], phase: &str) -> i64 {
+    phases
+        .iter()
+        .filter(|p| p.phase == phase)
+        .map(|p| p.total_ms)
+        .sum()
+}
+
+fn bottleneck(phases: &[PhaseMetrics]) -> Option<&'static str> {
+    let cpu = total_ms(phases, PHASE_METADATA) + total_ms(phases, PHASE_COMPRESSION);
+    let network = total_ms(phases, PHASE_UPLOAD);
+    match (cpu, network) {
+        (0, 0) => None,
+        (cpu, network) if cpu > network => Some("cpu"),
+        _ => Some("network"),
+    }
+}
+
+fn upload_throughput(phases: &[PhaseMetrics]) -> Option<f64> {
+    let upload = phases.iter().find(|p| p.phase == PHASE_UPLOAD)?;
+    (upload.total_ms > 0)
+        .then(|| upload.bytes as f64 / 1024.0 / 1024.0 / (upload.total_ms as f64 / 1000.0))
+}
+
+/// Everything recorded so far, summed per phase
+pub async fn performance_metrics() -> AppResult<PerformanceMetrics> {
+    let phases = database::get_phase_metrics().await?;
+    Ok(PerformanceMetrics {
+        enabled: is_enabled(),
+        bottleneck: bottleneck(&phases),
+        upload_mb_per_sec: upload_throughput(&phases),
+        phases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(phase: &str, total_ms: i64, bytes: i64) -> PhaseMetrics {
+        PhaseMetrics {
+            phase: phase.to_string(),
+            samples: 1,
+            total_ms,
+            max_ms: total_ms,
+            items: 10,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_bottleneck_compares_cpu_and_network_time() {
+        assert_eq!(bottleneck(&[]), None);
+
+        let slow_compression = [
+            phase(PHASE_METADATA, 2_000, 0),
+            phase(PHASE_COMPRESSION, 9_000, 0),
+            phase(PHASE_UPLOAD, 10_000, 20 * 1024 * 1024),
+        ];
+        assert_eq!(bottleneck(&slow_compression), Some("cpu"));
+        assert_eq!(upload_throughput(&slow_compression), Some(2.0));
+
+        let slow_upload = [
+            phase(PHASE_METADATA, 1_000, 0),
+            phase(PHASE_UPLOAD, 30_000, 0),
+        ];
+        assert_eq!(bottleneck(&slow_upload), Some("network"));
+    }
+}
@@ -2,13 +2,28 @@
 //
 // This module is responsible for coordinating VRChat photo uploads to Discord
 
+pub mod chunk_planner;
+pub mod clip_compression;
+pub mod compression_pool;
+pub mod compression_tiers;
 pub mod discord_client;
+pub mod disk_space;
+pub mod dry_run;
+pub mod forum_diagnostics;
 pub mod image_groups;
+pub mod message_text;
+pub mod metrics;
+pub mod notifications;
 pub mod progress_tracker;
+pub mod rate_scheduler;
 pub mod retry;
+pub mod session_log;
 pub mod session_manager;
+pub mod shutdown;
 pub mod upload_queue;
+pub mod verification;
 
+pub use dry_run::UploadPreview;
 pub use retry::retry_single_upload;
 pub use session_manager::{SessionManager, SessionOptions};
 pub use upload_queue::process_upload_queue;
@@ -0,0 +1,125 @@
+// Native desktop notifications for finished upload sessions
+//
+// Clicking a notification launches the app executable; the single-instance guard turns
+// that second launch into a signal that shows and focuses the existing window.
+
+use tauri_plugin_notification::NotificationExt;
+
+use crate::commands::UploadProgress;
+use crate::errors::{safe_progress_read, ProgressState};
+
+/// Show a notification for a session that completed, failed or was cancelled,
+/// respecting the `show_upload_notifications` setting
+pub fn notify_session_finished(
+    app_handle: &tauri::AppHandle,
+    progress_state: &ProgressState,
+    session_id: &str,
+) {
+    let enabled = crate::config::load_config()
+        .map(|c| c.show_upload_notifications)
+        .unwrap_or(true);
+    if !enabled {
+        return;
+    }
+
+    let Some((title, body)) = safe_progress_read(
+        progress_state,
+        session_id,
+        "notification",
+        session_notification_text,
+    )
+    .flatten() else {
+        return;
+    };
+
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show notification for session {session_id}: {e}");
+    }
+}
+
+/// Title and body for a finished session, or None while it is still running
+fn session_notification_text(progress: &UploadProgress) -> Option<(String, String)> {
+    let succeeded = progress.successful_uploads.len();
+    let failed = progress.failed_uploads.len();
+
+    match progress.session_status.as_str() {
+        "completed" if failed == 0 => Some((
+            "Upload complete".to_string(),
+            format!("Successfully uploaded {succeeded} photo(s)"),
+        )),
+        "completed" => Some((
+            "Upload partially complete".to_string(),
+            format!("{succeeded} uploaded, {failed} failed"),
+        )),
+        "failed" => Some((
+            "Upload failed".to_string(),
+            format!("{succeeded} uploaded, {failed} failed. Open the app to retry."),
+        )),
+        "cancelled" => Some((
+            "Upload cancelled".to_string(),
+            format!(
+                "Stopped after {} of {} photo(s)",
+                progress.completed, progress.total_images
+            ),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::FailedUpload;
+    use std::collections::{HashMap, VecDeque};
+    use tokio_util::sync::CancellationToken;
+
+    fn make_progress(status: &str, succeeded: usize, failed: usize) -> UploadProgress {
+        UploadProgress {
+            total_images: succeeded + failed,
+            completed: succeeded + failed,
+            current_image: None,
+            current_progress: 0.0,
+            failed_uploads: (0..failed)
+                .map(|i| FailedUpload {
+                    file_path: format!("f{i}.png"),
+                    error: "HTTP 500".to_string(),
+                    retry_count: 0,
+                    is_retryable: true,
+                })
+                .collect(),
+            successful_uploads: (0..succeeded).map(|i| format!("s{i}.png")).collect(),
+            session_status: status.to_string(),
+            estimated_time_remaining: None,
+            current_webhook_index: 0,
+            total_webhooks: 1,
+            current_webhook_name: String::new(),
+            total_bytes: 0,
+            uploaded_bytes: 0,
+            upload_speed_mbps: None,
+            throughput_samples: VecDeque::new(),
+            cancel_token: CancellationToken::new(),
+            files: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_notification_text_by_status() {
+        let (title, body) = session_notification_text(&make_progress("completed", 5, 0)).unwrap();
+        assert_eq!(title, "Upload complete");
+        assert!(body.contains('5'));
+
+        let (title, _) = session_notification_text(&make_progress("completed", 3, 2)).unwrap();
+        assert_eq!(title, "Upload partially complete");
+
+        let (title, _) = session_notification_text(&make_progress("failed", 0, 4)).unwrap();
+        assert_eq!(title, "Upload failed");
+
+        assert!(session_notification_text(&make_progress("active", 1, 0)).is_none());
+    }
+}
@@ -1,8 +1,12 @@
-use crate::commands::FailedUpload;
+use crate::commands::{FailedUpload, FilePhase, FileProgress, UploadProgress};
 use crate::errors::{safe_progress_read, safe_progress_update, ProgressState};
+use crate::uploader::session_log::{self, LogKind};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
+use std::time::{Duration, Instant};
 use tauri::Emitter;
-use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 /// Check if session is cancelled
 pub fn is_session_cancelled(progress_state: &ProgressState, session_id: &str) -> bool {
@@ -10,22 +14,78 @@ pub fn is_session_cancelled(progress_state: &ProgressState, session_id: &str) ->
         progress_state,
         session_id,
         "cancellation check",
-        |progress| progress.session_status == "cancelled",
+        |progress| progress.cancel_token.is_cancelled(),
     )
     .unwrap_or(true) // Treat missing/locked session as cancelled for safety
 }
 
+/// The session's cancellation token, so long-running work can stop the moment the
+/// session is cancelled instead of polling. A missing session gets an already
+/// cancelled token, matching `is_session_cancelled`.
+pub fn session_cancel_token(progress_state: &ProgressState, session_id: &str) -> CancellationToken {
+    safe_progress_read(progress_state, session_id, "cancel token", |progress| {
+        progress.cancel_token.clone()
+    })
+    .unwrap_or_else(|| {
+        let token = CancellationToken::new();
+        token.cancel();
+        token
+    })
+}
+
 /// Mark session as cancelled
 pub fn mark_session_cancelled(progress_state: &ProgressState, session_id: &str) {
     safe_progress_update(progress_state, session_id, "mark cancelled", |progress| {
         progress.session_status = "cancelled".to_string();
         progress.estimated_time_remaining = Some(0);
+        progress.cancel_token.cancel();
         log::info!(
             "Marked session {} as cancelled with {} completed uploads",
             session_id,
             progress.completed
         );
     });
+    session_log::record(session_id, LogKind::Session, "Session cancelled");
+}
+
+/// Initial per-file state for a new session
+pub fn pending_files(file_paths: &[String]) -> HashMap<String, FileProgress> {
+    file_paths
+        .iter()
+        .map(|p| (p.clone(), FileProgress::pending()))
+        .collect()
+}
+
+/// Record the phase of one file in the structured per-file state
+fn set_file_phase(progress: &mut UploadProgress, file_path: &str, phase: FilePhase, percent: f32) {
+    let file = progress
+        .files
+        .entry(file_path.to_string())
+        .or_insert_with(FileProgress::pending);
+    file.phase = phase;
+    file.percent = percent;
+    if phase != FilePhase::Failed {
+        file.error = None;
+    }
+}
+
+/// Move several files to the same phase at once (e.g. a whole chunk starts uploading)
+pub fn update_files_phase(
+    progress_state: &ProgressState,
+    session_id: &str,
+    file_paths: &[String],
+    phase: FilePhase,
+) {
+    safe_progress_update(
+        progress_state,
+        session_id,
+        "files phase update",
+        |progress| {
+            for file_path in file_paths {
+                set_file_phase(progress, file_path, phase, 0.0);
+            }
+        },
+    );
 }
 
 /// Generic update progress function
@@ -85,6 +145,12 @@ pub fn update_progress_current_with_phase(
 
         progress.current_image = Some(format!("{phase} - {filename}"));
         progress.current_progress = progress_percent;
+        set_file_phase(
+            progress,
+            &file_path,
+            FilePhase::from_label(phase),
+            progress_percent,
+        );
         log::debug!(
             "Progress: {} {} ({}%)",
             phase,
@@ -104,6 +170,14 @@ pub fn update_progress_success(
         progress.completed += 1;
         progress.successful_uploads.push(file_path.clone());
         progress.current_progress = 100.0;
+        set_file_phase(progress, &file_path, FilePhase::Completed, 100.0);
+
+        // Feed the throughput window with the original file size
+        let now = Instant::now();
+        progress.uploaded_bytes += std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+        let uploaded = progress.uploaded_bytes;
+        progress.throughput_samples.push_back((now, uploaded));
+        refresh_throughput_estimate(progress, now);
 
         // Remove from failed uploads if it was previously failed
         progress.failed_uploads.retain(|f| f.file_path != file_path);
@@ -127,6 +201,10 @@ pub fn update_progress_failure(
 ) {
     safe_progress_update(progress_state, session_id, "failure update", |progress| {
         progress.completed += 1;
+        set_file_phase(progress, &file_path, FilePhase::Failed, 0.0);
+        if let Some(file) = progress.files.get_mut(&file_path) {
+            file.error = Some(error.clone());
+        }
 
         // Check if this file already failed, increment retry count
         if let Some(existing_failure) = progress
@@ -154,6 +232,11 @@ pub fn update_progress_failure(
             progress.total_images
         );
     });
+    session_log::record(
+        session_id,
+        LogKind::Error,
+        format!("Failed {file_path}: {error}"),
+    );
 }
 
 /// Mark group as failed (forum channel failures)
@@ -171,6 +254,10 @@ pub fn update_progress_group_failure(
         "group failure update",
         |progress| {
             progress.completed += 1;
+            set_file_phase(progress, &file_path, FilePhase::Failed, 0.0);
+            if let Some(file) = progress.files.get_mut(&file_path) {
+                file.error = Some(format!("[Group: {group_id}] {error}"));
+            }
 
             progress.failed_uploads.push(FailedUpload {
                 file_path: file_path.clone(),
@@ -182,51 +269,119 @@ pub fn update_progress_group_failure(
             log::warn!("Progress: Group failure for {file_path} in group {group_id} - {error}");
         },
     );
+    session_log::record(
+        session_id,
+        LogKind::Error,
+        format!("Failed {file_path} in group {group_id}: {error}"),
+    );
 }
 
-/// Update estimated time remaining
-pub fn update_time_estimate(
-    progress_state: &ProgressState,
-    session_id: &str,
-    start_time: Instant,
-    completed: usize,
-    total: usize,
-) {
-    if completed == 0 {
-        return;
+/// Window over which upload throughput is measured
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(30);
+
+/// Total size of the given files on disk (unreadable files count as 0)
+pub fn total_file_bytes(file_paths: &[String]) -> u64 {
+    file_paths
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Start measuring throughput from now, e.g. once grouping finishes and uploads begin
+pub fn start_throughput_window(progress_state: &ProgressState, session_id: &str) {
+    safe_progress_update(progress_state, session_id, "throughput start", |progress| {
+        let uploaded = progress.uploaded_bytes;
+        progress
+            .throughput_samples
+            .push_back((Instant::now(), uploaded));
+    });
+}
+
+/// Bytes per second between the oldest sample in the window and `now`. Measuring up to
+/// `now` rather than the last sample means time spent compressing or waiting on the next
+/// chunk lowers the rate, so pending compression work shows up in the estimate.
+fn throughput_bytes_per_sec(samples: &VecDeque<(Instant, u64)>, now: Instant) -> Option<f64> {
+    let (base_time, base_bytes) = *samples.front()?;
+    let (_, latest_bytes) = *samples.back()?;
+    let elapsed = now.saturating_duration_since(base_time).as_secs_f64();
+    if elapsed <= 0.0 || latest_bytes <= base_bytes {
+        return None;
+    }
+    Some((latest_bytes - base_bytes) as f64 / elapsed)
+}
+
+/// Drop samples that fell out of the window, keeping the newest of them as the baseline
+fn prune_throughput_samples(samples: &mut VecDeque<(Instant, u64)>, now: Instant) {
+    while samples.len() > 1 && now.saturating_duration_since(samples[1].0) >= THROUGHPUT_WINDOW {
+        samples.pop_front();
     }
+}
 
-    let elapsed = start_time.elapsed().as_secs_f64();
-    let rate = completed as f64 / elapsed;
-    let remaining = total - completed;
+/// Recompute speed and ETA from measured throughput (bytes remaining / bytes per second)
+fn refresh_throughput_estimate(progress: &mut UploadProgress, now: Instant) {
+    prune_throughput_samples(&mut progress.throughput_samples, now);
 
-    // Account for potential compression overhead (estimate 30% longer if compression is likely needed)
-    let compression_factor = 1.3; // Assume 30% overhead for compression if needed
-    let estimated_seconds = if rate > 0.0 {
-        ((remaining as f64 / rate) * compression_factor) as u64
-    } else {
-        0
-    };
+    match throughput_bytes_per_sec(&progress.throughput_samples, now) {
+        Some(bytes_per_sec) => {
+            let remaining_bytes = progress.total_bytes.saturating_sub(progress.uploaded_bytes);
+            progress.upload_speed_mbps = Some(bytes_per_sec / (1024.0 * 1024.0));
+            progress.estimated_time_remaining =
+                Some((remaining_bytes as f64 / bytes_per_sec).ceil() as u64);
+        }
+        None => progress.upload_speed_mbps = None,
+    }
+}
 
+/// Update estimated time remaining
+pub fn update_time_estimate(progress_state: &ProgressState, session_id: &str) {
     safe_progress_update(
         progress_state,
         session_id,
         "time estimate update",
         |progress| {
-            progress.estimated_time_remaining = Some(estimated_seconds);
+            refresh_throughput_estimate(progress, Instant::now());
 
-            // Log time estimate for debugging
-            if estimated_seconds > 0 {
-                let minutes = estimated_seconds / 60;
-                let seconds = estimated_seconds % 60;
+            if let (Some(eta), Some(speed)) = (
+                progress.estimated_time_remaining,
+                progress.upload_speed_mbps,
+            ) {
                 log::debug!(
-                    "ETA updated: {minutes}m {seconds}s (rate: {rate:.2} images/sec, remaining: {remaining})"
+                    "ETA updated: {}m {}s ({speed:.2} MB/s, {} bytes remaining)",
+                    eta / 60,
+                    eta % 60,
+                    progress.total_bytes.saturating_sub(progress.uploaded_bytes)
                 );
             }
         },
     );
 }
 
+/// Park session while Discord is unreachable
+pub fn mark_session_waiting_for_network(progress_state: &ProgressState, session_id: &str) {
+    safe_progress_update(progress_state, session_id, "mark waiting", |progress| {
+        progress.session_status = "waiting_for_network".to_string();
+        progress.estimated_time_remaining = None;
+        log::warn!("Session {session_id} waiting for network connectivity");
+    });
+    session_log::record(
+        session_id,
+        LogKind::Network,
+        "Discord unreachable, waiting for network",
+    );
+}
+
+/// Resume a session parked by `mark_session_waiting_for_network` (a cancel wins)
+pub fn mark_session_resumed(progress_state: &ProgressState, session_id: &str) {
+    safe_progress_update(progress_state, session_id, "mark resumed", |progress| {
+        if progress.session_status == "waiting_for_network" {
+            progress.session_status = "active".to_string();
+            log::info!("Session {session_id} resumed after network returned");
+        }
+    });
+    session_log::record(session_id, LogKind::Network, "Network returned, resuming");
+}
+
 /// Mark session as completed
 pub fn mark_session_completed(progress_state: &ProgressState, session_id: &str) {
     safe_progress_update(progress_state, session_id, "mark completed", |progress| {
@@ -241,6 +396,7 @@ pub fn mark_session_completed(progress_state: &ProgressState, session_id: &str)
             progress.failed_uploads.len()
         );
     });
+    session_log::record(session_id, LogKind::Session, "Session completed");
 }
 
 /// Mark session as failed
@@ -257,6 +413,66 @@ pub fn mark_session_failed(progress_state: &ProgressState, session_id: &str) {
             progress.failed_uploads.len()
         );
     });
+    session_log::record(session_id, LogKind::Session, "Session failed");
+}
+
+/// Mark session as interrupted by the app shutting down; its unposted files have
+/// been saved as failed groups
+pub fn mark_session_interrupted(progress_state: &ProgressState, session_id: &str) {
+    safe_progress_update(progress_state, session_id, "mark interrupted", |progress| {
+        progress.session_status = "interrupted".to_string();
+        progress.estimated_time_remaining = Some(0);
+        log::info!(
+            "Session {} interrupted by shutdown with {} completed uploads",
+            session_id,
+            progress.completed
+        );
+    });
+    session_log::record(
+        session_id,
+        LogKind::Session,
+        "Session interrupted by shutdown",
+    );
+}
+
+/// Files of the session that haven't been posted to the current webhook yet
+pub fn unposted_files(progress_state: &ProgressState, session_id: &str) -> Vec<String> {
+    safe_progress_read(
+        progress_state,
+        session_id,
+        "read unposted files",
+        |progress| {
+            let mut files: Vec<String> = progress
+                .files
+                .iter()
+                .filter(|(_, file)| file.phase != FilePhase::Completed)
+                .map(|(path, _)| path.clone())
+                .collect();
+            files.sort();
+            files
+        },
+    )
+    .unwrap_or_default()
+}
+
+/// Payload of the `group-completed` event, sent once per group whether it uploaded or not
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupCompletedEvent {
+    pub session_id: String,
+    pub group_id: String,
+    pub group_index: usize,
+    pub total_groups: usize,
+    pub webhook_name: String,
+    pub success: bool,
+    pub file_paths: Vec<String>,
+    pub thread_id: Option<String>,
+    /// Link to the group's first message, when Discord returned one
+    pub jump_url: Option<String>,
+    pub duration_ms: u64,
+}
+
+pub fn emit_group_completed(app_handle: &tauri::AppHandle, event: &GroupCompletedEvent) {
+    app_handle.emit("group-completed", event).ok();
 }
 
 /// Emit full session progress to UI
@@ -278,3 +494,86 @@ pub fn emit_session_progress(
         app_handle.emit("upload-progress", payload).ok();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throughput_measured_up_to_now() {
+        let start = Instant::now();
+        let samples = VecDeque::from(vec![(start, 0), (start + Duration::from_secs(2), 4_000)]);
+
+        let rate = throughput_bytes_per_sec(&samples, start + Duration::from_secs(4)).unwrap();
+        assert!((rate - 1_000.0).abs() < f64::EPSILON);
+
+        // No bytes moved yet: no estimate rather than a zero speed
+        let idle = VecDeque::from(vec![(start, 0)]);
+        assert!(throughput_bytes_per_sec(&idle, start + Duration::from_secs(1)).is_none());
+    }
+
+    fn make_progress_state(file_paths: &[String]) -> ProgressState {
+        let state: ProgressState = Default::default();
+        state.lock().unwrap().insert(
+            "s".to_string(),
+            UploadProgress {
+                total_images: file_paths.len(),
+                completed: 0,
+                current_image: None,
+                current_progress: 0.0,
+                failed_uploads: Vec::new(),
+                successful_uploads: Vec::new(),
+                session_status: "active".to_string(),
+                estimated_time_remaining: None,
+                current_webhook_index: 0,
+                total_webhooks: 1,
+                current_webhook_name: String::new(),
+                total_bytes: 0,
+                uploaded_bytes: 0,
+                upload_speed_mbps: None,
+                throughput_samples: VecDeque::new(),
+                cancel_token: CancellationToken::new(),
+                files: pending_files(file_paths),
+            },
+        );
+        state
+    }
+
+    #[test]
+    fn test_per_file_phases() {
+        let files = vec!["a.png".to_string(), "b.png".to_string()];
+        let state = make_progress_state(&files);
+
+        update_files_phase(&state, "s", &files, FilePhase::Uploading);
+        update_progress_current_with_phase(&state, "s", "a.png".to_string(), "Compressing", 40.0);
+        update_progress_failure(
+            &state,
+            "s",
+            "b.png".to_string(),
+            "HTTP 500".to_string(),
+            true,
+        );
+
+        let guard = state.lock().unwrap();
+        let progress = guard.get("s").unwrap();
+        assert_eq!(progress.files["a.png"].phase, FilePhase::Compressing);
+        assert_eq!(progress.files["a.png"].percent, 40.0);
+        assert_eq!(progress.files["b.png"].phase, FilePhase::Failed);
+        assert_eq!(progress.files["b.png"].error.as_deref(), Some("HTTP 500"));
+    }
+
+    #[test]
+    fn test_prune_keeps_baseline_inside_window() {
+        let start = Instant::now();
+        let mut samples = VecDeque::from(vec![
+            (start, 0),
+            (start + Duration::from_secs(10), 100),
+            (start + Duration::from_secs(50), 500),
+        ]);
+
+        prune_throughput_samples(&mut samples, start + Duration::from_secs(60));
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].1, 100);
+    }
+}
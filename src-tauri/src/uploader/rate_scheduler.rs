@@ -0,0 +1,178 @@
+// Rate scheduler - paces requests to Discord per webhook
+//
+// Every client on a connection pool shares one scheduler. Each webhook has its own
+// bucket: requests to the same webhook are spaced out and hold off while Discord says
+// the bucket is empty or after a 429, while requests to other webhooks go straight
+// through. Callers reserve their slot up-front, so sessions, retries and group
+// overrides posting at the same time interleave instead of tripping the limit together.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Spacing between requests to one webhook unless a slower interval is set for it
+pub const DEFAULT_REQUEST_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Earliest time the next request may start
+    next_slot: Instant,
+    interval: Duration,
+    /// Requests Discord says are left before `reset_at`, when it told us
+    remaining: Option<u32>,
+    reset_at: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(now: Instant) -> Self {
+        Self {
+            next_slot: now,
+            interval: DEFAULT_REQUEST_INTERVAL,
+            remaining: None,
+            reset_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RateScheduler {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait for this webhook's next free slot. The slot is taken as soon as this is
+    /// called, so concurrent callers queue up behind each other.
+    pub async fn acquire(&self, webhook_id: &str) {
+        let wait = self.reserve(webhook_id, Instant::now());
+        if !wait.is_zero() {
+            log::debug!("Waiting {wait:?} for webhook {webhook_id}'s rate limit");
+            sleep(wait).await;
+        }
+    }
+
+    /// Take the next slot for `webhook_id`, returning how long until it starts
+    fn reserve(&self, webhook_id: &str, now: Instant) -> Duration {
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Duration::ZERO;
+        };
+        let bucket = buckets
+            .entry(webhook_id.to_string())
+            .or_insert_with(|| Bucket::new(now));
+
+        let mut start = bucket.next_slot.max(now);
+        match (bucket.remaining, bucket.reset_at) {
+            (Some(0), Some(reset_at)) => {
+                // Discord's count refills at the reset; until the next response we only
+                // know our own spacing
+                start = start.max(reset_at);
+                bucket.remaining = None;
+                bucket.reset_at = None;
+            }
+            (Some(remaining), _) => bucket.remaining = Some(remaining - 1),
+            (None, _) => {}
+        }
+        bucket.next_slot = start + bucket.interval;
+        start - now
+    }
+
+    /// Space requests to this webhook at least `interval` apart (forum channels are
+    /// limited more strictly than plain channels)
+    pub fn set_interval(&self, webhook_id: &str, interval: Duration) {
+        if let Ok(mut buckets) = self.buckets.lock() {
+            buckets
+                .entry(webhook_id.to_string())
+                .or_insert_with(|| Bucket::new(Instant::now()))
+                .interval = interval;
+        }
+    }
+
+    /// Update the webhook's bucket from Discord's X-RateLimit headers
+    pub fn record_response(&self, webhook_id: &str, headers: &reqwest::header::HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let remaining = header("x-ratelimit-remaining").and_then(|v| v.parse::<u32>().ok());
+        let reset_after = header("x-ratelimit-reset-after")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(Duration::from_secs_f64);
+        self.record_limits(webhook_id, remaining, reset_after, Instant::now());
+    }
+
+    fn record_limits(
+        &self,
+        webhook_id: &str,
+        remaining: Option<u32>,
+        reset_after: Option<Duration>,
+        now: Instant,
+    ) {
+        let (Some(remaining), Some(reset_after)) = (remaining, reset_after) else {
+            return;
+        };
+        if let Ok(mut buckets) = self.buckets.lock() {
+            let bucket = buckets
+                .entry(webhook_id.to_string())
+                .or_insert_with(|| Bucket::new(now));
+            bucket.remaining = Some(remaining);
+            bucket.reset_at = Some(now + reset_after);
+        }
+    }
+
+    /// Hold off every request to this webhook for `delay` after it was rate limited
+    pub fn defer(&self, webhook_id: &str, delay: Duration) {
+        let now = Instant::now();
+        if let Ok(mut buckets) = self.buckets.lock() {
+            let bucket = buckets
+                .entry(webhook_id.to_string())
+                .or_insert_with(|| Bucket::new(now));
+            bucket.next_slot = bucket.next_slot.max(now + delay);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_spaces_same_webhook_and_interleaves_others() {
+        let scheduler = RateScheduler::new();
+        scheduler.set_interval("forum", Duration::from_secs(2));
+        let now = Instant::now();
+
+        assert_eq!(scheduler.reserve("a", now), Duration::ZERO);
+        // A second request to the same webhook queues behind the first
+        assert_eq!(scheduler.reserve("a", now), DEFAULT_REQUEST_INTERVAL);
+        assert_eq!(scheduler.reserve("a", now), DEFAULT_REQUEST_INTERVAL * 2);
+        // Another webhook has its own bucket
+        assert_eq!(scheduler.reserve("b", now), Duration::ZERO);
+
+        scheduler.reserve("forum", now);
+        assert_eq!(scheduler.reserve("forum", now), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_reserve_waits_for_reset_when_bucket_is_empty() {
+        let scheduler = RateScheduler::new();
+        scheduler.set_interval("a", Duration::ZERO);
+        let now = Instant::now();
+
+        scheduler.record_limits("a", Some(1), Some(Duration::from_secs(3)), now);
+        assert_eq!(scheduler.reserve("a", now), Duration::ZERO);
+        // Our own request used the last one, so the next waits for the reset
+        assert_eq!(
+            scheduler.reserve("a", now + Duration::from_secs(1)),
+            Duration::from_secs(2)
+        );
+
+        scheduler.record_limits("a", Some(0), Some(Duration::from_secs(3)), now);
+        assert_eq!(scheduler.reserve("a", now), Duration::from_secs(3));
+    }
+}
@@ -3,15 +3,19 @@ use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 use crate::commands::Webhook;
-use crate::errors::{safe_emit_event, ProgressState};
+use crate::errors::ProgressState;
 use crate::{database, image_processor, security};
 
 use super::discord_client::DiscordClient;
-use super::image_groups::create_discord_payload;
+use super::image_groups::{create_discord_payload, timestamp_style_for, DEFAULT_TIMESTAMP_STYLE};
 use super::progress_tracker::{
-    update_progress_current, update_progress_failure, update_progress_success,
+    emit_session_progress, update_progress_current, update_progress_failure,
+    update_progress_success,
 };
-use super::upload_queue::upload_image_chunk_with_thread_id;
+use super::upload_queue::{
+    load_discord_user_map, mention_user_ids, upload_image_chunk_with_thread_id, ChunkResponse,
+};
+use super::verification::UploadVerification;
 
 /// Retry a failed upload
 pub async fn retry_single_upload(
@@ -23,7 +27,7 @@ pub async fn retry_single_upload(
     session_id: String,
     app_handle: tauri::AppHandle,
 ) {
-    let client = DiscordClient::new();
+    let client = DiscordClient::for_app(&app_handle);
 
     // Resolve compression settings (Config Priority: Request Override > Global Config > Default)
     let config = crate::config::load_config().ok();
@@ -42,8 +46,18 @@ pub async fn retry_single_upload(
             .map(|c| c.compression_format.clone())
             .unwrap_or(default_format)
     });
+    let default_timestamp_style = config
+        .as_ref()
+        .map(|c| c.discord_timestamp_style.clone())
+        .unwrap_or_else(|| DEFAULT_TIMESTAMP_STYLE.to_string());
+    let link_player_profiles = config.as_ref().is_some_and(|c| c.link_player_profiles);
+    let timezone = image_processor::resolve_screenshot_timezone(
+        config
+            .as_ref()
+            .and_then(|c| c.screenshot_timezone.as_deref()),
+    );
 
-    if let Err(e) = security::InputValidator::validate_image_file(&file_path) {
+    if let Err(e) = security::InputValidator::validate_upload_file(&file_path) {
         update_progress_failure(
             &progress_state,
             &session_id,
@@ -56,11 +70,11 @@ pub async fn retry_single_upload(
 
     update_progress_current(&progress_state, &session_id, file_path.clone());
 
-    let metadata = image_processor::extract_metadata(&file_path)
+    let metadata = image_processor::extract_metadata(&file_path, timezone)
         .await
         .ok()
         .flatten();
-    let timestamp = image_processor::get_timestamp_from_filename(&file_path);
+    let timestamp = image_processor::get_timestamp_from_filename_in_timezone(&file_path, timezone);
     let all_players = metadata
         .as_ref()
         .map(|m| m.players.clone())
@@ -72,27 +86,22 @@ pub async fn retry_single_upload(
         .unwrap_or_default();
 
     // Load Discord user mappings for player tagging
-    let discord_mappings_list = database::get_discord_user_mappings()
-        .await
-        .unwrap_or_default();
-    let discord_user_map: HashMap<String, String> = discord_mappings_list
-        .into_iter()
-        .flat_map(|m| {
-            let mut items = Vec::new();
-            if let Some(uid) = m.vrchat_user_id {
-                items.push((uid.to_lowercase(), m.discord_user_id.clone()));
-            }
-            if let Some(name) = m.vrchat_display_name {
-                items.push((name.to_lowercase(), m.discord_user_id));
-            }
-            items
-        })
-        .collect();
+    let discord_user_map = load_discord_user_map().await;
+    client.set_mention_users(mention_user_ids(&discord_user_map));
+    client.set_restrict_mentions(config.as_ref().is_none_or(|c| c.restrict_caption_mentions));
+    client.set_keep_original_filenames(config.as_ref().is_some_and(|c| c.keep_original_filenames));
+    client.set_upload_verification(
+        config
+            .as_ref()
+            .map(|c| UploadVerification::from_config(&c.upload_verification))
+            .unwrap_or_default(),
+    );
 
     let (text_fields, player_messages) = create_discord_payload(
         &all_worlds,
         &all_players,
         timestamp,
+        timestamp_style_for(&webhook, &default_timestamp_style),
         true,
         0,
         webhook.is_forum,
@@ -100,6 +109,7 @@ pub async fn retry_single_upload(
         true,
         1, // Single image retry
         &discord_user_map,
+        link_player_profiles,
     );
 
     let dummy_progress_state = Arc::new(Mutex::new(HashMap::new()));
@@ -118,7 +128,10 @@ pub async fn retry_single_upload(
     )
     .await
     {
-        Ok(response_data) => {
+        Ok(ChunkResponse {
+            response_data,
+            verification,
+        }) => {
             // Send player messages if any (for single file retries)
             if !player_messages.is_empty() {
                 // For forum channels, extract thread_id first
@@ -144,23 +157,33 @@ pub async fn retry_single_upload(
                 .to_string_lossy()
                 .to_string();
 
-            let file_hash = image_processor::get_file_hash(&file_path).await.ok();
+            let file_hash = image_processor::get_file_hash(&file_path)
+                .await
+                .ok()
+                .map(|h| h.tagged());
             let file_size = security::FileSystemGuard::get_file_size(&file_path).ok();
-            let webhook_id = webhook.id;
-            let file_path_for_db = file_path.clone();
-
-            tokio::spawn(async move {
-                let _ = database::record_upload(
-                    file_path_for_db,
-                    file_name,
-                    file_hash,
-                    file_size,
-                    webhook_id,
-                    "success",
-                    None,
-                )
-                .await;
-            });
+            let guild_id = client.get_webhook_guild_id(&webhook.url).await;
+            let posted =
+                super::discord_client::extract_posted_message(&response_data, guild_id.as_deref());
+            let attachment_url = posted.as_ref().and_then(|p| p.attachment_url(0));
+
+            let record = database::UploadRecord {
+                file_path: file_path.clone(),
+                file_name,
+                file_hash,
+                file_size,
+                webhook_id: webhook.id,
+                status: "success".to_string(),
+                error_message: None,
+                message: posted,
+                attachment_url,
+                session_id: Some(session_id.clone()),
+                group_id: None,
+                verification_status: verification.into_iter().next().flatten(),
+            };
+            if let Err(e) = database::record_uploads_batch(&[record]).await {
+                log::error!("Failed to record retried upload of {file_path}: {e}");
+            }
 
             update_progress_success(&progress_state, &session_id, file_path.clone());
             log::info!("Successfully retried upload for {file_path}");
@@ -172,22 +195,23 @@ pub async fn retry_single_upload(
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string();
-            let error_message = format!("Retry failed: {e}");
-            let webhook_id = webhook.id;
-            let file_path_for_db = file_path.clone();
-
-            tokio::spawn(async move {
-                let _ = database::record_upload(
-                    file_path_for_db,
-                    file_name,
-                    None,
-                    None,
-                    webhook_id,
-                    "failed",
-                    Some(error_message),
-                )
-                .await;
-            });
+            let record = database::UploadRecord {
+                file_path: file_path.clone(),
+                file_name,
+                file_hash: None,
+                file_size: None,
+                webhook_id: webhook.id,
+                status: "failed".to_string(),
+                error_message: Some(format!("Retry failed: {e}")),
+                message: None,
+                attachment_url: None,
+                session_id: Some(session_id.clone()),
+                group_id: None,
+                verification_status: None,
+            };
+            if let Err(db_error) = database::record_uploads_batch(&[record]).await {
+                log::error!("Failed to record failed retry of {file_path}: {db_error}");
+            }
 
             update_progress_failure(
                 &progress_state,
@@ -200,5 +224,5 @@ pub async fn retry_single_upload(
         }
     }
 
-    safe_emit_event(&app_handle, "upload-progress", &session_id);
+    emit_session_progress(&app_handle, &progress_state, &session_id);
 }
@@ -0,0 +1,199 @@
+// Per-session event log for bug reports
+//
+// The upload queue records what happened in each session (grouping, chunks, Discord
+// responses, errors) into an in-memory buffer that can be exported with
+// `export_session_log`. Only the most recent sessions are kept.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// Sessions kept in memory; older ones are dropped first
+const MAX_SESSIONS: usize = 20;
+/// Entries kept per session so a huge upload can't grow the buffer without bound
+const MAX_ENTRIES_PER_SESSION: usize = 5000;
+
+static SESSION_LOGS: OnceLock<Mutex<SessionLogStore>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogKind {
+    Session,
+    Group,
+    Chunk,
+    Discord,
+    Network,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLogEntry {
+    pub timestamp: DateTime<Local>,
+    /// Milliseconds since the session's first entry
+    pub elapsed_ms: u64,
+    pub kind: LogKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLog {
+    pub session_id: String,
+    pub started_at: DateTime<Local>,
+    pub entries: Vec<SessionLogEntry>,
+    /// Entries dropped after the per-session cap was reached
+    pub dropped_entries: usize,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+#[derive(Default)]
+struct SessionLogStore {
+    logs: HashMap<String, SessionLog>,
+    order: VecDeque<String>,
+}
+
+fn store() -> &'static Mutex<SessionLogStore> {
+    SESSION_LOGS.get_or_init(|| Mutex::new(SessionLogStore::default()))
+}
+
+/// Append an event to the session's log
+pub fn record(session_id: &str, kind: LogKind, message: impl Into<String>) {
+    push_entry(session_id, kind, message.into(), None);
+}
+
+/// Append an event with structured details (Discord responses, timings, options)
+pub fn record_with_details(
+    session_id: &str,
+    kind: LogKind,
+    message: impl Into<String>,
+    details: serde_json::Value,
+) {
+    push_entry(session_id, kind, message.into(), Some(details));
+}
+
+fn push_entry(
+    session_id: &str,
+    kind: LogKind,
+    message: String,
+    details: Option<serde_json::Value>,
+) {
+    let Ok(mut store) = store().lock() else {
+        log::warn!("Failed to acquire session log lock (non-critical)");
+        return;
+    };
+
+    if !store.logs.contains_key(session_id) {
+        store.order.push_back(session_id.to_string());
+        while store.order.len() > MAX_SESSIONS {
+            if let Some(oldest) = store.order.pop_front() {
+                store.logs.remove(&oldest);
+            }
+        }
+    }
+
+    let log = store
+        .logs
+        .entry(session_id.to_string())
+        .or_insert_with(|| SessionLog {
+            session_id: session_id.to_string(),
+            started_at: Local::now(),
+            entries: Vec::new(),
+            dropped_entries: 0,
+            started: Some(Instant::now()),
+        });
+
+    if log.entries.len() >= MAX_ENTRIES_PER_SESSION {
+        log.dropped_entries += 1;
+        return;
+    }
+
+    let elapsed_ms = log
+        .started
+        .map(|s| s.elapsed().as_millis() as u64)
+        .unwrap_or_default();
+    log.entries.push(SessionLogEntry {
+        timestamp: Local::now(),
+        elapsed_ms,
+        kind,
+        message,
+        details,
+    });
+}
+
+/// Snapshot of a session's log, if it is still in the buffer
+pub fn get_session_log(session_id: &str) -> Option<SessionLog> {
+    store().lock().ok()?.logs.get(session_id).cloned()
+}
+
+/// Human-readable report, one line per entry with details inlined as JSON
+pub fn render_text(log: &SessionLog) -> String {
+    let mut text = format!(
+        "Upload session {}\nStarted: {}\nEntries: {}\n\n",
+        log.session_id,
+        log.started_at.format("%Y-%m-%d %H:%M:%S"),
+        log.entries.len()
+    );
+
+    for entry in &log.entries {
+        let kind = serde_json::to_value(entry.kind)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        text.push_str(&format!(
+            "[+{:>8.3}s] [{kind}] {}",
+            entry.elapsed_ms as f64 / 1000.0,
+            entry.message
+        ));
+        if let Some(details) = &entry.details {
+            text.push_str(&format!(" {details}"));
+        }
+        text.push('\n');
+    }
+
+    if log.dropped_entries > 0 {
+        text.push_str(&format!(
+            "\n... {} more entries were dropped\n",
+            log.dropped_entries
+        ));
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_render_session_log() {
+        let session_id = "test_session_log_render";
+        record(session_id, LogKind::Session, "Session started");
+        record_with_details(
+            session_id,
+            LogKind::Discord,
+            "Chunk posted",
+            serde_json::json!({ "message_id": "111" }),
+        );
+
+        let log = get_session_log(session_id).unwrap();
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[1].kind, LogKind::Discord);
+
+        let text = render_text(&log);
+        assert!(text.contains("[session] Session started"));
+        assert!(text.contains(r#"[discord] Chunk posted {"message_id":"111"}"#));
+
+        let json = serde_json::to_value(&log).unwrap();
+        assert_eq!(json["entries"][0]["kind"], "session");
+        assert!(json["entries"][0].get("details").is_none());
+    }
+
+    #[test]
+    fn test_unknown_session_has_no_log() {
+        assert!(get_session_log("test_session_log_missing").is_none());
+    }
+}
@@ -1,12 +1,20 @@
+use std::collections::HashMap;
 use tauri::Manager;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-use crate::commands::UploadProgress;
+use crate::commands::{
+    AppConfig, CustomGroup, FailedGroupOptions, FileProgress, UploadProgress, Webhook,
+};
 use crate::errors::{AppError, AppResult, ProgressState};
+use crate::uploader::dry_run::{build_upload_preview, UploadPreview};
+use crate::uploader::notifications::notify_session_finished;
 use crate::uploader::progress_tracker::{
-    emit_session_progress, is_session_cancelled, mark_session_completed,
+    emit_session_progress, is_session_cancelled, mark_session_completed, pending_files,
+    total_file_bytes,
 };
-use crate::{database, security, uploader};
+use crate::uploader::shutdown::{self, SessionCheckpoint};
+use crate::{database, image_processor, security, uploader};
 
 /// Central manager for upload sessions to ensure unified behavior
 pub struct SessionManager;
@@ -20,10 +28,66 @@ pub struct SessionOptions {
     pub include_player_names: bool,
     pub grouping_time_window: u32,
     pub group_by_world: bool,
+    pub group_by_instance: bool,
     pub upload_quality: Option<u8>,
     pub compression_format: Option<String>,
     pub single_thread_mode: bool,
     pub merge_no_metadata: bool,
+    /// Put photos without metadata into the world group their timestamp falls in
+    pub adopt_orphans: bool,
+    /// Custom captions keyed by group ID, replacing the generated caption
+    pub caption_overrides: HashMap<String, String>,
+    /// Comment or hashtags appended to the first message of every group
+    pub extra_message: Option<String>,
+    /// Time zone override for filename timestamps; None uses the configured time zone
+    pub screenshot_timezone: Option<String>,
+    /// User-arranged groups that replace automatic grouping (files must also be in `file_paths`)
+    pub custom_groups: Option<Vec<CustomGroup>>,
+    /// Webhook IDs keyed by group ID, sending those groups somewhere other than the session webhook
+    pub group_webhook_overrides: HashMap<String, i64>,
+    /// Post a summary message after all groups upload; None uses the configured default
+    pub post_summary: Option<bool>,
+    /// Message flag overrides; None uses each webhook's own setting
+    pub suppress_embeds: Option<bool>,
+    pub suppress_notifications: Option<bool>,
+    /// Forum tags for every post, replacing the webhook's configured tags (templates)
+    pub forum_tag_ids: Option<Vec<String>>,
+}
+
+impl SessionOptions {
+    /// Options from the saved settings, for uploads started outside the main window
+    /// (command line, deep links)
+    pub fn from_config(
+        webhook_ids: Vec<i64>,
+        file_paths: Vec<String>,
+        group_by_metadata: bool,
+        config: &AppConfig,
+    ) -> Self {
+        Self {
+            webhook_ids,
+            file_paths,
+            group_by_metadata,
+            max_images_per_message: config.max_images_per_message,
+            include_player_names: true,
+            grouping_time_window: config.auto_upload_time_window,
+            group_by_world: true,
+            group_by_instance: false,
+            upload_quality: Some(config.upload_quality),
+            compression_format: Some(config.compression_format.clone()),
+            single_thread_mode: config.single_thread_mode,
+            merge_no_metadata: config.merge_no_metadata,
+            adopt_orphans: config.adopt_orphans,
+            caption_overrides: HashMap::new(),
+            extra_message: None,
+            screenshot_timezone: None,
+            custom_groups: None,
+            group_webhook_overrides: HashMap::new(),
+            post_summary: None,
+            suppress_embeds: None,
+            suppress_notifications: None,
+            forum_tag_ids: None,
+        }
+    }
 }
 
 impl SessionManager {
@@ -36,46 +100,8 @@ impl SessionManager {
         let session_id = Uuid::new_v4().to_string();
         let progress_state = app_handle.state::<ProgressState>();
 
-        // 1. Initial Validation
-        if options.file_paths.is_empty() {
-            return Err(AppError::UploadFailed {
-                reason: "No files provided".to_string(),
-            });
-        }
-
-        if options.webhook_ids.is_empty() {
-            return Err(AppError::UploadFailed {
-                reason: "No webhooks specified".to_string(),
-            });
-        }
-
-        for id in &options.webhook_ids {
-            if *id <= 0 {
-                return Err(AppError::UploadFailed {
-                    reason: "Invalid webhook ID".to_string(),
-                });
-            }
-        }
-
-        // 2. File path validation
-        for file_path in &options.file_paths {
-            security::InputValidator::validate_image_file(file_path)?;
-        }
-
-        // 3. Fetch ALL webhooks (fail fast if any not found)
-        let mut webhooks = Vec::new();
-        for id in &options.webhook_ids {
-            let webhook = match database::get_webhook_by_id(*id).await {
-                Ok(w) => w,
-                Err(AppError::Database(sqlx::Error::RowNotFound)) => {
-                    return Err(AppError::UploadFailed {
-                        reason: format!("Webhook with ID {id} not found"),
-                    });
-                }
-                Err(e) => return Err(e),
-            };
-            webhooks.push(webhook);
-        }
+        // 1-3. Validate options and fetch ALL webhooks
+        let webhooks = Self::validate_and_fetch_webhooks(&options).await?;
 
         let num_webhooks = webhooks.len();
         let total_images = options.file_paths.len() * num_webhooks;
@@ -99,6 +125,12 @@ impl SessionManager {
                     current_webhook_index: 0,
                     total_webhooks: num_webhooks,
                     current_webhook_name: webhooks[0].name.clone(),
+                    total_bytes: total_file_bytes(&options.file_paths) * num_webhooks as u64,
+                    uploaded_bytes: 0,
+                    upload_speed_mbps: None,
+                    throughput_samples: std::collections::VecDeque::new(),
+                    cancel_token: CancellationToken::new(),
+                    files: pending_files(&options.file_paths),
                 },
             );
         }
@@ -113,6 +145,7 @@ impl SessionManager {
         for id in &options.webhook_ids {
             database::update_webhook_usage(*id).await?;
         }
+        crate::tray::refresh_webhook_menu(app_handle).await;
 
         // 6. Load config for defaults if quality/format are missing
         let config = crate::config::load_config().ok();
@@ -122,16 +155,69 @@ impl SessionManager {
             .unwrap_or(85);
         let format = options
             .compression_format
+            .clone()
             .or(config.as_ref().map(|c| c.compression_format.clone()))
             .unwrap_or_else(|| "webp".to_string());
 
+        // The UI asks before starting on a full drive; other entry points just warn
+        let disk_space = uploader::disk_space::check_disk_space(&options.file_paths, &format);
+        if !disk_space.sufficient {
+            log::warn!(
+                "Session {session_id} may run out of temp space: ~{} MB needed in {}, {} MB free",
+                disk_space.required_bytes / 1024 / 1024,
+                disk_space.temp_directory,
+                disk_space.available_bytes.unwrap_or(0) / 1024 / 1024
+            );
+        }
+
         // 7. Spawn Coordinator Task
         let handle_clone = app_handle.clone();
         let session_id_clone = session_id.clone();
         let progress_state_clone = progress_state.inner().clone();
+        let checkpoint_options = FailedGroupOptions {
+            max_images_per_message: options.max_images_per_message,
+            include_player_names: options.include_player_names,
+            upload_quality: Some(quality),
+            compression_format: Some(format.clone()),
+            caption: None,
+            extra_message: options.extra_message.clone(),
+        };
+        let running_session = shutdown::track_session(
+            &session_id,
+            SessionCheckpoint {
+                webhook_ids: webhooks.iter().map(|w| w.id).collect(),
+                file_paths: options.file_paths.clone(),
+                options: checkpoint_options.clone(),
+            },
+        );
 
         tokio::spawn(async move {
-            for (idx, webhook) in webhooks.into_iter().enumerate() {
+            let _running_session = running_session;
+            for (idx, webhook) in webhooks.iter().cloned().enumerate() {
+                // On shutdown, save this and the remaining webhooks' files for later
+                if shutdown::is_shutting_down() {
+                    log::info!(
+                        "Session {} interrupted before webhook {}/{}",
+                        session_id_clone,
+                        idx + 1,
+                        num_webhooks
+                    );
+                    Self::checkpoint_webhooks(
+                        &session_id_clone,
+                        &webhooks[idx..],
+                        &options.file_paths,
+                        &checkpoint_options,
+                    )
+                    .await;
+                    shutdown::mark_interrupted(
+                        &handle_clone,
+                        &progress_state_clone,
+                        &session_id_clone,
+                    )
+                    .await;
+                    return;
+                }
+
                 // Check cancellation before each webhook
                 if is_session_cancelled(&progress_state_clone, &session_id_clone) {
                     log::info!(
@@ -140,6 +226,11 @@ impl SessionManager {
                         idx + 1,
                         num_webhooks
                     );
+                    notify_session_finished(
+                        &handle_clone,
+                        &progress_state_clone,
+                        &session_id_clone,
+                    );
                     return;
                 }
 
@@ -153,6 +244,9 @@ impl SessionManager {
                             // Clear successful/failed uploads so frontend resets item states
                             p.successful_uploads.clear();
                             p.failed_uploads.clear();
+                            for file in p.files.values_mut() {
+                                *file = FileProgress::pending();
+                            }
                         }
                     }
                 }
@@ -176,18 +270,14 @@ impl SessionManager {
                     webhook.name
                 );
 
+                let mut webhook_options = options.clone();
+                webhook_options.max_images_per_message = effective_max_images;
+                webhook_options.upload_quality = Some(quality);
+                webhook_options.compression_format = Some(format.clone());
+
                 uploader::process_upload_queue(
                     webhook,
-                    options.file_paths.clone(),
-                    options.group_by_metadata,
-                    effective_max_images,
-                    options.include_player_names,
-                    options.grouping_time_window,
-                    options.group_by_world,
-                    Some(quality),
-                    Some(format.clone()),
-                    options.single_thread_mode,
-                    options.merge_no_metadata,
+                    webhook_options,
                     progress_state_clone.clone(),
                     session_id_clone.clone(),
                     handle_clone.clone(),
@@ -208,6 +298,23 @@ impl SessionManager {
                     }
                 };
 
+                // The queue saved its own unposted groups; save the later webhooks'
+                let interrupted = progress_state_clone.lock().is_ok_and(|progress| {
+                    progress
+                        .get(&session_id_clone)
+                        .is_some_and(|p| p.session_status == "interrupted")
+                });
+                if interrupted {
+                    Self::checkpoint_webhooks(
+                        &session_id_clone,
+                        &webhooks[idx + 1..],
+                        &options.file_paths,
+                        &checkpoint_options,
+                    )
+                    .await;
+                    return;
+                }
+
                 if should_stop {
                     log::info!(
                         "Session {} stopped after webhook {}/{} (status changed)",
@@ -215,6 +322,11 @@ impl SessionManager {
                         idx + 1,
                         num_webhooks
                     );
+                    notify_session_finished(
+                        &handle_clone,
+                        &progress_state_clone,
+                        &session_id_clone,
+                    );
                     return;
                 }
 
@@ -225,8 +337,135 @@ impl SessionManager {
             // All webhooks done — mark truly completed
             mark_session_completed(&progress_state_clone, &session_id_clone);
             emit_session_progress(&handle_clone, &progress_state_clone, &session_id_clone);
+            notify_session_finished(&handle_clone, &progress_state_clone, &session_id_clone);
         });
 
         Ok(session_id)
     }
+
+    /// Save every file for each of `webhooks` so an interrupted session can be resumed
+    async fn checkpoint_webhooks(
+        session_id: &str,
+        webhooks: &[Webhook],
+        file_paths: &[String],
+        options: &FailedGroupOptions,
+    ) {
+        for webhook in webhooks {
+            shutdown::checkpoint_group(
+                session_id,
+                webhook.id,
+                shutdown::INTERRUPTED_GROUP_ID,
+                file_paths,
+                options,
+            )
+            .await;
+        }
+    }
+
+    /// Builds a dry-run preview of a session: grouping, captions, chunking and compression
+    /// estimation run as normal, but no session records are created and nothing is sent.
+    pub async fn preview_session(options: SessionOptions) -> AppResult<UploadPreview> {
+        let webhooks = Self::validate_and_fetch_webhooks(&options).await?;
+        build_upload_preview(&options, webhooks).await
+    }
+
+    /// Validates session options and fetches every requested webhook (fails fast if any is missing)
+    async fn validate_and_fetch_webhooks(options: &SessionOptions) -> AppResult<Vec<Webhook>> {
+        // 1. Initial Validation
+        if options.file_paths.is_empty() {
+            return Err(AppError::UploadFailed {
+                reason: "No files provided".to_string(),
+            });
+        }
+
+        if options.webhook_ids.is_empty() {
+            return Err(AppError::UploadFailed {
+                reason: "No webhooks specified".to_string(),
+            });
+        }
+
+        for id in &options.webhook_ids {
+            if *id <= 0 {
+                return Err(AppError::UploadFailed {
+                    reason: "Invalid webhook ID".to_string(),
+                });
+            }
+        }
+
+        for caption in options.caption_overrides.values() {
+            if caption.trim().is_empty() || caption.chars().count() > 2000 {
+                return Err(AppError::validation(
+                    "caption_overrides",
+                    "Captions must be between 1 and 2000 characters",
+                ));
+            }
+        }
+
+        super::image_groups::validate_extra_message(options.extra_message.as_deref())?;
+
+        if let Some(custom_groups) = &options.custom_groups {
+            let mut seen_ids = std::collections::HashSet::new();
+            let mut seen_files = std::collections::HashSet::new();
+            for group in custom_groups {
+                if group.group_id.trim().is_empty() || !seen_ids.insert(group.group_id.as_str()) {
+                    return Err(AppError::validation(
+                        "custom_groups",
+                        "Every group needs a unique, non-empty ID",
+                    ));
+                }
+                for file_path in &group.file_paths {
+                    if !seen_files.insert(file_path.as_str()) {
+                        return Err(AppError::validation(
+                            "custom_groups",
+                            &format!("File appears in more than one group: {file_path}"),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(timezone) = &options.screenshot_timezone {
+            image_processor::parse_timezone(timezone)?;
+        }
+
+        if !options.group_webhook_overrides.is_empty() && options.webhook_ids.len() > 1 {
+            return Err(AppError::validation(
+                "group_webhook_overrides",
+                "Per-group webhooks can only be used with a single session webhook",
+            ));
+        }
+
+        // 2. File path validation
+        for file_path in &options.file_paths {
+            security::InputValidator::validate_upload_file(file_path)?;
+        }
+
+        // 3. Fetch ALL webhooks (fail fast if any not found)
+        let mut webhooks = Vec::new();
+        for id in &options.webhook_ids {
+            let webhook = match database::get_webhook_by_id(*id).await {
+                Ok(w) => w,
+                Err(AppError::Database(sqlx::Error::RowNotFound)) => {
+                    return Err(AppError::UploadFailed {
+                        reason: format!("Webhook with ID {id} not found"),
+                    });
+                }
+                Err(e) => return Err(e),
+            };
+            webhooks.push(webhook);
+        }
+
+        // 4. Per-group webhooks must exist too, so a typo doesn't silently fall back
+        for (group_id, id) in &options.group_webhook_overrides {
+            if let Err(AppError::Database(sqlx::Error::RowNotFound)) =
+                database::get_webhook_by_id(*id).await
+            {
+                return Err(AppError::UploadFailed {
+                    reason: format!("Webhook with ID {id} for group {group_id} not found"),
+                });
+            }
+        }
+
+        Ok(webhooks)
+    }
 }
@@ -0,0 +1,223 @@
+// Graceful shutdown
+//
+// Quitting while an upload is running would otherwise drop requests mid-flight with
+// nothing recorded. When exit is requested, running sessions stop at the next chunk
+// boundary: the chunk being sent is allowed to finish, then everything not yet
+// posted is saved as failed groups (resumable from the failed uploads list) and
+// the session is marked "interrupted". Sessions that don't reach a boundary in
+// time are cancelled and checkpointed from their progress state instead.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tauri::Manager;
+
+use crate::commands::FailedGroupOptions;
+use crate::database;
+use crate::errors::ProgressState;
+
+use super::progress_tracker::{
+    emit_session_progress, mark_session_cancelled, mark_session_interrupted, unposted_files,
+};
+
+/// How long the chunk in flight gets to finish before the app exits anyway
+pub const CHUNK_FINISH_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Error stored on checkpointed groups
+pub const INTERRUPTED_MESSAGE: &str = "Interrupted when the app was closed";
+/// Group ID for files checkpointed per webhook rather than per group
+pub const INTERRUPTED_GROUP_ID: &str = "interrupted";
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// What a running session needs checkpointed if it doesn't stop in time
+#[derive(Debug, Clone)]
+pub struct SessionCheckpoint {
+    pub webhook_ids: Vec<i64>,
+    pub file_paths: Vec<String>,
+    pub options: FailedGroupOptions,
+}
+
+fn running_sessions() -> &'static Mutex<HashMap<String, SessionCheckpoint>> {
+    static RUNNING: OnceLock<Mutex<HashMap<String, SessionCheckpoint>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Keeps a session registered as running until dropped
+pub struct RunningSession {
+    session_id: String,
+}
+
+impl Drop for RunningSession {
+    fn drop(&mut self) {
+        if let Ok(mut running) = running_sessions().lock() {
+            running.remove(&self.session_id);
+        }
+    }
+}
+
+/// Register a session as running for as long as the returned guard lives
+pub fn track_session(session_id: &str, checkpoint: SessionCheckpoint) -> RunningSession {
+    if let Ok(mut running) = running_sessions().lock() {
+        running.insert(session_id.to_string(), checkpoint);
+    }
+    RunningSession {
+        session_id: session_id.to_string(),
+    }
+}
+
+fn has_running_sessions() -> bool {
+    running_sessions().lock().is_ok_and(|r| !r.is_empty())
+}
+
+/// Whether the app is shutting down; uploads check this between chunks
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Save files that weren't posted so they can be resumed after a restart
+pub async fn checkpoint_group(
+    session_id: &str,
+    webhook_id: i64,
+    group_id: &str,
+    file_paths: &[String],
+    options: &FailedGroupOptions,
+) {
+    if file_paths.is_empty() {
+        return;
+    }
+    match database::record_failed_group(
+        session_id,
+        webhook_id,
+        group_id,
+        file_paths,
+        options,
+        Some(INTERRUPTED_MESSAGE.to_string()),
+    )
+    .await
+    {
+        Ok(id) => log::info!(
+            "Checkpointed {} unposted file(s) of group {group_id} as #{id}",
+            file_paths.len()
+        ),
+        Err(e) => log::error!("Failed to checkpoint group {group_id}: {e}"),
+    }
+}
+
+/// Mark a session interrupted in memory and in the database
+pub async fn mark_interrupted(
+    app_handle: &tauri::AppHandle,
+    progress_state: &ProgressState,
+    session_id: &str,
+) {
+    mark_session_interrupted(progress_state, session_id);
+    emit_session_progress(app_handle, progress_state, session_id);
+    if let Err(e) = database::update_upload_session_status(session_id, "interrupted").await {
+        log::warn!("Failed to persist interrupted state for session {session_id}: {e}");
+    }
+}
+
+/// Start shutting down. Returns true if uploads are running and the exit should be
+/// held back; the app exits by itself once they've stopped. A second request while
+/// waiting isn't held back.
+pub fn begin(app_handle: &tauri::AppHandle) -> bool {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) || !has_running_sessions() {
+        return false;
+    }
+
+    log::info!("Exit requested during an upload, stopping after the current chunk");
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let deadline = Instant::now() + CHUNK_FINISH_TIMEOUT;
+        while has_running_sessions() && Instant::now() < deadline {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        checkpoint_stuck_sessions(&app_handle).await;
+        app_handle.exit(0);
+    });
+    true
+}
+
+/// Cancel sessions that didn't stop in time and save what they hadn't posted
+async fn checkpoint_stuck_sessions(app_handle: &tauri::AppHandle) {
+    let stuck: Vec<(String, SessionCheckpoint)> = match running_sessions().lock() {
+        Ok(running) => running
+            .iter()
+            .map(|(id, checkpoint)| (id.clone(), checkpoint.clone()))
+            .collect(),
+        Err(_) => return,
+    };
+    let progress_state = app_handle.state::<ProgressState>().inner().clone();
+
+    for (session_id, checkpoint) in stuck {
+        log::warn!("Session {session_id} didn't stop in time, checkpointing its progress");
+        let current_webhook = progress_state
+            .lock()
+            .ok()
+            .and_then(|p| p.get(&session_id).map(|s| s.current_webhook_index));
+        // A single-file retry is tracked apart from its session and has no progress of
+        // its own, so everything it was given is unposted
+        let (current_webhook, unposted) = match current_webhook {
+            Some(index) => (index, unposted_files(&progress_state, &session_id)),
+            None => (0, checkpoint.file_paths.clone()),
+        };
+        mark_session_cancelled(&progress_state, &session_id);
+
+        for (index, webhook_id) in checkpoint
+            .webhook_ids
+            .iter()
+            .enumerate()
+            .skip(current_webhook)
+        {
+            let files = if index == current_webhook {
+                &unposted
+            } else {
+                &checkpoint.file_paths
+            };
+            checkpoint_group(
+                &session_id,
+                *webhook_id,
+                INTERRUPTED_GROUP_ID,
+                files,
+                &checkpoint.options,
+            )
+            .await;
+        }
+        mark_interrupted(app_handle, &progress_state, &session_id).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_session_guard_unregisters() {
+        let checkpoint = SessionCheckpoint {
+            webhook_ids: vec![1],
+            file_paths: vec!["a.png".to_string()],
+            options: FailedGroupOptions {
+                max_images_per_message: 10,
+                include_player_names: true,
+                upload_quality: None,
+                compression_format: None,
+                caption: None,
+                extra_message: None,
+            },
+        };
+        let guard = track_session("shutdown-test-session", checkpoint);
+        assert!(running_sessions()
+            .lock()
+            .unwrap()
+            .contains_key("shutdown-test-session"));
+
+        drop(guard);
+        assert!(!running_sessions()
+            .lock()
+            .unwrap()
+            .contains_key("shutdown-test-session"));
+    }
+}
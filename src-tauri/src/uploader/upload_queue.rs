@@ -1,36 +1,98 @@
 use std::collections::HashMap;
 use std::path::Path;
 use tauri::Emitter;
-use tokio::time::{sleep, Duration, Instant};
-
-use crate::commands::Webhook;
-use crate::errors::{safe_emit_event, AppError, AppResult, ProgressState};
-use crate::{database, image_processor, security};
-
-use super::discord_client::{extract_thread_id, DiscordClient, UploadPayload};
-use super::image_groups::{create_discord_payload, ImageGroup};
+use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
+
+use crate::commands::{FailedGroupOptions, FilePhase, Webhook, WorldInfo};
+use crate::errors::{AppError, AppResult, ProgressState};
+use crate::{database, image_processor, security, temp_manifest};
+
+use super::chunk_planner::{needs_conversion, plan_chunks, webhook_upload_limit};
+use super::clip_compression;
+use super::compression_pool;
+use super::compression_tiers;
+use super::discord_client::{
+    extract_posted_message, extract_thread_id, message_flags, DiscordClient, PostedMessage,
+    UploadPayload,
+};
+use super::image_groups::{
+    append_avatar_caption, applied_forum_tags, apply_caption_customizations, build_session_summary,
+    build_thread_closing_message, create_discord_payload, create_world_link_buttons,
+    timestamp_style_for, ImageGroup, SummaryEntry, ThreadRecap, DEFAULT_TIMESTAMP_STYLE,
+};
+use super::metrics;
 use super::progress_tracker::*;
+use super::session_log::{self, LogKind};
+use super::session_manager::SessionOptions;
+use super::shutdown;
+use super::verification::{self, UploadVerification};
 
-/// Process the upload queue
-#[allow(clippy::too_many_arguments)]
+/// Process the upload queue for a single webhook
 pub async fn process_upload_queue(
     webhook: Webhook,
-    file_paths: Vec<String>,
-    group_by_metadata: bool,
-    max_images_per_message: u8,
-    include_player_names: bool,
-    time_window_minutes: u32,
-    group_by_world: bool,
-    upload_quality: Option<u8>,
-    compression_format: Option<String>,
-    single_thread_mode: bool,
-    merge_no_metadata: bool,
+    options: SessionOptions,
+    progress_state: ProgressState,
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    mark_completed: bool,
+) {
+    // Temp files are recorded against the session so a killed upload's leftovers
+    // can be told apart from a running one's
+    temp_manifest::scope(
+        session_id.clone(),
+        run_upload_queue(
+            webhook,
+            options,
+            progress_state,
+            session_id,
+            app_handle,
+            mark_completed,
+        ),
+    )
+    .await
+}
+
+async fn run_upload_queue(
+    webhook: Webhook,
+    options: SessionOptions,
     progress_state: ProgressState,
     session_id: String,
     app_handle: tauri::AppHandle,
     mark_completed: bool,
 ) {
-    let client = DiscordClient::new();
+    let SessionOptions {
+        file_paths,
+        group_by_metadata,
+        max_images_per_message,
+        include_player_names,
+        grouping_time_window: time_window_minutes,
+        group_by_world,
+        group_by_instance,
+        upload_quality,
+        compression_format,
+        single_thread_mode,
+        merge_no_metadata,
+        adopt_orphans,
+        caption_overrides,
+        extra_message,
+        screenshot_timezone,
+        custom_groups,
+        group_webhook_overrides,
+        post_summary,
+        suppress_embeds,
+        suppress_notifications,
+        forum_tag_ids,
+        ..
+    } = options;
+
+    let cancel_token = session_cancel_token(&progress_state, &session_id);
+    let client =
+        DiscordClient::for_app(&app_handle).with_cancellation(&session_id, cancel_token.clone());
+    client.set_message_flags(
+        &webhook.url,
+        resolve_message_flags(&webhook, suppress_embeds, suppress_notifications),
+    );
 
     log::info!("Starting upload session {session_id}");
     log::info!("Single Thread Mode: {single_thread_mode}, Merge No Metadata: {merge_no_metadata}");
@@ -66,6 +128,45 @@ pub async fn process_upload_queue(
             .unwrap_or(default_format)
     });
 
+    let include_avatar_in_caption = config.as_ref().is_some_and(|c| c.include_avatar_in_caption);
+    let use_link_buttons = config.as_ref().is_some_and(|c| c.use_link_buttons);
+    let link_player_profiles = config.as_ref().is_some_and(|c| c.link_player_profiles);
+    let forum_thread_max_age_days = config
+        .as_ref()
+        .filter(|c| c.reuse_forum_threads)
+        .map(|c| c.forum_thread_max_age_days);
+    let post_thread_closing = config
+        .as_ref()
+        .is_some_and(|c| c.post_thread_closing_message);
+    let default_timestamp_style = config
+        .as_ref()
+        .map(|c| c.discord_timestamp_style.clone())
+        .unwrap_or_else(|| DEFAULT_TIMESTAMP_STYLE.to_string());
+
+    // Resolve screenshot time zone (Request Override > Global Config > System Local)
+    let timezone = image_processor::resolve_screenshot_timezone(
+        screenshot_timezone.as_deref().or(config
+            .as_ref()
+            .and_then(|c| c.screenshot_timezone.as_deref())),
+    );
+
+    session_log::record_with_details(
+        &session_id,
+        LogKind::Session,
+        format!("Upload started for webhook '{}'", webhook.name),
+        serde_json::json!({
+            "webhook_id": webhook.id,
+            "is_forum": webhook.is_forum,
+            "files": file_paths.len(),
+            "group_by_metadata": group_by_metadata,
+            "custom_groups": custom_groups.as_ref().map(|g| g.len()),
+            "max_images_per_message": max_images_per_message,
+            "single_thread_mode": single_thread_mode,
+            "quality": effective_quality,
+            "format": effective_format,
+        }),
+    );
+
     // Initial cancellation check
     if is_session_cancelled(&progress_state, &session_id) {
         log::info!("Session {session_id} was cancelled before processing started");
@@ -83,7 +184,7 @@ pub async fn process_upload_queue(
             return;
         }
 
-        if let Err(e) = security::InputValidator::validate_image_file(file_path) {
+        if let Err(e) = security::InputValidator::validate_upload_file(file_path) {
             log::error!("File validation failed for {file_path}: {e}");
             update_progress_failure(
                 &progress_state,
@@ -114,6 +215,12 @@ pub async fn process_upload_queue(
     }
 
     // Show metadata loading phase for all files
+    update_files_phase(
+        &progress_state,
+        &session_id,
+        &valid_files,
+        FilePhase::LoadingMetadata,
+    );
     if let Some(first_file) = valid_files.first() {
         update_progress_current_with_phase(
             &progress_state,
@@ -137,20 +244,27 @@ pub async fn process_upload_queue(
         )
         .ok();
 
-    // Group images if requested
-    let groups = if group_by_metadata {
+    // Group images: user-arranged groups win over automatic grouping
+    let metadata_timer = metrics::start(&session_id, metrics::PHASE_METADATA);
+    let file_count = valid_files.len();
+    let groups = if let Some(custom_groups) = &custom_groups {
+        super::image_groups::build_custom_groups(custom_groups, &valid_files, timezone).await
+    } else if group_by_metadata {
         super::image_groups::group_images_by_metadata(
             valid_files,
             time_window_minutes,
             group_by_world,
+            group_by_instance,
             merge_no_metadata,
-            app_handle.clone(),
-            session_id.clone(),
+            adopt_orphans,
+            timezone,
+            Some((app_handle.clone(), session_id.clone())),
         )
         .await
     } else {
-        super::image_groups::create_individual_groups_with_metadata(valid_files).await
+        super::image_groups::create_individual_groups_with_metadata(valid_files, timezone).await
     };
+    metadata_timer.finish(file_count, 0);
 
     // Emit grouping complete event
     app_handle
@@ -164,52 +278,84 @@ pub async fn process_upload_queue(
         )
         .ok();
 
-    let start_time = Instant::now();
-    let mut total_processed = 0;
+    // Metadata is loaded; files wait in the queue until their chunk comes up
+    let grouped_files: Vec<String> = groups.iter().flat_map(|g| g.images.clone()).collect();
+    update_files_phase(
+        &progress_state,
+        &session_id,
+        &grouped_files,
+        FilePhase::Pending,
+    );
+
+    // Chunks that won't fit uncompressed get compressed in the background while
+    // earlier chunks upload
+    let _prefetch = compression_pool::start(
+        &session_id,
+        files_needing_compression(
+            &groups,
+            &webhook,
+            max_images_per_message,
+            &effective_format,
+            config.as_ref().and_then(|c| c.max_upload_dimension),
+        ),
+        effective_quality,
+        &effective_format,
+        &cancel_token,
+    );
+
     let total_groups = groups.len();
+    start_throughput_window(&progress_state, &session_id);
+    session_log::record_with_details(
+        &session_id,
+        LogKind::Group,
+        format!(
+            "Grouped {} files into {total_groups} group(s)",
+            grouped_files.len()
+        ),
+        serde_json::json!(groups
+            .iter()
+            .map(|g| serde_json::json!({
+                "group_id": g.group_id,
+                "images": g.images.len(),
+                "worlds": g.all_worlds.iter().map(|w| &w.name).collect::<Vec<_>>(),
+                "timestamp": g.timestamp,
+            }))
+            .collect::<Vec<_>>()),
+    );
 
     log::info!("Processing {total_groups} groups for session {session_id}");
 
-    // Load overrides
-    let overrides = database::get_user_webhook_overrides()
-        .await
-        .unwrap_or_default();
-    let override_map: HashMap<String, i64> = overrides
-        .into_iter()
-        .flat_map(|o| {
-            let mut items = Vec::new();
-            if let Some(uid) = o.user_id {
-                items.push((uid, o.webhook_id));
-            }
-            if let Some(name) = o.user_display_name {
-                items.push((name, o.webhook_id));
-            }
-            items
-        })
-        .collect();
+    // Load overrides and Discord user mappings (VRChat player → Discord @mention)
+    let override_map = load_user_webhook_override_map().await;
+    let discord_user_map = load_discord_user_map().await;
+    client.set_mention_users(mention_user_ids(&discord_user_map));
+    client.set_restrict_mentions(config.as_ref().is_none_or(|c| c.restrict_caption_mentions));
+    client.set_keep_original_filenames(config.as_ref().is_some_and(|c| c.keep_original_filenames));
+    client.set_upload_verification(
+        config
+            .as_ref()
+            .map(|c| UploadVerification::from_config(&c.upload_verification))
+            .unwrap_or_default(),
+    );
 
-    // Load Discord user mappings (VRChat player → Discord @mention)
-    let discord_mappings_list = database::get_discord_user_mappings()
-        .await
-        .unwrap_or_default();
-    let discord_user_map: HashMap<String, String> = discord_mappings_list
-        .into_iter()
-        .flat_map(|m| {
-            let mut items = Vec::new();
-            if let Some(uid) = m.vrchat_user_id {
-                items.push((uid.to_lowercase(), m.discord_user_id.clone()));
-            }
-            if let Some(name) = m.vrchat_display_name {
-                items.push((name.to_lowercase(), m.discord_user_id));
-            }
-            items
-        })
-        .collect();
+    // Groups not yet posted when the app shuts down are saved with these
+    let shutdown_checkpoint_options = FailedGroupOptions {
+        max_images_per_message,
+        include_player_names,
+        upload_quality: Some(effective_quality),
+        compression_format: Some(effective_format.clone()),
+        caption: None,
+        extra_message: extra_message.clone(),
+    };
 
     let mut merged_thread_id: Option<String> = None;
+    let mut summary_entries: Vec<SummaryEntry> = Vec::new();
+    // (webhook URL, thread ID, recap) of every forum thread posted into, in order
+    let mut thread_recaps: Vec<(String, String, ThreadRecap)> = Vec::new();
 
     // Process each group
-    for (group_index, group) in groups.into_iter().enumerate() {
+    let mut groups = groups.into_iter().enumerate();
+    while let Some((group_index, group)) = groups.next() {
         // Check cancellation before each group
         if is_session_cancelled(&progress_state, &session_id) {
             log::info!(
@@ -244,57 +390,173 @@ pub async fn process_upload_queue(
             )
             .ok();
 
+        if shutdown::is_shutting_down() {
+            let unfinished = std::iter::once(group)
+                .chain(groups.map(|(_, g)| g))
+                .map(|g| (g.group_id, g.images))
+                .collect();
+            interrupt_for_shutdown(
+                &app_handle,
+                &progress_state,
+                &session_id,
+                webhook.id,
+                unfinished,
+                &shutdown_checkpoint_options,
+                &caption_overrides,
+            )
+            .await;
+            return;
+        }
+
         // Check for overrides
-        let mut target_webhook = webhook.clone();
-        for player in &group.all_players {
-            // Check ID first, then Display Name
-            let found_webhook_id = override_map
-                .get(&player.id)
-                .or_else(|| override_map.get(&player.display_name));
-
-            if let Some(&webhook_id) = found_webhook_id {
-                if let Ok(w) = database::get_webhook_by_id(webhook_id).await {
-                    log::info!(
-                        "redirecting group {} to webhook '{}' due to override for user '{}'",
-                        group.group_id,
-                        w.name,
-                        player.display_name
-                    );
-                    target_webhook = w;
-                    break; // First match wins
-                }
+        let target_webhook =
+            resolve_group_webhook(&group, &webhook, &group_webhook_overrides, &override_map).await;
+        client.set_message_flags(
+            &target_webhook.url,
+            resolve_message_flags(&target_webhook, suppress_embeds, suppress_notifications),
+        );
+        client.set_forum_tags(
+            &target_webhook.url,
+            load_forum_tags(&target_webhook, &group.all_worlds, forum_tag_ids.as_deref()).await,
+        );
+
+        // Forum posts of a single world can go into that world's remembered thread
+        let thread_world_id = forum_thread_max_age_days
+            .filter(|_| target_webhook.is_forum && !single_thread_mode)
+            .and(forum_thread_world_id(&group.all_worlds));
+        let remembered_thread_id = match (&thread_world_id, forum_thread_max_age_days) {
+            (Some(world_id), Some(max_age_days)) => {
+                database::get_forum_thread(target_webhook.id, world_id, max_age_days)
+                    .await
+                    .unwrap_or_else(|e| {
+                        log::warn!("Could not look up forum thread for {world_id}: {e}");
+                        None
+                    })
             }
-        }
+            _ => None,
+        };
 
         // Determine thread ID strategy
         let target_thread_id = if single_thread_mode {
             merged_thread_id.clone()
         } else {
-            None
+            remembered_thread_id.clone()
         };
+        if let Some(tid) = &remembered_thread_id {
+            session_log::record(
+                &session_id,
+                LogKind::Discord,
+                format!("Reusing forum thread {tid} for group {}", group.group_id),
+            );
+        }
 
-        let (group_success, new_thread_id) = process_image_group_with_failure_handling(
-            &client,
-            &target_webhook,
-            group,
-            max_images_per_message,
-            include_player_names,
-            &progress_state,
+        session_log::record(
             &session_id,
+            LogKind::Group,
+            format!(
+                "Group {}/{total_groups} ({}, {} images) -> webhook '{}'",
+                group_index + 1,
+                group.group_id,
+                group.images.len(),
+                target_webhook.name
+            ),
+        );
+        let group_started = std::time::Instant::now();
+
+        let caption_override = caption_overrides.get(&group.group_id).cloned();
+        let group_id = group.group_id.clone();
+        let group_files = group.images.clone();
+        let group_worlds = group.all_worlds.clone();
+        let group_players = group.all_players.clone();
+
+        let (group_success, new_thread_id, first_posted) =
+            process_image_group_with_failure_handling(
+                &client,
+                &target_webhook,
+                group,
+                max_images_per_message,
+                include_player_names,
+                include_avatar_in_caption,
+                timestamp_style_for(&target_webhook, &default_timestamp_style),
+                use_link_buttons,
+                &progress_state,
+                &session_id,
+                &app_handle,
+                target_thread_id.is_none(), // Any group without a thread ID acts as a "first group" for its thread
+                effective_quality,
+                effective_format.clone(),
+                target_thread_id,
+                &discord_user_map,
+                link_player_profiles,
+                caption_override.as_deref(),
+                extra_message.as_deref(),
+            )
+            .await;
+
+        // A group cut short by shutdown is saved with the rest rather than failed
+        if !group_success && shutdown::is_shutting_down() {
+            let unposted = unposted_files(&progress_state, &session_id);
+            let unfinished = std::iter::once((
+                group_id,
+                group_files
+                    .into_iter()
+                    .filter(|f| unposted.contains(f))
+                    .collect(),
+            ))
+            .chain(groups.map(|(_, g)| (g.group_id, g.images)))
+            .collect();
+            interrupt_for_shutdown(
+                &app_handle,
+                &progress_state,
+                &session_id,
+                webhook.id,
+                unfinished,
+                &shutdown_checkpoint_options,
+                &caption_overrides,
+            )
+            .await;
+            return;
+        }
+
+        emit_group_completed(
             &app_handle,
-            target_thread_id.is_none(), // Any group without a thread ID acts as a "first group" for its thread
-            effective_quality,
-            effective_format.clone(),
-            target_thread_id,
-            &discord_user_map,
-        )
-        .await;
+            &GroupCompletedEvent {
+                session_id: session_id.clone(),
+                group_id: group_id.clone(),
+                group_index,
+                total_groups,
+                webhook_name: target_webhook.name.clone(),
+                success: group_success,
+                file_paths: group_files.clone(),
+                thread_id: new_thread_id.clone(),
+                jump_url: first_posted.as_ref().and_then(|m| m.jump_url.clone()),
+                duration_ms: group_started.elapsed().as_millis() as u64,
+            },
+        );
+
+        if let Some(world_id) = &thread_world_id {
+            if group_success {
+                if let Some(tid) = &new_thread_id {
+                    if let Err(e) =
+                        database::remember_forum_thread(target_webhook.id, world_id, tid).await
+                    {
+                        log::warn!("Could not remember forum thread for {world_id}: {e}");
+                    }
+                }
+            } else if remembered_thread_id.is_some() {
+                // The thread may be gone or locked; the next attempt starts a new post
+                log::warn!("Forgetting forum thread for {world_id} after a failed upload");
+                database::forget_forum_thread(target_webhook.id, world_id)
+                    .await
+                    .ok();
+            }
+        }
 
         // Update merged thread ID if we are in single thread mode and got a new ID
         if single_thread_mode && merged_thread_id.is_none() {
-            if let Some(tid) = new_thread_id {
+            if let Some(tid) = &new_thread_id {
                 log::info!("🧵 Single Thread Mode: Captured thread ID {tid}");
-                merged_thread_id = Some(tid);
+                merged_thread_id = Some(tid.clone());
             }
         }
 
@@ -308,29 +570,78 @@ pub async fn process_upload_queue(
             return;
         }
 
+        session_log::record(
+            &session_id,
+            LogKind::Group,
+            format!(
+                "Group {group_id} {} after {} ms",
+                if group_success { "uploaded" } else { "failed" },
+                group_started.elapsed().as_millis()
+            ),
+        );
+
         if !group_success {
             log::error!(
                 "Group {} failed - stopping remaining groups",
                 group_index + 1
             );
+            let options = FailedGroupOptions {
+                max_images_per_message,
+                include_player_names,
+                upload_quality: Some(effective_quality),
+                compression_format: Some(effective_format.clone()),
+                caption: caption_override,
+                extra_message: extra_message.clone(),
+            };
+            persist_failed_group(
+                &progress_state,
+                &session_id,
+                target_webhook.id,
+                &group_id,
+                &group_files,
+                &options,
+            )
+            .await;
             mark_session_failed(&progress_state, &session_id);
             emit_session_progress(&app_handle, &progress_state, &session_id);
             return;
         }
 
-        total_processed += 1;
-
         // Update estimated time remaining
-        update_time_estimate(
-            &progress_state,
-            &session_id,
-            start_time,
-            total_processed,
-            total_groups,
-        );
+        summary_entries.push(SummaryEntry {
+            worlds: group_worlds,
+            photo_count: group_files.len(),
+            jump_url: first_posted.and_then(|m| m.jump_url),
+        });
 
-        // Small delay between groups to be nice to Discord
-        sleep(Duration::from_millis(500)).await;
+        if let (true, true, Some(tid)) = (
+            post_thread_closing,
+            target_webhook.is_forum,
+            new_thread_id.as_deref(),
+        ) {
+            let timestamps: Vec<i64> = group_files
+                .iter()
+                .filter_map(|f| {
+                    image_processor::get_timestamp_from_filename_in_timezone(f, timezone)
+                })
+                .collect();
+            let position = thread_recaps
+                .iter()
+                .position(|(url, thread_id, _)| *url == target_webhook.url && thread_id == tid);
+            let index = position.unwrap_or_else(|| {
+                thread_recaps.push((
+                    target_webhook.url.clone(),
+                    tid.to_string(),
+                    ThreadRecap::default(),
+                ));
+                thread_recaps.len() - 1
+            });
+            thread_recaps[index]
+                .2
+                .add_photos(group_files.len(), &group_players, &timestamps);
+        }
+
+        update_time_estimate(&progress_state, &session_id);
     }
 
     if is_session_cancelled(&progress_state, &session_id) {
@@ -339,6 +650,62 @@ pub async fn process_upload_queue(
         return;
     }
 
+    // Webhooks can't archive threads (that takes Manage Threads on a bot), so closing
+    // a thread means leaving a recap; Discord auto-archives it once it goes quiet
+    for (webhook_url, thread_id, recap) in &thread_recaps {
+        let message = build_thread_closing_message(recap);
+        match client
+            .send_text_message(webhook_url, &message, Some(thread_id))
+            .await
+        {
+            Ok(()) => session_log::record(
+                &session_id,
+                LogKind::Discord,
+                format!("Posted closing message in thread {thread_id}"),
+            ),
+            Err(e) => log::warn!("Failed to post closing message in thread {thread_id}: {e}"),
+        }
+    }
+
+    let post_summary = post_summary
+        .or(config.as_ref().map(|c| c.post_upload_summary))
+        .unwrap_or(false);
+    if post_summary && !summary_entries.is_empty() {
+        let summary = build_session_summary(&summary_entries);
+        // The summary thread only gets the webhook's tags for every post
+        client.set_forum_tags(
+            &webhook.url,
+            load_forum_tags(&webhook, &[], forum_tag_ids.as_deref()).await,
+        );
+        let result = if let Some(tid) = merged_thread_id.as_deref() {
+            client
+                .send_text_message(&webhook.url, &summary, Some(tid))
+                .await
+        } else if webhook.is_forum {
+            let thread_name = format!(
+                "Upload Summary [{}]",
+                chrono::Local::now().format("%Y-%m-%d")
+            );
+            client
+                .send_forum_text_message(&webhook.url, &summary, Some(&thread_name))
+                .await
+                .map(|_| ())
+        } else {
+            client.send_text_message(&webhook.url, &summary, None).await
+        };
+        match result {
+            Ok(()) => session_log::record(&session_id, LogKind::Discord, "Posted upload summary"),
+            Err(e) => {
+                log::warn!("Failed to post upload summary for session {session_id}: {e}");
+                session_log::record(
+                    &session_id,
+                    LogKind::Error,
+                    format!("Failed to post upload summary: {e}"),
+                );
+            }
+        }
+    }
+
     if mark_completed {
         // Mark session as completed
         mark_session_completed(&progress_state, &session_id);
@@ -363,6 +730,250 @@ pub async fn process_upload_queue(
     }
 }
 
+/// Load user → webhook overrides keyed by VRChat user ID and display name
+pub(crate) async fn load_user_webhook_override_map() -> HashMap<String, i64> {
+    let overrides = database::get_user_webhook_overrides()
+        .await
+        .unwrap_or_default();
+    overrides
+        .into_iter()
+        .flat_map(|o| {
+            let mut items = Vec::new();
+            if let Some(uid) = o.user_id {
+                items.push((uid, o.webhook_id));
+            }
+            if let Some(name) = o.user_display_name {
+                items.push((name, o.webhook_id));
+            }
+            items
+        })
+        .collect()
+}
+
+/// Load VRChat player → Discord user ID mappings keyed by lowercased ID and display name.
+/// Empty when mentioning mapped players is turned off, so captions use plain names.
+pub(crate) async fn load_discord_user_map() -> HashMap<String, String> {
+    let mention_players = crate::config::load_config()
+        .map(|c| c.mention_mapped_players)
+        .unwrap_or(true);
+    if !mention_players {
+        return HashMap::new();
+    }
+
+    let discord_mappings_list = database::get_discord_user_mappings()
+        .await
+        .unwrap_or_default();
+    discord_mappings_list
+        .into_iter()
+        .flat_map(|m| {
+            let mut items = Vec::new();
+            if let Some(uid) = m.vrchat_user_id {
+                items.push((uid.to_lowercase(), m.discord_user_id.clone()));
+            }
+            if let Some(name) = m.vrchat_display_name {
+                items.push((name.to_lowercase(), m.discord_user_id));
+            }
+            items
+        })
+        .collect()
+}
+
+/// Discord users a caption built from `discord_user_map` may ping
+pub(crate) fn mention_user_ids(discord_user_map: &HashMap<String, String>) -> Vec<String> {
+    let mut user_ids: Vec<String> = discord_user_map.values().cloned().collect();
+    user_ids.sort();
+    user_ids.dedup();
+    user_ids
+}
+
+/// Message flags for posts through a webhook: the session's choice wins over the
+/// webhook's own defaults
+fn resolve_message_flags(
+    webhook: &Webhook,
+    suppress_embeds: Option<bool>,
+    suppress_notifications: Option<bool>,
+) -> u64 {
+    message_flags(
+        suppress_embeds.unwrap_or(webhook.suppress_embeds),
+        suppress_notifications.unwrap_or(webhook.suppress_notifications),
+    )
+}
+
+/// World whose forum thread a group is posted to: only groups from exactly one known world
+fn forum_thread_world_id(all_worlds: &[WorldInfo]) -> Option<String> {
+    match all_worlds {
+        [world] if !world.id.is_empty() => Some(world.id.clone()),
+        _ => None,
+    }
+}
+
+/// Forum tag IDs for a post about `worlds` through a forum webhook (best-effort).
+/// Tags chosen for the session replace the webhook's configured ones.
+async fn load_forum_tags(
+    webhook: &Webhook,
+    worlds: &[WorldInfo],
+    session_tags: Option<&[String]>,
+) -> Vec<String> {
+    if !webhook.is_forum {
+        return Vec::new();
+    }
+    if let Some(tags) = session_tags {
+        return tags.to_vec();
+    }
+    match database::get_webhook_forum_tags(webhook.id).await {
+        Ok(tags) => applied_forum_tags(&tags, worlds),
+        Err(e) => {
+            log::warn!(
+                "Could not load forum tags for webhook '{}': {e}",
+                webhook.name
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Resolve the webhook a group should be posted to. An explicit per-group assignment
+/// wins over user webhook overrides, which win over the session webhook.
+pub(crate) async fn resolve_group_webhook(
+    group: &ImageGroup,
+    default_webhook: &Webhook,
+    group_webhook_overrides: &HashMap<String, i64>,
+    override_map: &HashMap<String, i64>,
+) -> Webhook {
+    if let Some(&webhook_id) = group_webhook_overrides.get(&group.group_id) {
+        match database::get_webhook_by_id(webhook_id).await {
+            Ok(w) => {
+                log::info!(
+                    "sending group {} to assigned webhook '{}'",
+                    group.group_id,
+                    w.name
+                );
+                return w;
+            }
+            Err(e) => log::warn!(
+                "Assigned webhook {webhook_id} for group {} unavailable: {e}",
+                group.group_id
+            ),
+        }
+    }
+
+    for player in &group.all_players {
+        // Check ID first, then Display Name
+        let found_webhook_id = override_map
+            .get(&player.id)
+            .or_else(|| override_map.get(&player.display_name));
+
+        if let Some(&webhook_id) = found_webhook_id {
+            if let Ok(w) = database::get_webhook_by_id(webhook_id).await {
+                log::info!(
+                    "redirecting group {} to webhook '{}' due to override for user '{}'",
+                    group.group_id,
+                    w.name,
+                    player.display_name
+                );
+                return w; // First match wins
+            }
+        }
+    }
+
+    default_webhook.clone()
+}
+
+/// How often a parked session re-checks whether Discord is reachable
+const NETWORK_RECHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Spacing between requests to a forum webhook, whose posts and thread creation are
+/// limited more strictly than plain channels
+const FORUM_REQUEST_INTERVAL: Duration = Duration::from_millis(2000);
+
+/// Block until Discord is reachable again after a request failed because it wasn't,
+/// parking the session in "waiting_for_network" meanwhile. The state is only kept in
+/// memory: nothing resumes a parked session after a restart, so the database keeps it
+/// active. Returns false if cancelled or shutting down while waiting.
+async fn wait_for_network(
+    client: &DiscordClient,
+    progress_state: &ProgressState,
+    session_id: &str,
+    app_handle: &tauri::AppHandle,
+) -> bool {
+    mark_session_waiting_for_network(progress_state, session_id);
+    emit_session_progress(app_handle, progress_state, session_id);
+
+    loop {
+        sleep(NETWORK_RECHECK_INTERVAL).await;
+
+        if is_session_cancelled(progress_state, session_id) || shutdown::is_shutting_down() {
+            return false;
+        }
+        if client.is_discord_reachable().await {
+            break;
+        }
+    }
+
+    mark_session_resumed(progress_state, session_id);
+    emit_session_progress(app_handle, progress_state, session_id);
+    true
+}
+
+/// Stop for app shutdown: save the unfinished groups (group ID, files) so they can
+/// be resumed later, and mark the session interrupted
+async fn interrupt_for_shutdown(
+    app_handle: &tauri::AppHandle,
+    progress_state: &ProgressState,
+    session_id: &str,
+    webhook_id: i64,
+    unfinished: Vec<(String, Vec<String>)>,
+    options: &FailedGroupOptions,
+    caption_overrides: &HashMap<String, String>,
+) {
+    log::info!(
+        "Session {session_id} interrupted by shutdown with {} group(s) left",
+        unfinished.len()
+    );
+    for (group_id, files) in unfinished {
+        let options = FailedGroupOptions {
+            caption: caption_overrides.get(&group_id).cloned(),
+            ..options.clone()
+        };
+        shutdown::checkpoint_group(session_id, webhook_id, &group_id, &files, &options).await;
+    }
+    shutdown::mark_interrupted(app_handle, progress_state, session_id).await;
+}
+
+/// Save a failed group to the database so it can still be retried after a restart
+async fn persist_failed_group(
+    progress_state: &ProgressState,
+    session_id: &str,
+    webhook_id: i64,
+    group_id: &str,
+    file_paths: &[String],
+    options: &FailedGroupOptions,
+) {
+    let error_message = progress_state.lock().ok().and_then(|progress| {
+        progress.get(session_id).and_then(|p| {
+            p.failed_uploads
+                .iter()
+                .rev()
+                .find(|f| file_paths.contains(&f.file_path))
+                .map(|f| f.error.clone())
+        })
+    });
+
+    match database::record_failed_group(
+        session_id,
+        webhook_id,
+        group_id,
+        file_paths,
+        options,
+        error_message,
+    )
+    .await
+    {
+        Ok(id) => log::info!("Persisted failed group {group_id} as #{id}"),
+        Err(e) => log::error!("Failed to persist failed group {group_id}: {e}"),
+    }
+}
+
 /// Process image group with error handling
 #[allow(clippy::too_many_arguments)]
 async fn process_image_group_with_failure_handling(
@@ -371,6 +982,9 @@ async fn process_image_group_with_failure_handling(
     group: ImageGroup,
     max_images_per_message: u8,
     include_player_names: bool,
+    include_avatar_in_caption: bool,
+    timestamp_style: &str,
+    use_link_buttons: bool,
     progress_state: &ProgressState,
     session_id: &str,
     app_handle: &tauri::AppHandle,
@@ -379,8 +993,14 @@ async fn process_image_group_with_failure_handling(
     format: String,
     override_thread_id: Option<String>,
     discord_user_map: &HashMap<String, String>,
-) -> (bool, Option<String>) {
+    link_player_profiles: bool,
+    caption_override: Option<&str>,
+    extra_message: Option<&str>,
+) -> (bool, Option<String>, Option<PostedMessage>) {
     let is_forum_channel = webhook.is_forum;
+    if is_forum_channel {
+        client.set_request_interval(&webhook.url, FORUM_REQUEST_INTERVAL);
+    }
     log::info!(
         "🚀 Starting group upload (ID: {}, {} images)",
         group.group_id,
@@ -393,7 +1013,7 @@ async fn process_image_group_with_failure_handling(
             session_id,
             group.group_id
         );
-        return (false, None);
+        return (false, None, None);
     }
 
     // For forum channels, be extra careful about chunk sizes
@@ -406,11 +1026,14 @@ async fn process_image_group_with_failure_handling(
         max_images_per_message
     };
 
-    let chunks: Vec<Vec<String>> = group
-        .images
-        .chunks(effective_max_images as usize)
-        .map(|chunk| chunk.to_vec())
-        .collect();
+    // Plan chunks against both the image count and the webhook's size budget so
+    // messages don't have to be discovered as too large by Discord first
+    let chunks = plan_chunks(
+        &group.images,
+        effective_max_images as usize,
+        webhook_upload_limit(webhook),
+        &format,
+    );
 
     if is_forum_channel {
         log::info!(
@@ -428,9 +1051,16 @@ async fn process_image_group_with_failure_handling(
 
     let mut first_message = true;
     let mut thread_id: Option<String> = override_thread_id;
+    let guild_id = client.get_webhook_guild_id(&webhook.url).await;
+    let mut first_posted: Option<PostedMessage> = None;
 
     // Process chunks and stop on first failure OR cancellation
     for (chunk_index, chunk) in chunks.iter().enumerate() {
+        // On shutdown, stop between chunks; the caller saves what's left
+        if shutdown::is_shutting_down() {
+            log::info!("Stopping group {} for shutdown", group.group_id);
+            return (false, None, None);
+        }
         if is_session_cancelled(progress_state, session_id) {
             log::info!(
                 "❌ Session {} cancelled during chunk {} of group {}",
@@ -438,7 +1068,7 @@ async fn process_image_group_with_failure_handling(
                 chunk_index + 1,
                 group.group_id
             );
-            return (false, None);
+            return (false, None, None);
         }
 
         log::info!(
@@ -449,10 +1079,11 @@ async fn process_image_group_with_failure_handling(
             chunk.len()
         );
 
-        let (text_fields, overflow_messages) = create_discord_payload(
+        let (mut text_fields, mut overflow_messages) = create_discord_payload(
             &group.all_worlds,
             &group.all_players,
             group.timestamp,
+            timestamp_style,
             first_message,
             chunk_index,
             is_forum_channel && is_first_group, // Only first group creates new thread
@@ -460,12 +1091,36 @@ async fn process_image_group_with_failure_handling(
             include_player_names,
             group.images.len(),
             discord_user_map,
+            link_player_profiles,
         );
 
-        // If this is the first message and we have overflow player messages,
-        // we need to send text first, then overflow, then images
+        // Avatar line, user-edited caption and session comment only apply to the group's first message
+        if first_message {
+            if include_avatar_in_caption {
+                append_avatar_caption(&mut text_fields, &group.all_avatars);
+            }
+            apply_caption_customizations(
+                &mut text_fields,
+                &mut overflow_messages,
+                caption_override,
+                extra_message,
+            );
+        }
+
+        let link_buttons = if first_message && use_link_buttons {
+            text_fields
+                .get("content")
+                .and_then(|content| create_world_link_buttons(&group.all_worlds, content))
+        } else {
+            None
+        };
+
+        // If this is the first message and we have overflow player messages or link
+        // buttons, we need to send text first, then overflow, then images
         let mut text_fields_for_images = text_fields.clone();
-        if first_message && (is_forum_channel || !overflow_messages.is_empty()) {
+        if first_message
+            && (is_forum_channel || !overflow_messages.is_empty() || link_buttons.is_some())
+        {
             // Send the main text message first (this creates the forum thread if applicable)
             log::info!(
                 "📤 Sending text message first (has {} overflow messages)",
@@ -498,11 +1153,30 @@ async fn process_image_group_with_failure_handling(
                     "Creating Thread",
                     0.0,
                 );
-                safe_emit_event(app_handle, "upload-progress", session_id);
+                emit_session_progress(app_handle, progress_state, session_id);
 
-                let forum_result = client
-                    .send_forum_text_message(&webhook.url, &main_content, thread_name.as_deref())
-                    .await;
+                let forum_result = match &link_buttons {
+                    Some(buttons) => {
+                        client
+                            .send_text_message_with_link_buttons(
+                                &webhook.url,
+                                &main_content,
+                                buttons,
+                                None,
+                                thread_name.as_deref(),
+                            )
+                            .await
+                    }
+                    None => {
+                        client
+                            .send_forum_text_message(
+                                &webhook.url,
+                                &main_content,
+                                thread_name.as_deref(),
+                            )
+                            .await
+                    }
+                };
 
                 match forum_result {
                     Ok(response_data) => {
@@ -512,6 +1186,11 @@ async fn process_image_group_with_failure_handling(
                             log::info!(
                                 "✅ Forum thread created with thread_id: {extracted_thread_id}"
                             );
+                            session_log::record(
+                                session_id,
+                                LogKind::Discord,
+                                format!("Created forum thread {extracted_thread_id}"),
+                            );
 
                             // Send overflow messages to the thread
                             for (i, overflow_msg) in overflow_messages.iter().enumerate() {
@@ -544,6 +1223,7 @@ async fn process_image_group_with_failure_handling(
                             let worlds_only_msg = super::image_groups::create_worlds_only_message(
                                 &group.all_worlds,
                                 group.timestamp,
+                                timestamp_style,
                                 group.images.len(),
                             );
 
@@ -570,6 +1250,7 @@ async fn process_image_group_with_failure_handling(
                                                 super::image_groups::create_split_player_messages(
                                                     &group.all_players,
                                                     discord_user_map,
+                                                    link_player_profiles,
                                                 );
                                             for (i, player_msg) in
                                                 player_messages.iter().enumerate()
@@ -649,7 +1330,7 @@ async fn process_image_group_with_failure_handling(
                                                     if include_player_names
                                                         && !group.all_players.is_empty()
                                                     {
-                                                        let player_messages = super::image_groups::create_split_player_messages(&group.all_players, discord_user_map);
+                                                        let player_messages = super::image_groups::create_split_player_messages(&group.all_players, discord_user_map, link_player_profiles);
                                                         for (i, player_msg) in
                                                             player_messages.iter().enumerate()
                                                         {
@@ -685,7 +1366,7 @@ async fn process_image_group_with_failure_handling(
                                                         group.group_id.clone(),
                                                     );
                                                 }
-                                                return (false, None);
+                                                return (false, None, None);
                                             }
                                         }
                                     } else {
@@ -702,7 +1383,7 @@ async fn process_image_group_with_failure_handling(
                                                 group.group_id.clone(),
                                             );
                                         }
-                                        return (false, None);
+                                        return (false, None, None);
                                     }
                                 }
                             }
@@ -719,7 +1400,7 @@ async fn process_image_group_with_failure_handling(
                                     group.group_id.clone(),
                                 );
                             }
-                            return (false, None);
+                            return (false, None, None);
                         }
                     }
                 }
@@ -729,9 +1410,23 @@ async fn process_image_group_with_failure_handling(
             } else {
                 // Non-forum channel: send text first, then overflow, then images
                 // With retry logic for message too long errors
-                let send_result = client
-                    .send_text_message(&webhook.url, &main_content, thread_id.as_deref())
-                    .await;
+                let send_result = match &link_buttons {
+                    Some(buttons) => client
+                        .send_text_message_with_link_buttons(
+                            &webhook.url,
+                            &main_content,
+                            buttons,
+                            thread_id.as_deref(),
+                            None,
+                        )
+                        .await
+                        .map(|_| ()),
+                    None => {
+                        client
+                            .send_text_message(&webhook.url, &main_content, thread_id.as_deref())
+                            .await
+                    }
+                };
 
                 match send_result {
                     Ok(_) => {
@@ -757,6 +1452,7 @@ async fn process_image_group_with_failure_handling(
                             let worlds_only_msg = super::image_groups::create_worlds_only_message(
                                 &group.all_worlds,
                                 group.timestamp,
+                                timestamp_style,
                                 group.images.len(),
                             );
 
@@ -779,6 +1475,7 @@ async fn process_image_group_with_failure_handling(
                                             super::image_groups::create_split_player_messages(
                                                 &group.all_players,
                                                 discord_user_map,
+                                                link_player_profiles,
                                             );
                                         for (i, player_msg) in player_messages.iter().enumerate() {
                                             if let Err(e3) = client
@@ -851,6 +1548,7 @@ async fn process_image_group_with_failure_handling(
                                                 super::image_groups::create_split_player_messages(
                                                     &group.all_players,
                                                     discord_user_map,
+                                                    link_player_profiles,
                                                 );
                                             for (i, player_msg) in
                                                 player_messages.iter().enumerate()
@@ -912,14 +1610,14 @@ async fn process_image_group_with_failure_handling(
                     );
                 }
             }
-            return (false, None);
+            return (false, None, None);
         }
 
         // Update progress to show current files being uploaded/compressed
         for (file_index, file_path) in chunk.iter().enumerate() {
             if is_session_cancelled(progress_state, session_id) {
                 log::info!("❌ Session {session_id} cancelled while updating progress");
-                return (false, None);
+                return (false, None, None);
             }
 
             // Show initial progress for this file
@@ -953,25 +1651,44 @@ async fn process_image_group_with_failure_handling(
             update_progress_current(progress_state, session_id, first_file.clone());
         }
 
-        // Upload the chunk with thread_id support
-        match upload_image_chunk_with_thread_id(
-            client,
-            webhook,
-            chunk.clone(),
-            text_fields_for_images,
-            thread_id.as_deref(),
-            progress_state,
-            session_id,
-            app_handle,
-            quality,
-            format.clone(),
-        )
-        .await
-        {
-            Ok(response_data) => {
+        // Upload the chunk with thread_id support. Connection failures while Discord is
+        // unreachable park the session until the network returns instead of failing the group.
+        let chunk_started = std::time::Instant::now();
+        let upload_result = loop {
+            let result = upload_image_chunk_with_thread_id(
+                client,
+                webhook,
+                chunk.clone(),
+                text_fields_for_images.clone(),
+                thread_id.as_deref(),
+                progress_state,
+                session_id,
+                app_handle,
+                quality,
+                format.clone(),
+            )
+            .await;
+
+            match result {
+                Err(AppError::Network(ref e)) if !client.is_discord_reachable().await => {
+                    log::warn!("Chunk upload hit a network error, waiting to retry: {e}");
+                    if !wait_for_network(client, progress_state, session_id, app_handle).await {
+                        log::info!("❌ Session {session_id} cancelled while waiting for network");
+                        return (false, None, None);
+                    }
+                }
+                other => break other,
+            }
+        };
+
+        match upload_result {
+            Ok(ChunkResponse {
+                response_data,
+                verification,
+            }) => {
                 if is_session_cancelled(progress_state, session_id) {
                     log::info!("❌ Session {session_id} cancelled after successful chunk upload");
-                    return (false, None);
+                    return (false, None, None);
                 }
 
                 // For forum channels, extract thread_id from first response (if not already extracted via text message)
@@ -1011,41 +1728,62 @@ async fn process_image_group_with_failure_handling(
                                     "Forum channel thread_id extraction failed - response missing thread info".to_string(), true, group.group_id.clone());
                             }
 
-                            return (false, None);
+                            return (false, None, None);
                         } else {
                             log::info!("ℹ️ Only one chunk, continuing despite thread_id extraction failure");
                         }
                     }
                 }
 
-                // Record successful uploads in database and update progress
+                let posted = extract_posted_message(&response_data, guild_id.as_deref());
+                session_log::record_with_details(
+                    session_id,
+                    LogKind::Chunk,
+                    format!(
+                        "Chunk {}/{} of group {} posted ({} images)",
+                        chunk_index + 1,
+                        chunks.len(),
+                        group.group_id,
+                        chunk.len()
+                    ),
+                    serde_json::json!({
+                        "duration_ms": chunk_started.elapsed().as_millis() as u64,
+                        "message_id": posted.as_ref().map(|p| &p.message_id),
+                        "channel_id": posted.as_ref().map(|p| &p.channel_id),
+                        "attachments": posted.as_ref().map(|p| p.attachment_urls.len()),
+                    }),
+                );
+                if first_posted.is_none() {
+                    first_posted = posted.clone();
+                }
+
+                // Record the chunk's uploads in one transaction before reporting progress
+                let mut records = Vec::with_capacity(chunk.len());
                 for (file_index, file_path) in chunk.iter().enumerate() {
-                    let file_name = Path::new(file_path)
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string();
-
-                    let file_hash = image_processor::get_file_hash(file_path).await.ok();
-                    let file_size = security::FileSystemGuard::get_file_size(file_path).ok();
-
-                    // Record in database (non-blocking)
-                    let file_path_clone = file_path.clone();
-                    let file_name_clone = file_name.clone();
-                    let webhook_id = webhook.id;
-                    tokio::spawn(async move {
-                        let _ = database::record_upload(
-                            file_path_clone,
-                            file_name_clone,
-                            file_hash,
-                            file_size,
-                            webhook_id,
-                            "success",
-                            None,
-                        )
-                        .await;
+                    let file_hash = image_processor::get_file_hash(file_path)
+                        .await
+                        .ok()
+                        .map(|h| h.tagged());
+                    records.push(database::UploadRecord {
+                        file_path: file_path.clone(),
+                        file_name: file_name_of(file_path),
+                        file_hash,
+                        file_size: security::FileSystemGuard::get_file_size(file_path).ok(),
+                        webhook_id: webhook.id,
+                        status: "success".to_string(),
+                        error_message: None,
+                        message: posted.clone(),
+                        attachment_url: posted.as_ref().and_then(|p| p.attachment_url(file_index)),
+                        session_id: Some(session_id.to_string()),
+                        group_id: Some(group.group_id.clone()),
+                        verification_status: verification.get(file_index).cloned().flatten(),
                     });
+                }
+                if let Err(e) = database::record_uploads_batch(&records).await {
+                    log::error!("Failed to record uploads of chunk {}: {e}", chunk_index + 1);
+                }
 
+                for (file_index, file_path) in chunk.iter().enumerate() {
                     update_progress_success(progress_state, session_id, file_path.clone());
 
                     // Emit per-file success event
@@ -1070,8 +1808,27 @@ async fn process_image_group_with_failure_handling(
                     chunk.len()
                 );
             }
+            // A cancelled session isn't a failure; leave the remaining files alone
+            Err(AppError::UploadCancelled { phase, .. }) => {
+                log::info!("❌ Session {session_id} cancelled during {phase}");
+                return (false, None, None);
+            }
             Err(e) => {
                 log::error!("❌ CHUNK FAILED in group {}: {}", group.group_id, e);
+                session_log::record_with_details(
+                    session_id,
+                    LogKind::Error,
+                    format!(
+                        "Chunk {}/{} of group {} failed: {e}",
+                        chunk_index + 1,
+                        chunks.len(),
+                        group.group_id
+                    ),
+                    serde_json::json!({
+                        "duration_ms": chunk_started.elapsed().as_millis() as u64,
+                        "files": chunk,
+                    }),
+                );
 
                 // Enhanced error logging for forum channels
                 if is_forum_channel && e.to_string().contains("thread_name or thread_id") {
@@ -1099,31 +1856,31 @@ async fn process_image_group_with_failure_handling(
                 let remaining_files: Vec<String> =
                     chunks.iter().skip(chunk_index).flatten().cloned().collect();
 
-                for file_path in &remaining_files {
-                    let file_name = Path::new(file_path)
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string();
-
-                    // Record failed upload in database (non-blocking)
-                    let file_path_clone = file_path.clone();
-                    let file_name_clone = file_name.clone();
-                    let error_message = format!("Group failure: {e}");
-                    let webhook_id = webhook.id;
-                    tokio::spawn(async move {
-                        let _ = database::record_upload(
-                            file_path_clone,
-                            file_name_clone,
-                            None,
-                            None,
-                            webhook_id,
-                            "failed",
-                            Some(error_message),
-                        )
-                        .await;
-                    });
+                let records: Vec<database::UploadRecord> = remaining_files
+                    .iter()
+                    .map(|file_path| database::UploadRecord {
+                        file_path: file_path.clone(),
+                        file_name: file_name_of(file_path),
+                        file_hash: None,
+                        file_size: None,
+                        webhook_id: webhook.id,
+                        status: "failed".to_string(),
+                        error_message: Some(format!("Group failure: {e}")),
+                        message: None,
+                        attachment_url: None,
+                        session_id: Some(session_id.to_string()),
+                        group_id: Some(group.group_id.clone()),
+                        verification_status: None,
+                    })
+                    .collect();
+                if let Err(db_error) = database::record_uploads_batch(&records).await {
+                    log::error!(
+                        "Failed to record failed uploads of group {}: {db_error}",
+                        group.group_id
+                    );
+                }
 
+                for file_path in &remaining_files {
                     // Mark as group failure (retryable)
                     update_progress_group_failure(
                         progress_state,
@@ -1138,21 +1895,14 @@ async fn process_image_group_with_failure_handling(
                 // Emit progress update for failed group
                 emit_session_progress(app_handle, progress_state, session_id);
 
-                return (false, None);
+                return (false, None, None);
             }
         }
 
         first_message = false;
 
         // Emit progress update
-        safe_emit_event(app_handle, "upload-progress", session_id);
-
-        // Rate limiting delay between chunks (longer for forum channels)
-        if is_forum_channel {
-            sleep(Duration::from_millis(2000)).await; // 2s for forum channels
-        } else {
-            sleep(Duration::from_millis(1000)).await; // 1s for regular channels
-        }
+        emit_session_progress(app_handle, progress_state, session_id);
     }
 
     if is_forum_channel {
@@ -1169,7 +1919,7 @@ async fn process_image_group_with_failure_handling(
             group.images.len()
         );
     }
-    (true, thread_id)
+    (true, thread_id, first_posted)
 }
 
 /// Upload image chunk with thread ID support
@@ -1185,7 +1935,7 @@ pub async fn upload_image_chunk_with_thread_id(
     app_handle: &tauri::AppHandle,
     quality: u8,
     format: String,
-) -> AppResult<String> {
+) -> AppResult<ChunkResponse> {
     log::info!(
         "Starting upload of {} files for session {}",
         file_paths.len(),
@@ -1198,6 +1948,12 @@ pub async fn upload_image_chunk_with_thread_id(
     }
 
     // Update progress to show upload phase
+    update_files_phase(
+        progress_state,
+        session_id,
+        &file_paths,
+        FilePhase::Uploading,
+    );
     if let Some(first_file) = file_paths.first() {
         update_progress_current_with_phase(
             progress_state,
@@ -1206,7 +1962,7 @@ pub async fn upload_image_chunk_with_thread_id(
             "Uploading",
             0.0,
         );
-        safe_emit_event(app_handle, "upload-progress", session_id);
+        emit_session_progress(app_handle, progress_state, session_id);
 
         // Emit streaming event for upload start
         app_handle
@@ -1223,29 +1979,61 @@ pub async fn upload_image_chunk_with_thread_id(
             .ok();
     }
 
-    // Try normal upload first
-    let result = try_upload_chunk_with_thread_id(
-        client,
-        webhook,
-        &file_paths,
-        &text_fields,
-        thread_id,
+    // Oversized images are downscaled and unsupported formats converted first so
+    // uploads are consistently sized; progress keeps tracking the original paths
+    let (upload_paths, mut resized_paths) = prepare_attachments(&file_paths).await;
+
+    // Image compression can't make a clip smaller, so one over the limit has to be
+    // re-encoded up front or can't be sent at all
+    let upload_limit = webhook_upload_limit(webhook);
+    let upload_paths = match compress_oversized_clips(
+        upload_paths,
+        &mut resized_paths,
+        upload_limit,
         progress_state,
         session_id,
+        app_handle,
     )
-    .await;
+    .await
+    {
+        Ok(paths) => paths,
+        Err(e) => {
+            for path in &resized_paths {
+                tokio::fs::remove_file(path).await.ok();
+            }
+            return Err(e);
+        }
+    };
+    let descriptions = load_attachment_descriptions(&file_paths).await;
 
-    match result {
+    // Try normal upload first, unless the payload clearly exceeds the webhook's limit
+    let estimated_size = estimate_payload_size(&upload_paths, &text_fields);
+    let result = if estimated_size > upload_limit {
+        Err(payload_too_large(estimated_size, upload_limit))
+    } else {
+        try_upload_chunk_with_thread_id(
+            client,
+            webhook,
+            &upload_paths,
+            &text_fields,
+            &descriptions,
+            thread_id,
+            progress_state,
+            session_id,
+        )
+        .await
+    };
+
+    let outcome = match result {
         Ok(response) => {
             log::info!("Upload successful without compression for session {session_id}");
             Ok(response)
         }
+        // Check cancellation before trying compression
+        Err(_) if is_session_cancelled(progress_state, session_id) => {
+            Err(AppError::upload_cancelled("before compression", session_id))
+        }
         Err(e) => {
-            // Check cancellation before trying compression
-            if is_session_cancelled(progress_state, session_id) {
-                return Err(AppError::upload_cancelled("before compression", session_id));
-            }
-
             // Check if it was a size-related error (413 HTTP status or Discord error 40005)
             let err_str = e.to_string();
             if err_str.contains("413")
@@ -1261,8 +2049,9 @@ pub async fn upload_image_chunk_with_thread_id(
                 upload_compressed_chunk_with_thread_id(
                     client,
                     webhook,
-                    file_paths,
+                    upload_paths,
                     text_fields,
+                    &descriptions,
                     thread_id,
                     progress_state,
                     session_id,
@@ -1275,19 +2064,269 @@ pub async fn upload_image_chunk_with_thread_id(
                 Err(e)
             }
         }
+    };
+
+    for path in &resized_paths {
+        tokio::fs::remove_file(path).await.ok();
+    }
+    outcome
+}
+
+/// Per-file multipart overhead: boundary, Content-Disposition and Content-Type headers
+const MULTIPART_FILE_OVERHEAD: u64 = 256;
+
+/// Rough size of the multipart body for these files and text fields
+fn estimate_payload_size(file_paths: &[String], text_fields: &HashMap<String, String>) -> u64 {
+    let files: u64 = file_paths
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len() + MULTIPART_FILE_OVERHEAD)
+        .sum();
+    let text: u64 = text_fields
+        .iter()
+        .map(|(key, value)| (key.len() + value.len()) as u64 + MULTIPART_FILE_OVERHEAD)
+        .sum();
+    files + text
+}
+
+/// Files in chunks whose originals already exceed the webhook's upload limit, in
+/// upload order. Those chunks are certain to be compressed, so they can be prepared
+/// ahead of time. Group webhook overrides aren't known yet, so the session webhook's
+/// limit is used; downscaled uploads are skipped since they compress resized copies.
+fn files_needing_compression(
+    groups: &[ImageGroup],
+    webhook: &Webhook,
+    max_images_per_message: u8,
+    format: &str,
+    max_upload_dimension: Option<u32>,
+) -> Vec<String> {
+    if max_upload_dimension.is_some_and(|d| d > 0) {
+        return Vec::new();
+    }
+
+    let upload_limit = webhook_upload_limit(webhook);
+    let max_images = if webhook.is_forum {
+        max_images_per_message.min(10)
+    } else {
+        max_images_per_message
+    };
+    let no_text = HashMap::new();
+
+    groups
+        .iter()
+        .flat_map(|group| plan_chunks(&group.images, max_images as usize, upload_limit, format))
+        .filter(|chunk| estimate_payload_size(chunk, &no_text) > upload_limit)
+        .flatten()
+        .filter(|path| !security::is_video_file(path))
+        .collect()
+}
+
+fn clip_too_large(file_path: &str, size: u64, upload_limit: u64, hint: &str) -> AppError {
+    AppError::UploadFailed {
+        reason: format!(
+            "Video clip {} is {:.2} MB, over the webhook's {:.0} MB upload limit. {hint}",
+            file_name_of(file_path),
+            size as f64 / 1024.0 / 1024.0,
+            upload_limit as f64 / 1024.0 / 1024.0
+        ),
+    }
+}
+
+/// Re-encode clips that are bigger than the upload limit on their own with FFmpeg,
+/// when clip compression is on. The copies are added to `temp_paths`; a clip that
+/// can't be brought under the limit fails the chunk.
+async fn compress_oversized_clips(
+    upload_paths: Vec<String>,
+    temp_paths: &mut Vec<String>,
+    upload_limit: u64,
+    progress_state: &ProgressState,
+    session_id: &str,
+    app_handle: &tauri::AppHandle,
+) -> AppResult<Vec<String>> {
+    let mut prepared = Vec::with_capacity(upload_paths.len());
+    for path in upload_paths {
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if !security::is_video_file(&path) || size + MULTIPART_FILE_OVERHEAD <= upload_limit {
+            prepared.push(path);
+            continue;
+        }
+
+        let config = crate::config::load_config().ok();
+        let ffmpeg = config
+            .as_ref()
+            .filter(|c| c.compress_clips)
+            .map(|c| clip_compression::locate_ffmpeg(c.ffmpeg_path.as_deref()));
+        let ffmpeg = match ffmpeg {
+            Some(Some(ffmpeg)) => ffmpeg,
+            Some(None) => return Err(clip_too_large(
+                &path,
+                size,
+                upload_limit,
+                "Clip compression is on, but FFmpeg wasn't found; set its location in settings.",
+            )),
+            None => return Err(clip_too_large(
+                &path,
+                size,
+                upload_limit,
+                "Clips are uploaded as recorded unless clip compression is turned on in settings.",
+            )),
+        };
+
+        let cancel_token = session_cancel_token(progress_state, session_id);
+        let on_progress = |percent: f32| {
+            update_progress_current_with_phase(
+                progress_state,
+                session_id,
+                path.clone(),
+                "Compressing",
+                percent,
+            );
+            emit_session_progress(app_handle, progress_state, session_id);
+        };
+        let Some(compressed) = clip_compression::compress_clip(
+            &ffmpeg,
+            &path,
+            upload_limit.saturating_sub(MULTIPART_FILE_OVERHEAD),
+            &cancel_token,
+            on_progress,
+        )
+        .await?
+        else {
+            return Err(AppError::upload_cancelled("clip compression", session_id));
+        };
+        temp_paths.push(compressed.clone());
+
+        let compressed_size = std::fs::metadata(&compressed).map(|m| m.len()).unwrap_or(0);
+        if compressed_size + MULTIPART_FILE_OVERHEAD > upload_limit {
+            return Err(clip_too_large(
+                &path,
+                compressed_size,
+                upload_limit,
+                "It's still too large after re-encoding; trim it first.",
+            ));
+        }
+        log::info!(
+            "Re-encoded {path} from {:.2} MB to {:.2} MB",
+            size as f64 / 1024.0 / 1024.0,
+            compressed_size as f64 / 1024.0 / 1024.0
+        );
+        prepared.push(compressed);
+    }
+    Ok(prepared)
+}
+
+/// Error for a payload that wasn't sent because it won't fit; worded so the size
+/// fallbacks treat it like Discord's own 413
+fn payload_too_large(size: u64, limit: u64) -> AppError {
+    log::info!(
+        "Payload of {:.2} MB exceeds the webhook's {:.0} MB limit, skipping the upload attempt",
+        size as f64 / 1024.0 / 1024.0,
+        limit as f64 / 1024.0 / 1024.0
+    );
+    AppError::UploadFailed {
+        reason: format!(
+            "Payload too large ({:.2} MB, limit {:.0} MB)",
+            size as f64 / 1024.0 / 1024.0,
+            limit as f64 / 1024.0 / 1024.0
+        ),
+    }
+}
+
+/// Apply `max_upload_dimension` from the config and convert images Discord wouldn't
+/// show inline to PNG; video clips are left alone. Returns the paths to upload and
+/// the temp files that were created for them.
+async fn prepare_attachments(file_paths: &[String]) -> (Vec<String>, Vec<String>) {
+    let max_dimension = crate::config::load_config()
+        .ok()
+        .and_then(|c| c.max_upload_dimension)
+        .filter(|&d| d > 0);
+
+    let mut upload_paths = Vec::with_capacity(file_paths.len());
+    let mut temp_paths = Vec::new();
+    for file_path in file_paths {
+        if security::is_video_file(file_path) {
+            upload_paths.push(file_path.clone());
+            continue;
+        }
+        let resized = match max_dimension {
+            Some(max_dimension) => {
+                image_processor::resize_to_max_dimension(file_path, max_dimension)
+                    .await
+                    .unwrap_or_else(|e| {
+                        log::warn!("Could not downscale {file_path}, uploading original: {e}");
+                        None
+                    })
+            }
+            None => None,
+        };
+        // Resized copies are already PNG
+        let prepared = match resized {
+            Some(resized) => Some(resized),
+            None if needs_conversion(file_path) => {
+                match image_processor::convert_to_png(file_path).await {
+                    Ok(converted) => Some(converted),
+                    Err(e) => {
+                        log::warn!("Could not convert {file_path}, uploading original: {e}");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        match prepared {
+            Some(prepared) => {
+                temp_paths.push(prepared.clone());
+                upload_paths.push(prepared);
+            }
+            None => upload_paths.push(file_path.clone()),
+        }
+    }
+    (upload_paths, temp_paths)
+}
+
+fn file_name_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Alt text for each file in order; a missing database just means no descriptions
+async fn load_attachment_descriptions(file_paths: &[String]) -> Vec<Option<String>> {
+    match database::get_photo_descriptions(file_paths).await {
+        Ok(mut descriptions) => file_paths
+            .iter()
+            .map(|path| descriptions.remove(path))
+            .collect(),
+        Err(e) => {
+            log::debug!("Could not load photo descriptions: {e}");
+            vec![None; file_paths.len()]
+        }
+    }
+}
+
+fn add_attachment_descriptions(payload: &mut UploadPayload, descriptions: &[Option<String>]) {
+    for (i, description) in descriptions.iter().enumerate() {
+        if let Some(description) = description {
+            payload.set_attachment_description(i, description.clone());
+        }
     }
 }
 
 /// Try upload without compression
+#[allow(clippy::too_many_arguments)]
 async fn try_upload_chunk_with_thread_id(
     client: &DiscordClient,
     webhook: &Webhook,
     file_paths: &[String],
     text_fields: &HashMap<String, String>,
+    descriptions: &[Option<String>],
     thread_id: Option<&str>,
     progress_state: &ProgressState,
     session_id: &str,
-) -> AppResult<String> {
+) -> AppResult<ChunkResponse> {
     // Check cancellation before building payload
     if is_session_cancelled(progress_state, session_id) {
         return Err(AppError::upload_cancelled("payload creation", session_id));
@@ -1325,16 +2364,14 @@ async fn try_upload_chunk_with_thread_id(
 
         payload.add_file(file_path, format!("files[{i}]")).await?;
     }
+    add_attachment_descriptions(&mut payload, descriptions);
 
     // Final cancellation check before HTTP request
     if is_session_cancelled(progress_state, session_id) {
         return Err(AppError::upload_cancelled("HTTP request", session_id));
     }
 
-    // Use the method that handles thread_id in URL
-    client
-        .send_webhook_with_thread_id(&webhook.url, &payload, thread_id)
-        .await
+    send_chunk(client, webhook, &payload, thread_id, session_id).await
 }
 
 /// Upload with compression
@@ -1344,30 +2381,40 @@ async fn upload_compressed_chunk_with_thread_id(
     webhook: &Webhook,
     file_paths: Vec<String>,
     text_fields: HashMap<String, String>,
+    descriptions: &[Option<String>],
     thread_id: Option<&str>,
     progress_state: &ProgressState,
     session_id: &str,
     app_handle: &tauri::AppHandle,
     quality: u8,
     format: String,
-) -> AppResult<String> {
+) -> AppResult<ChunkResponse> {
+    let cancel_token = session_cancel_token(progress_state, session_id);
     let mut current_format = format.clone();
     let mut current_quality = quality;
     let mut current_scale: Option<f32> = None;
-    // Define fallback tiers
-    // 0: Original attempt
-    // 1: Lossless WebP
-    // 2: Lossy WebP 90%
-    // 3: Lossy WebP 75%
-    // 4: Lossy WebP 85% + 50% Res
-    // 5: Lossy WebP 75% + 50% Res
-    // 6: Lossy WebP 70% + 25% Res
+    // Tier 0 is the original attempt; each size failure steps down one fallback tier
+    let config = crate::config::load_config().ok();
+    let fallback_tiers = compression_tiers::fallback_tiers(
+        quality,
+        config
+            .as_ref()
+            .map_or(compression_tiers::DEFAULT_MIN_QUALITY, |c| {
+                c.min_upload_quality
+            }),
+        config
+            .as_ref()
+            .map_or(compression_tiers::DEFAULT_MIN_SCALE_PERCENT, |c| {
+                c.min_upload_scale_percent
+            }),
+    );
     let mut tier = 0;
 
     loop {
         // --- 1. Compression Phase ---
         let mut compressed_paths = Vec::new();
         let mut cleanup_paths = Vec::new();
+        let compression_timer = metrics::start(session_id, metrics::PHASE_COMPRESSION);
 
         log::info!(
             "Attempting upload (Tier {tier}): Format={current_format}, Quality={current_quality}"
@@ -1382,6 +2429,12 @@ async fn upload_compressed_chunk_with_thread_id(
                 return Err(AppError::upload_cancelled("compression", session_id));
             }
 
+            // Clips go as they are; the other files make room around them
+            if security::is_video_file(file_path) {
+                compressed_paths.push(file_path.clone());
+                continue;
+            }
+
             // Update UI
             update_progress_current_with_phase(
                 progress_state,
@@ -1392,14 +2445,32 @@ async fn upload_compressed_chunk_with_thread_id(
             );
             emit_session_progress(app_handle, progress_state, session_id);
 
-            match image_processor::compress_image_with_format(
-                file_path,
-                current_quality,
-                &current_format,
-                current_scale,
-            )
-            .await
-            {
+            let prefetched = if current_scale.is_none() {
+                compression_pool::take(session_id, file_path, current_quality, &current_format)
+                    .await
+            } else {
+                None
+            };
+            let compressed = match prefetched {
+                Some(p) => Some(Ok(p)),
+                None => {
+                    compress_unless_cancelled(
+                        &cancel_token,
+                        file_path,
+                        current_quality,
+                        &current_format,
+                        current_scale,
+                    )
+                    .await
+                }
+            };
+            let Some(compressed) = compressed else {
+                for path in &cleanup_paths {
+                    tokio::fs::remove_file(path).await.ok();
+                }
+                return Err(AppError::upload_cancelled("compression", session_id));
+            };
+            match compressed {
                 Ok(p) => {
                     compressed_paths.push(p.clone());
                     cleanup_paths.push(p);
@@ -1420,6 +2491,7 @@ async fn upload_compressed_chunk_with_thread_id(
             .filter_map(|p| std::fs::metadata(p).ok())
             .map(|m| m.len())
             .sum();
+        compression_timer.finish(cleanup_paths.len(), total_size);
         log::info!(
             "Tier {} payload size: {:.2} MB",
             tier,
@@ -1434,9 +2506,23 @@ async fn upload_compressed_chunk_with_thread_id(
         }
 
         // --- 2. Upload Phase ---
-        // Helper to perform upload
-        let upload_result =
-            upload_chunk_files(client, webhook, &compressed_paths, &text_fields, thread_id).await;
+        // Tiers that are still too big go straight to the next one
+        let estimated_size = estimate_payload_size(&compressed_paths, &text_fields);
+        let upload_limit = webhook_upload_limit(webhook);
+        let upload_result = if estimated_size > upload_limit {
+            Err(payload_too_large(estimated_size, upload_limit))
+        } else {
+            upload_chunk_files(
+                client,
+                webhook,
+                &compressed_paths,
+                &text_fields,
+                descriptions,
+                thread_id,
+                session_id,
+            )
+            .await
+        };
 
         match upload_result {
             Ok(response) => {
@@ -1462,50 +2548,19 @@ async fn upload_compressed_chunk_with_thread_id(
 
                     // Move to next tier
                     tier += 1;
-                    match tier {
-                        1 => {
-                            log::info!("Fallback to Tier 1: Lossless WebP");
-                            current_format = "lossless_webp".to_string();
-                        }
-                        2 => {
-                            log::info!("Fallback to Tier 2: Lossy WebP (Quality 90)");
-                            current_format = "webp".to_string();
-                            current_quality = 90;
-                        }
-                        3 => {
-                            log::info!("Fallback to Tier 3: Lossy WebP (Quality 75) - Aggressive");
-                            current_format = "webp".to_string();
-                            current_quality = 75;
-                        }
-                        4 => {
-                            log::info!(
-                                "Fallback to Tier 4: Lossy WebP (Quality 85) + 50% Resolution"
-                            );
-                            current_format = "webp".to_string();
-                            current_quality = 85;
-                            current_scale = Some(0.5);
-                        }
-                        5 => {
-                            log::info!(
-                                "Fallback to Tier 5: Lossy WebP (Quality 75) + 50% Resolution"
-                            );
-                            current_format = "webp".to_string();
-                            current_quality = 75;
-                            current_scale = Some(0.5);
-                        }
-                        6 => {
-                            log::info!(
-                                "Fallback to Tier 6: Lossy WebP (Quality 70) + 25% Resolution"
-                            );
-                            current_format = "webp".to_string();
-                            current_quality = 70;
-                            current_scale = Some(0.25);
-                        }
-                        _ => {
-                            log::error!("All fallback tiers failed.");
-                            return Err(e); // Give up
-                        }
-                    }
+                    let Some(next) = fallback_tiers.get(tier - 1) else {
+                        log::error!("All fallback tiers failed.");
+                        return Err(e); // Give up
+                    };
+                    log::info!(
+                        "Fallback to Tier {tier}: {} (Quality {}) at {:.0}% resolution",
+                        next.format,
+                        next.quality,
+                        next.scale.unwrap_or(1.0) * 100.0
+                    );
+                    current_format = next.format.clone();
+                    current_quality = next.quality;
+                    current_scale = next.scale;
                     // Continue loop to retry with new settings
                     continue;
                 } else {
@@ -1517,13 +2572,45 @@ async fn upload_compressed_chunk_with_thread_id(
     }
 }
 
+/// Compress a file, giving up as soon as the session is cancelled (`None`). An
+/// encode that is already running finishes in the background and its output is removed.
+async fn compress_unless_cancelled(
+    cancel_token: &CancellationToken,
+    file_path: &str,
+    quality: u8,
+    format: &str,
+    scale: Option<f32>,
+) -> Option<AppResult<String>> {
+    let file_path = file_path.to_string();
+    let format = format.to_string();
+    let mut task = tokio::spawn(temp_manifest::inherit(async move {
+        image_processor::compress_image_with_format(&file_path, quality, &format, scale).await
+    }));
+
+    tokio::select! {
+        result = &mut task => Some(result.unwrap_or_else(|e| {
+            Err(AppError::ImageProcessing(format!("Task failed: {e}")))
+        })),
+        _ = cancel_token.cancelled() => {
+            tokio::spawn(async move {
+                if let Ok(Ok(output)) = task.await {
+                    tokio::fs::remove_file(output).await.ok();
+                }
+            });
+            None
+        }
+    }
+}
+
 async fn upload_chunk_files(
     client: &DiscordClient,
     webhook: &Webhook,
     file_paths: &[String],
     text_fields: &HashMap<String, String>,
+    descriptions: &[Option<String>],
     thread_id: Option<&str>,
-) -> AppResult<String> {
+    session_id: &str,
+) -> AppResult<ChunkResponse> {
     let mut payload = UploadPayload::new();
     for (k, v) in text_fields {
         payload.add_text_field(k.clone(), v.clone());
@@ -1531,7 +2618,34 @@ async fn upload_chunk_files(
     for (i, file_path) in file_paths.iter().enumerate() {
         payload.add_file(file_path, format!("files[{i}]")).await?;
     }
-    client
-        .send_webhook_with_thread_id(&webhook.url, &payload, thread_id)
-        .await
+    add_attachment_descriptions(&mut payload, descriptions);
+    send_chunk(client, webhook, &payload, thread_id, session_id).await
+}
+
+/// Discord's response to a posted chunk, with each attachment's verification status
+/// (see `verification`) in upload order
+pub struct ChunkResponse {
+    pub response_data: String,
+    pub verification: Vec<Option<String>>,
+}
+
+async fn send_chunk(
+    client: &DiscordClient,
+    webhook: &Webhook,
+    payload: &UploadPayload,
+    thread_id: Option<&str>,
+    session_id: &str,
+) -> AppResult<ChunkResponse> {
+    let upload_timer = metrics::start(session_id, metrics::PHASE_UPLOAD);
+    // Use the method that handles thread_id in URL
+    let response_data = client
+        .send_webhook_with_thread_id(&webhook.url, payload, thread_id)
+        .await?;
+    let bytes = payload.files().map(|(_, size)| size).sum();
+    upload_timer.finish(payload.file_count(), bytes);
+    let verification = verification::verify_upload(client, payload, &response_data).await;
+    Ok(ChunkResponse {
+        response_data,
+        verification,
+    })
 }
@@ -0,0 +1,202 @@
+// Upload verification
+//
+// After a chunk is posted, its attachments can be checked against what was sent:
+// either by the size Discord reports for each attachment, or by downloading it
+// from the CDN and comparing SHA-256 checksums. The outcome is stored per file in
+// upload_history so truncated or corrupted uploads can be found later.
+
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+use super::discord_client::{DiscordClient, UploadPayload};
+
+/// Verification modes, as stored in the config
+pub const UPLOAD_VERIFICATION_MODES: &[&str] = &["off", "size", "checksum"];
+pub const DEFAULT_UPLOAD_VERIFICATION: &str = "off";
+
+/// Attachment matches what was sent
+pub const STATUS_VERIFIED: &str = "verified";
+pub const STATUS_SIZE_MISMATCH: &str = "size_mismatch";
+pub const STATUS_CHECKSUM_MISMATCH: &str = "checksum_mismatch";
+/// Discord didn't report the attachment, or it couldn't be downloaded
+pub const STATUS_UNVERIFIED: &str = "unverified";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UploadVerification {
+    #[default]
+    Off,
+    /// Compare the size Discord reports for each attachment
+    Size,
+    /// Download each attachment and compare its SHA-256
+    Checksum,
+}
+
+impl UploadVerification {
+    /// Mode from the config value; unknown values turn verification off
+    pub fn from_config(mode: &str) -> Self {
+        match mode {
+            "size" => Self::Size,
+            "checksum" => Self::Checksum,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Attachment details from a webhook message response, in upload order
+#[derive(Debug, Clone, PartialEq)]
+struct PostedAttachment {
+    url: Option<String>,
+    size: Option<u64>,
+}
+
+fn posted_attachments(response_data: &str) -> Vec<PostedAttachment> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(response_data) else {
+        return Vec::new();
+    };
+    json.get("attachments")
+        .and_then(|a| a.as_array())
+        .map(|attachments| {
+            attachments
+                .iter()
+                .map(|a| PostedAttachment {
+                    url: a.get("url").and_then(|u| u.as_str()).map(str::to_string),
+                    size: a.get("size").and_then(|s| s.as_u64()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+/// Checksum of a sent file, read in pieces rather than all at once
+async fn file_sha256(path: &str) -> std::io::Result<Vec<u8>> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(hasher.finalize().to_vec());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Size check for one attachment against the number of bytes that were sent
+fn check_size(sent_size: u64, attachment: Option<&PostedAttachment>) -> &'static str {
+    match attachment.and_then(|a| a.size) {
+        Some(size) if size == sent_size => STATUS_VERIFIED,
+        Some(_) => STATUS_SIZE_MISMATCH,
+        None => STATUS_UNVERIFIED,
+    }
+}
+
+async fn check_checksum(
+    client: &DiscordClient,
+    sent_path: &str,
+    sent_size: u64,
+    attachment: Option<&PostedAttachment>,
+) -> &'static str {
+    // A size mismatch is already conclusive, no need to download
+    if check_size(sent_size, attachment) == STATUS_SIZE_MISMATCH {
+        return STATUS_SIZE_MISMATCH;
+    }
+    let Some(url) = attachment.and_then(|a| a.url.as_deref()) else {
+        return STATUS_UNVERIFIED;
+    };
+    let sent = match file_sha256(sent_path).await {
+        Ok(sent) => sent,
+        Err(e) => {
+            log::warn!("Could not read {sent_path} for verification: {e}");
+            return STATUS_UNVERIFIED;
+        }
+    };
+    match client.download_attachment(url).await {
+        Ok(downloaded) if sha256(&downloaded) == sent => STATUS_VERIFIED,
+        Ok(_) => STATUS_CHECKSUM_MISMATCH,
+        Err(e) => {
+            log::warn!("Could not download attachment for verification: {e}");
+            STATUS_UNVERIFIED
+        }
+    }
+}
+
+/// Verification status of each file in `payload`, in upload order; all None when
+/// verification is off
+pub async fn verify_upload(
+    client: &DiscordClient,
+    payload: &UploadPayload,
+    response_data: &str,
+) -> Vec<Option<String>> {
+    let mode = client.upload_verification();
+    if mode == UploadVerification::Off {
+        return vec![None; payload.file_count()];
+    }
+
+    let attachments = posted_attachments(response_data);
+    let mut statuses = Vec::with_capacity(payload.file_count());
+    for (index, (sent_path, sent_size)) in payload.files().enumerate() {
+        let attachment = attachments.get(index);
+        let status = match mode {
+            UploadVerification::Size => check_size(sent_size, attachment),
+            _ => check_checksum(client, sent_path, sent_size, attachment).await,
+        };
+        if status != STATUS_VERIFIED {
+            log::warn!("Attachment {index} failed upload verification: {status}");
+        }
+        statuses.push(Some(status.to_string()));
+    }
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_size_against_reported_attachments() {
+        let response = r#"{"id": "1", "channel_id": "2", "attachments": [
+            {"id": "1", "url": "https://cdn.discordapp.com/a.png", "size": 4},
+            {"id": "2", "url": "https://cdn.discordapp.com/b.png", "size": 3},
+            {"id": "3", "url": "https://cdn.discordapp.com/c.png"}
+        ]}"#;
+        let attachments = posted_attachments(response);
+
+        assert_eq!(check_size(4, attachments.first()), STATUS_VERIFIED);
+        assert_eq!(check_size(4, attachments.get(1)), STATUS_SIZE_MISMATCH);
+        assert_eq!(check_size(4, attachments.get(2)), STATUS_UNVERIFIED);
+        assert_eq!(check_size(4, attachments.get(3)), STATUS_UNVERIFIED);
+        assert!(posted_attachments("not json").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_checksum_matches_in_memory_checksum() {
+        // Larger than one read so the file is hashed in pieces
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let path = std::env::temp_dir().join(format!("verify_{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&path, &data).unwrap();
+
+        let checksum = file_sha256(path.to_str().unwrap()).await.unwrap();
+        assert_eq!(checksum, sha256(&data));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_upload_verification_from_config() {
+        assert_eq!(
+            UploadVerification::from_config("checksum"),
+            UploadVerification::Checksum
+        );
+        assert_eq!(
+            UploadVerification::from_config("size"),
+            UploadVerification::Size
+        );
+        assert_eq!(
+            UploadVerification::from_config(DEFAULT_UPLOAD_VERIFICATION),
+            UploadVerification::Off
+        );
+    }
+}
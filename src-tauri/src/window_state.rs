@@ -0,0 +1,190 @@
+// Window state persistence
+//
+// The main window's position, size and maximized state are saved to a small file
+// next to the config when it's hidden or the app exits, and restored at startup.
+// Positions are in physical pixels across the whole desktop, so a window that was
+// on a second monitor comes back there; if that monitor is gone the saved position
+// is dropped and the window opens centered as usual.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::config;
+use crate::errors::AppResult;
+
+const STATE_FILE_NAME: &str = "window-state.json";
+
+/// How much of the window's top edge must be on a monitor for the saved position to
+/// be used, so the title bar can still be grabbed
+const MIN_VISIBLE_WIDTH: i32 = 100;
+const MIN_VISIBLE_HEIGHT: i32 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// A monitor's area in physical pixels
+#[derive(Debug, Clone, Copy)]
+struct MonitorArea {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl MonitorArea {
+    /// Whether enough of the window's top edge falls inside this monitor
+    fn shows_title_bar(&self, state: &WindowState) -> bool {
+        let left = state.x.max(self.x);
+        let right = (state.x + state.width as i32).min(self.x + self.width as i32);
+        let top = state.y.max(self.y);
+        let bottom = (state.y + MIN_VISIBLE_HEIGHT).min(self.y + self.height as i32);
+        right - left >= MIN_VISIBLE_WIDTH.min(state.width as i32)
+            && bottom - top >= MIN_VISIBLE_HEIGHT
+    }
+}
+
+fn is_visible(state: &WindowState, monitors: &[MonitorArea]) -> bool {
+    state.width > 0
+        && state.height > 0
+        && monitors
+            .iter()
+            .any(|monitor| monitor.shows_title_bar(state))
+}
+
+fn state_path() -> AppResult<PathBuf> {
+    Ok(config::get_config_directory()?.join(STATE_FILE_NAME))
+}
+
+fn load_state() -> Option<WindowState> {
+    let contents = fs::read_to_string(state_path().ok()?).ok()?;
+    serde_json::from_str(&contents)
+        .map_err(|e| log::warn!("Ignoring unreadable window state: {e}"))
+        .ok()
+}
+
+/// Current state of `window`, or None while it's minimized. A maximized window keeps
+/// the last normal size, with its position moved onto the monitor it's maximized on.
+fn capture(window: &WebviewWindow) -> Option<WindowState> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    let maximized = window.is_maximized().unwrap_or(false);
+
+    let (width, height) = match load_state() {
+        Some(previous) if maximized => (previous.width, previous.height),
+        _ => (size.width, size.height),
+    };
+    Some(WindowState {
+        x: position.x,
+        y: position.y,
+        width,
+        height,
+        maximized,
+    })
+}
+
+/// Save the window's position and size for the next launch
+pub fn save(window: &WebviewWindow) {
+    let Some(state) = capture(window) else {
+        return;
+    };
+    let result = state_path().and_then(|path| {
+        fs::write(path, serde_json::to_string_pretty(&state)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to save window state: {e}");
+    }
+}
+
+/// Put the window back where it was last time, if that spot is still on a monitor
+pub fn restore(window: &WebviewWindow) {
+    let Some(state) = load_state() else {
+        return;
+    };
+
+    let monitors: Vec<MonitorArea> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| MonitorArea {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        })
+        .collect();
+    if !is_visible(&state, &monitors) {
+        log::info!("Saved window position is off-screen, opening centered");
+        return;
+    }
+
+    if let Err(e) = window.set_size(PhysicalSize::new(state.width, state.height)) {
+        log::warn!("Failed to restore window size: {e}");
+    }
+    if let Err(e) = window.set_position(PhysicalPosition::new(state.x, state.y)) {
+        log::warn!("Failed to restore window position: {e}");
+    }
+    if state.maximized {
+        if let Err(e) = window.maximize() {
+            log::warn!("Failed to restore maximized window: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: MonitorArea = MonitorArea {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    /// Second monitor to the left of the primary one
+    const LEFT: MonitorArea = MonitorArea {
+        x: -2560,
+        y: -360,
+        width: 2560,
+        height: 1440,
+    };
+
+    fn window_at(x: i32, y: i32) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width: 800,
+            height: 600,
+            maximized: false,
+        }
+    }
+
+    #[test]
+    fn test_saved_position_kept_on_any_connected_monitor() {
+        assert!(is_visible(&window_at(100, 100), &[PRIMARY]));
+        assert!(is_visible(&window_at(-2000, -200), &[PRIMARY, LEFT]));
+        // Mostly off the right edge, but the title bar can still be grabbed
+        assert!(is_visible(&window_at(1700, 500), &[PRIMARY]));
+    }
+
+    #[test]
+    fn test_saved_position_dropped_when_off_screen() {
+        // The left monitor was unplugged
+        assert!(!is_visible(&window_at(-2000, -200), &[PRIMARY]));
+        // Title bar above the top of the screen
+        assert!(!is_visible(&window_at(100, -500), &[PRIMARY]));
+        assert!(!is_visible(&window_at(100, 100), &[]));
+    }
+}
@@ -41,6 +41,10 @@ async fn setup_db() -> Pool<Sqlite> {
             error_message TEXT,
             uploaded_at DATETIME DEFAULT CURRENT_TIMESTAMP,
             retry_count INTEGER DEFAULT 0,
+            message_id TEXT,
+            channel_id TEXT,
+            message_url TEXT,
+            attachment_url TEXT,
             FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
         )
         "#,
@@ -87,6 +91,145 @@ async fn setup_db() -> Pool<Sqlite> {
     .await
     .unwrap();
 
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS webhook_forum_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL,
+            tag_id TEXT NOT NULL,
+            world_id TEXT NOT NULL DEFAULT '',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE,
+            UNIQUE(webhook_id, tag_id, world_id)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS forum_threads (
+            webhook_id INTEGER NOT NULL,
+            world_id TEXT NOT NULL,
+            thread_id TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            last_used_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (webhook_id, world_id),
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS failed_groups (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT NOT NULL,
+            webhook_id INTEGER NOT NULL,
+            group_id TEXT NOT NULL,
+            file_paths TEXT NOT NULL,
+            options TEXT NOT NULL,
+            error_message TEXT,
+            failed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS collections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS collection_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            collection_id INTEGER NOT NULL,
+            file_path TEXT NOT NULL,
+            added_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(collection_id, file_path),
+            FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS photo_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL,
+            tag TEXT NOT NULL COLLATE NOCASE,
+            source TEXT NOT NULL DEFAULT 'manual',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(file_path, tag)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS photo_metadata (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_path TEXT NOT NULL UNIQUE,
+            file_name TEXT NOT NULL,
+            world_name TEXT,
+            world_id TEXT,
+            author_name TEXT,
+            players TEXT NOT NULL DEFAULT '[]',
+            taken_at INTEGER,
+            indexed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS photo_descriptions (
+            file_path TEXT PRIMARY KEY,
+            description TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS photo_search USING fts5(file_name, world_name, player_names)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     pool
 }
 
@@ -340,6 +483,61 @@ async fn test_record_upload_success() {
     assert_eq!(row.get::<String, _>("upload_status"), "success");
 }
 
+#[tokio::test]
+async fn test_legacy_file_hash_migration_queries() {
+    let pool = setup_db().await;
+    let webhook_id = insert_webhook(
+        &pool,
+        "Hook",
+        "https://discord.com/api/webhooks/1/abc",
+        false,
+    )
+    .await;
+
+    for (path, hash) in [
+        ("/photos/old.png", Some("1f2e3d4c5b6a7988")),
+        ("/photos/old.png", Some("1f2e3d4c5b6a7988")),
+        ("/photos/new.png", Some("sha256:abcd")),
+        ("/photos/none.png", None),
+    ] {
+        sqlx::query(
+            "INSERT INTO upload_history (file_path, file_name, file_hash, webhook_id, upload_status)
+             VALUES (?, 'x.png', ?, ?, 'success')",
+        )
+        .bind(path)
+        .bind(hash)
+        .bind(webhook_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let legacy: Vec<String> = sqlx::query(
+        "SELECT DISTINCT file_path FROM upload_history
+         WHERE file_hash IS NOT NULL AND substr(file_hash, 1, length(?1)) != ?1",
+    )
+    .bind("sha256:")
+    .fetch_all(&pool)
+    .await
+    .unwrap()
+    .iter()
+    .map(|row| row.get("file_path"))
+    .collect();
+    assert_eq!(legacy, vec!["/photos/old.png".to_string()]);
+
+    let updated = sqlx::query(
+        "UPDATE upload_history SET file_hash = ?1
+         WHERE file_path = ?2 AND file_hash IS NOT NULL AND substr(file_hash, 1, length(?3)) != ?3",
+    )
+    .bind("sha256:ffff")
+    .bind("/photos/old.png")
+    .bind("sha256:")
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(updated.rows_affected(), 2);
+}
+
 #[tokio::test]
 async fn test_record_upload_failure() {
     let pool = setup_db().await;
@@ -376,6 +574,97 @@ async fn test_record_upload_failure() {
     assert_eq!(row.get::<String, _>("error_message"), "Network timeout");
 }
 
+#[tokio::test]
+async fn test_uploaded_message_info_lookup() {
+    let pool = setup_db().await;
+    let webhook_id = insert_webhook(
+        &pool,
+        "Hook",
+        "https://discord.com/api/webhooks/1/abc",
+        false,
+    )
+    .await;
+
+    sqlx::query(
+        r#"INSERT INTO upload_history
+        (file_path, file_name, webhook_id, upload_status, message_id, channel_id, message_url, attachment_url)
+        VALUES (?, ?, ?, 'success', ?, ?, ?, ?)"#,
+    )
+    .bind("/photos/posted.png")
+    .bind("posted.png")
+    .bind(webhook_id)
+    .bind("111")
+    .bind("222")
+    .bind("https://discord.com/channels/333/222/111")
+    .bind("https://cdn.discordapp.com/attachments/222/1/posted.png")
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let rows = sqlx::query(
+        r#"SELECT h.message_id, h.attachment_url, w.name AS webhook_name
+        FROM upload_history h
+        LEFT JOIN webhooks w ON w.id = h.webhook_id
+        WHERE h.file_path = ? AND h.upload_status = 'success'"#,
+    )
+    .bind("/photos/posted.png")
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<String, _>("message_id"), "111");
+    assert_eq!(rows[0].get::<String, _>("webhook_name"), "Hook");
+    assert_eq!(
+        rows[0].get::<String, _>("attachment_url"),
+        "https://cdn.discordapp.com/attachments/222/1/posted.png"
+    );
+}
+
+#[tokio::test]
+async fn test_mark_message_deleted() {
+    let pool = setup_db().await;
+    let webhook_id = insert_webhook(
+        &pool,
+        "Hook",
+        "https://discord.com/api/webhooks/1/abc",
+        false,
+    )
+    .await;
+
+    for (path, message_id) in [("/a.png", "111"), ("/b.png", "111"), ("/c.png", "222")] {
+        sqlx::query(
+            "INSERT INTO upload_history (file_path, file_name, webhook_id, upload_status, message_id) VALUES (?, ?, ?, 'success', ?)",
+        )
+        .bind(path)
+        .bind(path)
+        .bind(webhook_id)
+        .bind(message_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let rows = sqlx::query(
+        "UPDATE upload_history SET upload_status = 'deleted' WHERE message_id = ? AND upload_status = 'success' RETURNING file_path",
+    )
+    .bind("111")
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+
+    let mut deleted: Vec<String> = rows.iter().map(|r| r.get("file_path")).collect();
+    deleted.sort();
+    assert_eq!(deleted, vec!["/a.png", "/b.png"]);
+
+    let row = sqlx::query("SELECT upload_status FROM upload_history WHERE file_path = ?")
+        .bind("/c.png")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>("upload_status"), "success");
+}
+
 #[tokio::test]
 async fn test_create_upload_session() {
     let pool = setup_db().await;
@@ -640,3 +929,763 @@ async fn test_multiple_webhooks_ordering() {
     assert_eq!(rows[1].get::<String, _>("name"), "Middle");
     assert_eq!(rows[2].get::<String, _>("name"), "Zebra");
 }
+
+#[tokio::test]
+async fn test_failed_group_persistence() {
+    let pool = setup_db().await;
+    let webhook_id = insert_webhook(
+        &pool,
+        "Hook",
+        "https://discord.com/api/webhooks/1/abc",
+        false,
+    )
+    .await;
+
+    let file_paths = serde_json::to_string(&["a.png", "b.png"]).unwrap();
+    let id = sqlx::query(
+        "INSERT INTO failed_groups (session_id, webhook_id, group_id, file_paths, options, error_message) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind("session-1")
+    .bind(webhook_id)
+    .bind("wrld_1_t100")
+    .bind(&file_paths)
+    .bind(r#"{"max_images_per_message":10}"#)
+    .bind("HTTP 500")
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let row = sqlx::query(
+        "SELECT group_id, file_paths, error_message, failed_at FROM failed_groups WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(row.get::<String, _>("group_id"), "wrld_1_t100");
+    let stored: Vec<String> = serde_json::from_str(&row.get::<String, _>("file_paths")).unwrap();
+    assert_eq!(stored, vec!["a.png", "b.png"]);
+    assert_eq!(
+        row.get::<Option<String>, _>("error_message").as_deref(),
+        Some("HTTP 500")
+    );
+    assert!(!row.get::<String, _>("failed_at").is_empty());
+
+    sqlx::query("DELETE FROM failed_groups WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let count: i64 = sqlx::query("SELECT COUNT(*) as count FROM failed_groups")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_collection_items_are_unique_and_cascade() {
+    let pool = setup_db().await;
+
+    let collection_id = sqlx::query("INSERT INTO collections (name) VALUES (?)")
+        .bind("Best of 2024")
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+    let duplicate_name = sqlx::query("INSERT INTO collections (name) VALUES (?)")
+        .bind("Best of 2024")
+        .execute(&pool)
+        .await;
+    assert!(duplicate_name.is_err());
+
+    let mut added = 0;
+    for path in ["/a/one.png", "/b/two.png", "/a/one.png"] {
+        added += sqlx::query(
+            "INSERT OR IGNORE INTO collection_items (collection_id, file_path) VALUES (?, ?)",
+        )
+        .bind(collection_id)
+        .bind(path)
+        .execute(&pool)
+        .await
+        .unwrap()
+        .rows_affected();
+    }
+    assert_eq!(added, 2);
+
+    let row = sqlx::query(
+        "SELECT c.name, COUNT(i.id) AS item_count
+         FROM collections c
+         LEFT JOIN collection_items i ON i.collection_id = c.id
+         GROUP BY c.id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<i64, _>("item_count"), 2);
+
+    sqlx::query("DELETE FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let remaining: i64 = sqlx::query("SELECT COUNT(*) AS count FROM collection_items")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("count");
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test]
+async fn test_photo_tags_are_case_insensitive() {
+    let pool = setup_db().await;
+
+    let mut added = 0;
+    for (path, tag) in [
+        ("/a/one.png", "world:Void Club"),
+        ("/a/one.png", "WORLD:void club"),
+        ("/a/two.png", "world:void club"),
+    ] {
+        added += sqlx::query(
+            "INSERT OR IGNORE INTO photo_tags (file_path, tag, source) VALUES (?, ?, 'manual')",
+        )
+        .bind(path)
+        .bind(tag)
+        .execute(&pool)
+        .await
+        .unwrap()
+        .rows_affected();
+    }
+    assert_eq!(added, 2);
+
+    let files: Vec<String> =
+        sqlx::query("SELECT file_path FROM photo_tags WHERE tag = ? ORDER BY created_at, id")
+            .bind("World:VOID CLUB")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("file_path"))
+            .collect();
+    assert_eq!(files, vec!["/a/one.png", "/a/two.png"]);
+}
+
+#[tokio::test]
+async fn test_photo_search_matches_world_and_players() {
+    let pool = setup_db().await;
+
+    for (path, world, players) in [
+        ("/p/VRChat_1.png", "The Black Cat", "PlayerX Alice"),
+        ("/p/VRChat_2.png", "The Black Cat", "Bob"),
+        ("/p/VRChat_3.png", "Void Club", "PlayerX"),
+    ] {
+        let id = sqlx::query(
+            "INSERT INTO photo_metadata (file_path, file_name, world_name) VALUES (?, ?, ?)
+             RETURNING id",
+        )
+        .bind(path)
+        .bind(path.rsplit('/').next().unwrap())
+        .bind(world)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get::<i64, _>("id");
+
+        sqlx::query(
+            "INSERT INTO photo_search (rowid, file_name, world_name, player_names) VALUES (?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(path.rsplit('/').next().unwrap())
+        .bind(world)
+        .bind(players)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let search = |query: &'static str| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query(
+                "SELECT m.file_path FROM photo_search s
+                 JOIN photo_metadata m ON m.id = s.rowid
+                 WHERE photo_search MATCH ? ORDER BY m.file_path",
+            )
+            .bind(query)
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get::<String, _>("file_path"))
+            .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        search(r#""playerx"* "black"* "cat"*"#).await,
+        vec!["/p/VRChat_1.png"]
+    );
+    assert_eq!(search(r#""PlayerX"*"#).await.len(), 2);
+    assert_eq!(search(r#""VRChat_3"*"#).await, vec!["/p/VRChat_3.png"]);
+}
+
+#[tokio::test]
+async fn test_photo_description_upsert_replaces_previous() {
+    let pool = setup_db().await;
+
+    for description in ["First try", "Two friends on a bench"] {
+        sqlx::query(
+            "INSERT INTO photo_descriptions (file_path, description) VALUES (?, ?)
+             ON CONFLICT(file_path) DO UPDATE SET
+                 description = excluded.description,
+                 updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind("/a/one.png")
+        .bind(description)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let rows = sqlx::query("SELECT description FROM photo_descriptions")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(
+        rows[0].get::<String, _>("description"),
+        "Two friends on a bench"
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_forum_tag_unique_per_world() {
+    let pool = setup_db().await;
+    let webhook_id = insert_webhook(
+        &pool,
+        "Forum",
+        "https://discord.com/api/webhooks/1/abc",
+        true,
+    )
+    .await;
+
+    let insert = |tag_id: &'static str, world_id: &'static str| {
+        sqlx::query(
+            "INSERT INTO webhook_forum_tags (webhook_id, tag_id, world_id) VALUES (?, ?, ?)",
+        )
+        .bind(webhook_id)
+        .bind(tag_id)
+        .bind(world_id)
+        .execute(&pool)
+    };
+
+    insert("100", "").await.unwrap();
+    insert("100", "wrld_cat").await.unwrap();
+    // The same tag can't be mapped twice to the same posts
+    assert!(insert("100", "wrld_cat").await.is_err());
+
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM webhook_forum_tags WHERE webhook_id = ?")
+            .bind(webhook_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn test_forum_thread_staleness_and_replacement() {
+    let pool = setup_db().await;
+    let webhook_id = insert_webhook(
+        &pool,
+        "Forum",
+        "https://discord.com/api/webhooks/1/abc",
+        true,
+    )
+    .await;
+
+    let remember = |thread_id: &'static str| {
+        sqlx::query(
+            "INSERT INTO forum_threads (webhook_id, world_id, thread_id) VALUES (?, ?, ?)
+             ON CONFLICT(webhook_id, world_id) DO UPDATE SET
+                 created_at = CASE WHEN thread_id = excluded.thread_id
+                                   THEN created_at ELSE CURRENT_TIMESTAMP END,
+                 thread_id = excluded.thread_id,
+                 last_used_at = CURRENT_TIMESTAMP",
+        )
+        .bind(webhook_id)
+        .bind("wrld_cat")
+        .bind(thread_id)
+        .execute(&pool)
+    };
+    let lookup = |max_age_days: u32| {
+        sqlx::query_scalar::<_, String>(
+            "SELECT thread_id FROM forum_threads
+             WHERE webhook_id = ? AND world_id = ?
+               AND (? = 0 OR created_at >= datetime('now', '-' || ? || ' days'))",
+        )
+        .bind(webhook_id)
+        .bind("wrld_cat")
+        .bind(max_age_days)
+        .bind(max_age_days)
+        .fetch_optional(&pool)
+    };
+
+    remember("111").await.unwrap();
+    sqlx::query("UPDATE forum_threads SET created_at = datetime('now', '-10 days')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Posting into the same thread keeps its age
+    remember("111").await.unwrap();
+    assert_eq!(lookup(7).await.unwrap(), None);
+    assert_eq!(lookup(30).await.unwrap().as_deref(), Some("111"));
+    assert_eq!(lookup(0).await.unwrap().as_deref(), Some("111"));
+
+    // A new thread replaces the stale one
+    remember("222").await.unwrap();
+    assert_eq!(lookup(7).await.unwrap().as_deref(), Some("222"));
+}
+
+#[tokio::test]
+async fn test_upload_presets_unique_per_webhook_and_cascade() {
+    let pool = setup_db().await;
+    VRChat_Photo_Uploader::database::run_migrations(&pool)
+        .await
+        .unwrap();
+    let first = insert_webhook(&pool, "One", "https://discord.com/api/webhooks/1/a", false).await;
+    let second = insert_webhook(&pool, "Two", "https://discord.com/api/webhooks/2/b", false).await;
+
+    let insert = |webhook_id: i64, name: &'static str| {
+        sqlx::query(
+            "INSERT INTO upload_presets (webhook_id, name, max_images_per_message, compression_format)
+             VALUES (?, ?, 4, 'jpg')",
+        )
+        .bind(webhook_id)
+        .bind(name)
+        .execute(&pool)
+    };
+
+    insert(first, "Events").await.unwrap();
+    // Names only have to be unique per webhook
+    insert(second, "Events").await.unwrap();
+    assert!(insert(first, "Events").await.is_err());
+
+    sqlx::query("DELETE FROM webhooks WHERE id = ?")
+        .bind(first)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let remaining: Vec<i64> = sqlx::query_scalar("SELECT webhook_id FROM upload_presets")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec![second]);
+}
+
+#[tokio::test]
+async fn test_migrations_apply_once() {
+    let pool = setup_db().await;
+
+    VRChat_Photo_Uploader::database::run_migrations(&pool)
+        .await
+        .unwrap();
+    let version = VRChat_Photo_Uploader::database::schema_version(&pool)
+        .await
+        .unwrap();
+    assert!(version >= 2);
+
+    // A second run finds nothing newer to apply
+    VRChat_Photo_Uploader::database::run_migrations(&pool)
+        .await
+        .unwrap();
+    let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_version")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(applied, version);
+}
+
+#[tokio::test]
+async fn test_migrations_add_columns_missing_from_old_installs() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE webhooks (id INTEGER PRIMARY KEY, name TEXT, url TEXT)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "CREATE TABLE upload_history (id INTEGER PRIMARY KEY, file_path TEXT, webhook_id INTEGER)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    VRChat_Photo_Uploader::database::run_migrations(&pool)
+        .await
+        .unwrap();
+
+    for (table, column) in [
+        ("webhooks", "pinned"),
+        ("webhooks", "upload_limit_mb"),
+        ("upload_history", "upload_status"),
+        ("upload_history", "message_id"),
+        ("upload_history", "session_id"),
+        ("upload_history", "group_id"),
+    ] {
+        let found = sqlx::query("SELECT name FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+        assert!(found.is_some(), "{table}.{column} missing");
+    }
+}
+
+#[tokio::test]
+async fn test_upload_records_are_written_together() {
+    use VRChat_Photo_Uploader::database::{self, UploadRecord};
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+    sqlx::query("INSERT INTO webhooks (id, name, url) VALUES (1, 'Test', 'https://example.com')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let records: Vec<UploadRecord> = ["a.png", "b.png"]
+        .iter()
+        .map(|name| UploadRecord {
+            file_path: format!("/photos/{name}"),
+            file_name: name.to_string(),
+            file_hash: None,
+            file_size: Some(1024),
+            webhook_id: 1,
+            status: "success".to_string(),
+            error_message: None,
+            message: None,
+            attachment_url: None,
+            session_id: Some("session-1".to_string()),
+            group_id: Some("group-1".to_string()),
+            verification_status: Some("verified".to_string()),
+        })
+        .collect();
+    database::write_upload_records(&pool, &records)
+        .await
+        .unwrap();
+
+    let row = sqlx::query(
+        "SELECT COUNT(*) as count FROM upload_history WHERE session_id = ? AND group_id = ?",
+    )
+    .bind("session-1")
+    .bind("group-1")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<i64, _>("count"), 2);
+
+    let row = sqlx::query("SELECT verification_status FROM upload_history LIMIT 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<String, _>("verification_status"), "verified");
+
+    // An empty chunk writes nothing
+    database::write_upload_records(&pool, &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_upload_status_matches_path_or_hash() {
+    use VRChat_Photo_Uploader::database;
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+    sqlx::query("INSERT INTO webhooks (id, name, url) VALUES (1, 'Photos', 'https://example.com')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        sqlx::query(
+            "INSERT INTO upload_history (file_path, file_name, file_hash, webhook_id, upload_status)
+             VALUES ('/photos/a.png', 'a.png', 'sha256:aaa', 1, 'success')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query(
+        "INSERT INTO upload_history (file_path, file_name, file_hash, webhook_id, upload_status)
+         VALUES ('/photos/b.png', 'b.png', 'sha256:bbb', 1, 'failed')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let statuses = database::upload_status_for_files(
+        &pool,
+        &[
+            ("/photos/a.png".to_string(), None),
+            ("/copies/a.png".to_string(), Some("sha256:aaa".to_string())),
+            ("/photos/b.png".to_string(), Some("sha256:bbb".to_string())),
+        ],
+    )
+    .await
+    .unwrap();
+
+    assert!(statuses[0].uploaded);
+    assert_eq!(statuses[0].upload_count, 2);
+    assert_eq!(statuses[0].matched_by.as_deref(), Some("path"));
+    assert_eq!(statuses[0].webhook_name.as_deref(), Some("Photos"));
+    assert!(statuses[1].uploaded);
+    assert_eq!(statuses[1].matched_by.as_deref(), Some("hash"));
+    // Failed uploads don't count
+    assert!(!statuses[2].uploaded);
+}
+
+#[tokio::test]
+async fn test_legacy_history_import_matches_webhooks_and_skips_repeats() {
+    use VRChat_Photo_Uploader::database::{self, LegacyUpload, LegacyWebhook};
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+    sqlx::query(
+        "INSERT INTO webhooks (id, name, url) VALUES (1, 'Photos', 'https://example.com/a')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let webhooks = vec![
+        LegacyWebhook {
+            legacy_id: Some(10),
+            name: "Old Photos".to_string(),
+            url: "https://example.com/a".to_string(),
+        },
+        LegacyWebhook {
+            legacy_id: Some(11),
+            name: "Photos".to_string(),
+            url: "https://example.com/b".to_string(),
+        },
+    ];
+    let upload = |path: &str, legacy_webhook_id: Option<i64>| LegacyUpload {
+        file_path: path.to_string(),
+        file_hash: Some("d41d8cd9".to_string()),
+        file_size: Some(1024),
+        legacy_webhook_id,
+        webhook_url: None,
+        uploaded_at: Some("2023-11-14 22:13:20".to_string()),
+        status: "success".to_string(),
+    };
+    let uploads = vec![
+        upload("/photos/a.png", Some(10)),
+        upload("/photos/b.png", Some(11)),
+        // Two webhooks, so a row without one can't be placed
+        upload("/photos/c.png", None),
+    ];
+
+    let summary = database::write_legacy_history(&pool, &webhooks, &uploads)
+        .await
+        .unwrap();
+    assert_eq!(summary.webhooks_matched, 1);
+    assert_eq!(summary.webhooks_added, 1);
+    assert_eq!(summary.uploads_imported, 2);
+    assert_eq!(summary.uploads_skipped, 1);
+
+    // The clashing name got a suffix, and a's row went to the existing webhook
+    let row = sqlx::query(
+        "SELECT w.name FROM upload_history h JOIN webhooks w ON w.id = h.webhook_id
+         WHERE h.file_path = '/photos/b.png'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(row.get::<String, _>("name"), "Photos (imported)");
+    let row =
+        sqlx::query("SELECT webhook_id FROM upload_history WHERE file_path = '/photos/a.png'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(row.get::<i64, _>("webhook_id"), 1);
+
+    // Importing again adds nothing
+    let again = database::write_legacy_history(&pool, &webhooks, &uploads)
+        .await
+        .unwrap();
+    assert_eq!(again.webhooks_added, 0);
+    assert_eq!(again.uploads_imported, 0);
+}
+
+#[tokio::test]
+async fn test_read_indexed_photos_for_only_returns_requested_paths() {
+    use VRChat_Photo_Uploader::database;
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+
+    for (path, world) in [("/photos/a.png", "wrld_a"), ("/photos/b.png", "wrld_b")] {
+        sqlx::query(
+            "INSERT INTO photo_metadata (file_path, file_name, world_name, world_id, players)
+             VALUES (?, ?, 'World', ?, '[\"Alice\"]')",
+        )
+        .bind(path)
+        .bind(path.trim_start_matches("/photos/"))
+        .bind(world)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let indexed = database::read_indexed_photos_for(
+        &pool,
+        &[
+            "/photos/a.png".to_string(),
+            "/photos/unread.png".to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(indexed.len(), 1);
+    let photo = &indexed["/photos/a.png"];
+    assert_eq!(photo.world_id.as_deref(), Some("wrld_a"));
+    assert_eq!(photo.players, vec!["Alice".to_string()]);
+}
+
+#[tokio::test]
+async fn test_session_templates_round_trip_and_unique_names() {
+    use VRChat_Photo_Uploader::database::{self, SessionTemplate};
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+
+    let mut template = SessionTemplate {
+        id: None,
+        name: "Friday club night".to_string(),
+        webhook_ids: vec![3, 5],
+        group_by_metadata: true,
+        grouping_time_window: 30,
+        group_by_world: true,
+        group_by_instance: false,
+        merge_no_metadata: true,
+        adopt_orphans: false,
+        max_images_per_message: 8,
+        include_player_names: true,
+        single_thread_mode: true,
+        extra_message: Some("#clubnight".to_string()),
+        forum_tag_ids: vec!["1234567890".to_string()],
+        compression_format: Some("webp".to_string()),
+        upload_quality: Some(90),
+    };
+    let id = database::write_session_template(&pool, &template)
+        .await
+        .unwrap();
+    template.id = Some(id);
+    assert_eq!(
+        database::read_session_templates(&pool).await.unwrap(),
+        vec![template.clone()]
+    );
+
+    // Saving with an ID updates in place
+    template.webhook_ids = vec![5];
+    template.forum_tag_ids.clear();
+    assert_eq!(
+        database::write_session_template(&pool, &template)
+            .await
+            .unwrap(),
+        id
+    );
+    let saved = database::read_session_templates(&pool).await.unwrap();
+    assert_eq!(saved, vec![template.clone()]);
+
+    let duplicate = SessionTemplate {
+        id: None,
+        ..template
+    };
+    assert!(database::write_session_template(&pool, &duplicate)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_metrics_summed_per_phase() {
+    use VRChat_Photo_Uploader::database::{self, PhaseMetrics};
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+    assert!(database::read_phase_metrics(&pool)
+        .await
+        .unwrap()
+        .is_empty());
+
+    database::write_metric(&pool, "s1", "upload", 1_200, 10, 8_000_000)
+        .await
+        .unwrap();
+    database::write_metric(&pool, "s1", "upload", 800, 4, 2_000_000)
+        .await
+        .unwrap();
+    database::write_metric(&pool, "s1", "metadata", 300, 14, 0)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        database::read_phase_metrics(&pool).await.unwrap(),
+        vec![
+            PhaseMetrics {
+                phase: "metadata".to_string(),
+                samples: 1,
+                total_ms: 300,
+                max_ms: 300,
+                items: 14,
+                bytes: 0,
+            },
+            PhaseMetrics {
+                phase: "upload".to_string(),
+                samples: 2,
+                total_ms: 2_000,
+                max_ms: 1_200,
+                items: 14,
+                bytes: 10_000_000,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_indexed_photos_round_trip_with_world_id() {
+    use VRChat_Photo_Uploader::database::{self, PhotoMetadataRecord};
+
+    let pool = setup_db().await;
+    database::run_migrations(&pool).await.unwrap();
+
+    let mut record = PhotoMetadataRecord {
+        file_path: "/photos/a.png",
+        file_name: "a.png",
+        world_name: Some("The Great Pug"),
+        world_id: Some("wrld_pug"),
+        author_name: Some("Bob"),
+        players: vec!["Alice".to_string(), "Bob".to_string()],
+        taken_at: Some(1_700_000_000),
+    };
+    database::write_photo_metadata(&pool, &record)
+        .await
+        .unwrap();
+    // Indexing the same file again updates it in place
+    record.world_id = Some("wrld_pug_2");
+    database::write_photo_metadata(&pool, &record)
+        .await
+        .unwrap();
+
+    let indexed = database::read_indexed_photos(&pool).await.unwrap();
+    assert_eq!(indexed.len(), 1);
+    let photo = &indexed[0];
+    assert_eq!(photo.world_name.as_deref(), Some("The Great Pug"));
+    assert_eq!(photo.world_id.as_deref(), Some("wrld_pug_2"));
+    assert_eq!(photo.author_name.as_deref(), Some("Bob"));
+    assert_eq!(photo.players, vec!["Alice".to_string(), "Bob".to_string()]);
+    assert_eq!(photo.taken_at, Some(1_700_000_000));
+}
@@ -201,6 +201,7 @@ async fn test_send_image_with_metadata_message() {
         &worlds,
         &players,
         Some(1705312200),
+        "f",
         true,  // is_first_message
         0,     // chunk_index
         false, // is_forum_post
@@ -208,6 +209,7 @@ async fn test_send_image_with_metadata_message() {
         true,  // include_player_names
         1,     // image_count
         &no_mappings,
+        false,
     );
 
     let mut payload = UploadPayload::new();
@@ -258,6 +260,7 @@ async fn test_send_message_with_player_list() {
         &worlds,
         &players,
         Some(1705312200),
+        "f",
         true,  // is_first_message
         0,     // chunk_index
         false, // is_forum_post
@@ -265,6 +268,7 @@ async fn test_send_message_with_player_list() {
         true,  // include_player_names
         3,     // image_count
         &no_mappings,
+        false,
     );
 
     let client = DiscordClient::new();
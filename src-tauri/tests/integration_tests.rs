@@ -35,7 +35,7 @@ async fn test_full_image_processing_workflow() {
         let info_result = image_processor::get_image_info(&path_str);
 
         // 4. Try to extract metadata
-        let metadata_result = image_processor::extract_metadata(&path_str).await;
+        let metadata_result = image_processor::extract_metadata(&path_str, None).await;
 
         // Cleanup
         let _ = std::fs::remove_file(&test_file_path);
@@ -190,7 +190,7 @@ async fn test_async_operations_integration() {
 
         // Mix of async and sync operations
         let sync_validation = InputValidator::validate_file_path(&path_str);
-        let async_metadata = image_processor::extract_metadata(&path_str).await;
+        let async_metadata = image_processor::extract_metadata(&path_str, None).await;
         let sync_compression = image_processor::should_compress_image(&path_str);
 
         // Cleanup
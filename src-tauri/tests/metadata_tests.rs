@@ -27,7 +27,7 @@ async fn test_extract_vrcx_metadata_with_world_and_players() {
     let png_data = create_png_with_metadata(&metadata_json);
     let tmp = create_temp_png(&png_data, "meta_world_players.png");
 
-    let result = image_processor::extract_metadata(&tmp.path_str())
+    let result = image_processor::extract_metadata(&tmp.path_str(), None)
         .await
         .expect("extract_metadata should not return an error");
 
@@ -79,7 +79,7 @@ async fn test_extract_full_metadata_structure() {
     let png_data = create_png_with_metadata(&json.to_string());
     let tmp = create_temp_png(&png_data, "meta_full_structure.png");
 
-    let result = image_processor::extract_metadata(&tmp.path_str())
+    let result = image_processor::extract_metadata(&tmp.path_str(), None)
         .await
         .expect("extract_metadata should succeed");
 
@@ -110,7 +110,7 @@ async fn test_extract_metadata_no_metadata_returns_none() {
     let png_data = create_minimal_png();
     let tmp = create_temp_png(&png_data, "meta_no_metadata.png");
 
-    let result = image_processor::extract_metadata(&tmp.path_str())
+    let result = image_processor::extract_metadata(&tmp.path_str(), None)
         .await
         .expect("extract_metadata should succeed even without metadata");
 
@@ -133,7 +133,7 @@ async fn test_extract_metadata_non_png_file_returns_error() {
     std::fs::write(&path, b"hello world").expect("failed to write temp file");
 
     let path_str = path.to_string_lossy().to_string();
-    let result = image_processor::extract_metadata(&path_str).await;
+    let result = image_processor::extract_metadata(&path_str, None).await;
 
     // Cleanup
     let _ = std::fs::remove_file(&path);
@@ -152,6 +152,7 @@ async fn test_extract_metadata_non_png_file_returns_error() {
 async fn test_extract_metadata_nonexistent_file_returns_error() {
     let result = image_processor::extract_metadata(
         "/tmp/vrchat_photo_uploader_tests/this_file_does_not_exist_at_all.png",
+        None,
     )
     .await;
 
@@ -168,8 +169,9 @@ async fn test_extract_metadata_nonexistent_file_returns_error() {
 #[test]
 fn test_timestamp_from_standard_vrchat_filename() {
     // Standard VRChat screenshot pattern: VRChat_YYYY-MM-DD_HH-MM-SS.SSS_WIDTHxHEIGHT.png
-    let ts = image_processor::get_timestamp_from_filename(
+    let ts = image_processor::get_timestamp_from_filename_in_timezone(
         "/pictures/VRChat/2024-01-15_14-30-00.123_1920x1080.png",
+        None,
     );
     assert!(
         ts.is_some(),
@@ -187,8 +189,9 @@ fn test_timestamp_from_standard_vrchat_filename() {
 
 #[test]
 fn test_timestamp_from_filename_without_milliseconds() {
-    let ts = image_processor::get_timestamp_from_filename(
+    let ts = image_processor::get_timestamp_from_filename_in_timezone(
         "/screenshots/VRChat_2024-06-20_09-15-30_3840x2160.png",
+        None,
     );
     assert!(
         ts.is_some(),
@@ -196,11 +199,31 @@ fn test_timestamp_from_filename_without_milliseconds() {
     );
 }
 
+#[test]
+fn test_timestamp_from_filename_with_timezone_override() {
+    // 2024-01-15 14:30:00 in Tokyo (UTC+9) is 05:30:00 UTC
+    let tz = image_processor::parse_timezone("Asia/Tokyo").unwrap();
+    let ts = image_processor::get_timestamp_from_filename_in_timezone(
+        "/pictures/VRChat/2024-01-15_14-30-00.123_1920x1080.png",
+        Some(tz),
+    );
+    assert_eq!(ts, Some(1705296600));
+}
+
+#[test]
+fn test_parse_timezone_rejects_unknown_names() {
+    assert!(image_processor::parse_timezone("Europe/Berlin").is_ok());
+    assert!(image_processor::parse_timezone("Mars/Olympus_Mons").is_err());
+}
+
 #[test]
 fn test_timestamp_from_filename_no_pattern() {
     // A filename that does not match VRChat's naming convention and does not
     // exist on disk (so file creation time fallback also fails).
-    let ts = image_processor::get_timestamp_from_filename("/nonexistent/random_photo.png");
+    let ts = image_processor::get_timestamp_from_filename_in_timezone(
+        "/nonexistent/random_photo.png",
+        None,
+    );
     assert!(
         ts.is_none(),
         "Should return None when filename has no date pattern and file does not exist"
@@ -210,7 +233,8 @@ fn test_timestamp_from_filename_no_pattern() {
 #[test]
 fn test_timestamp_from_filename_only_date_no_time() {
     // Only a date component, no time component separated by underscore.
-    let ts = image_processor::get_timestamp_from_filename("/photos/2024-01-15.png");
+    let ts =
+        image_processor::get_timestamp_from_filename_in_timezone("/photos/2024-01-15.png", None);
     // The regex expects YYYY-MM-DD_HH-MM-SS, so a bare date should not match.
     assert!(
         ts.is_none(),
@@ -263,7 +287,7 @@ async fn test_extract_metadata_png_with_non_vrcx_text_chunks() {
 
     let tmp = create_temp_png(&buf, "meta_other_text_chunks.png");
 
-    let result = image_processor::extract_metadata(&tmp.path_str())
+    let result = image_processor::extract_metadata(&tmp.path_str(), None)
         .await
         .expect("extract_metadata should succeed");
 
@@ -342,6 +366,7 @@ fn make_test_metadata() -> ImageMetadata {
                 id: "usr_player_b".to_string(),
             },
         ],
+        avatar: None,
     }
 }
 
@@ -357,7 +382,8 @@ async fn test_embed_metadata_round_trip() {
     let metadata = make_test_metadata();
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata)
         .await
-        .expect("embed_metadata should succeed");
+        .expect("embed_metadata should succeed")
+        .output_path;
 
     // Verify output file exists
     assert!(
@@ -366,7 +392,7 @@ async fn test_embed_metadata_round_trip() {
     );
 
     // Extract metadata from the output file
-    let extracted = image_processor::extract_metadata(&output_path)
+    let extracted = image_processor::extract_metadata(&output_path, None)
         .await
         .expect("extract_metadata should succeed on embedded file");
 
@@ -404,11 +430,13 @@ async fn test_embed_metadata_creates_modified_file() {
         author: None,
         world: None,
         players: vec![],
+        avatar: None,
     };
 
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata)
         .await
-        .expect("embed_metadata should succeed");
+        .expect("embed_metadata should succeed")
+        .output_path;
 
     assert!(
         output_path.contains("embed_suffix_test_Modified.png"),
@@ -454,7 +482,8 @@ async fn test_embed_metadata_preserves_image() {
     let metadata = make_test_metadata();
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata)
         .await
-        .expect("embed_metadata should succeed");
+        .expect("embed_metadata should succeed")
+        .output_path;
 
     // Load and verify the output image
     let img = image::open(&output_path).expect("Output should be a valid image");
@@ -494,14 +523,16 @@ async fn test_embed_metadata_with_unicode() {
                 id: "usr_obrien".to_string(),
             },
         ],
+        avatar: None,
     };
 
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata)
         .await
-        .expect("embed_metadata should handle unicode");
+        .expect("embed_metadata should handle unicode")
+        .output_path;
 
     // Extract and verify unicode data survives the round-trip
-    let extracted = image_processor::extract_metadata(&output_path)
+    let extracted = image_processor::extract_metadata(&output_path, None)
         .await
         .expect("extract should succeed")
         .expect("should find metadata");
@@ -532,11 +563,13 @@ async fn test_embed_metadata_minimal() {
         author: None,
         world: None,
         players: vec![],
+        avatar: None,
     };
 
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata)
         .await
-        .expect("embed_metadata should succeed with minimal metadata");
+        .expect("embed_metadata should succeed with minimal metadata")
+        .output_path;
 
     // Should still produce a valid PNG
     let img = image::open(&output_path).expect("Output should be a valid image");
@@ -563,7 +596,7 @@ async fn test_embed_metadata_overwrites_existing() {
     let tmp = create_temp_png(&png_data, "embed_overwrite.png");
 
     // Verify original metadata is there
-    let original_extracted = image_processor::extract_metadata(&tmp.path_str())
+    let original_extracted = image_processor::extract_metadata(&tmp.path_str(), None)
         .await
         .expect("should succeed")
         .expect("should find original metadata");
@@ -584,14 +617,16 @@ async fn test_embed_metadata_overwrites_existing() {
             display_name: "NewPlayer".to_string(),
             id: "usr_newplayer".to_string(),
         }],
+        avatar: None,
     };
 
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), new_metadata)
         .await
-        .expect("embed_metadata should succeed");
+        .expect("embed_metadata should succeed")
+        .output_path;
 
     // Extract from the modified file and verify new metadata replaced old
-    let extracted = image_processor::extract_metadata(&output_path)
+    let extracted = image_processor::extract_metadata(&output_path, None)
         .await
         .expect("should succeed")
         .expect("should find new metadata");
@@ -637,14 +672,16 @@ async fn test_embed_metadata_many_players() {
             instance_id: "1~public".to_string(),
         }),
         players,
+        avatar: None,
     };
 
     let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata)
         .await
-        .expect("Should handle large player lists");
+        .expect("Should handle large player lists")
+        .output_path;
 
     // Verify all 50 players survived the round-trip
-    let extracted = image_processor::extract_metadata(&output_path)
+    let extracted = image_processor::extract_metadata(&output_path, None)
         .await
         .expect("should succeed")
         .expect("should find metadata");
@@ -660,3 +697,33 @@ async fn test_embed_metadata_many_players() {
     // Cleanup
     let _ = std::fs::remove_file(&output_path);
 }
+
+// ---------------------------------------------------------------------------
+// Verification: re-read an embedded file and diff it against the request
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_verify_embedded_metadata() {
+    let png_data = create_visible_test_png();
+    let tmp = create_temp_png(&png_data, "embed_verify.png");
+
+    let metadata = make_test_metadata();
+    let output_path = metadata_editor::embed_metadata(&tmp.path_str(), metadata.clone())
+        .await
+        .expect("embed_metadata should succeed")
+        .output_path;
+
+    let verification = metadata_editor::verify_embedded_metadata(&output_path, &metadata)
+        .await
+        .expect("verification should succeed");
+    assert!(verification.verified, "{:?}", verification.differences);
+
+    // The source file never had the metadata embedded
+    let verification = metadata_editor::verify_embedded_metadata(&tmp.path_str(), &metadata)
+        .await
+        .expect("verification should succeed");
+    assert!(!verification.verified);
+    assert!(!verification.differences.is_empty());
+
+    let _ = std::fs::remove_file(&output_path);
+}
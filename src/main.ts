@@ -16,12 +16,21 @@ function escapeHtml(str: string): string {
   return div.innerHTML;
 }
 
+/** Video clips are uploaded as recorded: no thumbnails, editing or dimensions. */
+function isVideoClip(path: string): boolean {
+  return ['mp4', 'webm'].includes(path.toLowerCase().split('.').pop() || '');
+}
+
 interface Webhook {
   id: number;
   name: string;
   url: string;
   is_forum: boolean;
   pinned: boolean;
+  suppress_embeds: boolean;
+  suppress_notifications: boolean;
+  upload_limit_mb: number | null;
+  timestamp_style: string | null;
 }
 
 interface QueueItem {
@@ -38,6 +47,123 @@ interface QueueItem {
   selected: boolean;
   thumbnailPath?: string;
   thumbnailLoaded?: boolean;
+  description?: string;
+  previousUpload?: FileUploadStatus;
+}
+
+interface HealthCheck {
+  name: string;
+  status: 'ok' | 'warning' | 'error';
+  message: string;
+  action: string | null;
+}
+
+interface HealthReport {
+  status: 'ok' | 'warning' | 'error';
+  webhook_count: number;
+  checks: HealthCheck[];
+}
+
+interface DiskSpaceCheck {
+  temp_directory: string;
+  required_bytes: number;
+  available_bytes: number | null;
+  sufficient: boolean;
+}
+
+interface GroupEstimate {
+  groups: number;
+  messages: number;
+  without_metadata: number;
+}
+
+interface PlanWarning {
+  kind: string;
+  message: string;
+  file_paths: string[];
+}
+
+interface UploadPlanValidation {
+  estimate: GroupEstimate;
+  warnings: PlanWarning[];
+}
+
+interface ProfileList {
+  profiles: string[];
+  active: string | null;
+}
+
+interface UploadPreset {
+  id: number | null;
+  webhook_id: number;
+  name: string;
+  group_by_metadata: boolean;
+  grouping_time_window: number;
+  max_images_per_message: number;
+  include_player_names: boolean;
+  compression_format: string | null;
+  upload_quality: number | null;
+  is_default: boolean;
+}
+
+interface SessionTemplate {
+  id: number | null;
+  name: string;
+  webhook_ids: number[];
+  group_by_metadata: boolean;
+  grouping_time_window: number;
+  group_by_world: boolean;
+  group_by_instance: boolean;
+  merge_no_metadata: boolean;
+  adopt_orphans: boolean;
+  max_images_per_message: number;
+  include_player_names: boolean;
+  single_thread_mode: boolean;
+  extra_message: string | null;
+  forum_tag_ids: string[];
+  compression_format: string | null;
+  upload_quality: number | null;
+}
+
+interface TempUsage {
+  temp_directory: string;
+  total_bytes: number;
+  file_count: number;
+  reclaimable_bytes: number;
+  reclaimable_files: number;
+}
+
+interface PhaseMetrics {
+  phase: 'metadata' | 'compression' | 'upload';
+  samples: number;
+  total_ms: number;
+  max_ms: number;
+  items: number;
+  bytes: number;
+}
+
+interface PerformanceMetrics {
+  enabled: boolean;
+  phases: PhaseMetrics[];
+  bottleneck: 'cpu' | 'network' | null;
+  upload_mb_per_sec: number | null;
+}
+
+interface LegacyImportSummary {
+  webhooks_added: number;
+  webhooks_matched: number;
+  uploads_imported: number;
+  uploads_skipped: number;
+}
+
+interface FileUploadStatus {
+  file_path: string;
+  uploaded: boolean;
+  upload_count: number;
+  last_uploaded_at?: string;
+  webhook_id?: number;
+  webhook_name?: string;
+  matched_by?: 'path' | 'hash';
 }
 
 interface UploadProgress {
@@ -52,6 +178,16 @@ interface UploadProgress {
   current_webhook_index: number;
   total_webhooks: number;
   current_webhook_name: string;
+  total_bytes?: number;
+  uploaded_bytes?: number;
+  upload_speed_mbps?: number;
+  files?: Record<string, FileProgress>;
+}
+
+interface FileProgress {
+  phase: 'pending' | 'loading_metadata' | 'preparing' | 'creating_thread' | 'compressing' | 'uploading' | 'completed' | 'failed';
+  percent: number;
+  error?: string;
 }
 
 interface FailedUpload {
@@ -69,12 +205,14 @@ interface AppConfig {
   auto_compress_threshold: number;
   upload_quality: number;
   compression_format: string; // "webp" or "jpg"
+  discord_timestamp_style?: string;
   enable_auto_upload?: boolean;
   auto_upload_webhook_id?: number;
   auto_upload_webhook_ids?: number[];
   vrchat_path?: string;
   single_thread_mode: boolean;
   merge_no_metadata: boolean;
+  adopt_orphans?: boolean;
   default_forum_mode: boolean;
   enable_multi_webhook: boolean;
   auto_upload_delay_seconds: number;
@@ -88,6 +226,32 @@ interface AppConfig {
   auto_upload_include_players: boolean;
   auto_upload_merge_no_metadata: boolean;
   auto_upload_ignored_folders: string[];
+  show_upload_notifications?: boolean;
+  post_upload_summary?: boolean;
+  log_level?: string;
+  max_upload_dimension?: number | null;
+  min_upload_quality?: number;
+  min_upload_scale_percent?: number;
+  compress_clips?: boolean;
+  ffmpeg_path?: string | null;
+  collect_metrics?: boolean;
+  temp_directory?: string | null;
+  keep_original_filenames?: boolean;
+  upload_verification?: string;
+  auto_launch?: boolean;
+  include_avatar_in_caption?: boolean;
+  mention_mapped_players?: boolean;
+  restrict_caption_mentions?: boolean;
+  link_player_profiles?: boolean;
+  use_link_buttons?: boolean;
+  reuse_forum_threads?: boolean;
+  forum_thread_max_age_days?: number;
+  post_thread_closing_message?: boolean;
+  roundup?: {
+    enabled: boolean;
+    cadence: 'weekly' | 'monthly';
+    webhook_id: number | null;
+  };
 }
 
 interface UserWebhookOverride {
@@ -97,6 +261,20 @@ interface UserWebhookOverride {
   webhook_id: number;
 }
 
+interface ForumTag {
+  id: number;
+  webhook_id: number;
+  tag_id: string;
+  world_id?: string;
+}
+
+interface ForumDiagnosticReport {
+  webhook_id: number;
+  steps: { name: string; ok: boolean; detail: string }[];
+  failed_step: string | null;
+  thread_id: string | null;
+}
+
 interface DiscordUserMapping {
   id: number;
   vrchat_display_name?: string;
@@ -126,11 +304,17 @@ class AppState {
   public selectedWebhookId: number | null = null;
   public selectedWebhookIds: number[] = [];
   public multiWebhookEnabled: boolean = false;
+  public presets: UploadPreset[] = [];
+  private presetWebhookId: number | null = null;
+  private applyingPreset: boolean = false;
+  public sessionTemplates: SessionTemplate[] = [];
   private lastSeenWebhookIndex: number = 0;
-  private progressPollingInterval: number | null = null;
+  private watchingSessionCompletion: boolean = false;
   private isUploading: boolean = false;
   private notificationsEnabled: boolean = true;
   private thumbnailObserver: IntersectionObserver | null = null;
+  private editingItemId: string | null = null;
+  private groupEstimateTimer: number | null = null;
 
   // Notification methods
   private async requestNotificationPermission(): Promise<boolean> {
@@ -281,7 +465,9 @@ class AppState {
     if (panel) panel.classList.remove('hidden');
 
     if (text) {
-      if (progress.session_status.includes('Preparing images...')) {
+      if (progress.session_status === 'waiting_for_network') {
+        text.textContent = 'Waiting for network...';
+      } else if (progress.session_status.includes('Preparing images...')) {
         text.textContent = progress.session_status;
       } else if (progress.session_status.includes('Preparing')) {
         text.textContent = 'Preparing batch...';
@@ -381,7 +567,7 @@ class AppState {
     if (!itemId) return;
 
     const item = this.uploadQueue.find(q => q.id === itemId);
-    if (!item) return;
+    if (!item || isVideoClip(item.filePath)) return;
 
     // Check if we need to generate it
     if (!item.thumbnailPath) {
@@ -457,6 +643,23 @@ class AppState {
     }
   }
 
+  // Select only this webhook, as picking it in the dropdown would; a selected template
+  // would otherwise send the upload to its own webhooks
+  selectWebhook(webhookId: number) {
+    const templateSelect = document.getElementById('sessionTemplate') as HTMLSelectElement;
+    if (templateSelect?.value) {
+      templateSelect.value = '';
+      this.updateTemplateControls();
+    }
+    this.selectedWebhookIds = [webhookId];
+    this.selectedWebhookId = webhookId;
+    this.updateWebhookSelector();
+    this.updateDropdownSummary();
+    this.updateSingleThreadVisibility();
+    this.updateControlButtons();
+    this.loadPresets();
+  }
+
   updateWebhookSelector() {
     const panel = document.getElementById('webhookDropdownPanel');
     if (!panel) return;
@@ -511,6 +714,7 @@ class AppState {
         this.updateSingleThreadVisibility();
         this.updateControlButtons();
         this.resetUploadStatuses();
+        this.loadPresets();
       });
 
       const nameSpan = document.createElement('span');
@@ -550,6 +754,254 @@ class AppState {
     this.updateControlButtons();
   }
 
+  // Presets only apply when exactly one webhook is selected; picking a webhook applies its default
+  async loadPresets(force = false) {
+    const container = document.getElementById('uploadPresetContainer');
+    const webhookId = this.selectedWebhookIds.length === 1 ? this.selectedWebhookIds[0] : null;
+    if (webhookId === this.presetWebhookId && !force) return;
+    const webhookChanged = webhookId !== this.presetWebhookId;
+    this.presetWebhookId = webhookId;
+
+    if (webhookId === null) {
+      this.presets = [];
+      container?.classList.add('hidden');
+      this.renderPresets(null);
+      return;
+    }
+
+    try {
+      this.presets = await invoke<UploadPreset[]>('get_presets', { webhookId });
+    } catch (error) {
+      this.presets = [];
+      console.warn('Failed to load upload presets:', error);
+    }
+    container?.classList.remove('hidden');
+
+    const defaultPreset = this.presets.find(p => p.is_default);
+    if (webhookChanged && defaultPreset) {
+      this.applyPreset(defaultPreset);
+    }
+    this.renderPresets(webhookChanged ? (defaultPreset?.id ?? null) : this.selectedPresetId());
+  }
+
+  renderPresets(selectedId: number | null) {
+    const select = document.getElementById('uploadPreset') as HTMLSelectElement;
+    if (!select) return;
+    select.innerHTML = '<option value="">Custom</option>';
+    this.presets.forEach(preset => {
+      const option = document.createElement('option');
+      option.value = String(preset.id);
+      option.textContent = preset.is_default ? `${preset.name} (default)` : preset.name;
+      select.appendChild(option);
+    });
+    select.value = selectedId !== null && this.presets.some(p => p.id === selectedId) ? String(selectedId) : '';
+    this.updatePresetControls();
+  }
+
+  selectedPresetId(): number | null {
+    const value = (document.getElementById('uploadPreset') as HTMLSelectElement)?.value;
+    return value ? parseInt(value) : null;
+  }
+
+  updatePresetControls() {
+    const preset = this.presets.find(p => p.id === this.selectedPresetId());
+    const deleteBtn = document.getElementById('deletePresetBtn') as HTMLButtonElement;
+    if (deleteBtn) deleteBtn.disabled = !preset;
+    const nameInput = document.getElementById('presetName') as HTMLInputElement;
+    if (nameInput) nameInput.value = preset?.name ?? '';
+    const defaultCheck = document.getElementById('presetIsDefault') as HTMLInputElement;
+    if (defaultCheck) defaultCheck.checked = preset?.is_default ?? false;
+  }
+
+  // Show a preset's settings in the upload controls
+  applyPreset(preset: UploadPreset) {
+    this.applyingPreset = true;
+    try {
+      const groupByMetadata = document.getElementById('groupByMetadata') as HTMLInputElement;
+      groupByMetadata.checked = preset.group_by_metadata;
+      groupByMetadata.dispatchEvent(new Event('change'));
+
+      const maxImages = document.getElementById('maxImages') as HTMLSelectElement;
+      const maxValue = String(preset.max_images_per_message);
+      if (!Array.from(maxImages.options).some(o => o.value === maxValue)) {
+        const option = document.createElement('option');
+        option.value = maxValue;
+        option.textContent = maxValue;
+        maxImages.appendChild(option);
+      }
+      maxImages.value = maxValue;
+
+      (document.getElementById('includePlayerNames') as HTMLInputElement).checked = preset.include_player_names;
+
+      const groupByTime = document.getElementById('groupByTime') as HTMLInputElement;
+      groupByTime.checked = preset.grouping_time_window > 0;
+      groupByTime.dispatchEvent(new Event('change'));
+      if (preset.grouping_time_window > 0) {
+        const timePreset = document.getElementById('groupingTimeWindowPreset') as HTMLSelectElement;
+        const windowValue = String(preset.grouping_time_window);
+        timePreset.value = Array.from(timePreset.options).some(o => o.value === windowValue) ? windowValue : 'custom';
+        timePreset.dispatchEvent(new Event('change'));
+        (document.getElementById('groupingTimeWindow') as HTMLInputElement).value = windowValue;
+      }
+    } finally {
+      this.applyingPreset = false;
+    }
+  }
+
+  // Editing a setting by hand leaves the preset, so its compression settings stop applying
+  presetSettingChanged() {
+    if (this.applyingPreset) return;
+    const select = document.getElementById('uploadPreset') as HTMLSelectElement;
+    if (select && select.value) {
+      select.value = '';
+      this.updatePresetControls();
+    }
+  }
+
+  async savePreset() {
+    const webhookId = this.presetWebhookId;
+    const name = (document.getElementById('presetName') as HTMLInputElement)?.value.trim() ?? '';
+    if (webhookId === null) return;
+    if (!name) {
+      this.showError('Enter a name for the preset');
+      return;
+    }
+
+    const existing = this.presets.find(p => p.name === name);
+    const groupByTime = (document.getElementById('groupByTime') as HTMLInputElement).checked;
+    const timeWindowValue = parseInt((document.getElementById('groupingTimeWindow') as HTMLInputElement).value);
+    const preset: UploadPreset = {
+      id: existing?.id ?? null,
+      webhook_id: webhookId,
+      name,
+      group_by_metadata: (document.getElementById('groupByMetadata') as HTMLInputElement).checked,
+      grouping_time_window: groupByTime ? (isNaN(timeWindowValue) ? 10 : timeWindowValue) : 0,
+      max_images_per_message: parseInt((document.getElementById('maxImages') as HTMLSelectElement).value),
+      include_player_names: (document.getElementById('includePlayerNames') as HTMLInputElement).checked,
+      compression_format: existing?.compression_format ?? null,
+      upload_quality: existing?.upload_quality ?? null,
+      is_default: (document.getElementById('presetIsDefault') as HTMLInputElement)?.checked ?? false,
+    };
+
+    try {
+      const id = await invoke<number>('save_preset', { preset });
+      await this.loadPresets(true);
+      this.renderPresets(id);
+      this.showSuccess(`Preset "${name}" saved`);
+    } catch (error) {
+      this.showError(`Failed to save preset: ${error}`);
+    }
+  }
+
+  async deletePreset() {
+    const preset = this.presets.find(p => p.id === this.selectedPresetId());
+    if (!preset || preset.id === null) return;
+    try {
+      await invoke('delete_preset', { id: preset.id });
+      await this.loadPresets(true);
+      this.renderPresets(null);
+      this.showSuccess(`Preset "${preset.name}" deleted`);
+    } catch (error) {
+      this.showError(`Failed to delete preset: ${error}`);
+    }
+  }
+
+  async loadSessionTemplates(selectedId: number | null = this.selectedTemplateId()) {
+    try {
+      this.sessionTemplates = await invoke<SessionTemplate[]>('get_session_templates');
+    } catch (error) {
+      this.sessionTemplates = [];
+      console.warn('Failed to load session templates:', error);
+    }
+
+    const select = document.getElementById('sessionTemplate') as HTMLSelectElement;
+    if (!select) return;
+    select.innerHTML = '<option value="">None</option>';
+    this.sessionTemplates.forEach(template => {
+      const option = document.createElement('option');
+      option.value = String(template.id);
+      option.textContent = `${template.name} (${template.webhook_ids.length} webhook${template.webhook_ids.length === 1 ? '' : 's'})`;
+      select.appendChild(option);
+    });
+    select.value = selectedId !== null && this.sessionTemplates.some(t => t.id === selectedId) ? String(selectedId) : '';
+    this.updateTemplateControls();
+  }
+
+  selectedTemplateId(): number | null {
+    const value = (document.getElementById('sessionTemplate') as HTMLSelectElement)?.value;
+    return value ? parseInt(value) : null;
+  }
+
+  updateTemplateControls() {
+    const template = this.sessionTemplates.find(t => t.id === this.selectedTemplateId());
+    const deleteBtn = document.getElementById('deleteTemplateBtn') as HTMLButtonElement;
+    if (deleteBtn) deleteBtn.disabled = !template;
+    const nameInput = document.getElementById('templateName') as HTMLInputElement;
+    if (nameInput) nameInput.value = template?.name ?? '';
+    const extraInput = document.getElementById('templateExtraMessage') as HTMLInputElement;
+    if (extraInput) extraInput.value = template?.extra_message ?? '';
+    const tagsInput = document.getElementById('templateForumTags') as HTMLInputElement;
+    if (tagsInput) tagsInput.value = template?.forum_tag_ids.join(', ') ?? '';
+    this.updateControlButtons();
+  }
+
+  // Save the selected webhooks and upload settings as an event template
+  async saveSessionTemplate() {
+    const name = (document.getElementById('templateName') as HTMLInputElement)?.value.trim() ?? '';
+    if (!name) {
+      this.showError('Enter a name for the template');
+      return;
+    }
+    if (this.selectedWebhookIds.length === 0) {
+      this.showError('Select the webhooks the template should post to');
+      return;
+    }
+
+    const existing = this.sessionTemplates.find(t => t.name === name);
+    const options = this.groupingOptions();
+    const extraMessage = (document.getElementById('templateExtraMessage') as HTMLInputElement)?.value.trim() ?? '';
+    const forumTags = (document.getElementById('templateForumTags') as HTMLInputElement)?.value ?? '';
+    const template: SessionTemplate = {
+      id: existing?.id ?? null,
+      name,
+      webhook_ids: [...this.selectedWebhookIds],
+      group_by_metadata: options.group_by_metadata,
+      grouping_time_window: options.grouping_time_window,
+      group_by_world: options.group_by_world,
+      group_by_instance: existing?.group_by_instance ?? false,
+      merge_no_metadata: options.merge_no_metadata,
+      adopt_orphans: options.adopt_orphans,
+      max_images_per_message: options.max_images_per_message,
+      include_player_names: (document.getElementById('includePlayerNames') as HTMLInputElement)?.checked ?? true,
+      single_thread_mode: (document.getElementById('singleThreadMode') as HTMLInputElement)?.checked ?? false,
+      extra_message: extraMessage || null,
+      forum_tag_ids: forumTags.split(',').map(tag => tag.trim()).filter(tag => tag),
+      compression_format: existing?.compression_format ?? null,
+      upload_quality: existing?.upload_quality ?? null,
+    };
+
+    try {
+      const id = await invoke<number>('save_session_template', { template });
+      await this.loadSessionTemplates(id);
+      this.showSuccess(`Template "${name}" saved`);
+    } catch (error) {
+      this.showError(`Failed to save template: ${error}`);
+    }
+  }
+
+  async deleteSessionTemplate() {
+    const template = this.sessionTemplates.find(t => t.id === this.selectedTemplateId());
+    if (!template || template.id === null) return;
+    if (!confirm(`Delete the template "${template.name}"?`)) return;
+    try {
+      await invoke('delete_session_template', { id: template.id });
+      await this.loadSessionTemplates(null);
+      this.showSuccess(`Template "${template.name}" deleted`);
+    } catch (error) {
+      this.showError(`Failed to delete template: ${error}`);
+    }
+  }
+
   updateDropdownSummary() {
     const summary = document.getElementById('webhookSelectionSummary');
     if (!summary) return;
@@ -597,11 +1049,25 @@ class AppState {
     if (editBtn) editBtn.disabled = true;
   }
 
-  async addWebhook(name: string, url: string, isForum: boolean) {
+  async addWebhook(name: string, url: string, isForum: boolean, suppressEmbeds = false, suppressNotifications = false, uploadLimitMb: number | null = null, timestampStyle: string | null = null) {
     try {
       await invoke('add_webhook', { name, url, isForum });
       await this.loadWebhooks();
 
+      const added = this.webhooks.find(w => w.url === url);
+      if (added && (suppressEmbeds || suppressNotifications)) {
+        await invoke('set_webhook_message_flags', { id: added.id, suppressEmbeds, suppressNotifications });
+      }
+      if (added && uploadLimitMb) {
+        await invoke('set_webhook_upload_limit', { id: added.id, uploadLimitMb });
+      }
+      if (added && timestampStyle) {
+        await invoke('set_webhook_timestamp_style', { id: added.id, timestampStyle });
+      }
+      if (added && (suppressEmbeds || suppressNotifications || uploadLimitMb || timestampStyle)) {
+        await this.loadWebhooks();
+      }
+
       this.updateWebhookSelector(); // This will now show the new webhook as selected
 
       this.showSuccess('Webhook added and selected!');
@@ -610,9 +1076,12 @@ class AppState {
     }
   }
 
-  async updateWebhook(id: number, name: string, url: string, isForum: boolean) {
+  async updateWebhook(id: number, name: string, url: string, isForum: boolean, suppressEmbeds = false, suppressNotifications = false, uploadLimitMb: number | null = null, timestampStyle: string | null = null) {
     try {
       await invoke('update_webhook', { id, name, url, isForum });
+      await invoke('set_webhook_message_flags', { id, suppressEmbeds, suppressNotifications });
+      await invoke('set_webhook_upload_limit', { id, uploadLimitMb });
+      await invoke('set_webhook_timestamp_style', { id, timestampStyle });
       await this.loadWebhooks();
       this.showSuccess('Webhook updated successfully!');
     } catch (error) {
@@ -633,7 +1102,7 @@ class AppState {
   resetUploadState() {
     this.isUploading = false;
     this.currentUploadSession = null;
-    this.stopProgressPolling();
+    this.watchingSessionCompletion = false;
 
     const startBtn = document.getElementById('startUpload') as HTMLButtonElement;
     const pauseBtn = document.getElementById('pauseUpload') as HTMLButtonElement;
@@ -660,6 +1129,31 @@ class AppState {
     console.log('Upload state reset completely');
   }
 
+  // Add every image in a folder (and its subfolders), queueing each batch as the scan finds it
+  async addFolderToQueue(folderPath: string) {
+    const scanId = crypto.randomUUID();
+    let queued = Promise.resolve();
+
+    const unlisten = await listen<{ scan_id: string; files: { path: string }[] }>('folder-scan-batch', (event) => {
+      if (event.payload.scan_id !== scanId) return;
+      const paths = event.payload.files.map(file => file.path);
+      queued = queued.then(() => this.addFilesToQueue(paths));
+    });
+
+    try {
+      const summary = await invoke<{ files_found: number }>('scan_folder', {
+        path: folderPath,
+        recursive: true,
+        since: null,
+        scanId,
+      });
+      await queued;
+      return summary.files_found;
+    } finally {
+      unlisten();
+    }
+  }
+
   // File handling
   async addFilesToQueue(filePaths: string[]) {
     if (this.isUploading) {
@@ -706,6 +1200,10 @@ class AppState {
       }
 
       const validPaths = Array.from(fileInfoMap.keys());
+      // Alt text saved for these files earlier
+      const descriptions = await invoke<Record<string, string>>('get_photo_descriptions', {
+        filePaths: validPaths
+      }).catch(() => ({} as Record<string, string>));
       if (validPaths.length > 0) {
         this.updateLoadingProgress('Generating thumbnails...', '');
         // OPTIMIZATION: Skip eager thumbnail generation for large batches
@@ -733,6 +1231,7 @@ class AppState {
             selected: true,
             thumbnailPath: undefined, // Will be generated lazily
             thumbnailLoaded: false,
+            description: descriptions[filePath],
           };
 
           validFiles.push(queueItem);
@@ -750,9 +1249,46 @@ class AppState {
 
     if (validFiles.length > 0) {
       this.showSuccess(`Added ${validFiles.length} files to upload queue`);
+      // Hashing can take a moment for large batches, so badges are filled in afterwards
+      this.loadUploadBadges(validFiles);
+    }
+  }
+
+  // Badge queued photos that were already shared, so they aren't posted twice by accident
+  async loadUploadBadges(items: QueueItem[]) {
+    try {
+      const statuses = await invoke<FileUploadStatus[]>('get_upload_status_for_files', {
+        filePaths: items.map(item => item.filePath)
+      });
+      let uploadedCount = 0;
+      for (const status of statuses) {
+        if (!status.uploaded) continue;
+        const item = items.find(i => i.filePath === status.file_path);
+        if (!item) continue;
+        item.previousUpload = status;
+        uploadedCount++;
+      }
+      if (uploadedCount > 0) {
+        this.updateQueueDisplay();
+      }
+    } catch (error) {
+      console.warn('Failed to load upload history for queued files:', error);
     }
   }
 
+  uploadBadgeHtml(item: QueueItem): string {
+    const previous = item.previousUpload;
+    if (!previous?.uploaded) return '';
+    const when = previous.last_uploaded_at
+      ? new Date(previous.last_uploaded_at.replace(' ', 'T') + 'Z').toLocaleString()
+      : 'earlier';
+    const where = previous.webhook_name ? ` to ${previous.webhook_name}` : '';
+    const copy = previous.matched_by === 'hash' ? ' (same photo, different file)' : '';
+    const times = previous.upload_count > 1 ? ` · ${previous.upload_count} times` : '';
+    const title = `Uploaded ${when}${where}${copy}${times}`;
+    return `<span class="uploaded-badge" title="${escapeHtml(title)}">Uploaded</span>`;
+  }
+
   updateQueueDisplay() {
     const queueContainer = document.getElementById('uploadQueue');
     const queueItems = document.getElementById('queueItems');
@@ -829,7 +1365,7 @@ class AppState {
     element.dataset.id = item.id;
 
     const sizeText = item.fileSize ? this.formatFileSize(item.fileSize) : '';
-    const dimensionsText = item.dimensions ? `${item.dimensions.width}×${item.dimensions.height}` : '';
+    const dimensionsText = item.dimensions?.width ? `${item.dimensions.width}×${item.dimensions.height}` : '';
 
     let statusIcon = '';
     if (item.status === 'success') {
@@ -846,19 +1382,26 @@ class AppState {
       statusIcon = '<span class="status-icon">📄</span>';
     }
 
+    const isClip = isVideoClip(item.filePath);
     const thumbSrc = item.thumbnailPath && item.thumbnailLoaded ? convertFileSrc(item.thumbnailPath) : '';
     element.innerHTML = `
       <input type="checkbox" class="queue-checkbox" ${item.selected ? 'checked' : ''}>
       <div class="queue-thumbnail" data-item-id="${item.id}">
-        ${item.thumbnailPath ?
+        ${isClip ?
+        `<video src="${convertFileSrc(item.filePath)}" class="queue-thumbnail-img" muted preload="metadata"></video>` :
+        item.thumbnailPath ?
         `<img src="${thumbSrc}" alt="${escapeHtml(item.filename)}" class="queue-thumbnail-img" loading="lazy" />` :
         escapeHtml(item.filename.substring(0, 3).toUpperCase())
       }
       </div>
       <div class="queue-info">
-        <div class="queue-filename">${escapeHtml(item.filename)}</div>
+        <div class="queue-filename">${escapeHtml(item.filename)} ${this.uploadBadgeHtml(item)}</div>
         <div class="queue-status">${escapeHtml(item.statusText || item.status)} ${statusIcon}</div>
         <div class="queue-size">${sizeText} ${dimensionsText}</div>
+        ${item.status === 'queued' ? `
+          <input type="text" class="form-control queue-description" maxlength="1024"
+            placeholder="Alt text (optional)" value="${escapeHtml(item.description || '')}">
+        ` : ''}
         ${item.status === 'uploading' ? `
           <div class="queue-progress">
             <div class="queue-progress-bar" style="width: ${item.progress}%"></div>
@@ -870,6 +1413,9 @@ class AppState {
         ${item.status === 'error' && item.retryCount < 3 ? `
           <button class="btn btn-small btn-secondary retry-btn" data-id="${item.id}">🔄 Retry</button>
         ` : ''}
+        ${item.status === 'queued' && !isClip ? `
+          <button class="btn btn-small btn-secondary edit-btn" data-id="${item.id}" title="Rotate or crop">✏️</button>
+        ` : ''}
         <button class="btn btn-small btn-secondary remove-btn" data-id="${item.id}">🗑️</button>
       </div>
     `;
@@ -893,6 +1439,27 @@ class AppState {
       this.retryUpload(item.id);
     });
 
+    const editBtn = element.querySelector('.edit-btn');
+    editBtn?.addEventListener('click', () => {
+      this.openImageEditor(item.id);
+    });
+
+    // Alt text is saved right away so it's used by the upload and kept for next time
+    const descriptionInput = element.querySelector('.queue-description') as HTMLInputElement | null;
+    descriptionInput?.addEventListener('change', async () => {
+      const queueItem = this.uploadQueue.find(q => q.id === item.id);
+      if (!queueItem) return;
+      try {
+        await invoke('set_photo_description', {
+          filePath: queueItem.filePath,
+          description: descriptionInput.value
+        });
+        queueItem.description = descriptionInput.value.trim() || undefined;
+      } catch (error) {
+        this.showError(`Failed to save description: ${error}`);
+      }
+    });
+
     // Setup lazy loading for thumbnail
     const thumbnail = element.querySelector('.queue-thumbnail') as HTMLElement;
     if (thumbnail) {
@@ -912,6 +1479,58 @@ class AppState {
     return element;
   }
 
+  // Pre-upload editor: rotate/crop a queued image into a temp copy that gets uploaded instead
+  openImageEditor(itemId: string) {
+    const item = this.uploadQueue.find(q => q.id === itemId);
+    if (!item) return;
+
+    this.editingItemId = itemId;
+    this.updateImageEditorPreview(item);
+    ModalManager.openModal('imageEditorModal');
+  }
+
+  private updateImageEditorPreview(item: QueueItem) {
+    const preview = document.getElementById('imageEditorPreview') as HTMLImageElement | null;
+    if (preview) {
+      preview.src = convertFileSrc(item.filePath);
+    }
+
+    const { width, height } = item.dimensions ?? { width: 0, height: 0 };
+    const values: Record<string, number> = { cropX: 0, cropY: 0, cropWidth: width, cropHeight: height };
+    Object.entries(values).forEach(([id, value]) => {
+      const input = document.getElementById(id) as HTMLInputElement | null;
+      if (input) input.value = value.toString();
+    });
+
+    const sizeLabel = document.getElementById('imageEditorSize');
+    if (sizeLabel) {
+      sizeLabel.textContent = width && height ? `${width}×${height}` : '';
+    }
+  }
+
+  async applyImageEdit(command: 'rotate_image' | 'crop_image', args: Record<string, number>) {
+    const item = this.uploadQueue.find(q => q.id === this.editingItemId);
+    if (!item) return;
+
+    try {
+      const editedPath = await invoke<string>(command, { filePath: item.filePath, ...args });
+      const [width, height, fileSize] = await invoke<[number, number, number]>('get_image_info', {
+        filePath: editedPath
+      });
+
+      item.filePath = editedPath;
+      item.dimensions = { width, height };
+      item.fileSize = fileSize;
+      item.thumbnailPath = undefined;
+      item.thumbnailLoaded = false;
+
+      this.updateImageEditorPreview(item);
+      this.updateQueueDisplay();
+    } catch (error) {
+      this.showError(`Failed to edit image: ${error}`);
+    }
+  }
+
   formatFileSize(bytes: number): string {
     if (bytes === 0) return '0 Bytes';
     const k = 1024;
@@ -935,14 +1554,6 @@ class AppState {
           (thumbnail as any)._previewCleanup();
         }
       }
-
-      // Clean up thumbnail temp file
-      if (item.thumbnailPath) {
-        const parts = item.thumbnailPath.split(/[\\/]/);
-        const filename = parts[parts.length - 1];
-        invoke('cleanup_temp_files', { tempFilenames: [filename] })
-          .catch(err => console.warn('Failed to cleanup thumbnail file:', err));
-      }
     }
 
     this.uploadQueue = this.uploadQueue.filter(item => item.id !== itemId);
@@ -961,20 +1572,6 @@ class AppState {
       }
     });
 
-    // Clean up thumbnail temp files
-    const thumbnailFiles = this.uploadQueue
-      .filter(item => item.thumbnailPath)
-      .map(item => {
-        // Extract just the filename from the full path
-        const parts = item.thumbnailPath!.split(/[\\/]/);
-        return parts[parts.length - 1];
-      });
-
-    if (thumbnailFiles.length > 0) {
-      invoke('cleanup_temp_files', { tempFilenames: thumbnailFiles })
-        .catch(err => console.warn('Failed to cleanup thumbnail files:', err));
-    }
-
     if (this.isUploading && this.currentUploadSession) {
       console.log('Stopping active upload to clear queue');
       this.forceStopUpload();
@@ -1025,9 +1622,88 @@ class AppState {
     const viewMetadataBtn = document.getElementById('viewMetadataBtn') as HTMLButtonElement;
 
     if (startBtn) {
-      startBtn.disabled = selectedCount === 0 || this.selectedWebhookIds.length === 0 || this.isUploading;
+      const hasTarget = this.selectedWebhookIds.length > 0 || this.selectedTemplateId() !== null;
+      startBtn.disabled = selectedCount === 0 || !hasTarget || this.isUploading;
     }
     if (viewMetadataBtn) viewMetadataBtn.disabled = selectedCount === 0;
+    this.scheduleGroupEstimate();
+  }
+
+  // Grouping fields of the upload form, as MessagePreviewOptions
+  groupingOptions() {
+    const groupByTime = (document.getElementById('groupByTime') as HTMLInputElement)?.checked;
+    const timeWindowValue = parseInt((document.getElementById('groupingTimeWindow') as HTMLInputElement)?.value);
+    return {
+      group_by_metadata: (document.getElementById('groupByMetadata') as HTMLInputElement)?.checked ?? true,
+      max_images_per_message: parseInt((document.getElementById('maxImages') as HTMLSelectElement)?.value) || 10,
+      grouping_time_window: groupByTime ? (isNaN(timeWindowValue) ? 10 : timeWindowValue) : 0,
+      group_by_world: (document.getElementById('groupByWorld') as HTMLInputElement)?.checked ?? true,
+      merge_no_metadata: (document.getElementById('mergeNoMetadata') as HTMLInputElement)?.checked ?? false,
+      adopt_orphans: (document.getElementById('adoptOrphans') as HTMLInputElement)?.checked ?? false,
+      is_forum: this.webhooks.some(w => this.selectedWebhookIds.includes(w.id) && w.is_forum),
+    };
+  }
+
+  // Ask before starting a batch that would split into many single-photo posts
+  async confirmUploadPlan(filePaths: string[]): Promise<boolean> {
+    try {
+      const validation = await invoke<UploadPlanValidation>('validate_upload_plan', {
+        filePaths,
+        options: this.groupingOptions(),
+      });
+      if (validation.warnings.length === 0) return true;
+      return confirm(`${validation.warnings.map(w => w.message).join('\n\n')}\n\nStart anyway?`);
+    } catch (error) {
+      console.warn('Upload plan check failed:', error);
+      return true;
+    }
+  }
+
+  // "This will create N posts" hint, from cached metadata so it stays cheap
+  scheduleGroupEstimate() {
+    if (this.groupEstimateTimer !== null) window.clearTimeout(this.groupEstimateTimer);
+    this.groupEstimateTimer = window.setTimeout(() => this.updateGroupEstimate(), 300);
+  }
+
+  async updateGroupEstimate() {
+    const estimateEl = document.getElementById('groupEstimate');
+    if (!estimateEl) return;
+    const filePaths = this.uploadQueue.filter(item => item.selected).map(item => item.filePath);
+    if (filePaths.length === 0 || this.isUploading) {
+      estimateEl.classList.add('hidden');
+      return;
+    }
+
+    try {
+      const estimate = await invoke<GroupEstimate>('estimate_groups', { filePaths, options: this.groupingOptions() });
+      const posts = estimate.groups === 1 ? '1 post' : `${estimate.groups} posts`;
+      const messages = estimate.messages === 1 ? '1 message' : `${estimate.messages} messages`;
+      let text = `This will create ${posts} (${messages})`;
+      if (estimate.without_metadata > 0) {
+        text += ` · ${estimate.without_metadata} photo${estimate.without_metadata === 1 ? '' : 's'} without known metadata`;
+      }
+      estimateEl.textContent = text;
+      estimateEl.classList.remove('hidden');
+    } catch (error) {
+      console.warn('Group estimate failed:', error);
+      estimateEl.classList.add('hidden');
+    }
+  }
+
+  // Ask before starting when the temp folder's drive looks too full for compressed copies
+  async confirmDiskSpace(filePaths: string[]): Promise<boolean> {
+    try {
+      const check = await invoke<DiskSpaceCheck>('check_upload_disk_space', { filePaths });
+      if (check.sufficient) return true;
+      const available = this.formatFileSize(check.available_bytes ?? 0);
+      return confirm(
+        `This upload may need about ${this.formatFileSize(check.required_bytes)} of temporary space for compressed copies, ` +
+        `but only ${available} is free in ${check.temp_directory}. Start anyway?`
+      );
+    } catch (error) {
+      console.warn('Disk space check failed:', error);
+      return true;
+    }
   }
 
   async startUpload() {
@@ -1036,7 +1712,9 @@ class AppState {
       return;
     }
 
-    if (this.selectedWebhookIds.length === 0) {
+    // A template brings its own webhooks and settings
+    const templateId = this.selectedTemplateId();
+    if (this.selectedWebhookIds.length === 0 && templateId === null) {
       this.showError('Please select at least one webhook first');
       return;
     }
@@ -1063,6 +1741,13 @@ class AppState {
       }
     }
 
+    if (templateId === null && !(await this.confirmUploadPlan(selectedItems.map(item => item.filePath)))) {
+      return;
+    }
+    if (!(await this.confirmDiskSpace(selectedItems.map(item => item.filePath)))) {
+      return;
+    }
+
     try {
       this.isUploading = true;
       this.lastSeenWebhookIndex = 0;
@@ -1076,6 +1761,7 @@ class AppState {
       const groupByTime = (document.getElementById('groupByTime') as HTMLInputElement).checked;
       const singleThreadMode = (document.getElementById('singleThreadMode') as HTMLInputElement).checked;
       const mergeNoMetadata = (document.getElementById('mergeNoMetadata') as HTMLInputElement).checked;
+      const adoptOrphans = (document.getElementById('adoptOrphans') as HTMLInputElement).checked;
 
       // Time window: if groupByTime is disabled, use 0 (no limit)
       const timeWindowValue = parseInt((document.getElementById('groupingTimeWindow') as HTMLInputElement).value);
@@ -1090,19 +1776,23 @@ class AppState {
 
       console.log('Starting upload with original file paths:', filePaths);
 
-      const sessionId = await invoke('upload_images', {
-        request: {
-          webhook_ids: this.selectedWebhookIds,
-          file_paths: filePaths,
-          group_by_metadata: groupByMetadata,
-          max_images_per_message: maxImages,
-          include_player_names: includePlayerNames,
-          grouping_time_window: groupingTimeWindow,
-          group_by_world: groupByWorld,
-          single_thread_mode: singleThreadMode,
-          merge_no_metadata: mergeNoMetadata
-        }
-      });
+      const sessionId = templateId !== null
+        ? await invoke('start_session_from_template', { templateId, filePaths })
+        : await invoke('upload_images', {
+            request: {
+              webhook_ids: this.selectedWebhookIds,
+              file_paths: filePaths,
+              group_by_metadata: groupByMetadata,
+              max_images_per_message: maxImages,
+              include_player_names: includePlayerNames,
+              grouping_time_window: groupingTimeWindow,
+              group_by_world: groupByWorld,
+              single_thread_mode: singleThreadMode,
+              merge_no_metadata: mergeNoMetadata,
+              adopt_orphans: adoptOrphans,
+              preset_id: this.selectedPresetId()
+            }
+          });
 
       // Store session ID
       this.currentUploadSession = sessionId as string;
@@ -1123,7 +1813,7 @@ class AppState {
       if (startBtn) startBtn.disabled = true;
       if (pauseBtn) pauseBtn.classList.remove('hidden');
 
-      this.startProgressPolling(sessionId as string);
+      await this.watchSession(sessionId as string);
 
     } catch (error) {
       this.isUploading = false;
@@ -1138,60 +1828,41 @@ class AppState {
     }
   }
 
-  startProgressPolling(sessionId: string) {
-    if (this.progressPollingInterval) {
-      clearInterval(this.progressPollingInterval);
-    }
+  // Session progress arrives through 'upload-progress' events; this also finishes the
+  // manual upload once its session reaches a final state
+  handleSessionProgress(progress: UploadProgress & { session_id?: string }) {
+    this.updateProgressFromSession(progress);
 
-    console.log('Starting progress polling for session:', sessionId);
+    if (!this.watchingSessionCompletion) return;
+    if (progress.session_id && progress.session_id !== this.currentUploadSession) return;
 
-    this.progressPollingInterval = window.setInterval(async () => {
-      try {
-        const progress: UploadProgress | null = await invoke('get_upload_progress', {
-          sessionId: sessionId
-        });
-
-        if (progress) {
-          console.log('Progress update:', {
-            completed: progress.completed,
-            total: progress.total_images,
-            status: progress.session_status,
-            current: progress.current_image,
-            successful: progress.successful_uploads.length,
-            failed: progress.failed_uploads.length
-          });
+    const allFilesProcessed = progress.completed >= progress.total_images;
+    const isLastWebhook = progress.current_webhook_index >= progress.total_webhooks - 1;
+    const sessionCompleted = (progress.session_status === 'completed' && isLastWebhook) ||
+      progress.session_status === 'failed' ||
+      progress.session_status === 'cancelled' ||
+      allFilesProcessed;
 
-          this.updateProgressFromSession(progress);
-
-          const allFilesProcessed = progress.completed >= progress.total_images;
-          const isLastWebhook = progress.current_webhook_index >= progress.total_webhooks - 1;
-          const sessionCompleted = (progress.session_status === 'completed' && isLastWebhook) ||
-            progress.session_status === 'failed' ||
-            progress.session_status === 'cancelled' ||
-            allFilesProcessed;
-
-          if (sessionCompleted) {
-            console.log('Upload session completed, stopping polling');
-            this.stopProgressPolling();
-            this.onUploadComplete(progress);
-          }
-        } else {
-          console.warn('No progress data received for session:', sessionId);
-        }
-      } catch (error) {
-        console.error('Failed to poll progress:', error);
-        this.stopProgressPolling();
-        this.showError(`Failed to get upload progress: ${error}`);
-        this.isUploading = false;
-        this.updateControlButtons();
-      }
-    }, 1000);
+    if (sessionCompleted) {
+      console.log('Upload session completed');
+      this.watchingSessionCompletion = false;
+      this.onUploadComplete(progress);
+    }
   }
 
-  stopProgressPolling() {
-    if (this.progressPollingInterval) {
-      clearInterval(this.progressPollingInterval);
-      this.progressPollingInterval = null;
+  // Start following a session; the one-off fetch covers events sent before the
+  // session ID was known here
+  async watchSession(sessionId: string) {
+    this.watchingSessionCompletion = true;
+    try {
+      const progress: UploadProgress | null = await invoke('get_upload_progress', {
+        sessionId: sessionId
+      });
+      if (progress) {
+        this.handleSessionProgress({ ...progress, session_id: sessionId });
+      }
+    } catch (error) {
+      console.error('Failed to get upload progress:', error);
     }
   }
 
@@ -1369,11 +2040,6 @@ class AppState {
       if (progressText) {
         progressText.textContent = 'Upload complete!';
       }
-      this.showDesktopNotification(
-        'VRChat Photo Uploader',
-        `Successfully uploaded ${successCount} files!`,
-        'success'
-      );
     } else if (successCount === 0) {
       if (hasGroupFailure) {
         this.showError(`Group upload failed - ${failedCount} files not uploaded. Use "Retry Failed" to retry the entire group.`);
@@ -1385,22 +2051,12 @@ class AppState {
           progressText.textContent = 'Upload failed';
         }
       }
-      this.showDesktopNotification(
-        'Upload Failed',
-        `Failed to upload ${failedCount} files`,
-        'error'
-      );
     } else {
       if (hasGroupFailure) {
         this.showWarning(`Partial success: ${successCount} files uploaded, but a group of ${failedCount} files failed. Use "Retry Failed" to retry the failed group.`);
       } else {
         this.showWarning(`Uploaded ${successCount} files successfully, ${failedCount} failed.`);
       }
-      this.showDesktopNotification(
-        'Upload Partially Complete',
-        `${successCount} succeeded, ${failedCount} failed`,
-        'info'
-      );
     }
 
     // Reset upload state
@@ -1493,7 +2149,7 @@ class AppState {
       retryBtn?.classList.add('hidden');
 
       if (this.currentUploadSession) {
-        this.startProgressPolling(this.currentUploadSession);
+        await this.watchSession(this.currentUploadSession);
       }
     }
   }
@@ -1985,6 +2641,7 @@ document.addEventListener('DOMContentLoaded', async () => {
 
   // Load initial data
   await state.loadWebhooks();
+  await state.loadSessionTemplates();
   state.loadNotificationsSetting();
 
   // Load config and apply default settings
@@ -2012,6 +2669,9 @@ document.addEventListener('DOMContentLoaded', async () => {
   // Initialize thumbnail lazy loading observer
   state.initThumbnailObserver();
 
+  // Surface startup problems (database, folders, network, webhooks) without blocking
+  runStartupHealthCheck();
+
   // Setup modal events
   ModalManager.setupModalEvents();
 
@@ -2019,19 +2679,58 @@ document.addEventListener('DOMContentLoaded', async () => {
     const { paths: filePaths } = event.payload as { paths: string[]; position: { x: number; y: number } };
     console.log('Native drag & drop - files:', filePaths);
 
-    // Filter for image files only
-    const imageFiles = filePaths.filter(path => {
+    // Filter for images and clips only
+    const isMedia = (path: string) => {
       const ext = path.toLowerCase().split('.').pop();
-      return ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp'].includes(ext || '');
-    });
+      return ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp', 'tif', 'tiff', 'mp4', 'webm'].includes(ext || '');
+    };
+    const imageFiles = filePaths.filter(isMedia);
+
+    // Anything else may be a folder; the backend scan rejects non-folders
+    let folderImages = 0;
+    for (const path of filePaths.filter(path => !isMedia(path))) {
+      try {
+        folderImages += await state.addFolderToQueue(path);
+      } catch (error) {
+        console.warn(`Skipping dropped item ${path}:`, error);
+      }
+    }
 
-    if (imageFiles.length === 0) {
+    if (imageFiles.length === 0 && folderImages === 0) {
       state.showWarning('No valid image files were dropped');
       return;
     }
 
-    await state.addFilesToQueue(imageFiles);
-    state.showSuccess(`Added ${imageFiles.length} images via drag & drop`);
+    if (imageFiles.length > 0) {
+      await state.addFilesToQueue(imageFiles);
+    }
+    state.showSuccess(`Added ${imageFiles.length + folderImages} images via drag & drop`);
+  });
+
+  // Files passed on the command line or forwarded by a second launch of the app
+  const addPendingFiles = async () => {
+    const pendingFiles = await invoke<string[]>('take_pending_files');
+    if (pendingFiles.length > 0) {
+      await state.addFilesToQueue(pendingFiles);
+      state.showSuccess(`Added ${pendingFiles.length} images`);
+    }
+  };
+  listen('external-files-queued', addPendingFiles);
+  addPendingFiles();
+
+  // vrcphoto:// links opened by other apps or web pages. A link can queue files and
+  // pick a webhook, but the upload only starts once the user confirms it.
+  listen<{ webhook_id: number; webhook_name: string; file_count: number }>('deep-link-upload-requested', async (event) => {
+    const { webhook_id, webhook_name, file_count } = event.payload;
+    await addPendingFiles();
+    if (!state.webhooks.some(w => w.id === webhook_id)) await state.loadWebhooks();
+    state.selectWebhook(webhook_id);
+    if (confirm(`A link asked to upload ${file_count} photo${file_count === 1 ? '' : 's'} to "${webhook_name}". Start the upload now?`)) {
+      await state.startUpload();
+    }
+  });
+  listen<string>('deep-link-error', (event) => {
+    state.showError(`Could not handle link: ${event.payload}`);
   });
 
   // Upload progress events
@@ -2141,7 +2840,36 @@ document.addEventListener('DOMContentLoaded', async () => {
     if (isBackground) {
       state.updateBackgroundPanelFromProgress(data);
     } else {
-      state.updateProgressFromSession(data);
+      state.handleSessionProgress(data);
+    }
+  });
+
+  listen<{
+    session_id: string;
+    group_id: string;
+    group_index: number;
+    total_groups: number;
+    webhook_name: string;
+    success: boolean;
+    file_paths: string[];
+    thread_id?: string;
+    jump_url?: string;
+    duration_ms: number;
+  }>('group-completed', (event) => {
+    const data = event.payload;
+    if (!state.isManualSession(data.session_id)) return;
+
+    console.log(`Group ${data.group_index + 1}/${data.total_groups} ${data.success ? 'uploaded' : 'failed'} to ${data.webhook_name} in ${data.duration_ms} ms`);
+    if (!data.success) return;
+
+    for (const filePath of data.file_paths) {
+      const item = state.findItemByPath(filePath);
+      if (item && item.status !== 'success') {
+        item.status = 'success';
+        item.statusText = 'uploaded';
+        item.progress = 100;
+        state.updateQueueItemProgress(item.id);
+      }
     }
   });
 
@@ -2189,14 +2917,13 @@ document.addEventListener('DOMContentLoaded', async () => {
   });
 
   // Listen for system tray events
-  listen('upload-files-request', async () => {
-    console.log('Tray: Upload files requested');
+  const pickFilesFromTray = async () => {
     try {
       const selected = await open({
         multiple: true,
         filters: [{
-          name: 'Images',
-          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp']
+          name: 'Images and clips',
+          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp', 'tif', 'tiff', 'mp4', 'webm']
         }]
       });
 
@@ -2210,6 +2937,31 @@ document.addEventListener('DOMContentLoaded', async () => {
     } catch (error) {
       state.showError(`Failed to select files from tray: ${error}`);
     }
+  };
+
+  listen('upload-files-request', async () => {
+    console.log('Tray: Upload files requested');
+    await pickFilesFromTray();
+  });
+
+  // Tray "Upload to" submenu: select that webhook, then pick files
+  listen<number>('upload-to-webhook-request', async (event) => {
+    console.log('Tray: Upload to webhook requested', event.payload);
+    if (!state.webhooks.some(w => w.id === event.payload)) {
+      await state.loadWebhooks();
+    }
+    if (!state.webhooks.some(w => w.id === event.payload)) {
+      state.showError('That webhook no longer exists');
+      return;
+    }
+
+    state.selectedWebhookIds = [event.payload];
+    state.selectedWebhookId = event.payload;
+    state.updateWebhookSelector();
+    state.updateDropdownSummary();
+    state.updateSingleThreadVisibility();
+    state.updateControlButtons();
+    await pickFilesFromTray();
   });
 
   listen('show-settings', () => {
@@ -2226,6 +2978,7 @@ document.addEventListener('DOMContentLoaded', async () => {
   listen('show-metadata-editor', () => {
     console.log('Tray: Metadata Editor requested');
     ModalManager.openModal('metadataEditorModal');
+    loadMetadataEdits();
   });
 
   // System tray VRChat folder
@@ -2283,8 +3036,8 @@ document.addEventListener('DOMContentLoaded', async () => {
       const selected = await open({
         multiple: true,
         filters: [{
-          name: 'Images',
-          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp']
+          name: 'Images and clips',
+          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp', 'tif', 'tiff', 'mp4', 'webm']
         }]
       });
 
@@ -2417,6 +3170,25 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
   });
 
+  // Upload presets
+  const uploadPresetSelect = document.getElementById('uploadPreset') as HTMLSelectElement;
+  uploadPresetSelect?.addEventListener('change', () => {
+    const preset = state.presets.find(p => p.id === state.selectedPresetId());
+    if (preset) state.applyPreset(preset);
+    state.updatePresetControls();
+  });
+  document.getElementById('savePresetBtn')?.addEventListener('click', () => state.savePreset());
+  document.getElementById('deletePresetBtn')?.addEventListener('click', () => state.deletePreset());
+
+  // Event templates
+  document.getElementById('sessionTemplate')?.addEventListener('change', () => state.updateTemplateControls());
+  document.getElementById('saveTemplateBtn')?.addEventListener('click', () => state.saveSessionTemplate());
+  document.getElementById('deleteTemplateBtn')?.addEventListener('click', () => state.deleteSessionTemplate());
+  ['groupByMetadata', 'maxImages', 'includePlayerNames', 'groupByTime', 'groupingTimeWindowPreset', 'groupingTimeWindow']
+    .forEach(id => document.getElementById(id)?.addEventListener('change', () => state.presetSettingChanged()));
+  ['groupByMetadata', 'maxImages', 'groupByTime', 'groupingTimeWindowPreset', 'groupingTimeWindow', 'groupByWorld', 'mergeNoMetadata', 'adoptOrphans']
+    .forEach(id => document.getElementById(id)?.addEventListener('change', () => state.scheduleGroupEstimate()));
+
   // Time window preset toggle
   const timePreset = document.getElementById('groupingTimeWindowPreset') as HTMLSelectElement;
   const timeInput = document.getElementById('groupingTimeWindow') as HTMLInputElement;
@@ -2459,6 +3231,10 @@ document.addEventListener('DOMContentLoaded', async () => {
     const nameInput = document.getElementById('webhookName') as HTMLInputElement;
     const urlInput = document.getElementById('webhookUrl') as HTMLInputElement;
     const isForumCheckbox = document.getElementById('webhookIsForum') as HTMLInputElement;
+    const suppressEmbedsCheckbox = document.getElementById('webhookSuppressEmbeds') as HTMLInputElement;
+    const suppressNotificationsCheckbox = document.getElementById('webhookSuppressNotifications') as HTMLInputElement;
+    const uploadLimitSelect = document.getElementById('webhookUploadLimit') as HTMLSelectElement;
+    const timestampStyleSelect = document.getElementById('webhookTimestampStyle') as HTMLSelectElement;
     const addBtn = addWebhookBtn;
 
     if (!nameInput.value.trim() || !urlInput.value.trim()) {
@@ -2467,6 +3243,10 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
 
     const isForum = isForumCheckbox?.checked || false;
+    const suppressEmbeds = suppressEmbedsCheckbox?.checked || false;
+    const suppressNotifications = suppressNotificationsCheckbox?.checked || false;
+    const uploadLimitMb = uploadLimitSelect?.value ? parseInt(uploadLimitSelect.value) : null;
+    const timestampStyle = timestampStyleSelect?.value || null;
     const editingId = addBtn.dataset.editingId;
 
     if (editingId) {
@@ -2474,13 +3254,21 @@ document.addEventListener('DOMContentLoaded', async () => {
         parseInt(editingId),
         nameInput.value.trim(),
         urlInput.value.trim(),
-        isForum
+        isForum,
+        suppressEmbeds,
+        suppressNotifications,
+        uploadLimitMb,
+        timestampStyle
       );
     } else {
       await state.addWebhook(
         nameInput.value.trim(),
         urlInput.value.trim(),
-        isForum
+        isForum,
+        suppressEmbeds,
+        suppressNotifications,
+        uploadLimitMb,
+        timestampStyle
       );
 
       // Reset selection after adding new webhook
@@ -2491,6 +3279,10 @@ document.addEventListener('DOMContentLoaded', async () => {
     nameInput.value = '';
     urlInput.value = '';
     if (isForumCheckbox) isForumCheckbox.checked = false;
+    if (suppressEmbedsCheckbox) suppressEmbedsCheckbox.checked = false;
+    if (suppressNotificationsCheckbox) suppressNotificationsCheckbox.checked = false;
+    if (uploadLimitSelect) uploadLimitSelect.value = '';
+    if (timestampStyleSelect) timestampStyleSelect.value = '';
     addBtn.textContent = '➕ Add Webhook';
     delete addBtn.dataset.editingId;
 
@@ -2558,6 +3350,14 @@ document.addEventListener('DOMContentLoaded', async () => {
     if (nameInput) nameInput.value = selectedWebhook.name;
     if (urlInput) urlInput.value = selectedWebhook.url;
     if (isForumCheckbox) isForumCheckbox.checked = selectedWebhook.is_forum;
+    const suppressEmbedsCheckbox = document.getElementById('webhookSuppressEmbeds') as HTMLInputElement;
+    const suppressNotificationsCheckbox = document.getElementById('webhookSuppressNotifications') as HTMLInputElement;
+    if (suppressEmbedsCheckbox) suppressEmbedsCheckbox.checked = selectedWebhook.suppress_embeds;
+    if (suppressNotificationsCheckbox) suppressNotificationsCheckbox.checked = selectedWebhook.suppress_notifications;
+    const uploadLimitSelect = document.getElementById('webhookUploadLimit') as HTMLSelectElement;
+    if (uploadLimitSelect) uploadLimitSelect.value = selectedWebhook.upload_limit_mb?.toString() ?? '';
+    const timestampStyleSelect = document.getElementById('webhookTimestampStyle') as HTMLSelectElement;
+    if (timestampStyleSelect) timestampStyleSelect.value = selectedWebhook.timestamp_style ?? '';
 
     const addBtn = document.getElementById('addWebhookBtn');
     if (addBtn) {
@@ -2576,8 +3376,8 @@ document.addEventListener('DOMContentLoaded', async () => {
       const selected = await open({
         multiple: true,
         filters: [{
-          name: 'Images',
-          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp']
+          name: 'Images and clips',
+          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp', 'tif', 'tiff', 'mp4', 'webm']
         }]
       });
 
@@ -2626,8 +3426,8 @@ document.addEventListener('DOMContentLoaded', async () => {
       const selected = await open({
         multiple: true,
         filters: [{
-          name: 'Images',
-          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp']
+          name: 'Images and clips',
+          extensions: ['png', 'jpg', 'jpeg', 'webp', 'gif', 'bmp', 'tif', 'tiff', 'mp4', 'webm']
         }]
       });
 
@@ -2775,14 +3575,66 @@ document.addEventListener('DOMContentLoaded', async () => {
   const settingsBtn = document.getElementById('settingsBtn');
   settingsBtn?.addEventListener('click', async () => {
     updateVRChatFolderDisplay();
+    loadProfiles();
 
     // Load current config to populate settings
     try {
       const config = await invoke<AppConfig>('get_app_config');
+      const contextMenuCheck = document.getElementById('enableContextMenu') as HTMLInputElement;
+      if (contextMenuCheck) contextMenuCheck.checked = await invoke<boolean>('get_context_menu_integration');
+      const autoLaunchCheck = document.getElementById('autoLaunch') as HTMLInputElement;
+      if (autoLaunchCheck) autoLaunchCheck.checked = config.auto_launch || false;
+
       const compressionFormat = document.getElementById('compressionFormat') as HTMLSelectElement;
       if (compressionFormat && config.compression_format) {
         compressionFormat.value = config.compression_format;
       }
+      const timestampStyleSelect = document.getElementById('discordTimestampStyle') as HTMLSelectElement;
+      if (timestampStyleSelect && config.discord_timestamp_style) {
+        timestampStyleSelect.value = config.discord_timestamp_style;
+      }
+
+      const maxUploadDimension = document.getElementById('maxUploadDimension') as HTMLSelectElement;
+      if (maxUploadDimension) maxUploadDimension.value = config.max_upload_dimension?.toString() ?? '';
+      const minQualityInput = document.getElementById('minUploadQuality') as HTMLInputElement;
+      if (minQualityInput) minQualityInput.value = (config.min_upload_quality ?? 55).toString();
+      const minScaleInput = document.getElementById('minUploadScalePercent') as HTMLInputElement;
+      if (minScaleInput) minScaleInput.value = (config.min_upload_scale_percent ?? 25).toString();
+      const compressClipsCheck = document.getElementById('compressClips') as HTMLInputElement;
+      if (compressClipsCheck) compressClipsCheck.checked = config.compress_clips || false;
+      const ffmpegPathInput = document.getElementById('ffmpegPath') as HTMLInputElement;
+      if (ffmpegPathInput) ffmpegPathInput.value = config.ffmpeg_path ?? '';
+      const collectMetricsCheck = document.getElementById('collectMetrics') as HTMLInputElement;
+      if (collectMetricsCheck) collectMetricsCheck.checked = config.collect_metrics || false;
+      updatePerformanceMetricsInfo();
+      const tempDirectoryInput = document.getElementById('tempDirectory') as HTMLInputElement;
+      if (tempDirectoryInput) tempDirectoryInput.value = config.temp_directory ?? '';
+      updateTempUsageInfo();
+      const keepFilenamesCheck = document.getElementById('keepOriginalFilenames') as HTMLInputElement;
+      if (keepFilenamesCheck) keepFilenamesCheck.checked = config.keep_original_filenames || false;
+      const uploadVerificationSelect = document.getElementById('uploadVerification') as HTMLSelectElement;
+      if (uploadVerificationSelect) uploadVerificationSelect.value = config.upload_verification || 'off';
+
+      const includeAvatarCheck = document.getElementById('includeAvatarInCaption') as HTMLInputElement;
+      if (includeAvatarCheck) includeAvatarCheck.checked = config.include_avatar_in_caption || false;
+      const mentionPlayersCheck = document.getElementById('mentionMappedPlayers') as HTMLInputElement;
+      if (mentionPlayersCheck) mentionPlayersCheck.checked = config.mention_mapped_players ?? true;
+      const restrictMentionsCheck = document.getElementById('restrictCaptionMentions') as HTMLInputElement;
+      if (restrictMentionsCheck) restrictMentionsCheck.checked = config.restrict_caption_mentions ?? true;
+      const linkProfilesCheck = document.getElementById('linkPlayerProfiles') as HTMLInputElement;
+      if (linkProfilesCheck) linkProfilesCheck.checked = config.link_player_profiles || false;
+
+      const useLinkButtonsCheck = document.getElementById('useLinkButtons') as HTMLInputElement;
+      if (useLinkButtonsCheck) useLinkButtonsCheck.checked = config.use_link_buttons || false;
+
+      const reuseForumThreadsCheck = document.getElementById('reuseForumThreads') as HTMLInputElement;
+      if (reuseForumThreadsCheck) reuseForumThreadsCheck.checked = config.reuse_forum_threads || false;
+
+      const forumThreadMaxAge = document.getElementById('forumThreadMaxAgeDays') as HTMLInputElement;
+      if (forumThreadMaxAge) forumThreadMaxAge.value = (config.forum_thread_max_age_days ?? 7).toString();
+
+      const postThreadClosingCheck = document.getElementById('postThreadClosingMessage') as HTMLInputElement;
+      if (postThreadClosingCheck) postThreadClosingCheck.checked = config.post_thread_closing_message || false;
 
       const autoUploadCheck = document.getElementById('enableAutoUpload') as HTMLInputElement;
       if (autoUploadCheck) autoUploadCheck.checked = config.enable_auto_upload || false;
@@ -2962,6 +3814,9 @@ document.addEventListener('DOMContentLoaded', async () => {
       const mergeNoMetadata = document.getElementById('mergeNoMetadata') as HTMLInputElement;
       if (mergeNoMetadata) mergeNoMetadata.checked = config.merge_no_metadata;
 
+      const adoptOrphans = document.getElementById('adoptOrphans') as HTMLInputElement;
+      if (adoptOrphans) adoptOrphans.checked = config.adopt_orphans ?? false;
+
       const autoUploadBatchSize = document.getElementById('autoUploadBatchSize') as HTMLInputElement;
       if (autoUploadBatchSize) autoUploadBatchSize.value = (config.auto_upload_batch_size || 10).toString();
 
@@ -3071,9 +3926,27 @@ document.addEventListener('DOMContentLoaded', async () => {
   const metadataEditorBtn = document.getElementById('metadataEditorBtn');
   metadataEditorBtn?.addEventListener('click', () => {
     ModalManager.openModal('metadataEditorModal');
+    loadMetadataEdits();
   });
 
   // Metadata Editor functionality
+  // Pre-upload image editor
+  document.getElementById('rotateLeftBtn')?.addEventListener('click', () => {
+    state.applyImageEdit('rotate_image', { degrees: 270 });
+  });
+  document.getElementById('rotateRightBtn')?.addEventListener('click', () => {
+    state.applyImageEdit('rotate_image', { degrees: 90 });
+  });
+  document.getElementById('applyCropBtn')?.addEventListener('click', () => {
+    const value = (id: string) => parseInt((document.getElementById(id) as HTMLInputElement).value, 10) || 0;
+    state.applyImageEdit('crop_image', {
+      x: value('cropX'),
+      y: value('cropY'),
+      width: value('cropWidth'),
+      height: value('cropHeight')
+    });
+  });
+
   const loadPngMetadataBtn = document.getElementById('loadPngMetadataBtn');
   loadPngMetadataBtn?.addEventListener('click', async () => {
     try {
@@ -3174,6 +4047,22 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
   });
 
+  async function runStartupHealthCheck() {
+    try {
+      const report = await invoke<HealthReport>('run_health_check');
+      for (const check of report.checks.filter(c => c.status !== 'ok')) {
+        const text = check.action ? `${check.message}. ${check.action}.` : check.message;
+        if (check.status === 'error') {
+          state.showError(text);
+        } else {
+          state.showWarning(text);
+        }
+      }
+    } catch (error) {
+      console.error('Health check failed:', error);
+    }
+  }
+
   async function loadAppVersion() {
     try {
       const version = await getVersion();
@@ -3253,6 +4142,15 @@ document.addEventListener('DOMContentLoaded', async () => {
       if (worldInstanceId) worldInstanceId.value = metadata.world.instance_id || metadata.world.instanceId || '';
     }
 
+    // Avatar fields
+    const avatarName = document.getElementById('avatarName') as HTMLInputElement;
+    const avatarId = document.getElementById('avatarId') as HTMLInputElement;
+
+    if (metadata.avatar) {
+      if (avatarName) avatarName.value = metadata.avatar.name || metadata.avatar.displayName || '';
+      if (avatarId) avatarId.value = metadata.avatar.id || '';
+    }
+
     // Players field
     const playersText = document.getElementById('playersText') as HTMLTextAreaElement;
     if (playersText && metadata.players && Array.isArray(metadata.players)) {
@@ -3306,6 +4204,8 @@ document.addEventListener('DOMContentLoaded', async () => {
     const worldName = (document.getElementById('worldName') as HTMLInputElement).value.trim();
     const worldId = (document.getElementById('worldId') as HTMLInputElement).value.trim();
     const worldInstanceId = (document.getElementById('worldInstanceId') as HTMLInputElement).value.trim();
+    const avatarName = (document.getElementById('avatarName') as HTMLInputElement).value.trim();
+    const avatarId = (document.getElementById('avatarId') as HTMLInputElement).value.trim();
     const playersText = (document.getElementById('playersText') as HTMLTextAreaElement).value.trim();
 
     // Parse players
@@ -3333,6 +4233,7 @@ document.addEventListener('DOMContentLoaded', async () => {
       created_at: new Date().toISOString(),
       author: authorDisplayName && authorId ? { display_name: authorDisplayName, id: authorId } : null,
       world: worldName && worldId ? { name: worldName, id: worldId, instance_id: worldInstanceId } : null,
+      avatar: avatarName ? { name: avatarName, id: avatarId } : null,
       players: players.map(p => ({ display_name: p.displayName, id: p.id }))
     };
 
@@ -3345,12 +4246,19 @@ document.addEventListener('DOMContentLoaded', async () => {
 
     try {
       // Use the update_image_metadata command to embed metadata
-      const outputPath = await invoke('update_image_metadata', {
+      const asSidecar = (document.getElementById('exportSidecarMetadata') as HTMLInputElement)?.checked ?? false;
+      const result = await invoke<{ edit_id: number | null; output_path: string }>('update_image_metadata', {
         filePath: selectedPngPath,
-        metadata: metadata
-      }) as string;
+        metadata: metadata,
+        asSidecar
+      });
 
-      state.showSuccess(`Metadata embedded successfully! Saved as: ${outputPath.split(/[\\/]/).pop()}`);
+      state.showSuccess(`Metadata ${asSidecar ? 'exported' : 'embedded'} successfully! Saved as: ${result.output_path.split(/[\\/]/).pop()}`);
+      loadMetadataEdits();
+      // Sidecars are plain JSON written as-is; only re-encoded PNGs need reading back
+      if (!asSidecar) {
+        verifyEmbeddedMetadata(result.output_path, metadata);
+      }
 
       // Reset the form
       selectedPngPath = null;
@@ -3365,6 +4273,81 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
   }
 
+  interface MetadataVerification {
+    verified: boolean;
+    source: string;
+    differences: { field: string; expected: string | null; actual: string | null }[];
+  }
+
+  // Read the output back so a write that silently lost the metadata doesn't go unnoticed
+  async function verifyEmbeddedMetadata(outputPath: string, expected: object) {
+    try {
+      const verification = await invoke<MetadataVerification>('verify_embedded_metadata', {
+        filePath: outputPath,
+        expected
+      });
+      if (verification.verified) return;
+
+      const details = verification.differences
+        .slice(0, 5)
+        .map(d => `${d.field}: expected "${d.expected ?? '(none)'}", found "${d.actual ?? '(none)'}"`)
+        .join('; ');
+      const more = verification.differences.length > 5 ? ` (+${verification.differences.length - 5} more)` : '';
+      state.showWarning(`Embedded metadata doesn't match what was requested - ${details}${more}`);
+    } catch (error) {
+      state.showWarning(`Could not verify embedded metadata: ${error}`);
+    }
+  }
+
+  interface MetadataEdit {
+    id: number;
+    source_path: string;
+    output_path: string;
+    backup_path: string | null;
+    created_at: string;
+    undone_at: string | null;
+  }
+
+  async function loadMetadataEdits() {
+    const container = document.getElementById('metadataEditHistory');
+    if (!container) return;
+
+    try {
+      const edits = await invoke<MetadataEdit[]>('get_metadata_edits', { limit: 10 });
+      if (edits.length === 0) {
+        container.textContent = 'No edits yet';
+        return;
+      }
+
+      container.innerHTML = edits.map(edit => {
+        const filename = edit.output_path.split(/[\\/]/).pop() || edit.output_path;
+        const when = new Date(edit.created_at.replace(' ', 'T') + 'Z').toLocaleString();
+        const action = edit.undone_at
+          ? '<span>undone</span>'
+          : `<button class="btn btn-small btn-secondary undo-edit-btn" data-id="${edit.id}">↩️ Undo</button>`;
+        const replaced = edit.backup_path ? ' (replaced an earlier output)' : '';
+        return `<div style="display: flex; gap: 8px; align-items: center; margin-bottom: 4px;">
+          <span title="${escapeHtml(edit.output_path)}">${escapeHtml(filename)} · ${escapeHtml(when)}${replaced}</span>
+          ${action}
+        </div>`;
+      }).join('');
+
+      container.querySelectorAll<HTMLButtonElement>('.undo-edit-btn').forEach(btn => {
+        btn.addEventListener('click', async () => {
+          try {
+            const outputPath = await invoke<string>('undo_metadata_edit', { editId: Number(btn.dataset.id) });
+            state.showSuccess(`Undid edit of ${outputPath.split(/[\\/]/).pop()}`);
+          } catch (error) {
+            state.showError(`Failed to undo edit: ${error}`);
+          }
+          loadMetadataEdits();
+        });
+      });
+    } catch (error) {
+      console.warn('Failed to load metadata edits:', error);
+    }
+  }
+
   // Theme handling
   const themeSelect = document.getElementById('themeSelect') as HTMLSelectElement;
   themeSelect?.addEventListener('change', (e) => {
@@ -3400,6 +4383,28 @@ document.addEventListener('DOMContentLoaded', async () => {
 
   loadTheme();
 
+  // Explorer context-menu entry (stored in the registry, not the config)
+  const enableContextMenu = document.getElementById('enableContextMenu') as HTMLInputElement;
+  enableContextMenu?.addEventListener('change', async () => {
+    try {
+      await invoke('set_context_menu_integration', { enabled: enableContextMenu.checked });
+    } catch (error) {
+      enableContextMenu.checked = !enableContextMenu.checked;
+      state.showError(`Failed to update Explorer menu: ${error}`);
+    }
+  });
+
+  // Start on login (registry entry plus the config flag, updated right away)
+  const autoLaunch = document.getElementById('autoLaunch') as HTMLInputElement;
+  autoLaunch?.addEventListener('change', async () => {
+    try {
+      await invoke('set_auto_launch', { enabled: autoLaunch.checked });
+    } catch (error) {
+      autoLaunch.checked = !autoLaunch.checked;
+      state.showError(`Failed to update start on login: ${error}`);
+    }
+  });
+
   // Notification settings
   const enableNotifications = document.getElementById('enableNotifications') as HTMLInputElement;
   enableNotifications?.addEventListener('change', (e) => {
@@ -3429,6 +4434,18 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
   });
 
+  document.getElementById('browseTempDirectoryBtn')?.addEventListener('click', async () => {
+    const selected = await open({ directory: true, title: 'Select Temp Folder' });
+    const tempDirectoryInput = document.getElementById('tempDirectory') as HTMLInputElement;
+    if (selected && typeof selected === 'string' && tempDirectoryInput) {
+      tempDirectoryInput.value = selected;
+    }
+  });
+  document.getElementById('resetTempDirectoryBtn')?.addEventListener('click', () => {
+    const tempDirectoryInput = document.getElementById('tempDirectory') as HTMLInputElement;
+    if (tempDirectoryInput) tempDirectoryInput.value = '';
+  });
+
   // Settings Save and Cancel buttons
   const saveSettingsBtn = document.getElementById('saveSettingsBtn');
   saveSettingsBtn?.addEventListener('click', async () => {
@@ -3464,7 +4481,10 @@ document.addEventListener('DOMContentLoaded', async () => {
 
       const compressionFormat = document.getElementById('compressionFormat') as HTMLSelectElement;
 
+      // Start from the stored config so settings without a control here are kept
+      const currentConfig = await invoke<AppConfig>('get_app_config');
       const config: AppConfig = {
+        ...currentConfig,
         last_webhook_id: state.selectedWebhookIds[0] ?? undefined,
         last_webhook_ids: state.selectedWebhookIds,
         group_by_metadata: groupByMetadata?.checked || true,
@@ -3473,14 +4493,34 @@ document.addEventListener('DOMContentLoaded', async () => {
         auto_compress_threshold: 8,
         upload_quality: 85,
         compression_format: compressionFormat?.value || 'webp',
+        discord_timestamp_style: (document.getElementById('discordTimestampStyle') as HTMLSelectElement)?.value || 'f',
+        max_upload_dimension: parseInt((document.getElementById('maxUploadDimension') as HTMLSelectElement)?.value) || null,
+        min_upload_quality: Math.min(100, Math.max(1, parseInt((document.getElementById('minUploadQuality') as HTMLInputElement)?.value) || 55)),
+        min_upload_scale_percent: Math.min(100, Math.max(1, parseInt((document.getElementById('minUploadScalePercent') as HTMLInputElement)?.value) || 25)),
+        compress_clips: (document.getElementById('compressClips') as HTMLInputElement)?.checked || false,
+        ffmpeg_path: (document.getElementById('ffmpegPath') as HTMLInputElement)?.value.trim() || null,
+        collect_metrics: (document.getElementById('collectMetrics') as HTMLInputElement)?.checked || false,
+        temp_directory: (document.getElementById('tempDirectory') as HTMLInputElement)?.value || null,
+        keep_original_filenames: (document.getElementById('keepOriginalFilenames') as HTMLInputElement)?.checked || false,
+        upload_verification: (document.getElementById('uploadVerification') as HTMLSelectElement)?.value || 'off',
+        auto_launch: (document.getElementById('autoLaunch') as HTMLInputElement)?.checked || false,
         enable_auto_upload: enableAutoUpload,
         auto_upload_webhook_id: autoUploadWebhookIds[0] ?? undefined,
         auto_upload_webhook_ids: autoUploadWebhookIds,
         vrchat_path: selectedVRChatFolder || undefined,
         single_thread_mode: (document.getElementById('singleThreadMode') as HTMLInputElement)?.checked || false,
         merge_no_metadata: (document.getElementById('mergeNoMetadata') as HTMLInputElement)?.checked || false,
+        adopt_orphans: (document.getElementById('adoptOrphans') as HTMLInputElement)?.checked ?? false,
         default_forum_mode: false,
         enable_multi_webhook: (document.getElementById('enableMultiWebhook') as HTMLInputElement)?.checked ?? false,
+        include_avatar_in_caption: (document.getElementById('includeAvatarInCaption') as HTMLInputElement)?.checked ?? false,
+        mention_mapped_players: (document.getElementById('mentionMappedPlayers') as HTMLInputElement)?.checked ?? true,
+        restrict_caption_mentions: (document.getElementById('restrictCaptionMentions') as HTMLInputElement)?.checked ?? true,
+        link_player_profiles: (document.getElementById('linkPlayerProfiles') as HTMLInputElement)?.checked ?? false,
+        use_link_buttons: (document.getElementById('useLinkButtons') as HTMLInputElement)?.checked ?? false,
+        reuse_forum_threads: (document.getElementById('reuseForumThreads') as HTMLInputElement)?.checked ?? false,
+        forum_thread_max_age_days: parseInt((document.getElementById('forumThreadMaxAgeDays') as HTMLInputElement)?.value || '7'),
+        post_thread_closing_message: (document.getElementById('postThreadClosingMessage') as HTMLInputElement)?.checked ?? false,
         auto_upload_delay_seconds: parseInt((document.getElementById('autoUploadDelay') as HTMLInputElement)?.value || '5'),
         auto_upload_batch_size: parseInt((document.getElementById('autoUploadBatchSize') as HTMLInputElement)?.value || '10'),
         auto_upload_forum_channel: false,
@@ -3491,7 +4531,8 @@ document.addEventListener('DOMContentLoaded', async () => {
         auto_upload_time_window: parseInt((document.getElementById('autoUploadTimeWindow') as HTMLInputElement)?.value || '60'),
         auto_upload_include_players: (document.getElementById('autoUploadIncludePlayers') as HTMLInputElement)?.checked ?? true,
         auto_upload_merge_no_metadata: (document.getElementById('autoUploadMergeNoMetadata') as HTMLInputElement)?.checked ?? false,
-        auto_upload_ignored_folders: ignoredFolders
+        auto_upload_ignored_folders: ignoredFolders,
+        show_upload_notifications: enableNotifications?.checked ?? true
       };
 
       await invoke('save_app_config', { config });
@@ -3518,11 +4559,185 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
   });
 
+  // Settings profiles
+  const profileSelect = document.getElementById('profileSelect') as HTMLSelectElement;
+  const profileNameInput = document.getElementById('profileName') as HTMLInputElement;
+
+  async function loadProfiles() {
+    if (!profileSelect) return;
+    try {
+      const list = await invoke<ProfileList>('list_profiles');
+      profileSelect.innerHTML = '';
+      if (list.profiles.length === 0) {
+        profileSelect.innerHTML = '<option value="">No profiles saved</option>';
+      }
+      list.profiles.forEach(name => {
+        const option = document.createElement('option');
+        option.value = name;
+        option.textContent = name === list.active ? `${name} (active)` : name;
+        profileSelect.appendChild(option);
+      });
+      if (list.active) profileSelect.value = list.active;
+      updateProfileButtons(list.active);
+    } catch (error) {
+      console.warn('Failed to load profiles:', error);
+    }
+  }
+
+  function updateProfileButtons(active: string | null) {
+    const selected = profileSelect?.value || '';
+    const switchBtn = document.getElementById('switchProfileBtn') as HTMLButtonElement;
+    const deleteBtn = document.getElementById('deleteProfileBtn') as HTMLButtonElement;
+    if (switchBtn) switchBtn.disabled = !selected || selected === active;
+    if (deleteBtn) deleteBtn.disabled = !selected;
+  }
+
+  profileSelect?.addEventListener('change', async () => {
+    const list = await invoke<ProfileList>('list_profiles').catch(() => null);
+    updateProfileButtons(list?.active ?? null);
+  });
+
+  document.getElementById('saveProfileBtn')?.addEventListener('click', async () => {
+    const name = profileNameInput?.value.trim() ?? '';
+    if (!name) {
+      state.showError('Enter a name for the profile');
+      return;
+    }
+    try {
+      await invoke('save_profile', { name });
+      if (profileNameInput) profileNameInput.value = '';
+      await loadProfiles();
+      state.showSuccess(`Saved current settings as "${name}"`);
+    } catch (error) {
+      state.showError(`Failed to save profile: ${error}`);
+    }
+  });
+
+  document.getElementById('switchProfileBtn')?.addEventListener('click', async () => {
+    const name = profileSelect?.value;
+    if (!name) return;
+    try {
+      const config = await invoke<AppConfig>('switch_profile', { name });
+      updateBackgroundQueueButtonVisibility(config.enable_auto_upload || false);
+
+      // Apply the profile's webhook selection the way startup does
+      const savedIds = config.last_webhook_ids && config.last_webhook_ids.length > 0
+        ? config.last_webhook_ids
+        : config.last_webhook_id ? [config.last_webhook_id] : [];
+      state.multiWebhookEnabled = config.enable_multi_webhook || false;
+      state.selectedWebhookIds = savedIds.filter(id => state.webhooks.some(w => w.id === id));
+      if (!state.multiWebhookEnabled && state.selectedWebhookIds.length > 1) {
+        state.selectedWebhookIds = [state.selectedWebhookIds[0]];
+      }
+      state.selectedWebhookId = state.selectedWebhookIds[0] ?? null;
+      state.updateWebhookSelector();
+      state.loadPresets();
+
+      // Reload the settings form with the profile's values
+      settingsBtn?.click();
+      state.showSuccess(`Switched to profile "${name}"`);
+    } catch (error) {
+      state.showError(`Failed to switch profile: ${error}`);
+    }
+  });
+
+  document.getElementById('deleteProfileBtn')?.addEventListener('click', async () => {
+    const name = profileSelect?.value;
+    if (!name || !confirm(`Delete the profile "${name}"? Your current settings stay as they are.`)) return;
+    try {
+      await invoke('delete_profile', { name });
+      await loadProfiles();
+      state.showSuccess(`Deleted profile "${name}"`);
+    } catch (error) {
+      state.showError(`Failed to delete profile: ${error}`);
+    }
+  });
+
   const cancelSettingsBtn = document.getElementById('cancelSettingsBtn');
   cancelSettingsBtn?.addEventListener('click', () => {
     ModalManager.closeModal('settingsModal');
   });
 
+  // Queue every file carrying a tag
+  const addTaggedBtn = document.getElementById('addTaggedBtn');
+  addTaggedBtn?.addEventListener('click', async () => {
+    const input = document.getElementById('tagSelectInput') as HTMLInputElement;
+    const tag = input?.value.trim();
+    if (!tag) {
+      state.showWarning('Enter a tag first');
+      return;
+    }
+
+    try {
+      const files = await invoke<string[]>('get_files_by_tag', { tag });
+      if (files.length === 0) {
+        state.showWarning(`No files are tagged "${tag}"`);
+        return;
+      }
+      await state.addFilesToQueue(files);
+      state.showSuccess(`Added ${files.length} files tagged "${tag}"`);
+    } catch (error) {
+      state.showError(`Failed to load tagged files: ${error}`);
+    }
+  });
+
+  // Queue photos whose indexed metadata matches a search
+  const addSearchResultsBtn = document.getElementById('addSearchResultsBtn');
+  addSearchResultsBtn?.addEventListener('click', async () => {
+    const input = document.getElementById('photoSearchInput') as HTMLInputElement;
+    const query = input?.value.trim();
+    if (!query) {
+      state.showWarning('Enter something to search for');
+      return;
+    }
+
+    try {
+      const results = await invoke<{ file_path: string }[]>('search_photos', { query });
+      if (results.length === 0) {
+        state.showWarning(`No photos match "${query}"`);
+        return;
+      }
+      await state.addFilesToQueue(results.map(result => result.file_path));
+      state.showSuccess(`Added ${results.length} photos matching "${query}"`);
+    } catch (error) {
+      state.showError(`Search failed: ${error}`);
+    }
+  });
+
+  const clearThumbnailCacheBtn = document.getElementById('clearThumbnailCacheBtn');
+  clearThumbnailCacheBtn?.addEventListener('click', async () => {
+    try {
+      const removed = await invoke<number>('clear_thumbnail_cache');
+      state.showSuccess(`Cleared ${removed} cached thumbnail${removed === 1 ? '' : 's'}`);
+    } catch (error) {
+      state.showError(`Failed to clear thumbnail cache: ${error}`);
+    }
+  });
+
+  const importPythonHistoryBtn = document.getElementById('importPythonHistoryBtn');
+  importPythonHistoryBtn?.addEventListener('click', async () => {
+    const selected = await open({
+      title: 'Select Python Uploader Database or History File',
+      filters: [{
+        name: 'Uploader history',
+        extensions: ['db', 'sqlite', 'sqlite3', 'json']
+      }]
+    });
+    if (!selected || typeof selected !== 'string') return;
+
+    try {
+      const summary = await invoke<LegacyImportSummary>('import_python_history', { path: selected });
+      await state.loadWebhooks();
+      state.showSuccess(
+        `Imported ${summary.uploads_imported} upload${summary.uploads_imported === 1 ? '' : 's'} ` +
+        `and ${summary.webhooks_added} new webhook${summary.webhooks_added === 1 ? '' : 's'}` +
+        (summary.uploads_skipped > 0 ? ` (${summary.uploads_skipped} skipped)` : '')
+      );
+    } catch (error) {
+      state.showError(`Failed to import Python uploader history: ${error}`);
+    }
+  });
+
   // Clear VRChat folder button
   const clearVRChatFolderBtn = document.getElementById('clearVRChatFolderBtn');
   clearVRChatFolderBtn?.addEventListener('click', () => {
@@ -3538,6 +4753,45 @@ document.addEventListener('DOMContentLoaded', async () => {
     state.showSuccess('VRChat folder location cleared');
   });
 
+  // Show how much the temp folder holds and how much crashed uploads left behind
+  async function updateTempUsageInfo() {
+    const info = document.getElementById('tempUsageInfo');
+    if (!info) return;
+    try {
+      const usage = await invoke<TempUsage>('get_temp_usage');
+      info.textContent = `Using ${state.formatFileSize(usage.total_bytes)} in ${usage.file_count} file${usage.file_count === 1 ? '' : 's'}` +
+        (usage.reclaimable_files > 0
+          ? `, ${state.formatFileSize(usage.reclaimable_bytes)} left by interrupted uploads is removed on next start`
+          : '');
+    } catch (error) {
+      info.textContent = '';
+      console.warn('Failed to read temp folder usage:', error);
+    }
+  }
+
+  async function updatePerformanceMetricsInfo() {
+    const info = document.getElementById('performanceMetricsInfo');
+    if (!info) return;
+    try {
+      const metrics = await invoke<PerformanceMetrics>('get_performance_metrics');
+      if (metrics.phases.length === 0) {
+        info.textContent = '';
+        return;
+      }
+      const phases = metrics.phases.map(p =>
+        `${p.phase}: ${(p.total_ms / 1000).toFixed(1)}s over ${p.samples} run${p.samples === 1 ? '' : 's'}`
+      );
+      const throughput = metrics.upload_mb_per_sec !== null ? `, uploading at ${metrics.upload_mb_per_sec.toFixed(2)} MB/s` : '';
+      const bound = metrics.bottleneck === 'cpu'
+        ? 'Most time goes to reading and compressing photos (CPU-bound)'
+        : 'Most time goes to uploading (network-bound)';
+      info.textContent = `${phases.join(', ')}${throughput}. ${bound}.`;
+    } catch (error) {
+      info.textContent = '';
+      console.warn('Failed to read performance metrics:', error);
+    }
+  }
+
   function updateVRChatFolderDisplay() {
     const currentPathSpan = document.getElementById('currentVRChatPath');
     const clearBtn = document.getElementById('clearVRChatFolderBtn') as HTMLButtonElement;
@@ -3652,6 +4906,8 @@ document.addEventListener('DOMContentLoaded', async () => {
     ModalManager.openModal('webhookModal');
     updateOverrideWebhookSelect();
     loadUserOverrides();
+    updateForumTagWebhookSelect();
+    loadForumTags();
   });
 
   const addOverrideBtn = document.getElementById('addOverrideBtn');
@@ -3695,6 +4951,144 @@ document.addEventListener('DOMContentLoaded', async () => {
     }
   });
 
+  // Forum post tags for the selected forum webhook
+  async function loadForumTags() {
+    const listContainer = document.getElementById('forumTagsList');
+    const select = document.getElementById('forumTagWebhookSelect') as HTMLSelectElement;
+    if (!listContainer || !select) return;
+
+    const webhookId = parseInt(select.value);
+    if (!webhookId) {
+      listContainer.innerHTML = '<div style="color: var(--text-muted); padding: 5px; font-style: italic;">Select a forum webhook to see its tags.</div>';
+      return;
+    }
+
+    try {
+      const tags = await invoke<ForumTag[]>('get_webhook_forum_tags', { webhookId });
+      listContainer.innerHTML = '';
+
+      if (tags.length === 0) {
+        listContainer.innerHTML = '<div style="color: var(--text-muted); padding: 5px; font-style: italic;">No tags configured.</div>';
+        return;
+      }
+
+      const table = document.createElement('table');
+      table.style.width = '100%';
+      table.style.borderCollapse = 'collapse';
+      table.style.fontSize = '0.9rem';
+
+      tags.forEach(tag => {
+        const row = document.createElement('tr');
+        row.style.borderBottom = '1px solid var(--border-color)';
+        row.innerHTML = `
+          <td style="padding: 4px;"><strong>${escapeHtml(tag.tag_id)}</strong></td>
+          <td style="padding: 4px;">${tag.world_id ? escapeHtml(tag.world_id) : 'All posts'}</td>
+          <td style="padding: 4px; text-align: right;">
+            <button class="btn btn-small btn-secondary delete-forum-tag-btn" data-id="${tag.id}">🗑️</button>
+          </td>
+        `;
+        table.appendChild(row);
+      });
+
+      listContainer.appendChild(table);
+
+      document.querySelectorAll('.delete-forum-tag-btn').forEach(btn => {
+        btn.addEventListener('click', async (e) => {
+          const id = parseInt((e.target as HTMLElement).dataset.id || '0');
+          if (id > 0) {
+            try {
+              await invoke('delete_webhook_forum_tag', { id });
+              loadForumTags();
+            } catch (err) {
+              state.showError(`Failed to delete forum tag: ${err}`);
+            }
+          }
+        });
+      });
+    } catch (error) {
+      console.error('Failed to load forum tags:', error);
+    }
+  }
+
+  function updateForumTagWebhookSelect() {
+    const select = document.getElementById('forumTagWebhookSelect') as HTMLSelectElement;
+    if (!select) return;
+
+    const previous = select.value;
+    select.innerHTML = '<option value="">Select Forum Webhook...</option>';
+    state.webhooks.filter(w => w.is_forum).forEach(w => {
+      const opt = document.createElement('option');
+      opt.value = w.id.toString();
+      opt.textContent = w.name;
+      select.appendChild(opt);
+    });
+    select.value = previous;
+  }
+
+  document.getElementById('forumTagWebhookSelect')?.addEventListener('change', () => loadForumTags());
+
+  const addForumTagBtn = document.getElementById('addForumTagBtn');
+  addForumTagBtn?.addEventListener('click', async () => {
+    const select = document.getElementById('forumTagWebhookSelect') as HTMLSelectElement;
+    const tagInput = document.getElementById('forumTagIdInput') as HTMLInputElement;
+    const worldInput = document.getElementById('forumTagWorldInput') as HTMLInputElement;
+
+    const webhookId = parseInt(select.value);
+    const tagId = tagInput.value.trim();
+    const worldId = worldInput.value.trim() || null;
+
+    if (!webhookId) {
+      state.showError('Please select a forum webhook');
+      return;
+    }
+    if (!tagId) {
+      state.showError('Please enter a Tag ID');
+      return;
+    }
+
+    try {
+      await invoke('add_webhook_forum_tag', { webhookId, tagId, worldId });
+      tagInput.value = '';
+      worldInput.value = '';
+      loadForumTags();
+    } catch (error) {
+      state.showError(`Failed to add forum tag: ${error}`);
+    }
+  });
+
+  // Run the forum thread_id check against the selected forum webhook
+  const diagnoseForumWebhookBtn = document.getElementById('diagnoseForumWebhookBtn') as HTMLButtonElement;
+  diagnoseForumWebhookBtn?.addEventListener('click', async () => {
+    const select = document.getElementById('forumTagWebhookSelect') as HTMLSelectElement;
+    const result = document.getElementById('forumDiagnosticResult');
+    const webhookId = parseInt(select.value);
+    if (!webhookId) {
+      state.showError('Please select a forum webhook');
+      return;
+    }
+    if (!confirm('This posts a test thread to the forum and deletes its messages afterwards. Continue?')) return;
+
+    diagnoseForumWebhookBtn.disabled = true;
+    try {
+      const report = await invoke<ForumDiagnosticReport>('diagnose_forum_webhook', { webhookId });
+      if (result) {
+        result.innerHTML = report.steps
+          .map(step => `<div>${step.ok ? '✅' : '❌'} ${escapeHtml(step.detail)}</div>`)
+          .join('');
+        result.classList.remove('hidden');
+      }
+      if (report.failed_step) {
+        state.showError(`Forum test failed at step "${report.failed_step}"`);
+      } else {
+        state.showSuccess('Forum thread posting works');
+      }
+    } catch (error) {
+      state.showError(`Forum test failed: ${error}`);
+    } finally {
+      diagnoseForumWebhookBtn.disabled = false;
+    }
+  });
+
   // Discord User Mapping Logic (VRChat player → Discord @mention)
   async function loadDiscordMappings() {
     try {